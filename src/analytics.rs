@@ -0,0 +1,391 @@
+//! Institutional reporting over a credential store: issuance volume,
+//! credentials per skill and level, expiry and revocation rates, and why
+//! verifications fail. Reports export as JSON or as long-format CSV
+//! (`metric,dimension,value`) for spreadsheets.
+
+use crate::events::EventHandler;
+use crate::status::{CredentialStatus, StatusList};
+use crate::store::{CredentialStore, StoreError};
+use crate::verifier::VerificationError;
+use crate::Microcredential;
+use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Bucket size for issuance volume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Period {
+    /// `2026-10-16`
+    Day,
+    /// ISO week, `2026-W42`
+    Week,
+    /// `2026-10`
+    Month,
+    /// `2026`
+    Year,
+}
+
+impl Period {
+    pub fn bucket(&self, at: &DateTime<Utc>) -> String {
+        match self {
+            Period::Day => at.format("%Y-%m-%d").to_string(),
+            Period::Week => {
+                let week = at.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Period::Month => at.format("%Y-%m").to_string(),
+            Period::Year => at.format("%Y").to_string(),
+        }
+    }
+}
+
+/// Counts verification outcomes by failure kind. Register it on a
+/// `CredentialVerifier` with `add_event_handler` and pass it to
+/// `Report::generate`.
+#[derive(Debug, Default)]
+pub struct VerificationStats {
+    counts: Mutex<VerificationBreakdown>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VerificationBreakdown {
+    pub succeeded: u64,
+    pub failed: u64,
+    /// Failures keyed by `failure_kind`.
+    pub failures: BTreeMap<String, u64>,
+}
+
+impl VerificationStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, result: &Result<bool, VerificationError>) {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        let kind = match result {
+            Ok(true) => {
+                counts.succeeded += 1;
+                return;
+            }
+            Ok(false) => "rejected",
+            Err(err) => failure_kind(err),
+        };
+        counts.failed += 1;
+        *counts.failures.entry(kind.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn snapshot(&self) -> VerificationBreakdown {
+        self.counts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl EventHandler for VerificationStats {
+    fn on_verified(&self, _credential: &Microcredential, result: &Result<bool, VerificationError>) {
+        self.record(result);
+    }
+}
+
+/// Stable snake_case name for a verification error, without its message,
+/// so failures group by cause.
+pub fn failure_kind(err: &VerificationError) -> &'static str {
+    match err {
+        VerificationError::SerializationError(_) => "serialization_error",
+        VerificationError::InvalidSignature => "invalid_signature",
+        VerificationError::ExpiredCredential => "expired_credential",
+        VerificationError::MissingSignature => "missing_signature",
+        VerificationError::TrustedIssuerNotFound => "trusted_issuer_not_found",
+        VerificationError::NoActiveIssuerKey => "no_active_issuer_key",
+        VerificationError::InsufficientSkillLevel => "insufficient_skill_level",
+        VerificationError::InvalidSkillLevel(_) => "invalid_skill_level",
+        VerificationError::MissingHolderBinding => "missing_holder_binding",
+        VerificationError::HolderBindingMismatch => "holder_binding_mismatch",
+        VerificationError::SuspendedCredential(_) => "suspended_credential",
+        VerificationError::RevokedCredential(_) => "revoked_credential",
+        VerificationError::CertificateConstraintViolation(_) => "certificate_constraint_violation",
+        VerificationError::UnsupportedProof(_) => "unsupported_proof",
+        VerificationError::InvalidCertificateChain(_) => "invalid_certificate_chain",
+        VerificationError::NotYetValid(_) => "not_yet_valid",
+        VerificationError::OfflinePackExpired(_) => "offline_pack_expired",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkillCount {
+    pub skill_id: String,
+    pub skill_name: String,
+    pub level: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub generated_at: DateTime<Utc>,
+    pub period: Period,
+    pub total: u64,
+    /// Credentials issued per period bucket, oldest first.
+    pub issuance: BTreeMap<String, u64>,
+    /// Per skill and level, most issued first.
+    pub skills: Vec<SkillCount>,
+    /// Per level label, across skills.
+    pub levels: BTreeMap<String, u64>,
+    /// Past their expiry at `generated_at`.
+    pub expired: u64,
+    pub suspended: u64,
+    pub revoked: u64,
+    pub expiry_rate: f64,
+    pub revocation_rate: f64,
+    pub verification: VerificationBreakdown,
+}
+
+impl Report {
+    /// Reads every credential in `store`. A credential's status is looked up
+    /// in the list published by its issuer; without one it counts as active.
+    pub fn generate(
+        store: &dyn CredentialStore,
+        status_lists: &[StatusList],
+        verifications: &VerificationStats,
+        period: Period,
+        now: DateTime<Utc>,
+    ) -> Result<Self, StoreError> {
+        let mut report = Report {
+            generated_at: now,
+            period,
+            total: 0,
+            issuance: BTreeMap::new(),
+            skills: Vec::new(),
+            levels: BTreeMap::new(),
+            expired: 0,
+            suspended: 0,
+            revoked: 0,
+            expiry_rate: 0.0,
+            revocation_rate: 0.0,
+            verification: verifications.snapshot(),
+        };
+        let mut skills: BTreeMap<(String, String), SkillCount> = BTreeMap::new();
+
+        for id in store.ids()? {
+            let credential = match store.get(&id)? {
+                Some(credential) => credential,
+                None => continue,
+            };
+            report.total += 1;
+            *report
+                .issuance
+                .entry(period.bucket(&credential.issued_at))
+                .or_insert(0) += 1;
+
+            let level = credential.skill.level.label().to_string();
+            *report.levels.entry(level.clone()).or_insert(0) += 1;
+            skills
+                .entry((credential.skill.id.clone(), level.clone()))
+                .or_insert_with(|| SkillCount {
+                    skill_id: credential.skill.id.clone(),
+                    skill_name: credential.skill.name.clone(),
+                    level,
+                    count: 0,
+                })
+                .count += 1;
+
+            if credential
+                .expires_at
+                .is_some_and(|expires_at| expires_at < now)
+            {
+                report.expired += 1;
+            }
+            let status = status_lists
+                .iter()
+                .find(|list| list.issuer_id == credential.issuer.id)
+                .map(|list| list.status_of(&credential.id));
+            match status {
+                Some(CredentialStatus::Suspended { .. }) => report.suspended += 1,
+                Some(CredentialStatus::Revoked { .. }) => report.revoked += 1,
+                _ => {}
+            }
+        }
+
+        report.skills = skills.into_values().collect();
+        report.skills.sort_by_key(|skill| Reverse(skill.count));
+        if report.total > 0 {
+            report.expiry_rate = report.expired as f64 / report.total as f64;
+            report.revocation_rate = report.revoked as f64 / report.total as f64;
+        }
+        Ok(report)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("reports serialize")
+    }
+
+    /// One `metric,dimension,value` row per figure, with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![
+            ("total".to_string(), String::new(), self.total.to_string()),
+            (
+                "expired".to_string(),
+                String::new(),
+                self.expired.to_string(),
+            ),
+            (
+                "suspended".to_string(),
+                String::new(),
+                self.suspended.to_string(),
+            ),
+            (
+                "revoked".to_string(),
+                String::new(),
+                self.revoked.to_string(),
+            ),
+            (
+                "expiry_rate".to_string(),
+                String::new(),
+                format!("{:.4}", self.expiry_rate),
+            ),
+            (
+                "revocation_rate".to_string(),
+                String::new(),
+                format!("{:.4}", self.revocation_rate),
+            ),
+        ];
+        for (bucket, count) in &self.issuance {
+            rows.push(("issued".to_string(), bucket.clone(), count.to_string()));
+        }
+        for skill in &self.skills {
+            let dimension = format!("{}/{}", skill.skill_id, skill.level);
+            rows.push(("skill".to_string(), dimension, skill.count.to_string()));
+        }
+        for (level, count) in &self.levels {
+            rows.push(("level".to_string(), level.clone(), count.to_string()));
+        }
+        let verification = &self.verification;
+        rows.push((
+            "verifications_succeeded".to_string(),
+            String::new(),
+            verification.succeeded.to_string(),
+        ));
+        rows.push((
+            "verifications_failed".to_string(),
+            String::new(),
+            verification.failed.to_string(),
+        ));
+        for (kind, count) in &verification.failures {
+            rows.push((
+                "verification_failure".to_string(),
+                kind.clone(),
+                count.to_string(),
+            ));
+        }
+
+        let mut csv = String::from("metric,dimension,value\n");
+        for (metric, dimension, value) in rows {
+            csv.push_str(&format!("{},{},{}\n", metric, csv_field(&dimension), value));
+        }
+        csv
+    }
+}
+
+/// Quotes a field containing a separator, quote or line break (RFC 4180).
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuer::CredentialIssuer;
+    use crate::store::InMemoryStore;
+    use crate::{Skill, SkillLevel, Subject};
+    use chrono::{Duration, TimeZone};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_report() {
+        let issuer = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let mut store = InMemoryStore::new();
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+        let mut status = StatusList::new(issuer.get_issuer_info().id, now);
+        let skills = [
+            ("rust", "Rust, Systems", SkillLevel::Advanced),
+            ("rust", "Rust, Systems", SkillLevel::Advanced),
+            ("sql", "SQL", SkillLevel::Beginner),
+            ("rust", "Rust, Systems", SkillLevel::Beginner),
+        ];
+        for (index, (id, name, level)) in skills.into_iter().enumerate() {
+            let mut credential = issuer
+                .issue_credential(
+                    Subject {
+                        id: Uuid::new_v4(),
+                        name: "Student".to_string(),
+                        email: "student@test.edu".to_string(),
+                        unknown_fields: Default::default(),
+                    },
+                    Skill {
+                        id: id.to_string(),
+                        name: name.to_string(),
+                        description: "Skill".to_string(),
+                        level,
+                        unknown_fields: Default::default(),
+                    },
+                    Vec::new(),
+                    Some(now - Duration::days(1) + Duration::days(index as i64)),
+                )
+                .unwrap();
+            credential.issued_at = now - Duration::days(40 * index as i64);
+            if index == 3 {
+                status
+                    .revoke(credential.id, "Misconduct".to_string(), now)
+                    .unwrap();
+            }
+            store.insert(&credential).unwrap();
+        }
+
+        let stats = VerificationStats::new();
+        stats.record(&Ok(true));
+        stats.record(&Err(VerificationError::ExpiredCredential));
+        stats.record(&Err(VerificationError::RevokedCredential("x".to_string())));
+        stats.record(&Err(VerificationError::ExpiredCredential));
+
+        let report = Report::generate(&store, &[status], &stats, Period::Month, now).unwrap();
+        assert_eq!(report.total, 4);
+        assert_eq!(
+            report.issuance.iter().collect::<Vec<_>>(),
+            vec![
+                (&"2026-06".to_string(), &1),
+                (&"2026-07".to_string(), &1),
+                (&"2026-09".to_string(), &1),
+                (&"2026-10".to_string(), &1)
+            ]
+        );
+        assert_eq!(report.skills[0].skill_id, "rust");
+        assert_eq!(report.skills[0].level, "Advanced");
+        assert_eq!(report.skills[0].count, 2);
+        assert_eq!(report.levels["Beginner"], 2);
+        assert_eq!(report.expired, 1);
+        assert_eq!(report.revoked, 1);
+        assert_eq!(report.expiry_rate, 0.25);
+        assert_eq!(report.verification.failures["expired_credential"], 2);
+        assert_eq!(report.verification.failed, 3);
+
+        let csv = report.to_csv();
+        assert!(csv.starts_with("metric,dimension,value\ntotal,,4\n"));
+        assert!(csv.contains("\nskill,rust/Advanced,2\n"));
+        assert!(csv.contains("\nrevocation_rate,,0.2500\n"));
+        assert!(csv.contains("\nverification_failure,expired_credential,2\n"));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["period"], "month");
+        assert_eq!(json["issuance"]["2026-10"], 1);
+    }
+}
@@ -0,0 +1,287 @@
+//! Store exports for research without personal data. Subject fields are
+//! stripped or replaced by pseudonyms from a keyed hash, so the same student
+//! gets the same pseudonym throughout an export (and across exports made
+//! with the same key) while nobody without the key can link it back.
+//!
+//! Only fields useful for analysis are exported. Evidence is reduced to its
+//! types, and metadata, attachments, holder keys and signatures are dropped:
+//! free text and signed bytes would let a reader re-identify the subject or
+//! match the record against the original credential. Dates are coarsened
+//! to the month by default, since exact issuance times single out a cohort
+//! or an individual ceremony.
+
+use crate::analytics::csv_field;
+use crate::store::{CredentialStore, StoreError};
+use crate::{EvidenceType, Microcredential};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Pseudonym length in bytes, hex-encoded in exports.
+const PSEUDONYM_BYTES: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FieldTreatment {
+    Strip,
+    /// Replace with a stable keyed-hash pseudonym.
+    Pseudonymize,
+    Keep,
+}
+
+/// Precision of exported dates. Coarsened dates are truncated to the start
+/// of their UTC day or month.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DateGranularity {
+    Exact,
+    Day,
+    #[default]
+    Month,
+}
+
+impl DateGranularity {
+    pub fn apply(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let day = match self {
+            DateGranularity::Exact => return at,
+            DateGranularity::Day => at.date_naive(),
+            DateGranularity::Month => {
+                NaiveDate::from_ymd_opt(at.year(), at.month(), 1).expect("first of the month")
+            }
+        };
+        day.and_hms_opt(0, 0, 0).expect("midnight").and_utc()
+    }
+}
+
+/// How identifying fields are exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnonymizationPolicy {
+    /// Credential ids identify the subject to anyone holding the original
+    /// credentials, so they are treated like subject fields.
+    pub credential_id: FieldTreatment,
+    pub subject_id: FieldTreatment,
+    pub subject_name: FieldTreatment,
+    pub subject_email: FieldTreatment,
+    /// Applied to issuance, validity and expiry dates.
+    pub dates: DateGranularity,
+}
+
+impl Default for AnonymizationPolicy {
+    fn default() -> Self {
+        Self {
+            credential_id: FieldTreatment::Pseudonymize,
+            subject_id: FieldTreatment::Pseudonymize,
+            subject_name: FieldTreatment::Strip,
+            subject_email: FieldTreatment::Strip,
+            dates: DateGranularity::default(),
+        }
+    }
+}
+
+/// One credential as exported.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnonymizedRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_email: Option<String>,
+    pub issuer_id: Uuid,
+    pub issuer_name: String,
+    pub skill_id: String,
+    pub skill_name: String,
+    pub level: String,
+    pub issued_at: DateTime<Utc>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Custom evidence types by their registered id.
+    pub evidence_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnonymizedExport {
+    pub generated_at: DateTime<Utc>,
+    pub records: Vec<AnonymizedRecord>,
+}
+
+impl AnonymizedExport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("exports serialize")
+    }
+
+    /// One row per credential with a header row. Stripped fields are empty
+    /// and evidence types are joined with `;`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "credential_id,subject_id,subject_name,subject_email,issuer_id,issuer_name,\
+             skill_id,skill_name,level,issued_at,valid_from,expires_at,evidence_types\n",
+        );
+        let optional = |value: &Option<String>| csv_field(value.as_deref().unwrap_or(""));
+        let timestamp =
+            |at: &Option<DateTime<Utc>>| at.map(|at| at.to_rfc3339()).unwrap_or_default();
+        for record in &self.records {
+            let fields = [
+                optional(&record.credential_id),
+                optional(&record.subject_id),
+                optional(&record.subject_name),
+                optional(&record.subject_email),
+                record.issuer_id.to_string(),
+                csv_field(&record.issuer_name),
+                csv_field(&record.skill_id),
+                csv_field(&record.skill_name),
+                csv_field(&record.level),
+                record.issued_at.to_rfc3339(),
+                timestamp(&record.valid_from),
+                timestamp(&record.expires_at),
+                csv_field(&record.evidence_types.join(";")),
+            ];
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Applies an `AnonymizationPolicy` with a secret pseudonym key. Keep the
+/// key away from the researchers; reuse it to keep pseudonyms stable across
+/// exports, or use a fresh one per study so exports cannot be joined.
+pub struct Anonymizer {
+    key: [u8; 32],
+    policy: AnonymizationPolicy,
+}
+
+impl Anonymizer {
+    pub fn new(key: [u8; 32], policy: AnonymizationPolicy) -> Self {
+        Self { key, policy }
+    }
+
+    /// A random pseudonym key.
+    pub fn generate_key() -> [u8; 32] {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        key
+    }
+
+    /// Keyed hash of `value`, separated by `field` so equal values in
+    /// different fields get unrelated pseudonyms.
+    pub fn pseudonym(&self, field: &str, value: &str) -> String {
+        let mut hasher = blake3::Hasher::new_keyed(&self.key);
+        hasher.update(field.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(value.as_bytes());
+        hex::encode(&hasher.finalize().as_bytes()[..PSEUDONYM_BYTES])
+    }
+
+    pub fn anonymize(&self, credential: &Microcredential) -> AnonymizedRecord {
+        let subject = &credential.subject;
+        AnonymizedRecord {
+            credential_id: self.apply(
+                self.policy.credential_id,
+                "credential_id",
+                &credential.id.to_string(),
+            ),
+            subject_id: self.apply(
+                self.policy.subject_id,
+                "subject_id",
+                &subject.id.to_string(),
+            ),
+            subject_name: self.apply(self.policy.subject_name, "subject_name", &subject.name),
+            // Case-insensitive, so one address spelled two ways stays one
+            // subject.
+            subject_email: self.apply(
+                self.policy.subject_email,
+                "subject_email",
+                &subject.email.to_lowercase(),
+            ),
+            issuer_id: credential.issuer.id,
+            issuer_name: credential.issuer.name.clone(),
+            skill_id: credential.skill.id.clone(),
+            skill_name: credential.skill.name.clone(),
+            level: credential.skill.level.label().to_string(),
+            issued_at: self.policy.dates.apply(credential.issued_at),
+            valid_from: credential.valid_from.map(|at| self.policy.dates.apply(at)),
+            expires_at: credential.expires_at.map(|at| self.policy.dates.apply(at)),
+            evidence_types: credential
+                .evidence
+                .iter()
+                .map(|evidence| evidence_type_name(&evidence.evidence_type))
+                .collect(),
+        }
+    }
+
+    /// Anonymizes every credential in `store`.
+    pub fn export(
+        &self,
+        store: &dyn CredentialStore,
+        now: DateTime<Utc>,
+    ) -> Result<AnonymizedExport, StoreError> {
+        let mut records = Vec::new();
+        for id in store.ids()? {
+            if let Some(credential) = store.get(&id)? {
+                records.push(self.anonymize(&credential));
+            }
+        }
+        Ok(AnonymizedExport {
+            generated_at: now,
+            records,
+        })
+    }
+
+    fn apply(&self, treatment: FieldTreatment, field: &str, value: &str) -> Option<String> {
+        match treatment {
+            FieldTreatment::Strip => None,
+            FieldTreatment::Pseudonymize => Some(self.pseudonym(field, value)),
+            FieldTreatment::Keep => Some(value.to_string()),
+        }
+    }
+}
+
+fn evidence_type_name(evidence_type: &EvidenceType) -> String {
+    match evidence_type {
+        EvidenceType::Project => "project".to_string(),
+        EvidenceType::Assessment => "assessment".to_string(),
+        EvidenceType::Portfolio => "portfolio".to_string(),
+        EvidenceType::Certification => "certification".to_string(),
+        EvidenceType::Custom { type_id, .. } => type_id.clone(),
+        #[allow(deprecated)]
+        EvidenceType::Other(name) => name.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudonyms_are_keyed_and_field_separated() {
+        let policy = AnonymizationPolicy::default();
+        let anonymizer = Anonymizer::new([7; 32], policy.clone());
+        let pseudonym = anonymizer.pseudonym("subject_id", "42");
+        assert_eq!(pseudonym.len(), PSEUDONYM_BYTES * 2);
+        assert_eq!(pseudonym, anonymizer.pseudonym("subject_id", "42"));
+        assert_ne!(pseudonym, anonymizer.pseudonym("credential_id", "42"));
+        assert_ne!(
+            pseudonym,
+            Anonymizer::new([8; 32], policy).pseudonym("subject_id", "42")
+        );
+    }
+
+    #[test]
+    fn test_dates_are_coarsened() {
+        let at = "2026-03-17T13:45:09Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(DateGranularity::Exact.apply(at), at);
+        assert_eq!(
+            DateGranularity::Day.apply(at).to_rfc3339(),
+            "2026-03-17T00:00:00+00:00"
+        );
+        assert_eq!(
+            DateGranularity::default().apply(at).to_rfc3339(),
+            "2026-03-01T00:00:00+00:00"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Proptest generators for the credential model (feature `proptest`), so
+//! services built on this crate can property-test their pipelines with
+//! realistic credentials. Generated values satisfy
+//! `Microcredential::invariants` and the default `ValidationRules`.
+//!
+//! ```ignore
+//! proptest! {
+//!     #[test]
+//!     fn stores_round_trip(credential in any::<Microcredential>()) { ... }
+//! }
+//! ```
+
+use crate::issuer::CredentialIssuer;
+use crate::levels::LevelScale;
+use crate::{
+    Evidence, EvidenceType, Issuer, IssuerKey, Microcredential, Skill, SkillLevel, Subject,
+};
+use chrono::{DateTime, Duration, Utc};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use serde_json::Map;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub fn uuid() -> impl Strategy<Value = Uuid> {
+    any::<u128>().prop_map(Uuid::from_u128)
+}
+
+/// Whole seconds between 2000 and 2100, as the issuer signs them.
+pub fn timestamp() -> impl Strategy<Value = DateTime<Utc>> {
+    (946_684_800i64..4_102_444_800)
+        .prop_map(|seconds| DateTime::from_timestamp(seconds, 0).expect("timestamp in range"))
+}
+
+pub fn subject() -> impl Strategy<Value = Subject> {
+    (
+        uuid(),
+        "[A-Z][a-z]{1,11}( [A-Z][a-z]{1,11}){0,2}",
+        "[a-z][a-z0-9.]{0,15}",
+        "[a-z]{2,10}\\.(edu|org|com)",
+    )
+        .prop_map(|(id, name, local, domain)| Subject {
+            id,
+            name,
+            email: format!("{}@{}", local, domain),
+            unknown_fields: Map::new(),
+        })
+}
+
+/// Levels on the default scale, EQF and Bloom's taxonomy.
+pub fn skill_level() -> impl Strategy<Value = SkillLevel> {
+    prop_oneof![
+        Just(SkillLevel::Beginner),
+        Just(SkillLevel::Intermediate),
+        Just(SkillLevel::Advanced),
+        Just(SkillLevel::Expert),
+        (1..=8u32).prop_map(|rank| LevelScale::eqf().level_at(rank).expect("EQF level")),
+        (1..=6u32).prop_map(|rank| LevelScale::blooms().level_at(rank).expect("Bloom's level")),
+    ]
+}
+
+pub fn skill() -> impl Strategy<Value = Skill> {
+    (
+        "[a-z]{2,8}(-[a-z0-9]{1,6}){0,2}",
+        "[A-Z][a-z]{2,10}( [a-z]{2,10}){0,3}",
+        "[ -~]{0,200}",
+        skill_level(),
+    )
+        .prop_map(|(id, name, description, level)| Skill {
+            id,
+            name,
+            description,
+            level,
+            unknown_fields: Map::new(),
+        })
+}
+
+pub fn evidence() -> impl Strategy<Value = Evidence> {
+    (
+        uuid(),
+        "[A-Z][a-z]{2,10}( [a-z]{2,10}){0,3}",
+        "[ -~]{0,200}",
+        "https://[a-z]{3,10}\\.(edu|org)/[a-z0-9]{1,12}",
+        prop_oneof![
+            Just(EvidenceType::Project),
+            Just(EvidenceType::Assessment),
+            Just(EvidenceType::Portfolio),
+            Just(EvidenceType::Certification),
+        ],
+    )
+        .prop_map(|(id, name, description, url, evidence_type)| Evidence {
+            id,
+            name,
+            description,
+            url,
+            evidence_type,
+            unknown_fields: Map::new(),
+        })
+}
+
+/// An issuer whose single key was activated at `activated_at`.
+pub fn issuer(activated_at: DateTime<Utc>) -> impl Strategy<Value = Issuer> {
+    (
+        uuid(),
+        "[A-Z][a-z]{2,10} (University|College|Academy)",
+        "[a-z]{3,10}",
+        any::<[u8; 32]>(),
+    )
+        .prop_map(move |(id, name, host, public_key)| Issuer {
+            id,
+            name,
+            url: format!("https://{}.edu", host),
+            public_key: public_key.to_vec().into(),
+            key_history: vec![IssuerKey {
+                public_key: public_key.to_vec().into(),
+                activated_at,
+                retired_at: None,
+                endorsement: None,
+            }],
+            unknown_fields: Map::new(),
+        })
+}
+
+/// An unsigned credential. Use `issued_by` for signed ones.
+pub fn microcredential() -> impl Strategy<Value = Microcredential> {
+    (timestamp(), 0..3650i64)
+        .prop_flat_map(|(issued_at, key_age_days)| {
+            (
+                uuid(),
+                issuer(issued_at - Duration::days(key_age_days)),
+                subject(),
+                skill(),
+                vec(evidence(), 0..5),
+                proptest::option::of(1..3650i64),
+                any::<bool>(),
+                Just(issued_at),
+            )
+        })
+        .prop_map(
+            |(id, issuer, subject, skill, evidence, valid_days, delayed, issued_at)| {
+                let expires_at = valid_days.map(|days| issued_at + Duration::days(days));
+                let mut credential =
+                    Microcredential::new(issuer, subject, skill, evidence, expires_at);
+                credential.id = id;
+                credential.issued_at = issued_at;
+                if delayed && expires_at.is_some() {
+                    credential.valid_from = Some(issued_at + Duration::hours(1));
+                }
+                credential
+            },
+        )
+}
+
+/// Credentials signed by `issuer` through its normal issuance path.
+pub fn issued_by(issuer: &CredentialIssuer) -> impl Strategy<Value = Microcredential> + '_ {
+    (subject(), skill(), vec(evidence(), 0..5)).prop_map(move |(subject, skill, evidence)| {
+        issuer
+            .issue_request(crate::issuer::CredentialRequest {
+                subject,
+                skill,
+                evidence,
+                valid_from: None,
+                expires_at: None,
+                metadata: HashMap::new(),
+                attachments: Vec::new(),
+                holder_key: None,
+                actor: None,
+            })
+            .expect("generated requests pass the default validation rules")
+    })
+}
+
+macro_rules! arbitrary_via {
+    ($($ty:ty => $strategy:ident),* $(,)?) => {
+        $(
+            impl Arbitrary for $ty {
+                type Parameters = ();
+                type Strategy = BoxedStrategy<Self>;
+
+                fn arbitrary_with(_: ()) -> Self::Strategy {
+                    $strategy().boxed()
+                }
+            }
+        )*
+    };
+}
+
+arbitrary_via! {
+    Subject => subject,
+    SkillLevel => skill_level,
+    Skill => skill,
+    Evidence => evidence,
+    Microcredential => microcredential,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::CredentialVerifier;
+
+    proptest! {
+        #[test]
+        fn test_generated_credentials_hold_invariants(credential in any::<Microcredential>()) {
+            let report = credential.invariants();
+            prop_assert!(report.is_valid(), "{}", report);
+        }
+    }
+
+    #[test]
+    fn test_issued_credentials_verify() {
+        let issuer = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer.get_issuer_info().clone());
+        proptest!(ProptestConfig::with_cases(32), |(credential in issued_by(&issuer))| {
+            prop_assert!(credential.invariants().is_valid());
+            prop_assert!(matches!(verifier.verify_credential(&credential), Ok(true)));
+        });
+    }
+}
@@ -0,0 +1,211 @@
+use crate::validation::ValidationReport;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// A file delivered alongside a credential (seal image, rubric PDF, ...).
+/// Unlike evidence it is not a claim about the subject.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub name: String,
+    pub mime_type: String,
+    pub size: u64,
+    /// Subresource-integrity style digest, e.g. `sha256-<base64>`.
+    pub digest: String,
+    pub content: AttachmentContent,
+    #[serde(flatten)]
+    pub unknown_fields: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum AttachmentContent {
+    Embedded {
+        #[serde(with = "base64_bytes")]
+        data: Vec<u8>,
+    },
+    External {
+        url: String,
+    },
+}
+
+impl Attachment {
+    pub fn embedded(name: String, mime_type: String, data: Vec<u8>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            mime_type,
+            size: data.len() as u64,
+            digest: sha256_digest(&data),
+            content: AttachmentContent::Embedded { data },
+            unknown_fields: Map::new(),
+        }
+    }
+
+    pub fn external(
+        name: String,
+        mime_type: String,
+        url: String,
+        size: u64,
+        digest: String,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            mime_type,
+            size,
+            digest,
+            content: AttachmentContent::External { url },
+            unknown_fields: Map::new(),
+        }
+    }
+
+    /// Checks fetched (or embedded) bytes against the recorded size and digest.
+    pub fn matches_content(&self, data: &[u8]) -> bool {
+        data.len() as u64 == self.size && sha256_digest(data) == self.digest
+    }
+}
+
+pub fn sha256_digest(data: &[u8]) -> String {
+    format!("sha256-{}", STANDARD.encode(Sha256::digest(data)))
+}
+
+#[derive(Debug, Clone)]
+pub struct AttachmentPolicy {
+    pub max_size: u64,
+    pub max_attachments: usize,
+    /// Allowed MIME types; empty allows any well-formed type.
+    pub allowed_mime_types: Vec<String>,
+}
+
+impl Default for AttachmentPolicy {
+    fn default() -> Self {
+        Self {
+            max_size: 5 * 1024 * 1024,
+            max_attachments: 10,
+            allowed_mime_types: Vec::new(),
+        }
+    }
+}
+
+impl AttachmentPolicy {
+    pub fn validate(&self, attachments: &[Attachment], report: &mut ValidationReport) {
+        if attachments.len() > self.max_attachments {
+            report.add_issue(
+                "attachments",
+                format!("allows at most {} entries", self.max_attachments),
+            );
+        }
+        for (index, attachment) in attachments.iter().enumerate() {
+            let field = format!("attachments[{}]", index);
+            if attachment.name.trim().is_empty() {
+                report.add_issue(format!("{}.name", field), "must not be empty");
+            }
+            if !is_valid_mime_type(&attachment.mime_type) {
+                report.add_issue(format!("{}.mime_type", field), "is not a valid MIME type");
+            } else if !self.allowed_mime_types.is_empty()
+                && !self
+                    .allowed_mime_types
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(&attachment.mime_type))
+            {
+                report.add_issue(
+                    format!("{}.mime_type", field),
+                    format!("'{}' is not allowed", attachment.mime_type),
+                );
+            }
+            if attachment.size > self.max_size {
+                report.add_issue(
+                    format!("{}.size", field),
+                    format!("exceeds {} bytes", self.max_size),
+                );
+            }
+            match &attachment.content {
+                AttachmentContent::Embedded { data } => {
+                    if !attachment.matches_content(data) {
+                        report.add_issue(
+                            format!("{}.digest", field),
+                            "does not match the embedded data",
+                        );
+                    }
+                }
+                AttachmentContent::External { url } => {
+                    if !crate::validation::is_valid_url(url) {
+                        report.add_issue(format!("{}.url", field), "is not a valid http(s) URL");
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn is_valid_mime_type(mime_type: &str) -> bool {
+    let essence = mime_type.split(';').next().unwrap_or("").trim();
+    let Some((kind, subtype)) = essence.split_once('/') else {
+        return false;
+    };
+    let is_token = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$&-^_.+".contains(c))
+    };
+    is_token(kind) && is_token(subtype)
+}
+
+mod base64_bytes {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_policy_enforces_size_and_type() {
+        let seal = Attachment::embedded(
+            "seal.png".to_string(),
+            "image/png".to_string(),
+            vec![0u8; 64],
+        );
+        let rubric = Attachment::external(
+            "rubric.pdf".to_string(),
+            "application/pdf".to_string(),
+            "https://example.com/rubric.pdf".to_string(),
+            10_000,
+            sha256_digest(b"rubric"),
+        );
+
+        let policy = AttachmentPolicy {
+            max_size: 1024,
+            max_attachments: 10,
+            allowed_mime_types: vec!["image/png".to_string()],
+        };
+        let mut report = ValidationReport::default();
+        policy.validate(&[seal.clone(), rubric], &mut report);
+        let fields: Vec<&str> = report.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec!["attachments[1].mime_type", "attachments[1].size"]
+        );
+
+        let json = serde_json::to_value(&seal).unwrap();
+        assert!(json["content"]["Embedded"]["data"].is_string());
+        let parsed: Attachment = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, seal);
+    }
+}
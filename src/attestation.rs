@@ -0,0 +1,88 @@
+use crate::crypto::{canonical_json, hash_credential, verify_signature};
+use crate::signer::SignerError;
+use crate::Microcredential;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AttestationError {
+    /// The verifier has no attestation key (see `set_attestation_signer`).
+    NoSigner,
+    Signer(SignerError),
+    Serialization(String),
+}
+
+impl fmt::Display for AttestationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AttestationError::NoSigner => write!(f, "Verifier has no attestation key"),
+            AttestationError::Signer(e) => write!(f, "Signing failed: {}", e),
+            AttestationError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+        }
+    }
+}
+
+impl Error for AttestationError {}
+
+impl From<SignerError> for AttestationError {
+    fn from(e: SignerError) -> Self {
+        AttestationError::Signer(e)
+    }
+}
+
+/// Signed statement that a verifier checked a credential at a given time,
+/// and with what result, for relying parties that did not verify it
+/// themselves. Like `IssuerProfile` it is self-signed: the signature proves
+/// the listed verifier key made the statement, trusting that key is up to
+/// the relying party.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationAttestation {
+    pub id: Uuid,
+    /// Identifier of the verifier, e.g. its URL.
+    pub verifier: String,
+    pub verifier_key: Vec<u8>,
+    pub credential_id: Uuid,
+    /// Hex SHA-256 of the whole canonical credential, signature and proof
+    /// included, pinning the exact credential that was checked.
+    pub credential_hash: String,
+    pub checked_at: DateTime<Utc>,
+    pub valid: bool,
+    /// Every failed check, as in `VerificationReport`.
+    pub failures: Vec<String>,
+    pub warnings: Vec<String>,
+    pub signature: Option<Vec<u8>>,
+}
+
+impl VerificationAttestation {
+    pub fn signing_payload(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        canonical_json(&unsigned)
+    }
+
+    pub fn verify_signature(&self) -> bool {
+        let Some(signature) = &self.signature else {
+            return false;
+        };
+        let Ok(payload) = self.signing_payload() else {
+            return false;
+        };
+        verify_signature(&self.verifier_key, &hash_credential(&payload), signature).unwrap_or(false)
+    }
+
+    /// Whether this attestation is about exactly `credential`.
+    pub fn covers(&self, credential: &Microcredential) -> bool {
+        credential.id == self.credential_id
+            && credential_hash(credential).is_ok_and(|hash| hash == self.credential_hash)
+    }
+}
+
+pub(crate) fn credential_hash(credential: &Microcredential) -> Result<String, AttestationError> {
+    let payload =
+        canonical_json(credential).map_err(|e| AttestationError::Serialization(e.to_string()))?;
+    Ok(hex::encode(hash_credential(&payload)))
+}
@@ -0,0 +1,301 @@
+//! Tamper-evident log of issuing practice, kept apart from the credential
+//! store for accreditation audits.
+//!
+//! Every record carries the hash of the record before it, so editing,
+//! dropping or reordering records breaks the chain from that point on.
+//! Publishing or countersigning the latest hash (`AuditLog::head`) pins
+//! the whole history up to it. An `AuditSink` makes each record durable
+//! before it is added, e.g. `JsonlFileSink`.
+
+use crate::crypto::{canonical_json, hash_credential};
+use crate::Microcredential;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// `previous_hash` of the first record.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum AuditError {
+    /// Record `sequence` is out of order or missing its predecessor.
+    SequenceGap(u64),
+    /// Record `sequence` does not link to the hash of the record before it.
+    BrokenLink(u64),
+    /// Record `sequence` was modified after it was written.
+    HashMismatch(u64),
+    Serialization(String),
+    /// The sink could not persist a record, which was therefore not added.
+    Sink(String),
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuditError::SequenceGap(sequence) => {
+                write!(f, "Audit record {} is out of sequence", sequence)
+            }
+            AuditError::BrokenLink(sequence) => {
+                write!(
+                    f,
+                    "Audit record {} does not link to its predecessor",
+                    sequence
+                )
+            }
+            AuditError::HashMismatch(sequence) => {
+                write!(f, "Audit record {} has been altered", sequence)
+            }
+            AuditError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+            AuditError::Sink(msg) => write!(f, "Audit record could not be persisted: {}", msg),
+        }
+    }
+}
+
+impl Error for AuditError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[non_exhaustive]
+pub enum AuditAction {
+    Issued,
+    Reissued { original_id: Uuid },
+    Renewed { previous_id: Uuid },
+}
+
+/// One issuance, as the issuer saw it at the time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub sequence: u64,
+    pub recorded_at: DateTime<Utc>,
+    /// Operator or service that triggered the issuance, from the request's
+    /// `actor`. Empty when the request named none.
+    pub actor: String,
+    pub action: AuditAction,
+    pub credential_id: Uuid,
+    pub issuer_id: Uuid,
+    pub subject_id: Uuid,
+    pub skill_id: String,
+    /// Key id of the signing key (see `proof::key_id`).
+    pub key_id: Option<String>,
+    /// Hex SHA-256 of the credential's signing payload.
+    pub credential_hash: String,
+    /// Issuer policy in force, see `CredentialIssuer::policy_snapshot`.
+    pub policy: Value,
+    pub previous_hash: String,
+    /// Hex SHA-256 of the canonical JSON of every other field.
+    pub hash: String,
+}
+
+impl AuditRecord {
+    fn compute_hash(&self) -> String {
+        let mut unhashed = serde_json::to_value(self).expect("audit records serialize");
+        if let Some(object) = unhashed.as_object_mut() {
+            object.remove("hash");
+        }
+        let bytes = canonical_json(&unhashed).expect("audit records serialize");
+        hex::encode(hash_credential(&bytes))
+    }
+}
+
+/// Durable storage that records are appended to as they are written.
+pub trait AuditSink: Send + Sync {
+    /// Persists `record`. The log only adds the record once this succeeds.
+    fn append(&self, record: &AuditRecord) -> Result<(), AuditError>;
+}
+
+/// Appends records to a file as JSON lines, the format `AuditLog::from_jsonl`
+/// reads, syncing each one to disk.
+#[derive(Debug)]
+pub struct JsonlFileSink {
+    file: Mutex<File>,
+}
+
+impl JsonlFileSink {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| AuditError::Sink(e.to_string()))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AuditSink for JsonlFileSink {
+    fn append(&self, record: &AuditRecord) -> Result<(), AuditError> {
+        let mut line =
+            serde_json::to_vec(record).map_err(|e| AuditError::Serialization(e.to_string()))?;
+        line.push(b'\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(&line)
+            .and_then(|()| file.sync_data())
+            .map_err(|e| AuditError::Sink(e.to_string()))
+    }
+}
+
+/// Append-only audit log, held in memory and optionally written through to
+/// an `AuditSink`. Persist it with `to_jsonl` and reload it with
+/// `from_jsonl`, which re-verifies the chain.
+#[derive(Default)]
+pub struct AuditLog {
+    records: Mutex<Vec<AuditRecord>>,
+    sink: Option<Box<dyn AuditSink>>,
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("records", &self.len())
+            .field("sink", &self.sink.is_some())
+            .finish()
+    }
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes every new record to `sink` before adding it. To continue a
+    /// persisted log, load it with `from_jsonl` first.
+    pub fn with_sink(mut self, sink: Box<dyn AuditSink>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Appends a record for the signed `credential` and returns it. Fails,
+    /// leaving the log unchanged, when the sink cannot persist the record.
+    pub fn record(
+        &self,
+        credential: &Microcredential,
+        action: AuditAction,
+        actor: &str,
+        policy: Value,
+        at: DateTime<Utc>,
+    ) -> Result<AuditRecord, AuditError> {
+        // Signing already serialized this payload once.
+        let payload = credential
+            .signing_payload()
+            .expect("signed credentials serialize");
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        let previous = records.last();
+        let mut record = AuditRecord {
+            sequence: previous.map_or(0, |record| record.sequence + 1),
+            recorded_at: at,
+            actor: actor.to_string(),
+            action,
+            credential_id: credential.id,
+            issuer_id: credential.issuer.id,
+            subject_id: credential.subject.id,
+            skill_id: credential.skill.id.clone(),
+            key_id: credential
+                .proof
+                .as_ref()
+                .and_then(|proof| proof.key_id())
+                .map(str::to_string),
+            credential_hash: hex::encode(hash_credential(&payload)),
+            policy,
+            previous_hash: previous.map_or(GENESIS_HASH.to_string(), |record| record.hash.clone()),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+        if let Some(sink) = &self.sink {
+            sink.append(&record)?;
+        }
+        records.push(record.clone());
+        Ok(record)
+    }
+
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Hash of the latest record, or `GENESIS_HASH` for an empty log.
+    pub fn head(&self) -> String {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .last()
+            .map_or(GENESIS_HASH.to_string(), |record| record.hash.clone())
+    }
+
+    pub fn verify(&self) -> Result<(), AuditError> {
+        verify_chain(&self.records())
+    }
+
+    /// Records issued for `credential_id`, e.g. to answer an auditor's
+    /// query about one credential.
+    pub fn records_for(&self, credential_id: &Uuid) -> Vec<AuditRecord> {
+        self.records()
+            .into_iter()
+            .filter(|record| record.credential_id == *credential_id)
+            .collect()
+    }
+
+    /// One JSON record per line.
+    pub fn to_jsonl(&self) -> Result<String, AuditError> {
+        let mut out = String::new();
+        for record in self.records() {
+            let line = serde_json::to_string(&record)
+                .map_err(|e| AuditError::Serialization(e.to_string()))?;
+            out.push_str(&line);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Loads an exported log, rejecting it unless the chain verifies.
+    pub fn from_jsonl(jsonl: &str) -> Result<Self, AuditError> {
+        let records = jsonl
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| AuditError::Serialization(e.to_string()))
+            })
+            .collect::<Result<Vec<AuditRecord>, _>>()?;
+        verify_chain(&records)?;
+        Ok(Self {
+            records: Mutex::new(records),
+            sink: None,
+        })
+    }
+}
+
+/// Checks sequence numbers, links and record hashes from the first record.
+pub fn verify_chain(records: &[AuditRecord]) -> Result<(), AuditError> {
+    let mut previous_hash = GENESIS_HASH;
+    for (index, record) in records.iter().enumerate() {
+        if record.sequence != index as u64 {
+            return Err(AuditError::SequenceGap(record.sequence));
+        }
+        if record.previous_hash != previous_hash {
+            return Err(AuditError::BrokenLink(record.sequence));
+        }
+        if record.compute_hash() != record.hash {
+            return Err(AuditError::HashMismatch(record.sequence));
+        }
+        previous_hash = &record.hash;
+    }
+    Ok(())
+}
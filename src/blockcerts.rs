@@ -0,0 +1,360 @@
+//! Blockcerts v2 certificates: credentials are hashed in batches into a
+//! Merkle tree whose root is anchored in a blockchain transaction, and each
+//! certificate carries a `MerkleProof2017` receipt (Chainpoint v2) linking
+//! its hash to the anchored root.
+//!
+//! The target hash is SHA-256 of the RDFC-1.0 N-Quads of the certificate
+//! without its `signature`. Broadcasting the anchoring transaction and
+//! looking it up again are left to the caller (`AnchorLookup`). An anchor
+//! only counts if its transaction was sent from an address the issuer
+//! published; anyone can anchor a root from their own wallet.
+
+use crate::ld::context::{ContextLoader, BLOCKCERTS_V2};
+use crate::ld::{self, LdError};
+use crate::Microcredential;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BlockcertsError {
+    Ld(LdError),
+    EmptyBatch,
+    MissingReceipt,
+    InvalidReceipt(String),
+    TargetHashMismatch,
+    MerkleRootMismatch,
+    /// The anchoring transaction does not carry the receipt's Merkle root.
+    AnchorMismatch(String),
+    /// The anchoring transaction was not sent from an issuer address.
+    UnknownSender(String),
+    Anchor(String),
+}
+
+impl fmt::Display for BlockcertsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockcertsError::Ld(e) => write!(f, "JSON-LD error: {}", e),
+            BlockcertsError::EmptyBatch => write!(f, "Batch has no certificates"),
+            BlockcertsError::MissingReceipt => write!(f, "Certificate has no Merkle receipt"),
+            BlockcertsError::InvalidReceipt(msg) => write!(f, "Invalid Merkle receipt: {}", msg),
+            BlockcertsError::TargetHashMismatch => {
+                write!(f, "Certificate does not match the receipt's target hash")
+            }
+            BlockcertsError::MerkleRootMismatch => {
+                write!(f, "Merkle proof does not lead to the receipt's root")
+            }
+            BlockcertsError::AnchorMismatch(source_id) => {
+                write!(
+                    f,
+                    "Transaction {} does not anchor the Merkle root",
+                    source_id
+                )
+            }
+            BlockcertsError::UnknownSender(source_id) => {
+                write!(
+                    f,
+                    "Transaction {} was not sent from an issuer address",
+                    source_id
+                )
+            }
+            BlockcertsError::Anchor(msg) => write!(f, "Anchor lookup failed: {}", msg),
+        }
+    }
+}
+
+impl Error for BlockcertsError {}
+
+impl From<LdError> for BlockcertsError {
+    fn from(e: LdError) -> Self {
+        BlockcertsError::Ld(e)
+    }
+}
+
+/// A transaction carrying a Merkle root, e.g. a Bitcoin `OP_RETURN` output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Anchor {
+    /// Transaction id.
+    pub source_id: String,
+    /// `BTCOpReturn` or `ETHData`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// `bitcoinMainnet`, `bitcoinTestnet`, `ethereumMainnet`, ...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<String>,
+}
+
+/// One step from a node towards the root: the sibling hash (hex) and the
+/// side it is concatenated on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ProofStep {
+    Left(String),
+    Right(String),
+}
+
+/// The `signature` block of a Blockcerts v2 certificate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MerkleReceipt {
+    #[serde(rename = "type")]
+    pub kind: Vec<String>,
+    pub target_hash: String,
+    pub merkle_root: String,
+    #[serde(default)]
+    pub proof: Vec<ProofStep>,
+    #[serde(default)]
+    pub anchors: Vec<Anchor>,
+}
+
+impl MerkleReceipt {
+    /// Walks the proof from the target hash and checks it ends at the root.
+    pub fn check_path(&self) -> Result<(), BlockcertsError> {
+        let mut node = decode_hash(&self.target_hash)?;
+        for step in &self.proof {
+            node = match step {
+                ProofStep::Left(sibling) => parent(&decode_hash(sibling)?, &node),
+                ProofStep::Right(sibling) => parent(&node, &decode_hash(sibling)?),
+            };
+        }
+        if node != decode_hash(&self.merkle_root)? {
+            return Err(BlockcertsError::MerkleRootMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// What a chain lookup found for an anchor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnchorTransaction {
+    /// The anchored bytes, e.g. the `OP_RETURN` payload.
+    pub data: Vec<u8>,
+    /// Address the transaction was sent from.
+    pub sender: String,
+}
+
+/// Looks up anchoring transactions. `issuer_addresses` are the addresses
+/// the issuer published; lookups may use them to narrow their query, and
+/// `verify` checks the returned sender against them either way.
+pub trait AnchorLookup: Send + Sync {
+    fn transaction(
+        &self,
+        anchor: &Anchor,
+        issuer_addresses: &[String],
+    ) -> Result<AnchorTransaction, String>;
+}
+
+/// Unsigned certificate for `credential`: its W3C VC form with the
+/// Blockcerts context.
+pub fn certificate_document(credential: &Microcredential) -> Value {
+    let mut document = ld::credential_document(credential);
+    ld::add_context(&mut document, BLOCKCERTS_V2).expect("credential document is an object");
+    document
+}
+
+/// SHA-256 of the canonical form of `document` without its `signature`.
+pub fn target_hash(
+    document: &Value,
+    loader: &dyn ContextLoader,
+) -> Result<[u8; 32], BlockcertsError> {
+    let mut unsigned = document.clone();
+    if let Some(object) = unsigned.as_object_mut() {
+        object.remove("signature");
+    }
+    Ok(Sha256::digest(ld::canonicalize(&unsigned, loader)?.as_bytes()).into())
+}
+
+/// Certificates hashed into one Merkle tree. Anchor `merkle_root` in a
+/// transaction, then attach the receipts with `certificates`.
+#[derive(Debug, Clone)]
+pub struct BlockcertsBatch {
+    documents: Vec<Value>,
+    /// Tree levels from the leaves up; the last level is the root. An odd
+    /// node at the end of a level is promoted unchanged.
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl BlockcertsBatch {
+    pub fn new(documents: Vec<Value>, loader: &dyn ContextLoader) -> Result<Self, BlockcertsError> {
+        if documents.is_empty() {
+            return Err(BlockcertsError::EmptyBatch);
+        }
+        let leaves = documents
+            .iter()
+            .map(|document| target_hash(document, loader))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => parent(left, right),
+                    [single] => *single,
+                    _ => unreachable!("chunks of two"),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Ok(Self { documents, levels })
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    pub fn merkle_root(&self) -> [u8; 32] {
+        self.levels.last().expect("batch is not empty")[0]
+    }
+
+    /// Receipt for the certificate at `index`.
+    pub fn receipt(&self, index: usize, anchors: &[Anchor]) -> MerkleReceipt {
+        let mut proof = Vec::new();
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if position % 2 == 1 {
+                proof.push(ProofStep::Left(hex::encode(level[position - 1])));
+            } else if let Some(sibling) = level.get(position + 1) {
+                proof.push(ProofStep::Right(hex::encode(sibling)));
+            }
+            position /= 2;
+        }
+        MerkleReceipt {
+            kind: vec!["MerkleProof2017".to_string(), "Extension".to_string()],
+            target_hash: hex::encode(self.levels[0][index]),
+            merkle_root: hex::encode(self.merkle_root()),
+            proof,
+            anchors: anchors.to_vec(),
+        }
+    }
+
+    /// The certificates with their receipts, once the root is anchored.
+    pub fn certificates(&self, anchors: &[Anchor]) -> Vec<Value> {
+        self.documents
+            .iter()
+            .enumerate()
+            .map(|(index, document)| {
+                let mut certificate = document.clone();
+                certificate["signature"] = json!(self.receipt(index, anchors));
+                certificate
+            })
+            .collect()
+    }
+}
+
+/// Checks that `document` matches its receipt and that the receipt's
+/// Merkle path leads to its root. Does not look at the anchors.
+pub fn verify_receipt(
+    document: &Value,
+    loader: &dyn ContextLoader,
+) -> Result<MerkleReceipt, BlockcertsError> {
+    let receipt: MerkleReceipt = serde_json::from_value(
+        document
+            .get("signature")
+            .cloned()
+            .ok_or(BlockcertsError::MissingReceipt)?,
+    )
+    .map_err(|e| BlockcertsError::InvalidReceipt(e.to_string()))?;
+    if !receipt.kind.iter().any(|kind| kind == "MerkleProof2017") {
+        return Err(BlockcertsError::InvalidReceipt(format!(
+            "unsupported type {:?}",
+            receipt.kind
+        )));
+    }
+    if target_hash(document, loader)? != decode_hash(&receipt.target_hash)? {
+        return Err(BlockcertsError::TargetHashMismatch);
+    }
+    receipt.check_path()?;
+    Ok(receipt)
+}
+
+/// `verify_receipt`, plus a check that every anchor carries the root and
+/// was sent from one of `issuer_addresses`.
+pub fn verify(
+    document: &Value,
+    loader: &dyn ContextLoader,
+    lookup: &dyn AnchorLookup,
+    issuer_addresses: &[String],
+) -> Result<MerkleReceipt, BlockcertsError> {
+    let receipt = verify_receipt(document, loader)?;
+    if receipt.anchors.is_empty() {
+        return Err(BlockcertsError::InvalidReceipt("no anchors".to_string()));
+    }
+    let root = decode_hash(&receipt.merkle_root)?;
+    for anchor in &receipt.anchors {
+        let transaction = lookup
+            .transaction(anchor, issuer_addresses)
+            .map_err(BlockcertsError::Anchor)?;
+        if !issuer_addresses
+            .iter()
+            .any(|address| same_address(address, &transaction.sender))
+        {
+            return Err(BlockcertsError::UnknownSender(anchor.source_id.clone()));
+        }
+        if transaction.data != root {
+            return Err(BlockcertsError::AnchorMismatch(anchor.source_id.clone()));
+        }
+    }
+    Ok(receipt)
+}
+
+/// Base58 Bitcoin addresses are case-sensitive; hex Ethereum addresses
+/// are not (their mixed case is only a checksum).
+fn same_address(published: &str, sender: &str) -> bool {
+    if published.starts_with("0x") && sender.starts_with("0x") {
+        published.eq_ignore_ascii_case(sender)
+    } else {
+        published == sender
+    }
+}
+
+fn parent(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn decode_hash(text: &str) -> Result<[u8; 32], BlockcertsError> {
+    let invalid = || BlockcertsError::InvalidReceipt(format!("invalid hash {}", text));
+    let mut hash = [0; 32];
+    hex::decode_to_slice(text, &mut hash).map_err(|_| invalid())?;
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_path_is_checked() {
+        // A two-leaf tree: root = SHA-256(left || right).
+        let left = [1; 32];
+        let right = [2; 32];
+        let mut receipt = MerkleReceipt {
+            kind: vec!["MerkleProof2017".to_string(), "Extension".to_string()],
+            target_hash: hex::encode(right),
+            merkle_root: hex::encode(parent(&left, &right)),
+            proof: vec![ProofStep::Left(hex::encode(left))],
+            anchors: Vec::new(),
+        };
+        receipt.check_path().unwrap();
+        let json = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(json["proof"][0]["left"], hex::encode(left));
+        assert_eq!(json["targetHash"], hex::encode(right));
+
+        receipt.proof = vec![ProofStep::Right(hex::encode(left))];
+        assert!(matches!(
+            receipt.check_path(),
+            Err(BlockcertsError::MerkleRootMismatch)
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::attachment::Attachment;
+use crate::issuer::CredentialRequest;
+use crate::validation::{ValidationReport, ValidationRules};
+use crate::{Evidence, Skill, Subject};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct CredentialBuilder {
+    subject: Option<Subject>,
+    skill: Option<Skill>,
+    evidence: Vec<Evidence>,
+    valid_from: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    metadata: HashMap<String, String>,
+    attachments: Vec<Attachment>,
+    holder_key: Option<Vec<u8>>,
+    actor: Option<String>,
+    rules: ValidationRules,
+}
+
+impl CredentialBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subject(mut self, subject: Subject) -> Self {
+        self.subject = Some(subject);
+        self
+    }
+
+    pub fn skill(mut self, skill: Skill) -> Self {
+        self.skill = Some(skill);
+        self
+    }
+
+    pub fn evidence(mut self, evidence: Evidence) -> Self {
+        self.evidence.push(evidence);
+        self
+    }
+
+    pub fn valid_from(mut self, valid_from: DateTime<Utc>) -> Self {
+        self.valid_from = Some(valid_from);
+        self
+    }
+
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    pub fn metadata(mut self, key: String, value: String) -> Self {
+        self.metadata.insert(key, value);
+        self
+    }
+
+    pub fn attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    pub fn holder_key(mut self, holder_key: Vec<u8>) -> Self {
+        self.holder_key = Some(holder_key);
+        self
+    }
+
+    /// Operator or service asking for the credential, for the audit log.
+    pub fn actor(mut self, actor: String) -> Self {
+        self.actor = Some(actor);
+        self
+    }
+
+    pub fn validation_rules(mut self, rules: ValidationRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        match &self.subject {
+            Some(subject) => self.rules.validate_subject(subject, &mut report),
+            None => report.add_issue("subject", "is required"),
+        }
+        match &self.skill {
+            Some(skill) => self.rules.validate_skill(skill, &mut report),
+            None => report.add_issue("skill", "is required"),
+        }
+        self.rules.validate_evidence(&self.evidence, &mut report);
+        self.rules
+            .attachments
+            .validate(&self.attachments, &mut report);
+        report
+    }
+
+    pub fn build(self) -> Result<CredentialRequest, ValidationReport> {
+        self.validate().into_result()?;
+        match (self.subject, self.skill) {
+            (Some(subject), Some(skill)) => Ok(CredentialRequest {
+                subject,
+                skill,
+                evidence: self.evidence,
+                valid_from: self.valid_from,
+                expires_at: self.expires_at,
+                metadata: self.metadata,
+                attachments: self.attachments,
+                holder_key: self.holder_key,
+                actor: self.actor,
+            }),
+            _ => unreachable!("validate() reports missing subject or skill"),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Options and results for `CredentialIssuer::issue_bulk`, the
+//! high-throughput path for enrollment peaks. Compared to calling
+//! `issue_request` in a loop it computes the issuance time and proof once
+//! per chunk, reuses serialization buffers and signs on several threads.
+
+use crate::Microcredential;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::thread;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkOptions {
+    /// Signing threads. One signs on the calling thread.
+    pub threads: usize,
+    /// Requests a worker takes from the queue at a time.
+    pub chunk_size: usize,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        Self {
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            chunk_size: 64,
+        }
+    }
+}
+
+/// A request that failed validation or signing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkFailure {
+    /// Position of the request in the input.
+    pub index: usize,
+    pub message: String,
+}
+
+impl fmt::Display for BulkFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Request {} failed: {}", self.index, self.message)
+    }
+}
+
+impl Error for BulkFailure {}
+
+#[derive(Debug, Default)]
+pub struct BulkOutcome {
+    /// Signed credentials, in request order.
+    pub credentials: Vec<Microcredential>,
+    pub failures: Vec<BulkFailure>,
+    /// Requesting actor by credential id, for the audit log.
+    pub(crate) actors: HashMap<Uuid, String>,
+}
+
+impl BulkOutcome {
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuer::{CredentialIssuer, CredentialRequest};
+    use crate::{Skill, SkillLevel, Subject};
+    use std::collections::HashMap;
+    use std::time::Instant;
+    use uuid::Uuid;
+
+    fn request(index: usize) -> CredentialRequest {
+        CredentialRequest {
+            subject: Subject {
+                id: Uuid::new_v4(),
+                name: format!("Student {}", index),
+                email: format!("student{}@test.edu", index),
+                unknown_fields: Default::default(),
+            },
+            skill: Skill {
+                id: "rust-101".to_string(),
+                name: "Rust Programming".to_string(),
+                description: "Ownership, borrowing and lifetimes".to_string(),
+                level: SkillLevel::Intermediate,
+                unknown_fields: Default::default(),
+            },
+            evidence: Vec::new(),
+            valid_from: None,
+            expires_at: None,
+            metadata: HashMap::new(),
+            attachments: Vec::new(),
+            holder_key: None,
+            actor: None,
+        }
+    }
+
+    /// Throughput check: `cargo test --release bulk -- --ignored`.
+    #[test]
+    #[ignore]
+    fn bench_bulk_signing_throughput() {
+        let issuer = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let requests: Vec<_> = (0..20_000).map(request).collect();
+        let started = Instant::now();
+        let outcome = issuer.issue_bulk(requests, &BulkOptions::default());
+        let elapsed = started.elapsed();
+        assert!(outcome.is_complete());
+        let per_minute = outcome.credentials.len() as f64 * 60.0 / elapsed.as_secs_f64();
+        assert!(
+            per_minute >= 50_000.0,
+            "{} credentials in {:?} ({:.0}/minute)",
+            outcome.credentials.len(),
+            elapsed,
+            per_minute
+        );
+    }
+}
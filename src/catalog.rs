@@ -0,0 +1,203 @@
+use crate::levels::LevelScale;
+use crate::validation::ValidationReport;
+use crate::Skill;
+use serde::{Deserialize, Serialize};
+use serde_json::Map;
+use std::collections::BTreeMap;
+
+/// Reference from a skill to an entry in an external framework, e.g. an
+/// ESCO skill or an O*NET occupation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alignment {
+    pub framework: String,
+    pub target_name: String,
+    pub target_url: String,
+    pub target_code: Option<String>,
+}
+
+/// One version of a skill as the issuer defines it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillDefinition {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Levels the skill can be awarded at.
+    pub scale: LevelScale,
+    pub alignments: Vec<Alignment>,
+    /// Assigned by `SkillCatalog::publish`, starting at 1.
+    pub version: u32,
+    /// Retired skills are kept for reference but can no longer be issued.
+    pub retired: bool,
+}
+
+impl SkillDefinition {
+    pub fn new(id: String, name: String, description: String, scale: LevelScale) -> Self {
+        Self {
+            id,
+            name,
+            description,
+            scale,
+            alignments: Vec::new(),
+            version: 0,
+            retired: false,
+        }
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignments.push(alignment);
+        self
+    }
+
+    /// The skill at the level labelled `level` on this skill's scale.
+    pub fn skill(&self, level: &str) -> Option<Skill> {
+        Some(Skill {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            level: self.scale.level(level)?,
+            unknown_fields: Map::new(),
+        })
+    }
+}
+
+/// Versioned skill definitions. Set one as `ValidationRules::skill_catalog`
+/// to reject skills that are unknown, retired or out of date.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SkillCatalog {
+    /// Every published version of each skill, oldest first.
+    skills: BTreeMap<String, Vec<SkillDefinition>>,
+}
+
+impl SkillCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes `definition` as the next version of its skill and returns
+    /// that version number.
+    pub fn publish(&mut self, mut definition: SkillDefinition) -> u32 {
+        let versions = self.skills.entry(definition.id.clone()).or_default();
+        definition.version = versions.len() as u32 + 1;
+        versions.push(definition);
+        versions.len() as u32
+    }
+
+    /// Marks the current version retired. Returns false for unknown skills.
+    pub fn retire(&mut self, id: &str) -> bool {
+        match self
+            .skills
+            .get_mut(id)
+            .and_then(|versions| versions.last_mut())
+        {
+            Some(definition) => {
+                definition.retired = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Current version of a skill.
+    pub fn get(&self, id: &str) -> Option<&SkillDefinition> {
+        self.skills.get(id)?.last()
+    }
+
+    pub fn version(&self, id: &str, version: u32) -> Option<&SkillDefinition> {
+        self.skills.get(id)?.get((version as usize).checked_sub(1)?)
+    }
+
+    /// Current versions of every skill, by id.
+    pub fn definitions(&self) -> impl Iterator<Item = &SkillDefinition> {
+        self.skills.values().filter_map(|versions| versions.last())
+    }
+
+    /// Checks `skill` against the current version of its definition.
+    pub fn validate(&self, skill: &Skill, report: &mut ValidationReport) {
+        let Some(definition) = self.get(&skill.id) else {
+            report.add_issue(
+                "skill.id",
+                format!("'{}' is not in the skill catalog", skill.id),
+            );
+            return;
+        };
+        if definition.retired {
+            report.add_issue("skill.id", format!("'{}' has been retired", skill.id));
+        }
+        if skill.name != definition.name {
+            report.add_issue(
+                "skill.name",
+                format!(
+                    "does not match version {} of the catalog",
+                    definition.version
+                ),
+            );
+        }
+        if skill.description != definition.description {
+            report.add_issue(
+                "skill.description",
+                format!(
+                    "does not match version {} of the catalog",
+                    definition.version
+                ),
+            );
+        }
+        if !definition.scale.contains(&skill.level) {
+            report.add_issue(
+                "skill.level",
+                format!("is not a level of scale '{}'", definition.scale.id),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SkillLevel;
+
+    #[test]
+    fn test_catalog_versions_and_validation() {
+        let mut catalog = SkillCatalog::new();
+        let welding = SkillDefinition::new(
+            "welding".to_string(),
+            "Welding".to_string(),
+            "MIG welding".to_string(),
+            LevelScale::eqf(),
+        )
+        .alignment(Alignment {
+            framework: "ESCO".to_string(),
+            target_name: "weld metal".to_string(),
+            target_url: "http://data.europa.eu/esco/skill/example".to_string(),
+            target_code: None,
+        });
+        assert_eq!(catalog.publish(welding.clone()), 1);
+        let skill = catalog.get("welding").unwrap().skill("EQF 4").unwrap();
+
+        let mut report = ValidationReport::default();
+        catalog.validate(&skill, &mut report);
+        assert!(report.is_valid());
+
+        let mut revised = welding;
+        revised.description = "MIG and TIG welding".to_string();
+        assert_eq!(catalog.publish(revised), 2);
+        assert_eq!(
+            catalog.version("welding", 1).unwrap().description,
+            "MIG welding"
+        );
+
+        let mut outdated = skill.clone();
+        outdated.level = SkillLevel::Advanced;
+        let mut report = ValidationReport::default();
+        catalog.validate(&outdated, &mut report);
+        let fields: Vec<&str> = report.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, vec!["skill.description", "skill.level"]);
+
+        catalog.retire("welding");
+        let mut report = ValidationReport::default();
+        catalog.validate(
+            &catalog.get("welding").unwrap().skill("EQF 4").unwrap(),
+            &mut report,
+        );
+        assert_eq!(report.issues[0].message, "'welding' has been retired");
+    }
+}
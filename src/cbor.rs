@@ -0,0 +1,257 @@
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+/// Serializes `value` as deterministically encoded CBOR (RFC 8949
+/// section 4.2.1): definite lengths, shortest-form integers and floats, and
+/// map keys sorted by their encoded bytes.
+pub fn to_canonical_vec<T: Serialize>(value: &T) -> Result<Vec<u8>, serde_json::Error> {
+    let mut out = Vec::new();
+    encode_value(&serde_json::to_value(value)?, &mut out);
+    Ok(out)
+}
+
+pub fn encode_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xf6),
+        Value::Bool(false) => out.push(0xf4),
+        Value::Bool(true) => out.push(0xf5),
+        Value::Number(number) => encode_number(number, out),
+        Value::String(text) => encode_text(text, out),
+        Value::Array(items) => {
+            encode_head(4, items.len() as u64, out);
+            for item in items {
+                encode_value(item, out);
+            }
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(Vec<u8>, &Value)> = map
+                .iter()
+                .map(|(key, value)| {
+                    let mut encoded = Vec::new();
+                    encode_text(key, &mut encoded);
+                    (encoded, value)
+                })
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            encode_head(5, entries.len() as u64, out);
+            for (key, value) in entries {
+                out.extend(key);
+                encode_value(value, out);
+            }
+        }
+    }
+}
+
+/// A CBOR data item, for structures JSON cannot express: byte strings,
+/// tags and non-text map keys.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Item {
+    Unsigned(u64),
+    Negative(i64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Item>),
+    /// Entries are written sorted by encoded key, like JSON objects.
+    Map(Vec<(Item, Item)>),
+    Tag(u64, Box<Item>),
+    Bool(bool),
+    Null,
+    Json(Value),
+}
+
+impl Item {
+    pub fn text(text: impl Into<String>) -> Self {
+        Item::Text(text.into())
+    }
+
+    pub fn int(value: i64) -> Self {
+        if value < 0 {
+            Item::Negative(value)
+        } else {
+            Item::Unsigned(value as u64)
+        }
+    }
+
+    /// `#6.24(bstr .cbor item)`: `item` embedded as an encoded byte string.
+    pub fn embedded(item: &Item) -> Self {
+        Item::Tag(24, Box::new(Item::Bytes(item.to_vec())))
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Item::Unsigned(value) => encode_head(0, *value, out),
+            Item::Negative(value) => encode_head(1, !(*value as u64), out),
+            Item::Bytes(bytes) => encode_bytes(bytes, out),
+            Item::Text(text) => encode_text(text, out),
+            Item::Array(items) => {
+                encode_head(4, items.len() as u64, out);
+                for item in items {
+                    item.encode(out);
+                }
+            }
+            Item::Map(entries) => {
+                let mut entries: Vec<(Vec<u8>, &Item)> = entries
+                    .iter()
+                    .map(|(key, value)| (key.to_vec(), value))
+                    .collect();
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                encode_head(5, entries.len() as u64, out);
+                for (key, value) in entries {
+                    out.extend(key);
+                    value.encode(out);
+                }
+            }
+            Item::Tag(tag, item) => {
+                encode_head(6, *tag, out);
+                item.encode(out);
+            }
+            Item::Bool(false) => out.push(0xf4),
+            Item::Bool(true) => out.push(0xf5),
+            Item::Null => out.push(0xf6),
+            Item::Json(value) => encode_value(value, out),
+        }
+    }
+}
+
+pub fn encode_text(text: &str, out: &mut Vec<u8>) {
+    encode_head(3, text.len() as u64, out);
+    out.extend(text.as_bytes());
+}
+
+pub fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    encode_head(2, bytes.len() as u64, out);
+    out.extend(bytes);
+}
+
+/// Major type and argument in the shortest form that holds `value`.
+pub fn encode_head(major: u8, value: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    if value < 24 {
+        out.push(major | value as u8);
+    } else if value <= u8::MAX as u64 {
+        out.push(major | 24);
+        out.push(value as u8);
+    } else if value <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend((value as u16).to_be_bytes());
+    } else if value <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend((value as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend(value.to_be_bytes());
+    }
+}
+
+fn encode_number(number: &Number, out: &mut Vec<u8>) {
+    if let Some(value) = number.as_u64() {
+        encode_head(0, value, out);
+    } else if let Some(value) = number.as_i64() {
+        encode_head(1, !(value as u64), out);
+    } else {
+        encode_float(number.as_f64().unwrap_or(f64::NAN), out);
+    }
+}
+
+fn encode_float(value: f64, out: &mut Vec<u8>) {
+    if value.is_nan() {
+        out.extend([0xf9, 0x7e, 0x00]);
+        return;
+    }
+    let single = value as f32;
+    if single as f64 != value {
+        out.push(0xfb);
+        out.extend(value.to_be_bytes());
+    } else if let Some(half) = exact_half(single) {
+        out.push(0xf9);
+        out.extend(half.to_be_bytes());
+    } else {
+        out.push(0xfa);
+        out.extend(single.to_be_bytes());
+    }
+}
+
+/// Bits of `value` as an IEEE 754 half, if it can be represented exactly.
+fn exact_half(value: f32) -> Option<u16> {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        // Infinity; NaN is handled by the caller.
+        return Some(sign | 0x7c00);
+    }
+    if exponent == 0 && mantissa == 0 {
+        return Some(sign);
+    }
+    let unbiased = exponent - 127;
+    if (-14..=15).contains(&unbiased) {
+        if mantissa & 0x1fff != 0 {
+            return None;
+        }
+        return Some(sign | (((unbiased + 15) as u16) << 10) | (mantissa >> 13) as u16);
+    }
+    if (-24..-14).contains(&unbiased) {
+        // Subnormal half: the implicit leading bit moves into the mantissa.
+        let full = mantissa | 0x80_0000;
+        let shift = 13 + (-14 - unbiased) as u32;
+        if full & ((1 << shift) - 1) != 0 {
+            return None;
+        }
+        return Some(sign | (full >> shift) as u16);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rfc8949_examples() {
+        let encode = |value: Value| {
+            let mut out = Vec::new();
+            encode_value(&value, &mut out);
+            out
+        };
+        assert_eq!(encode(json!(0)), [0x00]);
+        assert_eq!(encode(json!(24)), [0x18, 0x18]);
+        assert_eq!(encode(json!(1000)), [0x19, 0x03, 0xe8]);
+        assert_eq!(encode(json!(-1000)), [0x39, 0x03, 0xe7]);
+        assert_eq!(encode(json!(1.5)), [0xf9, 0x3e, 0x00]);
+        assert_eq!(encode(json!(100000.0)), [0xfa, 0x47, 0xc3, 0x50, 0x00]);
+        assert_eq!(encode(json!(1.1)), {
+            let mut expected = vec![0xfb];
+            expected.extend(1.1f64.to_be_bytes());
+            expected
+        });
+        assert_eq!(encode(json!(5.960464477539063e-8)), [0xf9, 0x00, 0x01]);
+        assert_eq!(encode(json!("IETF")), [0x64, 0x49, 0x45, 0x54, 0x46]);
+        assert_eq!(
+            encode(json!({"bb": 1, "a": [true, null]})),
+            [0xa2, 0x61, 0x61, 0x82, 0xf5, 0xf6, 0x62, 0x62, 0x62, 0x01]
+        );
+
+        assert_eq!(
+            Item::Tag(24, Box::new(Item::Bytes(b"dIETF".to_vec()))).to_vec(),
+            [0xd8, 0x18, 0x45, 0x64, 0x49, 0x45, 0x54, 0x46]
+        );
+        assert_eq!(
+            Item::Map(vec![
+                (Item::int(-1), Item::Null),
+                (Item::int(10), Item::Null),
+            ])
+            .to_vec(),
+            [0xa2, 0x0a, 0xf6, 0x20, 0xf6]
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::clock::Clock;
+use crate::crypto::{verify_signature, CryptoKeyPair};
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+const CHALLENGE_DOMAIN: &[u8] = b"microcred-rs/issuance-challenge/v1:";
+const POSSESSION_DOMAIN: &[u8] = b"microcred-possession:";
+
+/// Bytes a holder signs to prove possession of their bound key to the
+/// verifier `verifier_id`, for `CredentialVerifier::verify_possession`.
+/// Domain-separated so the signature is only good for that verifier and
+/// cannot be replayed as any other signature by the holder key.
+pub fn possession_message(verifier_id: &str, challenge: &[u8]) -> Vec<u8> {
+    [POSSESSION_DOMAIN, verifier_id.as_bytes(), challenge].concat()
+}
+
+/// Issuer-provided nonce the recipient signs with their holder key before a
+/// bound credential is minted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssuanceChallenge {
+    pub issuer_id: Uuid,
+    pub nonce: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl IssuanceChallenge {
+    pub fn new(issuer_id: Uuid, created_at: DateTime<Utc>, valid_for: Duration) -> Self {
+        let mut nonce = vec![0u8; 32];
+        OsRng.fill_bytes(&mut nonce);
+        Self {
+            issuer_id,
+            nonce,
+            created_at,
+            expires_at: created_at + valid_for,
+        }
+    }
+
+    /// Bytes the holder signs. Domain-separated so the signature cannot be
+    /// replayed as a presentation or possession proof.
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = CHALLENGE_DOMAIN.to_vec();
+        message.extend_from_slice(self.issuer_id.as_bytes());
+        message.extend_from_slice(&self.nonce);
+        message
+    }
+
+    pub fn is_expired(&self, clock: &dyn Clock) -> bool {
+        self.is_expired_at(clock.now())
+    }
+
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        now > self.expires_at
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PossessionProof {
+    pub nonce: Vec<u8>,
+    pub holder_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl PossessionProof {
+    pub fn create(challenge: &IssuanceChallenge, holder: &CryptoKeyPair) -> Self {
+        Self {
+            nonce: challenge.nonce.clone(),
+            holder_key: holder.public_key(),
+            signature: holder.sign(&challenge.message()),
+        }
+    }
+
+    pub fn verify(&self, challenge: &IssuanceChallenge) -> bool {
+        self.nonce == challenge.nonce
+            && verify_signature(&self.holder_key, &challenge.message(), &self.signature)
+                .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ChallengeError {
+    UnknownChallenge,
+    ChallengeExpired,
+    InvalidProof,
+    HolderKeyMismatch,
+}
+
+impl fmt::Display for ChallengeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChallengeError::UnknownChallenge => {
+                write!(f, "Challenge is unknown or has already been used")
+            }
+            ChallengeError::ChallengeExpired => write!(f, "Challenge has expired"),
+            ChallengeError::InvalidProof => write!(f, "Proof of possession is invalid"),
+            ChallengeError::HolderKeyMismatch => {
+                write!(f, "Proof was made with a different key than the requested holder key")
+            }
+        }
+    }
+}
+
+impl Error for ChallengeError {}
@@ -1,5 +1,7 @@
 use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer, Verifier, SECRET_KEY_LENGTH};
 use rand::rngs::OsRng;
+use serde::Serialize;
+use serde_json::Value;
 use sha2::{Digest, Sha256};
 
 pub struct CryptoKeyPair {
@@ -66,7 +68,47 @@ pub fn verify_signature(
 }
 
 pub fn hash_credential(credential_data: &[u8]) -> Vec<u8> {
-    let mut hasher = Sha256::new();
-    hasher.update(credential_data);
+    hash_with::<Sha256>(credential_data)
+}
+
+/// Hashes `data` with any RustCrypto-compatible digest.
+pub fn hash_with<D: Digest>(data: &[u8]) -> Vec<u8> {
+    let mut hasher = D::new();
+    hasher.update(data);
     hasher.finalize().to_vec()
-}
\ No newline at end of file
+}
+
+/// Serializes `value` as JSON with object keys sorted at every level, so the
+/// signed bytes do not depend on struct field order or on which fields a
+/// particular crate version knows about.
+pub fn canonical_json<T: Serialize>(value: &T) -> Result<Vec<u8>, serde_json::Error> {
+    let mut buffer = Vec::new();
+    write_canonical_json(value, &mut buffer)?;
+    Ok(buffer)
+}
+
+/// `canonical_json`, appended to `buffer`.
+pub(crate) fn write_canonical_json<T: Serialize>(
+    value: &T,
+    buffer: &mut Vec<u8>,
+) -> Result<(), serde_json::Error> {
+    let value = serde_json::to_value(value)?;
+    serde_json::to_writer(buffer, &sort_keys(value))
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
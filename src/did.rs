@@ -0,0 +1,241 @@
+//! DID documents that publish keys as `Multikey` verification methods,
+//! so consumers read `publicKeyMultibase` instead of guessing how raw key
+//! bytes were encoded.
+
+use crate::multibase::{self, MultibaseError};
+use crate::proof::key_id;
+use crate::Issuer;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+pub const DID_V1: &str = "https://www.w3.org/ns/did/v1";
+pub const MULTIKEY_V1: &str = "https://w3id.org/security/multikey/v1";
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum DidError {
+    UnsupportedMethod(String),
+    UnsupportedVerificationMethod(String),
+    InvalidKey(MultibaseError),
+}
+
+impl fmt::Display for DidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DidError::UnsupportedMethod(did) => write!(f, "Unsupported DID method: {}", did),
+            DidError::UnsupportedVerificationMethod(kind) => {
+                write!(f, "Unsupported verification method type {}", kind)
+            }
+            DidError::InvalidKey(e) => write!(f, "Invalid public key: {}", e),
+        }
+    }
+}
+
+impl Error for DidError {}
+
+impl From<MultibaseError> for DidError {
+    fn from(e: MultibaseError) -> Self {
+        DidError::InvalidKey(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMethod {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub controller: String,
+    pub public_key_multibase: String,
+}
+
+impl VerificationMethod {
+    pub fn multikey(id: String, controller: String, public_key: &[u8]) -> Self {
+        Self {
+            id,
+            kind: "Multikey".to_string(),
+            controller,
+            public_key_multibase: multibase::encode_multikey(public_key),
+        }
+    }
+
+    /// Raw Ed25519 public key of this method.
+    pub fn public_key(&self) -> Result<Vec<u8>, DidError> {
+        if self.kind != "Multikey" {
+            return Err(DidError::UnsupportedVerificationMethod(self.kind.clone()));
+        }
+        Ok(multibase::decode_multikey(&self.public_key_multibase)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub also_known_as: Vec<String>,
+    pub verification_method: Vec<VerificationMethod>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertion_method: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authentication: Vec<String>,
+}
+
+impl DidDocument {
+    /// Document for `issuer`, identified by the `did:web` DID of its URL
+    /// (with the URL itself in `alsoKnownAs`). Every key in the key history
+    /// is listed as `{did}#{key_id}`, so proofs from retired keys still
+    /// resolve; only unretired keys may make assertions.
+    pub fn for_issuer(issuer: &Issuer) -> Self {
+        let mut keys: Vec<(&[u8], bool)> = issuer
+            .key_history
+            .iter()
+            .map(|key| (key.public_key.as_slice(), key.retired_at.is_none()))
+            .collect();
+        if keys.is_empty() {
+            keys.push((&issuer.public_key, true));
+        }
+
+        let did = did_web(&issuer.url);
+        let mut document = Self::new(did.clone());
+        document.also_known_as.push(issuer.url.clone());
+        for (public_key, active) in keys {
+            let id = format!("{}#{}", did, key_id(public_key));
+            if active {
+                document.assertion_method.push(id.clone());
+            }
+            document
+                .verification_method
+                .push(VerificationMethod::multikey(id, did.clone(), public_key));
+        }
+        document
+    }
+
+    /// Resolves a `did:key` DID, which carries its key in the identifier.
+    pub fn from_did_key(did: &str) -> Result<Self, DidError> {
+        let multikey = did
+            .strip_prefix("did:key:")
+            .ok_or_else(|| DidError::UnsupportedMethod(did.to_string()))?;
+        let public_key = multibase::decode_multikey(multikey)?;
+        let id = format!("{}#{}", did, multikey);
+        let mut document = Self::new(did.to_string());
+        document
+            .verification_method
+            .push(VerificationMethod::multikey(
+                id.clone(),
+                did.to_string(),
+                &public_key,
+            ));
+        document.assertion_method.push(id.clone());
+        document.authentication.push(id);
+        Ok(document)
+    }
+
+    fn new(id: String) -> Self {
+        Self {
+            context: vec![DID_V1.to_string(), MULTIKEY_V1.to_string()],
+            id,
+            also_known_as: Vec::new(),
+            verification_method: Vec::new(),
+            assertion_method: Vec::new(),
+            authentication: Vec::new(),
+        }
+    }
+
+    /// Looks up a verification method by absolute id or `#fragment`.
+    pub fn verification_method(&self, id: &str) -> Option<&VerificationMethod> {
+        let id = match id.strip_prefix('#') {
+            Some(_) => format!("{}{}", self.id, id),
+            None => id.to_string(),
+        };
+        self.verification_method
+            .iter()
+            .find(|method| method.id == id)
+    }
+}
+
+/// `did:key` identifier of an Ed25519 public key, e.g. for holders.
+pub fn did_key(public_key: &[u8]) -> String {
+    format!("did:key:{}", multibase::encode_multikey(public_key))
+}
+
+/// `did:web` identifier of an HTTPS URL: the host (with a port's colon
+/// percent-encoded) followed by the path segments, joined by colons.
+pub fn did_web(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let mut segments = rest.split('/').filter(|segment| !segment.is_empty());
+    let host = segments.next().unwrap_or_default().replace(':', "%3A");
+    std::iter::once(format!("did:web:{}", host))
+        .chain(segments.map(str::to_string))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::CryptoKeyPair;
+    use crate::issuer::CredentialIssuer;
+
+    #[test]
+    fn test_issuer_document_lists_multikeys() {
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let old_key = issuer_service.get_public_key();
+        issuer_service.rotate_key();
+        let issuer = issuer_service.get_issuer_info();
+
+        let document = DidDocument::for_issuer(issuer);
+        assert_eq!(document.verification_method.len(), 2);
+        assert_eq!(
+            document.assertion_method,
+            vec![format!("did:web:test.edu#{}", key_id(&issuer.public_key))]
+        );
+        assert_eq!(document.id, "did:web:test.edu");
+        assert_eq!(document.also_known_as, vec!["https://test.edu".to_string()]);
+        assert_eq!(document.verification_method[0].controller, "did:web:test.edu");
+        let old = document
+            .verification_method(&format!("#{}", key_id(&old_key)))
+            .unwrap();
+        assert!(old.public_key_multibase.starts_with("z6Mk"));
+        assert_eq!(old.public_key().unwrap(), old_key);
+
+        let json = serde_json::to_value(&document).unwrap();
+        assert_eq!(json["verificationMethod"][1]["type"], "Multikey");
+        assert_eq!(json["alsoKnownAs"][0], "https://test.edu");
+        assert!(json.get("authentication").is_none());
+        let parsed: DidDocument = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, document);
+    }
+
+    #[test]
+    fn test_did_web_identifiers() {
+        assert_eq!(did_web("https://test.edu"), "did:web:test.edu");
+        assert_eq!(did_web("https://test.edu/"), "did:web:test.edu");
+        assert_eq!(
+            did_web("https://localhost:8443/issuers/cs?x=1"),
+            "did:web:localhost%3A8443:issuers:cs"
+        );
+    }
+
+    #[test]
+    fn test_did_key_resolves_to_its_key() {
+        let holder = CryptoKeyPair::generate();
+        let did = did_key(&holder.public_key());
+        assert!(did.starts_with("did:key:z6Mk"));
+
+        let document = DidDocument::from_did_key(&did).unwrap();
+        let method = document.verification_method(&document.authentication[0]);
+        assert_eq!(method.unwrap().public_key().unwrap(), holder.public_key());
+        assert!(matches!(
+            DidDocument::from_did_key("did:web:example.com"),
+            Err(DidError::UnsupportedMethod(_))
+        ));
+    }
+}
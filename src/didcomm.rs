@@ -0,0 +1,454 @@
+//! DIDComm v2 messages for the issue-credential and present-proof flows,
+//! with anonymous encryption (`ECDH-ES+A256KW` / `A256GCM` over X25519).
+
+use crate::issuer::CredentialRequest;
+use crate::presentation::Presentation;
+use crate::{Microcredential, Skill};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use aes_kw::KekAes256;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+pub const ISSUE_CREDENTIAL_PROTOCOL: &str = "https://didcomm.org/issue-credential/3.0";
+pub const PRESENT_PROOF_PROTOCOL: &str = "https://didcomm.org/present-proof/3.0";
+pub const PLAINTEXT_MEDIA_TYPE: &str = "application/didcomm-plain+json";
+pub const ENCRYPTED_MEDIA_TYPE: &str = "application/didcomm-encrypted+json";
+
+const KEY_WRAP_ALGORITHM: &str = "ECDH-ES+A256KW";
+const CONTENT_ENCRYPTION: &str = "A256GCM";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MessageKind {
+    OfferCredential,
+    RequestCredential,
+    IssueCredential,
+    RequestPresentation,
+    Presentation,
+    Ack,
+}
+
+impl MessageKind {
+    pub fn type_uri(&self) -> String {
+        match self {
+            MessageKind::OfferCredential => {
+                format!("{}/offer-credential", ISSUE_CREDENTIAL_PROTOCOL)
+            }
+            MessageKind::RequestCredential => {
+                format!("{}/request-credential", ISSUE_CREDENTIAL_PROTOCOL)
+            }
+            MessageKind::IssueCredential => {
+                format!("{}/issue-credential", ISSUE_CREDENTIAL_PROTOCOL)
+            }
+            MessageKind::RequestPresentation => {
+                format!("{}/request-presentation", PRESENT_PROOF_PROTOCOL)
+            }
+            MessageKind::Presentation => format!("{}/presentation", PRESENT_PROOF_PROTOCOL),
+            MessageKind::Ack => format!("{}/ack", ISSUE_CREDENTIAL_PROTOCOL),
+        }
+    }
+
+    pub fn from_type_uri(type_uri: &str) -> Option<Self> {
+        [
+            MessageKind::OfferCredential,
+            MessageKind::RequestCredential,
+            MessageKind::IssueCredential,
+            MessageKind::RequestPresentation,
+            MessageKind::Presentation,
+            MessageKind::Ack,
+        ]
+        .into_iter()
+        .find(|kind| kind.type_uri() == type_uri)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageAttachment {
+    pub id: String,
+    pub media_type: String,
+    pub format: Option<String>,
+    pub data: AttachmentData,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentData {
+    pub json: Value,
+}
+
+/// A plaintext DIDComm v2 message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub typ: String,
+    pub from: Option<String>,
+    pub to: Vec<String>,
+    pub thid: Option<String>,
+    pub created_time: i64,
+    pub body: Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<MessageAttachment>,
+}
+
+impl Message {
+    pub fn new(
+        kind: MessageKind,
+        from: String,
+        to: String,
+        thid: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            type_uri: kind.type_uri(),
+            typ: PLAINTEXT_MEDIA_TYPE.to_string(),
+            from: Some(from),
+            to: vec![to],
+            thid,
+            created_time: now.timestamp(),
+            body: json!({}),
+            attachments: Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> Option<MessageKind> {
+        MessageKind::from_type_uri(&self.type_uri)
+    }
+
+    /// Thread ID: replies carry `thid`, the first message starts the thread.
+    pub fn thread_id(&self) -> &str {
+        self.thid.as_deref().unwrap_or(&self.id)
+    }
+
+    pub fn attach<T: Serialize>(&mut self, format: &str, value: &T) -> Result<(), DidCommError> {
+        let json =
+            serde_json::to_value(value).map_err(|e| DidCommError::Malformed(e.to_string()))?;
+        self.attachments.push(MessageAttachment {
+            id: Uuid::new_v4().to_string(),
+            media_type: "application/json".to_string(),
+            format: Some(format.to_string()),
+            data: AttachmentData { json },
+        });
+        Ok(())
+    }
+
+    pub fn attachment<T: DeserializeOwned>(&self, format: &str) -> Result<T, DidCommError> {
+        let attachment = self
+            .attachments
+            .iter()
+            .find(|attachment| attachment.format.as_deref() == Some(format))
+            .ok_or_else(|| DidCommError::Malformed(format!("missing '{}' attachment", format)))?;
+        serde_json::from_value(attachment.data.json.clone())
+            .map_err(|e| DidCommError::Malformed(e.to_string()))
+    }
+
+    pub fn offer_credential(
+        from: String,
+        to: String,
+        skill: &Skill,
+        now: DateTime<Utc>,
+    ) -> Result<Self, DidCommError> {
+        let mut message = Self::new(MessageKind::OfferCredential, from, to, None, now);
+        message.attach("microcred/skill@v1", skill)?;
+        Ok(message)
+    }
+
+    pub fn request_credential(
+        from: String,
+        to: String,
+        thid: String,
+        request: &CredentialRequest,
+        now: DateTime<Utc>,
+    ) -> Result<Self, DidCommError> {
+        let mut message = Self::new(MessageKind::RequestCredential, from, to, Some(thid), now);
+        message.attach("microcred/credential-request@v1", request)?;
+        Ok(message)
+    }
+
+    pub fn issue_credential(
+        from: String,
+        to: String,
+        thid: String,
+        credential: &Microcredential,
+        now: DateTime<Utc>,
+    ) -> Result<Self, DidCommError> {
+        let mut message = Self::new(MessageKind::IssueCredential, from, to, Some(thid), now);
+        message.attach("microcred/credential@v1", credential)?;
+        Ok(message)
+    }
+
+    pub fn presentation(
+        from: String,
+        to: String,
+        thid: String,
+        presentation: &Presentation,
+        now: DateTime<Utc>,
+    ) -> Result<Self, DidCommError> {
+        let mut message = Self::new(MessageKind::Presentation, from, to, Some(thid), now);
+        message.attach("microcred/presentation@v1", presentation)?;
+        Ok(message)
+    }
+
+    pub fn ack(from: String, to: String, thid: String, now: DateTime<Utc>) -> Self {
+        let mut message = Self::new(MessageKind::Ack, from, to, Some(thid), now);
+        message.body = json!({ "status": "OK" });
+        message
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum DidCommError {
+    Malformed(String),
+    UnsupportedAlgorithm(String),
+    NoMatchingRecipient,
+    DecryptionFailed,
+}
+
+impl fmt::Display for DidCommError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DidCommError::Malformed(msg) => write!(f, "Malformed DIDComm message: {}", msg),
+            DidCommError::UnsupportedAlgorithm(alg) => {
+                write!(f, "Unsupported DIDComm algorithm: {}", alg)
+            }
+            DidCommError::NoMatchingRecipient => {
+                write!(f, "Message is not encrypted for this key")
+            }
+            DidCommError::DecryptionFailed => write!(f, "Message decryption failed"),
+        }
+    }
+}
+
+impl Error for DidCommError {}
+
+/// X25519 key agreement key, identified by its DID URL (`kid`).
+pub struct KeyAgreementKey {
+    pub kid: String,
+    secret: StaticSecret,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipientKey {
+    pub kid: String,
+    pub public_key: [u8; 32],
+}
+
+impl KeyAgreementKey {
+    pub fn generate(kid: String) -> Self {
+        Self {
+            kid,
+            secret: StaticSecret::from(random_bytes::<32>()),
+        }
+    }
+
+    pub fn from_secret(kid: String, secret: [u8; 32]) -> Self {
+        Self {
+            kid,
+            secret: StaticSecret::from(secret),
+        }
+    }
+
+    pub fn recipient(&self) -> RecipientKey {
+        RecipientKey {
+            kid: self.kid.clone(),
+            public_key: PublicKey::from(&self.secret).to_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Jwe {
+    protected: String,
+    recipients: Vec<JweRecipient>,
+    iv: String,
+    ciphertext: String,
+    tag: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JweRecipient {
+    header: JweRecipientHeader,
+    encrypted_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JweRecipientHeader {
+    kid: String,
+}
+
+/// Anonymously encrypts `message` for every recipient (JWE general JSON
+/// serialization).
+pub fn pack_anoncrypt(
+    message: &Message,
+    recipients: &[RecipientKey],
+) -> Result<String, DidCommError> {
+    if recipients.is_empty() {
+        return Err(DidCommError::NoMatchingRecipient);
+    }
+
+    let ephemeral = StaticSecret::from(random_bytes::<32>());
+    let epk = PublicKey::from(&ephemeral);
+    let apv = recipients_digest(recipients.iter().map(|r| r.kid.as_str()));
+
+    let protected = json!({
+        "typ": ENCRYPTED_MEDIA_TYPE,
+        "alg": KEY_WRAP_ALGORITHM,
+        "enc": CONTENT_ENCRYPTION,
+        "apv": URL_SAFE_NO_PAD.encode(&apv),
+        "epk": {
+            "kty": "OKP",
+            "crv": "X25519",
+            "x": URL_SAFE_NO_PAD.encode(epk.as_bytes()),
+        },
+    });
+    let protected = URL_SAFE_NO_PAD.encode(protected.to_string());
+
+    let cek = random_bytes::<32>();
+    let jwe_recipients = recipients
+        .iter()
+        .map(|recipient| {
+            let shared = ephemeral.diffie_hellman(&PublicKey::from(recipient.public_key));
+            let kek = concat_kdf(shared.as_bytes(), &apv);
+            let encrypted_key = KekAes256::new(&kek.into())
+                .wrap_vec(&cek)
+                .map_err(|_| DidCommError::Malformed("key wrap failed".to_string()))?;
+            Ok(JweRecipient {
+                header: JweRecipientHeader {
+                    kid: recipient.kid.clone(),
+                },
+                encrypted_key: URL_SAFE_NO_PAD.encode(encrypted_key),
+            })
+        })
+        .collect::<Result<Vec<_>, DidCommError>>()?;
+
+    let plaintext =
+        serde_json::to_vec(message).map_err(|e| DidCommError::Malformed(e.to_string()))?;
+    let iv = random_bytes::<12>();
+    let mut sealed = Aes256Gcm::new(&cek.into())
+        .encrypt(
+            Nonce::from_slice(&iv),
+            Payload {
+                msg: &plaintext,
+                aad: protected.as_bytes(),
+            },
+        )
+        .map_err(|_| DidCommError::DecryptionFailed)?;
+    let tag = sealed.split_off(sealed.len() - 16);
+
+    let jwe = Jwe {
+        protected,
+        recipients: jwe_recipients,
+        iv: URL_SAFE_NO_PAD.encode(iv),
+        ciphertext: URL_SAFE_NO_PAD.encode(sealed),
+        tag: URL_SAFE_NO_PAD.encode(tag),
+    };
+    serde_json::to_string(&jwe).map_err(|e| DidCommError::Malformed(e.to_string()))
+}
+
+pub fn unpack_anoncrypt(packed: &str, key: &KeyAgreementKey) -> Result<Message, DidCommError> {
+    let jwe: Jwe =
+        serde_json::from_str(packed).map_err(|e| DidCommError::Malformed(e.to_string()))?;
+    let header: Value = serde_json::from_slice(&decode(&jwe.protected)?)
+        .map_err(|e| DidCommError::Malformed(e.to_string()))?;
+
+    for (field, expected) in [("alg", KEY_WRAP_ALGORITHM), ("enc", CONTENT_ENCRYPTION)] {
+        let actual = header[field].as_str().unwrap_or_default();
+        if actual != expected {
+            return Err(DidCommError::UnsupportedAlgorithm(actual.to_string()));
+        }
+    }
+    if header["epk"]["crv"] != "X25519" {
+        return Err(DidCommError::UnsupportedAlgorithm(
+            header["epk"]["crv"].to_string(),
+        ));
+    }
+    let epk: [u8; 32] = decode(header["epk"]["x"].as_str().unwrap_or_default())?
+        .try_into()
+        .map_err(|_| DidCommError::Malformed("invalid epk".to_string()))?;
+    let apv = decode(header["apv"].as_str().unwrap_or_default())?;
+    if apv != recipients_digest(jwe.recipients.iter().map(|r| r.header.kid.as_str())) {
+        return Err(DidCommError::Malformed(
+            "apv does not match recipients".to_string(),
+        ));
+    }
+
+    let recipient = jwe
+        .recipients
+        .iter()
+        .find(|recipient| recipient.header.kid == key.kid)
+        .ok_or(DidCommError::NoMatchingRecipient)?;
+    let shared = key.secret.diffie_hellman(&PublicKey::from(epk));
+    let kek = concat_kdf(shared.as_bytes(), &apv);
+    let cek = KekAes256::new(&kek.into())
+        .unwrap_vec(&decode(&recipient.encrypted_key)?)
+        .map_err(|_| DidCommError::DecryptionFailed)?;
+    if cek.len() != 32 {
+        return Err(DidCommError::DecryptionFailed);
+    }
+
+    let iv = decode(&jwe.iv)?;
+    if iv.len() != 12 {
+        return Err(DidCommError::Malformed("invalid iv".to_string()));
+    }
+    let mut sealed = decode(&jwe.ciphertext)?;
+    sealed.extend_from_slice(&decode(&jwe.tag)?);
+    let plaintext = Aes256Gcm::new_from_slice(&cek)
+        .map_err(|_| DidCommError::DecryptionFailed)?
+        .decrypt(
+            Nonce::from_slice(&iv),
+            Payload {
+                msg: &sealed,
+                aad: jwe.protected.as_bytes(),
+            },
+        )
+        .map_err(|_| DidCommError::DecryptionFailed)?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| DidCommError::Malformed(e.to_string()))
+}
+
+/// `apv` as defined by DIDComm v2: SHA-256 of the sorted recipient kids
+/// joined with `.`.
+fn recipients_digest<'a>(kids: impl Iterator<Item = &'a str>) -> Vec<u8> {
+    let mut kids: Vec<&str> = kids.collect();
+    kids.sort_unstable();
+    Sha256::digest(kids.join(".").as_bytes()).to_vec()
+}
+
+/// Concat KDF from RFC 7518 §4.6.2 for a 256-bit key-wrapping key.
+fn concat_kdf(shared_secret: &[u8], apv: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(1u32.to_be_bytes());
+    hasher.update(shared_secret);
+    for field in [KEY_WRAP_ALGORITHM.as_bytes(), &[], apv] {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field);
+    }
+    hasher.update(256u32.to_be_bytes());
+    hasher.finalize().into()
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, DidCommError> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|e| DidCommError::Malformed(e.to_string()))
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
@@ -0,0 +1,58 @@
+//! Subject lookup against an external directory (LDAP, SCIM, a student
+//! information system), so names and emails in signed credentials come
+//! from the system of record rather than from whoever filled in a form.
+
+use crate::Subject;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DirectoryError {
+    Unavailable(String),
+    InvalidResponse(String),
+}
+
+impl fmt::Display for DirectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DirectoryError::Unavailable(msg) => write!(f, "Subject directory unavailable: {}", msg),
+            DirectoryError::InvalidResponse(msg) => {
+                write!(f, "Invalid subject directory response: {}", msg)
+            }
+        }
+    }
+}
+
+impl Error for DirectoryError {}
+
+/// Looks subjects up by id. Implementations wrap the directory client; see
+/// `CredentialIssuer::set_subject_resolver`.
+pub trait SubjectResolver: Send + Sync {
+    /// The directory entry for `id`, or `None` if there is none.
+    fn resolve(&self, id: &Uuid) -> Result<Option<Subject>, DirectoryError>;
+}
+
+/// Directory held in memory, e.g. loaded from a roster export.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryDirectory {
+    subjects: HashMap<Uuid, Subject>,
+}
+
+impl InMemoryDirectory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, subject: Subject) {
+        self.subjects.insert(subject.id, subject);
+    }
+}
+
+impl SubjectResolver for InMemoryDirectory {
+    fn resolve(&self, id: &Uuid) -> Result<Option<Subject>, DirectoryError> {
+        Ok(self.subjects.get(id).cloned())
+    }
+}
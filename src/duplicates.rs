@@ -0,0 +1,99 @@
+//! Detection of redundant credentials: a subject holding an unexpired
+//! credential for a skill at the same or a higher level than another one.
+
+use crate::Microcredential;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+/// What the issuer does when a request duplicates a credential the subject
+/// already holds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum DuplicatePolicy {
+    /// Issue anyway and report the duplicate as a warning.
+    #[default]
+    Allow,
+    /// Refuse to issue, failing with the `Duplicate` as the error.
+    Block,
+}
+
+/// `credential_id` is made redundant by `existing_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Duplicate {
+    pub credential_id: Uuid,
+    pub existing_id: Uuid,
+    pub subject_id: Uuid,
+    pub skill_id: String,
+}
+
+impl fmt::Display for Duplicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Subject {} already holds credential {} for skill '{}' at the same or a higher level",
+            self.subject_id, self.existing_id, self.skill_id
+        )
+    }
+}
+
+impl Error for Duplicate {}
+
+/// Whether `existing` makes `candidate` redundant at `now`: same issuer,
+/// subject and skill, unexpired, and at the same or a higher level on the
+/// same scale. Skill ids are only meaningful within one issuer's catalog.
+pub fn supersedes(
+    existing: &Microcredential,
+    candidate: &Microcredential,
+    now: DateTime<Utc>,
+) -> bool {
+    existing.id != candidate.id
+        && existing.issuer.id == candidate.issuer.id
+        && existing.subject.id == candidate.subject.id
+        && existing.skill.id == candidate.skill.id
+        && !existing.is_expired_at(now)
+        && existing.skill.level.meets(&candidate.skill.level)
+}
+
+/// Duplicates of `candidate` among `existing`.
+pub fn duplicates_of(
+    candidate: &Microcredential,
+    existing: &[Microcredential],
+    now: DateTime<Utc>,
+) -> Vec<Duplicate> {
+    existing
+        .iter()
+        .filter(|credential| supersedes(credential, candidate, now))
+        .map(|credential| Duplicate {
+            credential_id: candidate.id,
+            existing_id: credential.id,
+            subject_id: candidate.subject.id,
+            skill_id: candidate.skill.id.clone(),
+        })
+        .collect()
+}
+
+/// Every unexpired credential that another one makes redundant, reported
+/// once against the credential that supersedes it. Of two at the same level
+/// the later-issued one is the duplicate.
+pub fn find_duplicates(credentials: &[Microcredential], now: DateTime<Utc>) -> Vec<Duplicate> {
+    let mut duplicates = Vec::new();
+    for candidate in credentials.iter().filter(|c| !c.is_expired_at(now)) {
+        let existing = credentials.iter().find(|existing| {
+            supersedes(existing, candidate, now)
+                && (!candidate.skill.level.meets(&existing.skill.level)
+                    || (existing.issued_at, existing.id) < (candidate.issued_at, candidate.id))
+        });
+        if let Some(existing) = existing {
+            duplicates.push(Duplicate {
+                credential_id: candidate.id,
+                existing_id: existing.id,
+                subject_id: candidate.subject.id,
+                skill_id: candidate.skill.id.clone(),
+            });
+        }
+    }
+    duplicates
+}
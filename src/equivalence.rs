@@ -0,0 +1,186 @@
+//! Equivalence scoring between credentials from different issuers, for
+//! credit-transfer decisions. Skills are matched through the alignments in
+//! each issuer's `SkillCatalog`, levels by their relative position on their
+//! scales.
+
+use crate::catalog::{Alignment, SkillCatalog};
+use crate::levels::LevelScale;
+use crate::{Microcredential, Skill, SkillLevel};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+/// What the skill part of a match was based on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum SkillBasis {
+    /// Both skills have framework alignments in their issuer's catalog.
+    Alignment,
+    /// At least one skill has no alignments; only the names were compared.
+    Name,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquivalenceMatch {
+    /// Overall score from 0 (unrelated) to 1 (equivalent): the skill score,
+    /// scaled by the level score when both scales are known.
+    pub score: f64,
+    pub basis: SkillBasis,
+    pub skill_score: f64,
+    /// Alignments of the presented skill that the target skill shares.
+    pub shared_alignments: Vec<Alignment>,
+    /// 1 when the presented level is at least the target level, otherwise
+    /// reduced by the shortfall. `None` when either level's scale is not
+    /// registered.
+    pub level_score: Option<f64>,
+    /// Whether the presented level is at least the target level.
+    pub meets_level: Option<bool>,
+}
+
+impl EquivalenceMatch {
+    pub fn is_equivalent(&self, threshold: f64) -> bool {
+        self.score >= threshold && self.meets_level != Some(false)
+    }
+}
+
+/// Compares credentials using the skill catalogs of their issuers and the
+/// level scales they use. The default, EQF and Bloom's scales are known
+/// from the start.
+#[derive(Debug, Clone)]
+pub struct EquivalenceEvaluator {
+    catalogs: HashMap<Uuid, SkillCatalog>,
+    scales: HashMap<String, LevelScale>,
+}
+
+impl Default for EquivalenceEvaluator {
+    fn default() -> Self {
+        let mut evaluator = Self {
+            catalogs: HashMap::new(),
+            scales: HashMap::new(),
+        };
+        for scale in [
+            LevelScale::default_scale(),
+            LevelScale::eqf(),
+            LevelScale::blooms(),
+        ] {
+            evaluator.add_scale(scale);
+        }
+        evaluator
+    }
+}
+
+impl EquivalenceEvaluator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the catalog of the issuer with id `issuer_id`.
+    pub fn add_catalog(&mut self, issuer_id: Uuid, catalog: SkillCatalog) {
+        self.catalogs.insert(issuer_id, catalog);
+    }
+
+    pub fn add_scale(&mut self, scale: LevelScale) {
+        self.scales.insert(scale.id.clone(), scale);
+    }
+
+    /// Scores how well `presented` stands in for `target`, e.g. an external
+    /// credential against the one a course would award.
+    pub fn compare(
+        &self,
+        presented: &Microcredential,
+        target: &Microcredential,
+    ) -> EquivalenceMatch {
+        let presented_alignments = self.alignments(presented);
+        let target_alignments = self.alignments(target);
+
+        let (basis, skill_score, shared_alignments) =
+            if presented_alignments.is_empty() || target_alignments.is_empty() {
+                let score = name_similarity(&presented.skill, &target.skill);
+                (SkillBasis::Name, score, Vec::new())
+            } else {
+                let target_keys: BTreeSet<String> =
+                    target_alignments.iter().map(alignment_key).collect();
+                let presented_keys: BTreeSet<String> =
+                    presented_alignments.iter().map(alignment_key).collect();
+                let shared: Vec<Alignment> = presented_alignments
+                    .iter()
+                    .filter(|alignment| target_keys.contains(&alignment_key(alignment)))
+                    .cloned()
+                    .collect();
+                let union = presented_keys.union(&target_keys).count();
+                let score = presented_keys.intersection(&target_keys).count() as f64 / union as f64;
+                (SkillBasis::Alignment, score, shared)
+            };
+
+        let levels = self
+            .position(&presented.skill.level)
+            .zip(self.position(&target.skill.level));
+        let level_score = levels.map(|(presented, target)| 1.0 - (target - presented).max(0.0));
+        let meets_level = levels.map(|(presented, target)| presented >= target);
+
+        EquivalenceMatch {
+            score: skill_score * level_score.unwrap_or(1.0),
+            basis,
+            skill_score,
+            shared_alignments,
+            level_score,
+            meets_level,
+        }
+    }
+
+    /// Alignments of the skill version recorded in the credential's
+    /// `skill_version` metadata, or of the current version for credentials
+    /// issued without one.
+    fn alignments(&self, credential: &Microcredential) -> Vec<Alignment> {
+        let Some(catalog) = self.catalogs.get(&credential.issuer.id) else {
+            return Vec::new();
+        };
+        let definition = match credential.metadata.get("skill_version") {
+            Some(version) => version
+                .parse()
+                .ok()
+                .and_then(|version| catalog.version(&credential.skill.id, version)),
+            None => catalog.get(&credential.skill.id),
+        };
+        definition
+            .map(|definition| definition.alignments.clone())
+            .unwrap_or_default()
+    }
+
+    /// Position of `level` on its scale, from just above 0 to 1 for the top
+    /// level, so levels on scales of different lengths can be compared.
+    fn position(&self, level: &SkillLevel) -> Option<f64> {
+        let scale = self.scales.get(level.scale_id())?;
+        if !scale.contains(level) {
+            return None;
+        }
+        Some(level.rank() as f64 / scale.levels.len() as f64)
+    }
+}
+
+/// Alignments identify the same target by framework and code when there is
+/// one, otherwise by URL.
+fn alignment_key(alignment: &Alignment) -> String {
+    match &alignment.target_code {
+        Some(code) => format!("{}:{}", alignment.framework.to_lowercase(), code),
+        None => alignment.target_url.clone(),
+    }
+}
+
+/// Jaccard similarity of the lowercase words in the two skill names.
+fn name_similarity(a: &Skill, b: &Skill) -> f64 {
+    let words = |skill: &Skill| -> BTreeSet<String> {
+        skill
+            .name
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_lowercase())
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
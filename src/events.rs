@@ -0,0 +1,22 @@
+use crate::verifier::VerificationError;
+use crate::Microcredential;
+use uuid::Uuid;
+
+/// Observer for credential lifecycle events. Every method defaults to a
+/// no-op, so handlers only implement what they care about.
+///
+/// Handlers run synchronously on the calling thread; offload slow work.
+pub trait EventHandler: Send + Sync {
+    fn on_issued(&self, _credential: &Microcredential) {}
+
+    fn on_revoked(&self, _credential_id: &Uuid, _reason: &str) {}
+
+    fn on_renewed(&self, _previous_id: &Uuid, _renewed: &Microcredential) {}
+
+    fn on_verified(
+        &self,
+        _credential: &Microcredential,
+        _result: &Result<bool, VerificationError>,
+    ) {
+    }
+}
@@ -0,0 +1,226 @@
+use crate::validation::ValidationReport;
+use crate::{Evidence, EvidenceType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub const PROCTORED_EXAM: &str = "proctored-exam";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    DateTime,
+}
+
+impl FieldType {
+    fn accepts(&self, value: &Value) -> bool {
+        match self {
+            FieldType::String => value.is_string(),
+            FieldType::Integer => value.is_i64() || value.is_u64(),
+            FieldType::Number => value.is_number(),
+            FieldType::Boolean => value.is_boolean(),
+            FieldType::DateTime => value
+                .as_str()
+                .is_some_and(|s| s.parse::<DateTime<Utc>>().is_ok()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldDefinition {
+    pub name: String,
+    pub field_type: FieldType,
+    pub required: bool,
+}
+
+/// Schema for the typed attributes carried by `EvidenceType::Custom`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceTypeDefinition {
+    pub id: String,
+    pub name: String,
+    pub fields: Vec<FieldDefinition>,
+}
+
+impl EvidenceTypeDefinition {
+    pub fn new(id: String, name: String) -> Self {
+        Self {
+            id,
+            name,
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn field(mut self, name: &str, field_type: FieldType, required: bool) -> Self {
+        self.fields.push(FieldDefinition {
+            name: name.to_string(),
+            field_type,
+            required,
+        });
+        self
+    }
+
+    pub fn proctored_exam() -> Self {
+        Self::new(PROCTORED_EXAM.to_string(), "Proctored exam".to_string())
+            .field("proctor", FieldType::String, true)
+            .field("duration_minutes", FieldType::Integer, true)
+            .field("score", FieldType::Number, false)
+            .field("taken_at", FieldType::DateTime, false)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EvidenceTypeRegistry {
+    definitions: HashMap<String, EvidenceTypeDefinition>,
+}
+
+impl EvidenceTypeRegistry {
+    pub fn new() -> Self {
+        Self {
+            definitions: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, definition: EvidenceTypeDefinition) {
+        self.definitions.insert(definition.id.clone(), definition);
+    }
+
+    pub fn get(&self, type_id: &str) -> Option<&EvidenceTypeDefinition> {
+        self.definitions.get(type_id)
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = &EvidenceTypeDefinition> {
+        self.definitions.values()
+    }
+
+    /// Checks the attributes of `Custom` evidence against the registered
+    /// definition. Built-in evidence types need no definition; legacy
+    /// free-text types are no longer issued.
+    pub fn validate(&self, field: &str, evidence: &Evidence, report: &mut ValidationReport) {
+        #[allow(deprecated)]
+        if let EvidenceType::Other(name) = &evidence.evidence_type {
+            report.add_issue(
+                format!("{}.evidence_type", field),
+                format!("free-text evidence type '{}' must be registered", name),
+            );
+            return;
+        }
+        let EvidenceType::Custom {
+            type_id,
+            attributes,
+        } = &evidence.evidence_type
+        else {
+            return;
+        };
+
+        let Some(definition) = self.get(type_id) else {
+            report.add_issue(
+                format!("{}.evidence_type", field),
+                format!("unknown evidence type '{}'", type_id),
+            );
+            return;
+        };
+
+        for definition_field in &definition.fields {
+            let attribute_field = format!("{}.attributes.{}", field, definition_field.name);
+            match attributes.get(&definition_field.name) {
+                None if definition_field.required => {
+                    report.add_issue(attribute_field, "is required");
+                }
+                Some(value) if !definition_field.field_type.accepts(value) => {
+                    report.add_issue(
+                        attribute_field,
+                        format!("must be of type {:?}", definition_field.field_type),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        for name in attributes.keys() {
+            if !definition.fields.iter().any(|f| &f.name == name) {
+                report.add_issue(
+                    format!("{}.attributes.{}", field, name),
+                    format!("is not defined for evidence type '{}'", type_id),
+                );
+            }
+        }
+    }
+}
+
+impl Default for EvidenceTypeRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(EvidenceTypeDefinition::proctored_exam());
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn exam_evidence(attributes: Value) -> Evidence {
+        Evidence {
+            id: Uuid::new_v4(),
+            name: "Final exam".to_string(),
+            description: "Proctored final exam".to_string(),
+            url: "https://exams.example.com/1".to_string(),
+            evidence_type: EvidenceType::Custom {
+                type_id: PROCTORED_EXAM.to_string(),
+                attributes: attributes.as_object().unwrap().clone(),
+            },
+            unknown_fields: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_proctored_exam_attributes_are_typed() {
+        let registry = EvidenceTypeRegistry::default();
+
+        let mut report = ValidationReport::default();
+        let evidence = exam_evidence(json!({
+            "proctor": "Dr. Smith",
+            "duration_minutes": 90,
+            "score": 87.5
+        }));
+        registry.validate("evidence[0]", &evidence, &mut report);
+        assert!(report.is_valid());
+
+        let mut report = ValidationReport::default();
+        let evidence = exam_evidence(json!({ "duration_minutes": "long", "room": "B12" }));
+        registry.validate("evidence[0]", &evidence, &mut report);
+        let fields: Vec<&str> = report.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "evidence[0].attributes.proctor",
+                "evidence[0].attributes.duration_minutes",
+                "evidence[0].attributes.room",
+            ]
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_legacy_free_text_types_round_trip() {
+        let mut legacy = serde_json::to_value(exam_evidence(json!({}))).unwrap();
+        legacy["evidence_type"] = json!({"Other": "Hackathon"});
+        let evidence: Evidence = serde_json::from_value(legacy.clone()).unwrap();
+        assert!(matches!(
+            &evidence.evidence_type,
+            EvidenceType::Other(name) if name == "Hackathon"
+        ));
+        assert_eq!(serde_json::to_value(&evidence).unwrap(), legacy);
+
+        let mut report = ValidationReport::default();
+        EvidenceTypeRegistry::default().validate("evidence[0]", &evidence, &mut report);
+        assert_eq!(report.issues[0].field, "evidence[0].evidence_type");
+    }
+}
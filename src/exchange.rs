@@ -0,0 +1,223 @@
+use crate::issuer::{CredentialIssuer, CredentialRequest};
+use crate::{Evidence, Microcredential, Skill};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+/// What the issuer offers: the skill, the evidence it will attest and how
+/// long the credential will be valid. `expires_at` is when the offer itself
+/// lapses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialOffer {
+    pub skill: Skill,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<Evidence>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Where an issuance conversation currently stands. Serializable so a
+/// service can persist it between requests and resume later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ExchangeState {
+    OfferSent {
+        offer: CredentialOffer,
+    },
+    RequestReceived {
+        offer: CredentialOffer,
+        request: Box<CredentialRequest>,
+    },
+    CredentialIssued {
+        credential: Box<Microcredential>,
+    },
+    Acknowledged {
+        credential_id: Uuid,
+    },
+    Abandoned {
+        reason: String,
+    },
+}
+
+impl ExchangeState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExchangeState::OfferSent { .. } => "offer_sent",
+            ExchangeState::RequestReceived { .. } => "request_received",
+            ExchangeState::CredentialIssued { .. } => "credential_issued",
+            ExchangeState::Acknowledged { .. } => "acknowledged",
+            ExchangeState::Abandoned { .. } => "abandoned",
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            ExchangeState::Acknowledged { .. } | ExchangeState::Abandoned { .. }
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ExchangeError {
+    InvalidTransition {
+        state: &'static str,
+        action: &'static str,
+    },
+    OfferExpired,
+    SkillMismatch,
+    IssuanceFailed(String),
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExchangeError::InvalidTransition { state, action } => {
+                write!(f, "Cannot {} while exchange is {}", action, state)
+            }
+            ExchangeError::OfferExpired => write!(f, "Credential offer has expired"),
+            ExchangeError::SkillMismatch => {
+                write!(f, "Request does not match the offered skill")
+            }
+            ExchangeError::IssuanceFailed(msg) => write!(f, "Issuance failed: {}", msg),
+        }
+    }
+}
+
+impl Error for ExchangeError {}
+
+/// Issuer side of the offer → request → issuance → ack exchange. Times
+/// come from the issuer's clock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuanceExchange {
+    pub thread_id: Uuid,
+    pub state: ExchangeState,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IssuanceExchange {
+    pub fn offer(
+        issuer: &CredentialIssuer,
+        skill: Skill,
+        evidence: Vec<Evidence>,
+        credential_expires_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let now = issuer.now();
+        Self {
+            thread_id: Uuid::new_v4(),
+            state: ExchangeState::OfferSent {
+                offer: CredentialOffer {
+                    skill,
+                    evidence,
+                    credential_expires_at,
+                    created_at: now,
+                    expires_at,
+                },
+            },
+            updated_at: now,
+        }
+    }
+
+    pub fn receive_request(
+        &mut self,
+        issuer: &CredentialIssuer,
+        request: CredentialRequest,
+    ) -> Result<(), ExchangeError> {
+        let now = issuer.now();
+        let ExchangeState::OfferSent { offer } = &self.state else {
+            return Err(self.invalid("receive a request"));
+        };
+        if offer.expires_at.is_some_and(|expires_at| now > expires_at) {
+            return Err(ExchangeError::OfferExpired);
+        }
+        if offer.skill.id != request.skill.id {
+            return Err(ExchangeError::SkillMismatch);
+        }
+
+        let offer = offer.clone();
+        self.transition(
+            ExchangeState::RequestReceived {
+                offer,
+                request: Box::new(request),
+            },
+            now,
+        );
+        Ok(())
+    }
+
+    /// Issues the offered skill, evidence and expiry to the requesting
+    /// subject. Only the subject and holder key come from the request; the
+    /// holder does not get to choose the level, evidence, validity or
+    /// metadata.
+    pub fn issue(&mut self, issuer: &CredentialIssuer) -> Result<&Microcredential, ExchangeError> {
+        let ExchangeState::RequestReceived { offer, request } = &self.state else {
+            return Err(self.invalid("issue"));
+        };
+        let request = CredentialRequest {
+            subject: request.subject.clone(),
+            skill: offer.skill.clone(),
+            evidence: offer.evidence.clone(),
+            valid_from: None,
+            expires_at: offer.credential_expires_at,
+            metadata: HashMap::new(),
+            attachments: Vec::new(),
+            holder_key: request.holder_key.clone(),
+            actor: None,
+        };
+        let credential = issuer
+            .issue_request(request)
+            .map_err(|e| ExchangeError::IssuanceFailed(e.to_string()))?;
+
+        self.transition(
+            ExchangeState::CredentialIssued {
+                credential: Box::new(credential),
+            },
+            issuer.now(),
+        );
+        match &self.state {
+            ExchangeState::CredentialIssued { credential } => Ok(credential),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn acknowledge(&mut self, issuer: &CredentialIssuer) -> Result<(), ExchangeError> {
+        let ExchangeState::CredentialIssued { credential } = &self.state else {
+            return Err(self.invalid("acknowledge"));
+        };
+        let credential_id = credential.id;
+        self.transition(ExchangeState::Acknowledged { credential_id }, issuer.now());
+        Ok(())
+    }
+
+    pub fn abandon(
+        &mut self,
+        issuer: &CredentialIssuer,
+        reason: String,
+    ) -> Result<(), ExchangeError> {
+        if self.state.is_terminal() {
+            return Err(self.invalid("abandon"));
+        }
+        self.transition(ExchangeState::Abandoned { reason }, issuer.now());
+        Ok(())
+    }
+
+    fn transition(&mut self, state: ExchangeState, at: DateTime<Utc>) {
+        self.state = state;
+        self.updated_at = at;
+    }
+
+    fn invalid(&self, action: &'static str) -> ExchangeError {
+        ExchangeError::InvalidTransition {
+            state: self.state.name(),
+            action,
+        }
+    }
+}
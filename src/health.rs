@@ -0,0 +1,142 @@
+//! Readiness self-checks for issuer and verifier services, for
+//! orchestrators that gate traffic on them. See
+//! `CredentialIssuer::health` and `CredentialVerifier::health`.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum HealthStatus {
+    Healthy,
+    /// Working, but needs attention soon, e.g. stale revocation data.
+    Degraded,
+    /// Cannot serve requests.
+    Unhealthy,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub status: HealthStatus,
+    pub message: String,
+}
+
+impl HealthCheck {
+    pub fn new(name: &'static str, status: HealthStatus, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// When checks start reporting `Degraded`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthThresholds {
+    /// Warn this long before a signing certificate expires.
+    pub key_expiry_warning: Duration,
+    /// Revocation lists older than this are stale.
+    pub max_status_list_age: Duration,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            key_expiry_warning: Duration::days(30),
+            max_status_list_age: Duration::hours(24),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReport {
+    pub checked_at: DateTime<Utc>,
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    /// The worst status of any check.
+    pub fn status(&self) -> HealthStatus {
+        self.checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(HealthStatus::Healthy)
+    }
+
+    /// Whether the service can take traffic: nothing is `Unhealthy`.
+    pub fn is_ready(&self) -> bool {
+        self.status() != HealthStatus::Unhealthy
+    }
+
+    pub fn check(&self, name: &str) -> Option<&HealthCheck> {
+        self.checks.iter().find(|check| check.name == name)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut json = serde_json::to_value(self).expect("health report serializes");
+        json["status"] = serde_json::to_value(self.status()).expect("status serializes");
+        json
+    }
+}
+
+/// Check of a certificate or key that stops being usable at `not_after`.
+pub(crate) fn expiry_check(
+    name: &'static str,
+    not_after: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    warning: Duration,
+) -> HealthCheck {
+    match not_after {
+        Some(not_after) if not_after < now => HealthCheck::new(
+            name,
+            HealthStatus::Unhealthy,
+            format!("expired at {}", not_after.to_rfc3339()),
+        ),
+        Some(not_after) if not_after < now + warning => HealthCheck::new(
+            name,
+            HealthStatus::Degraded,
+            format!("expires at {}", not_after.to_rfc3339()),
+        ),
+        Some(not_after) => HealthCheck::new(
+            name,
+            HealthStatus::Healthy,
+            format!("valid until {}", not_after.to_rfc3339()),
+        ),
+        None => HealthCheck::new(name, HealthStatus::Healthy, "does not expire"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_status_is_the_worst_check() {
+        let now = Utc::now();
+        let warning = Duration::days(30);
+        let mut report = HealthReport {
+            checked_at: now,
+            checks: vec![
+                expiry_check("a", None, now, warning),
+                expiry_check("b", Some(now + Duration::days(7)), now, warning),
+            ],
+        };
+        assert_eq!(report.status(), HealthStatus::Degraded);
+        assert!(report.is_ready());
+
+        report.checks.push(expiry_check(
+            "c",
+            Some(now - Duration::days(1)),
+            now,
+            warning,
+        ));
+        assert_eq!(report.status(), HealthStatus::Unhealthy);
+        assert!(!report.is_ready());
+        assert_eq!(report.to_json()["status"], "unhealthy");
+        assert_eq!(report.to_json()["checks"][2]["name"], "c");
+    }
+}
@@ -0,0 +1,193 @@
+use crate::crypto::{canonical_json, hash_credential, verify_signature};
+use crate::{Issuer, Microcredential};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+/// Longest chain (in certificates) considered during path construction.
+pub const MAX_PATH_LENGTH: usize = 8;
+
+/// Limits a certificate places on its subject and everything below it.
+/// Empty lists mean unrestricted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IssuerConstraints {
+    /// Hosts (and their subdomains) the subject issuers' URLs must live in.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permitted_domains: Vec<String>,
+    /// Skill id prefixes the subject issuers may certify, e.g. `"cs."`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_skill_domains: Vec<String>,
+}
+
+impl IssuerConstraints {
+    pub fn permits_issuer(&self, issuer: &Issuer) -> bool {
+        let host = url_host(&issuer.url);
+        self.permitted_domains.is_empty()
+            || self
+                .permitted_domains
+                .iter()
+                .any(|domain| host == domain.as_str() || host.ends_with(&format!(".{}", domain)))
+    }
+
+    pub fn permits_skill(&self, skill_id: &str) -> bool {
+        self.allowed_skill_domains.is_empty()
+            || self
+                .allowed_skill_domains
+                .iter()
+                .any(|prefix| skill_id.starts_with(prefix.as_str()))
+    }
+}
+
+/// Statement by `issuer_id` that `subject` (and its current key) may issue
+/// credentials, optionally as an intermediate authority.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuerCertificate {
+    pub id: Uuid,
+    pub subject: Issuer,
+    pub issuer_id: Uuid,
+    pub is_ca: bool,
+    /// For authorities: how many further intermediates may follow.
+    pub path_len: Option<u32>,
+    pub constraints: IssuerConstraints,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub signature: Option<Vec<u8>>,
+}
+
+impl IssuerCertificate {
+    pub fn signing_payload(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        canonical_json(&unsigned)
+    }
+
+    pub fn is_valid_at(&self, at: DateTime<Utc>) -> bool {
+        self.not_before <= at && at <= self.not_after
+    }
+
+    pub fn verify(&self, signer_key: &[u8]) -> bool {
+        let Some(signature) = &self.signature else {
+            return false;
+        };
+        let Ok(payload) = self.signing_payload() else {
+            return false;
+        };
+        verify_signature(signer_key, &hash_credential(&payload), signature).unwrap_or(false)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum PathError {
+    NoPath,
+    ConstraintViolation(String),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathError::NoPath => write!(f, "No certification path to a trusted root"),
+            PathError::ConstraintViolation(msg) => {
+                write!(f, "Certificate constraint violated: {}", msg)
+            }
+        }
+    }
+}
+
+impl Error for PathError {}
+
+/// Builds a chain of valid certificates from `issuer_id` up to one of the
+/// pinned `roots`, leaf first. Validity is evaluated at `at`.
+pub fn build_path<'a>(
+    issuer_id: Uuid,
+    at: DateTime<Utc>,
+    roots: &[Issuer],
+    certificates: &'a [IssuerCertificate],
+) -> Result<Vec<&'a IssuerCertificate>, PathError> {
+    let mut path = Vec::new();
+    if extend_path(&mut path, issuer_id, at, roots, certificates) {
+        Ok(path)
+    } else {
+        Err(PathError::NoPath)
+    }
+}
+
+fn extend_path<'a>(
+    path: &mut Vec<&'a IssuerCertificate>,
+    subject_id: Uuid,
+    at: DateTime<Utc>,
+    roots: &[Issuer],
+    certificates: &'a [IssuerCertificate],
+) -> bool {
+    if path.len() >= MAX_PATH_LENGTH {
+        return false;
+    }
+
+    for candidate in certificates {
+        if candidate.subject.id != subject_id
+            || !candidate.is_valid_at(at)
+            || path.iter().any(|cert| cert.id == candidate.id)
+        {
+            continue;
+        }
+        if let Some(child) = path.last() {
+            // Certificates above the leaf must be authorities allowed to
+            // have this many intermediates below them.
+            let below = path.len() as u32 - 1;
+            if !candidate.is_ca || candidate.path_len.is_some_and(|max| below > max) {
+                continue;
+            }
+            if !child.verify(&candidate.subject.public_key) {
+                continue;
+            }
+        }
+
+        path.push(candidate);
+        let anchored = roots.iter().any(|root| {
+            root.id == candidate.issuer_id
+                && root
+                    .key_at(candidate.not_before)
+                    .is_some_and(|key| candidate.verify(key))
+        });
+        if anchored || extend_path(path, candidate.issuer_id, at, roots, certificates) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Applies every certificate's constraints on the path to the credential
+/// and to the issuers below it.
+pub fn check_constraints(
+    path: &[&IssuerCertificate],
+    credential: &Microcredential,
+) -> Result<(), PathError> {
+    for (index, certificate) in path.iter().enumerate() {
+        let constraints = &certificate.constraints;
+        if !constraints.permits_skill(&credential.skill.id) {
+            return Err(PathError::ConstraintViolation(format!(
+                "skill {} is outside the domains certified by {}",
+                credential.skill.id, certificate.id
+            )));
+        }
+        if let Some(subordinate) = path[..=index]
+            .iter()
+            .find(|cert| !constraints.permits_issuer(&cert.subject))
+        {
+            return Err(PathError::ConstraintViolation(format!(
+                "{} is outside the permitted domains of {}",
+                subordinate.subject.url, certificate.id
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    authority.split(':').next().unwrap_or("")
+}
@@ -0,0 +1,177 @@
+use crate::crypto::{canonical_json, hash_credential};
+use crate::Microcredential;
+use chrono::{DateTime, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Source of credential (and evidence) ids. Generators own both how the
+/// 128 id bits are laid out and how ids are written as text; the bits
+/// themselves stay in `Uuid` fields, since signed payloads and stores
+/// already key on that form.
+///
+/// `Uuid` is used here as a 128-bit value rather than as a UUID: ULIDs and
+/// other 128-bit schemes fit it unchanged, and `format_id`/`parse_id` give
+/// them their own text form at the edges (listings, URLs, logs). A generic
+/// id type would change the signed payload and the key of every store,
+/// status list and audit record, so ids wider or narrower than 128 bits are
+/// not supported.
+pub trait IdGenerator: Send + Sync {
+    /// `now` is the issuer's clock, for generators that embed the time.
+    fn next_id(&self, now: DateTime<Utc>) -> Uuid;
+
+    fn credential_id(&self, credential: &Microcredential) -> Result<Uuid, serde_json::Error> {
+        Ok(self.next_id(credential.issued_at))
+    }
+
+    /// Text form of an id for listings, URLs and logs.
+    fn format_id(&self, id: &Uuid) -> String {
+        id.hyphenated().to_string()
+    }
+
+    /// Parses the text form written by `format_id`.
+    fn parse_id(&self, text: &str) -> Option<Uuid> {
+        Uuid::parse_str(text).ok()
+    }
+}
+
+/// Built-in id generators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum IdStrategy {
+    /// Random UUIDv4.
+    #[default]
+    Random,
+    /// UUIDv5 over a hash of the credential content, so issuing the same
+    /// content twice yields the same id and duplicates are caught by id.
+    ContentDerived { namespace: Uuid },
+    /// ULID: 48-bit millisecond timestamp followed by 80 random bits, so ids
+    /// sort by creation time. Use `ulid_string` for the canonical text form.
+    Ulid,
+}
+
+impl IdStrategy {
+    /// Content-derived ids in a namespace owned by the issuer's URL.
+    pub fn content_derived(issuer_url: &str) -> Self {
+        IdStrategy::ContentDerived {
+            namespace: Uuid::new_v5(&Uuid::NAMESPACE_URL, issuer_url.as_bytes()),
+        }
+    }
+}
+
+impl IdGenerator for IdStrategy {
+    fn next_id(&self, now: DateTime<Utc>) -> Uuid {
+        match self {
+            IdStrategy::Ulid => new_ulid(now),
+            _ => Uuid::new_v4(),
+        }
+    }
+
+    fn credential_id(&self, credential: &Microcredential) -> Result<Uuid, serde_json::Error> {
+        match self {
+            IdStrategy::ContentDerived { namespace } => {
+                Ok(Uuid::new_v5(namespace, &content_hash(credential)?))
+            }
+            _ => Ok(self.next_id(credential.issued_at)),
+        }
+    }
+
+    fn format_id(&self, id: &Uuid) -> String {
+        match self {
+            IdStrategy::Ulid => ulid_string(id),
+            _ => id.hyphenated().to_string(),
+        }
+    }
+
+    /// ULID strategies also accept the hyphenated form, which ids issued
+    /// before switching strategy use.
+    fn parse_id(&self, text: &str) -> Option<Uuid> {
+        match self {
+            IdStrategy::Ulid => parse_ulid(text).or_else(|| Uuid::parse_str(text).ok()),
+            _ => Uuid::parse_str(text).ok(),
+        }
+    }
+}
+
+pub fn new_ulid(now: DateTime<Utc>) -> Uuid {
+    let millis = now.timestamp_millis().max(0) as u128 & ((1 << 48) - 1);
+    let mut random = [0u8; 10];
+    OsRng.fill_bytes(&mut random);
+    let random = random
+        .iter()
+        .fold(0u128, |acc, byte| (acc << 8) | *byte as u128);
+    Uuid::from_u128((millis << 80) | random)
+}
+
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// 26-character Crockford base32 form of a ULID.
+pub fn ulid_string(id: &Uuid) -> String {
+    let value = id.as_u128();
+    (0..26)
+        .rev()
+        .map(|index| CROCKFORD[((value >> (index * 5)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+pub fn parse_ulid(text: &str) -> Option<Uuid> {
+    if text.len() != 26 || text.as_bytes()[0] > b'7' {
+        return None;
+    }
+    let mut value = 0u128;
+    for byte in text.bytes() {
+        let digit = CROCKFORD
+            .iter()
+            .position(|c| *c == byte.to_ascii_uppercase())?;
+        value = (value << 5) | digit as u128;
+    }
+    Some(Uuid::from_u128(value))
+}
+
+/// SHA-256 of the canonical credential content. The id, issuance time,
+/// proof, signature and issuer key material are left out so that re-running an
+/// issuance (or rotating keys in between) does not change the hash.
+pub fn content_hash(credential: &Microcredential) -> Result<Vec<u8>, serde_json::Error> {
+    let mut value = serde_json::to_value(credential)?;
+    if let Value::Object(object) = &mut value {
+        for key in ["id", "issued_at", "proof", "signature"] {
+            object.remove(key);
+        }
+        object.insert(
+            "issuer".to_string(),
+            Value::String(credential.issuer.id.to_string()),
+        );
+    }
+    Ok(hash_credential(&canonical_json(&value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ulids_sort_by_creation_time() {
+        let start = Utc::now();
+        let ids: Vec<Uuid> = (0..3)
+            .map(|index| IdStrategy::Ulid.next_id(start + chrono::Duration::milliseconds(index)))
+            .collect();
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(sorted, ids);
+
+        let text = ulid_string(&ids[0]);
+        assert_eq!(text.len(), 26);
+        assert_eq!(parse_ulid(&text), Some(ids[0]));
+        assert_eq!(parse_ulid(&text.to_lowercase()), Some(ids[0]));
+        assert_eq!(parse_ulid("8ZZZZZZZZZZZZZZZZZZZZZZZZZ"), None);
+
+        assert_eq!(IdStrategy::Ulid.format_id(&ids[0]), text);
+        assert_eq!(IdStrategy::Ulid.parse_id(&text), Some(ids[0]));
+        let random = IdStrategy::Random.next_id(start);
+        let hyphenated = IdStrategy::Random.format_id(&random);
+        assert_eq!(hyphenated, random.to_string());
+        assert_eq!(IdStrategy::Ulid.parse_id(&hyphenated), Some(random));
+        assert_eq!(IdStrategy::Random.parse_id(&text), None);
+    }
+}
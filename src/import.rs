@@ -0,0 +1,551 @@
+//! Importers for badge exports from other platforms, for institutions
+//! migrating their historical badges.
+//!
+//! Imported credentials are unsigned: the source platform's signature
+//! does not cover this crate's payload, and the original issuer key is
+//! unknown. Ids are derived from the source ids, so importing the same
+//! export twice yields the same credentials. The source of each credential
+//! is recorded in its metadata (`imported_from`, `source_id`, ...).
+//!
+//! Badges revoked on the source platform are imported too, with the reason
+//! in `source_revoked`; `revocation_lists` turns them into status lists.
+
+use crate::status::StatusList;
+use crate::{Evidence, EvidenceType, Issuer, Microcredential, Skill, SkillLevel, Subject};
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ImportError {
+    InvalidJson(String),
+    MissingField { path: String },
+    InvalidDate { path: String, value: String },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::InvalidJson(msg) => write!(f, "Invalid JSON: {}", msg),
+            ImportError::MissingField { path } => write!(f, "Missing field: {}", path),
+            ImportError::InvalidDate { path, value } => {
+                write!(f, "Invalid timestamp at {}: {}", path, value)
+            }
+        }
+    }
+}
+
+impl Error for ImportError {}
+
+/// Converts a Credly badge export: a single issued badge, or the
+/// `{"data": [...]}` page returned by the Credly API. Skill levels are
+/// taken from the badge template; unrecognised levels map to `Beginner`
+/// and are kept in the `source_level` metadata entry.
+pub fn import_credly(json: &str) -> Result<Vec<Microcredential>, ImportError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| ImportError::InvalidJson(e.to_string()))?;
+    let badges = match value.get("data") {
+        Some(Value::Array(badges)) => badges.clone(),
+        _ => vec![value],
+    };
+    badges
+        .iter()
+        .enumerate()
+        .map(|(index, badge)| credly_badge(&Fields::new(badge, format!("[{}].", index))?))
+        .collect()
+}
+
+/// Converts a Badgr backpack or issuer assertion export: a single
+/// assertion, or the `{"result": [...]}` envelope of the Badgr v2 API.
+/// The assertions must have been exported with `badgeclass` and `issuer`
+/// expanded.
+pub fn import_badgr(json: &str) -> Result<Vec<Microcredential>, ImportError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| ImportError::InvalidJson(e.to_string()))?;
+    let assertions = match value.get("result") {
+        Some(Value::Array(assertions)) => assertions.clone(),
+        _ => vec![value],
+    };
+    assertions
+        .iter()
+        .enumerate()
+        .map(|(index, assertion)| {
+            badgr_assertion(&Fields::new(assertion, format!("[{}].", index))?)
+        })
+        .collect()
+}
+
+/// Status lists revoking every imported credential that was revoked on its
+/// source platform, one per issuer, for `CredentialVerifier::update_status_list`.
+pub fn revocation_lists(credentials: &[Microcredential], now: DateTime<Utc>) -> Vec<StatusList> {
+    let mut lists = BTreeMap::new();
+    for credential in credentials {
+        let Some(reason) = credential.metadata.get("source_revoked") else {
+            continue;
+        };
+        let list = lists
+            .entry(credential.issuer.id)
+            .or_insert_with(|| StatusList::new(credential.issuer.id, now));
+        // A badge listed twice in an export is already revoked the second time.
+        let _ = list.revoke(credential.id, reason.clone(), now);
+    }
+    lists.into_values().collect()
+}
+
+fn credly_badge(badge: &Fields) -> Result<Microcredential, ImportError> {
+    let source_id = badge.string("id")?;
+    let template = badge.object("badge_template")?;
+    let organization = credly_issuer(badge)?;
+
+    let issuer = imported_issuer(
+        &organization.string("name")?,
+        &organization.optional_string("url").unwrap_or_default(),
+        &organization.string("id")?,
+    );
+    let email = badge.optional_string("recipient_email").unwrap_or_default();
+    let id = source_uuid("credly", &source_id);
+    let subject = imported_subject(&badge.string("issued_to")?, &email, &id);
+    let source_level = template.optional_string("level");
+    let skill = Skill {
+        id: template.string("id")?,
+        name: template.string("name")?,
+        description: template.optional_string("description").unwrap_or_default(),
+        level: source_level
+            .as_deref()
+            .and_then(level_from_label)
+            .unwrap_or(SkillLevel::Beginner),
+        unknown_fields: Map::new(),
+    };
+
+    let evidence = badge
+        .optional_array("evidence")
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let url = item.get("url").and_then(Value::as_str);
+            imported_evidence(
+                &id,
+                index,
+                text(item, "title").unwrap_or_else(|| "Evidence".to_string()),
+                text(item, "description").unwrap_or_default(),
+                url.unwrap_or_default().to_string(),
+                // URL evidence links to work; id evidence to a record.
+                if url.is_some() {
+                    EvidenceType::Portfolio
+                } else {
+                    EvidenceType::Assessment
+                },
+            )
+        })
+        .collect();
+
+    let mut metadata = source_metadata("credly", &source_id);
+    if let Some(url) = template.optional_string("url") {
+        metadata.insert("source_url".to_string(), url);
+    }
+    if let Some(image_url) = badge
+        .optional_string("image_url")
+        .or_else(|| template.optional_string("image_url"))
+    {
+        metadata.insert("source_image".to_string(), image_url);
+    }
+    if let Some(level) = source_level.filter(|level| level_from_label(level).is_none()) {
+        metadata.insert("source_level".to_string(), level);
+    }
+    if badge.optional_string("state").as_deref() == Some("revoked") {
+        let reason = badge.optional_string("revocation_reason");
+        metadata.insert(
+            "source_revoked".to_string(),
+            revocation_reason("Credly", reason),
+        );
+    }
+
+    Ok(Microcredential {
+        id,
+        issued_at: badge.date("issued_at")?,
+        expires_at: badge.optional_date("expires_at")?,
+        metadata,
+        ..Microcredential::new(issuer, subject, skill, evidence, None)
+    })
+}
+
+/// The primary issuing organization of a Credly badge.
+fn credly_issuer<'a>(badge: &Fields<'a>) -> Result<Fields<'a>, ImportError> {
+    let issuer = badge.object("issuer")?;
+    let entities = issuer.optional_array("entities");
+    let primary = entities
+        .iter()
+        .find(|entity| entity.get("primary").and_then(Value::as_bool) == Some(true))
+        .or_else(|| entities.first())
+        .and_then(|entity| entity.get("entity"))
+        .ok_or_else(|| issuer.missing("entities[].entity"))?;
+    Fields::new(primary, format!("{}entities[].entity.", issuer.prefix))
+}
+
+fn badgr_assertion(assertion: &Fields) -> Result<Microcredential, ImportError> {
+    let source_id = assertion.string("entityId")?;
+    let badge_class = assertion.object("badgeclass")?;
+    let organization = assertion.object("issuer")?;
+    let recipient = assertion.object("recipient")?;
+
+    let issuer = imported_issuer(
+        &organization.string("name")?,
+        &organization.optional_string("url").unwrap_or_default(),
+        &organization.string("entityId")?,
+    );
+    // Hashed recipient identities cannot be recovered; the plaintext
+    // identity is only present in the recipient's own backpack export.
+    let email = recipient
+        .optional_string("plaintextIdentity")
+        .or_else(|| {
+            let hashed = recipient.optional_bool("hashed");
+            (!hashed).then(|| recipient.optional_string("identity"))?
+        })
+        .unwrap_or_default();
+    let name = recipient
+        .optional_string("name")
+        .unwrap_or_else(|| email.clone());
+    let id = source_uuid("badgr", &source_id);
+    let subject = imported_subject(&name, &email, &id);
+    let skill = Skill {
+        id: badge_class.string("entityId")?,
+        name: badge_class.string("name")?,
+        description: badge_class
+            .optional_string("description")
+            .unwrap_or_default(),
+        level: SkillLevel::Beginner,
+        unknown_fields: Map::new(),
+    };
+
+    let mut evidence: Vec<Evidence> = assertion
+        .optional_array("evidence")
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            imported_evidence(
+                &id,
+                index,
+                "Evidence".to_string(),
+                text(item, "narrative").unwrap_or_default(),
+                text(item, "url").unwrap_or_default(),
+                EvidenceType::Portfolio,
+            )
+        })
+        .collect();
+    if let Some(narrative) = assertion.optional_string("narrative") {
+        let index = evidence.len();
+        evidence.push(imported_evidence(
+            &id,
+            index,
+            "Narrative".to_string(),
+            narrative,
+            String::new(),
+            EvidenceType::Assessment,
+        ));
+    }
+
+    let mut metadata = source_metadata("badgr", &source_id);
+    if let Some(url) = assertion.optional_string("openBadgeId") {
+        metadata.insert("source_url".to_string(), url);
+    }
+    if let Some(image) = assertion.optional_string("image") {
+        metadata.insert("source_image".to_string(), image);
+    }
+    if let Some(criteria) = badge_class.optional_string("criteriaUrl") {
+        metadata.insert("criteria_url".to_string(), criteria);
+    }
+    if assertion.optional_bool("revoked") {
+        let reason = assertion.optional_string("revocationReason");
+        metadata.insert(
+            "source_revoked".to_string(),
+            revocation_reason("Badgr", reason),
+        );
+    }
+
+    Ok(Microcredential {
+        id,
+        issued_at: assertion.date("issuedOn")?,
+        expires_at: assertion.optional_date("expires")?,
+        metadata,
+        ..Microcredential::new(issuer, subject, skill, evidence, None)
+    })
+}
+
+/// Issuer without a public key: the original issuer key is not part of
+/// the export.
+fn imported_issuer(name: &str, url: &str, source_id: &str) -> Issuer {
+    let key = if url.is_empty() { source_id } else { url };
+    Issuer {
+        id: Uuid::new_v5(&Uuid::NAMESPACE_URL, key.as_bytes()),
+        name: name.to_string(),
+        url: url.to_string(),
+        public_key: Default::default(),
+        key_history: Vec::new(),
+        unknown_fields: Map::new(),
+    }
+}
+
+/// Subjects are identified by email across imports; without one, each
+/// credential gets its own subject.
+fn imported_subject(name: &str, email: &str, credential_id: &Uuid) -> Subject {
+    let key = format!("mailto:{}", email.to_lowercase());
+    Subject {
+        id: if email.is_empty() {
+            Uuid::new_v5(credential_id, b"subject")
+        } else {
+            Uuid::new_v5(&Uuid::NAMESPACE_URL, key.as_bytes())
+        },
+        name: name.to_string(),
+        email: email.to_string(),
+        unknown_fields: Map::new(),
+    }
+}
+
+fn imported_evidence(
+    credential_id: &Uuid,
+    index: usize,
+    name: String,
+    description: String,
+    url: String,
+    evidence_type: EvidenceType,
+) -> Evidence {
+    Evidence {
+        id: Uuid::new_v5(credential_id, index.to_string().as_bytes()),
+        name,
+        description,
+        url,
+        evidence_type,
+        unknown_fields: Map::new(),
+    }
+}
+
+fn source_uuid(platform: &str, source_id: &str) -> Uuid {
+    let key = format!("urn:{}:{}", platform, source_id);
+    Uuid::new_v5(&Uuid::NAMESPACE_URL, key.as_bytes())
+}
+
+fn source_metadata(platform: &str, source_id: &str) -> HashMap<String, String> {
+    HashMap::from([
+        ("imported_from".to_string(), platform.to_string()),
+        ("source_id".to_string(), source_id.to_string()),
+    ])
+}
+
+fn revocation_reason(platform: &str, reason: Option<String>) -> String {
+    reason.unwrap_or_else(|| format!("Revoked on {}", platform))
+}
+
+fn level_from_label(label: &str) -> Option<SkillLevel> {
+    match label.to_lowercase().as_str() {
+        "beginner" | "foundational" | "introductory" => Some(SkillLevel::Beginner),
+        "intermediate" => Some(SkillLevel::Intermediate),
+        "advanced" => Some(SkillLevel::Advanced),
+        "expert" => Some(SkillLevel::Expert),
+        _ => None,
+    }
+}
+
+fn text(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+/// A JSON object of the export, with the path prefix used in errors.
+struct Fields<'a> {
+    object: &'a Map<String, Value>,
+    prefix: String,
+}
+
+impl<'a> Fields<'a> {
+    fn new(value: &'a Value, prefix: String) -> Result<Self, ImportError> {
+        match value.as_object() {
+            Some(object) => Ok(Self { object, prefix }),
+            None => Err(ImportError::MissingField {
+                path: prefix.trim_end_matches('.').to_string(),
+            }),
+        }
+    }
+
+    fn missing(&self, key: &str) -> ImportError {
+        ImportError::MissingField {
+            path: format!("{}{}", self.prefix, key),
+        }
+    }
+
+    fn string(&self, key: &str) -> Result<String, ImportError> {
+        self.optional_string(key).ok_or_else(|| self.missing(key))
+    }
+
+    fn optional_string(&self, key: &str) -> Option<String> {
+        match self.object.get(key) {
+            Some(Value::String(text)) if !text.is_empty() => Some(text.clone()),
+            Some(Value::Number(number)) => Some(number.to_string()),
+            _ => None,
+        }
+    }
+
+    fn optional_bool(&self, key: &str) -> bool {
+        self.object
+            .get(key)
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    fn optional_array(&self, key: &str) -> &'a [Value] {
+        match self.object.get(key) {
+            Some(Value::Array(items)) => items,
+            _ => &[],
+        }
+    }
+
+    fn object(&self, key: &str) -> Result<Fields<'a>, ImportError> {
+        let value = self.object.get(key).ok_or_else(|| self.missing(key))?;
+        Fields::new(value, format!("{}{}.", self.prefix, key))
+    }
+
+    fn date(&self, key: &str) -> Result<DateTime<Utc>, ImportError> {
+        self.optional_date(key)?.ok_or_else(|| self.missing(key))
+    }
+
+    fn optional_date(&self, key: &str) -> Result<Option<DateTime<Utc>>, ImportError> {
+        let Some(value) = self.optional_string(key) else {
+            return Ok(None);
+        };
+        DateTime::parse_from_rfc3339(&value)
+            .map(|date| Some(date.with_timezone(&Utc)))
+            .map_err(|_| ImportError::InvalidDate {
+                path: format!("{}{}", self.prefix, key),
+                value,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::CredentialStatus;
+
+    #[test]
+    fn test_import_credly_page() {
+        let json = r#"{"data": [{
+            "id": "8f3c2b1a-0000-4000-8000-000000000001",
+            "issued_at": "2023-05-01T12:00:00.000Z",
+            "expires_at": null,
+            "issued_to": "Alice Smith",
+            "recipient_email": "Alice@Example.com",
+            "image_url": "https://images.credly.com/badge.png",
+            "badge_template": {
+                "id": "tmpl-1",
+                "name": "Cloud Practitioner",
+                "description": "Cloud fundamentals",
+                "level": "Foundational",
+                "url": "https://www.credly.com/org/acme/badge/cloud"
+            },
+            "issuer": {"entities": [
+                {"label": "Authorized by", "primary": false,
+                 "entity": {"id": "org-2", "name": "Partner"}},
+                {"label": "Issued by", "primary": true,
+                 "entity": {"id": "org-1", "name": "Acme", "url": "https://acme.example"}}
+            ]},
+            "evidence": [
+                {"type": "UrlEvidence", "title": "Lab", "url": "https://acme.example/lab"},
+                {"type": "IdEvidence", "title": "Exam", "description": "Exam 123"}
+            ]
+        }]}"#;
+        let credentials = import_credly(json).unwrap();
+        assert_eq!(credentials.len(), 1);
+        let credential = &credentials[0];
+        assert_eq!(credential.issuer.name, "Acme");
+        assert_eq!(credential.subject.email, "Alice@Example.com");
+        assert_eq!(credential.skill.level, SkillLevel::Beginner);
+        assert!(credential.signature.is_none());
+        assert!(matches!(
+            credential.evidence[1].evidence_type,
+            EvidenceType::Assessment
+        ));
+        assert_eq!(credential.metadata["imported_from"], "credly");
+        assert!(!credential.metadata.contains_key("source_level"));
+        assert_eq!(credential.id, import_credly(json).unwrap()[0].id);
+        assert!(revocation_lists(&credentials, Utc::now()).is_empty());
+
+        let revoked = json.replace(
+            r#""expires_at": null,"#,
+            r#""expires_at": null, "state": "revoked", "revocation_reason": "Fraud","#,
+        );
+        let credentials = import_credly(&revoked).unwrap();
+        assert_eq!(credentials[0].metadata["source_revoked"], "Fraud");
+        assert_eq!(revocation_lists(&credentials, Utc::now()).len(), 1);
+
+        let missing = json.replace(r#""issued_to": "Alice Smith","#, "");
+        assert_eq!(
+            import_credly(&missing).unwrap_err(),
+            ImportError::MissingField {
+                path: "[0].issued_to".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_import_badgr_assertions() {
+        let json = r#"{"status": {"success": true}, "result": [{
+            "entityId": "abc123",
+            "openBadgeId": "https://api.badgr.io/public/assertions/abc123",
+            "issuedOn": "2021-09-10T08:30:00Z",
+            "expires": "2024-09-10T08:30:00Z",
+            "revoked": false,
+            "recipient": {"identity": "sha256$deadbeef", "hashed": true,
+                          "plaintextIdentity": "bob@example.com"},
+            "narrative": "Built a compiler",
+            "evidence": [{"url": "https://github.com/bob/compiler", "narrative": "Source"}],
+            "badgeclass": {"entityId": "bc1", "name": "Compilers",
+                           "description": "Compiler construction",
+                           "criteriaUrl": "https://uni.example/criteria"},
+            "issuer": {"entityId": "iss1", "name": "Uni", "url": "https://uni.example"}
+        }, {
+            "entityId": "def456",
+            "issuedOn": "2021-09-10T08:30:00Z",
+            "revoked": true,
+            "recipient": {"identity": "carol@example.com", "hashed": false},
+            "badgeclass": {"entityId": "bc1", "name": "Compilers"},
+            "issuer": {"entityId": "iss1", "name": "Uni", "url": "https://uni.example"}
+        }]}"#;
+        let credentials = import_badgr(json).unwrap();
+        assert_eq!(credentials.len(), 2);
+        let credential = &credentials[0];
+        assert_eq!(credential.subject.email, "bob@example.com");
+        assert_eq!(credential.skill.name, "Compilers");
+        assert_eq!(credential.evidence.len(), 2);
+        assert_eq!(credential.evidence[1].description, "Built a compiler");
+        assert!(credential.expires_at.is_some());
+        assert_eq!(
+            credential.metadata["criteria_url"],
+            "https://uni.example/criteria"
+        );
+
+        assert!(!credential.metadata.contains_key("source_revoked"));
+        assert_eq!(
+            credentials[1].metadata["source_revoked"],
+            "Revoked on Badgr"
+        );
+        let lists = revocation_lists(&credentials, Utc::now());
+        assert_eq!(lists.len(), 1);
+        assert!(matches!(
+            lists[0].status_of(&credentials[1].id),
+            CredentialStatus::Revoked { .. }
+        ));
+        assert_eq!(lists[0].status_of(&credential.id), CredentialStatus::Active);
+
+        let bad_date = json.replace("2021-09-10T08:30:00Z", "10/09/2021");
+        assert!(matches!(
+            import_badgr(&bad_date),
+            Err(ImportError::InvalidDate { path, .. }) if path == "[0].issuedOn"
+        ));
+    }
+}
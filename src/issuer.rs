@@ -1,27 +1,202 @@
+use crate::attachment::Attachment;
+use crate::audit::{AuditAction, AuditError, AuditLog};
+use crate::blockcerts::{self, BlockcertsBatch};
+use crate::bulk::{BulkFailure, BulkOptions, BulkOutcome};
+use crate::challenge::{ChallengeError, IssuanceChallenge, PossessionProof};
+use crate::clock::{Clock, SystemClock};
 use crate::crypto::{hash_credential, CryptoKeyPair};
-use crate::{Evidence, Issuer, Microcredential, Skill, Subject};
-use chrono::{DateTime, Utc};
-use serde_json;
+use crate::did;
+use crate::directory::SubjectResolver;
+use crate::duplicates::{self, Duplicate, DuplicatePolicy};
+use crate::events::EventHandler;
+use crate::health::{self, HealthCheck, HealthReport, HealthStatus, HealthThresholds};
+use crate::hierarchy::{IssuerCertificate, IssuerConstraints};
+use crate::ids::{IdGenerator, IdStrategy};
+use crate::ld::context::{ContextLoader, OfflineContextLoader};
+use crate::ld::{self, LdProofOptions, LdSuite};
+use crate::manifest::CredentialManifest;
+use crate::mdoc::MdocDocument;
+use crate::multibase;
+use crate::openbadges::{BadgeHosting, HostedBadge};
+use crate::preview::{self, Preview, PreviewFormat, PreviewTemplate};
+use crate::profile::{Accreditation, IssuerEndpoint, IssuerProfile};
+use crate::proof::{key_id, HashAlgorithm, PayloadEncoding, Proof, ProofPurpose, SignatureSuite};
+use crate::salting;
+use crate::signer::Signer;
+use crate::status::{CredentialStatus, StatusError, StatusList};
+use crate::store::{BatchError, CredentialStore, StoreError};
+use crate::timestamps::TimestampPolicy;
+use crate::validation::{ValidationReport, ValidationRules};
+use crate::verifier::{Checks, CredentialVerifier};
+use crate::x509::Certificate;
+use crate::{Evidence, HolderBinding, Issuer, IssuerKey, Microcredential, Skill, Subject};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialRequest {
+    pub subject: Subject,
+    pub skill: Skill,
+    pub evidence: Vec<Evidence>,
+    #[serde(default)]
+    pub valid_from: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    /// Holder public key to bind the credential to (`cnf`), as a multikey
+    /// string or raw key bytes.
+    #[serde(default, deserialize_with = "multibase::deserialize_optional_key")]
+    pub holder_key: Option<Vec<u8>>,
+    /// Operator or service asking for the credential, recorded in the audit
+    /// log. Lifecycle approvals set it to the approver.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+}
+
+impl CredentialRequest {
+    /// A request for a fresh copy of `credential` expiring at `expires_at`,
+    /// for `CredentialIssuer::renew_request`.
+    pub fn renewing(credential: &Microcredential, expires_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            subject: credential.subject.clone(),
+            skill: credential.skill.clone(),
+            evidence: credential.evidence.clone(),
+            valid_from: None,
+            expires_at,
+            metadata: credential.metadata.clone(),
+            attachments: credential.attachments.clone(),
+            holder_key: credential
+                .holder_binding
+                .as_ref()
+                .map(|binding| binding.public_key.to_vec()),
+            actor: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReissuanceRequest {
+    pub original_credential_id: Uuid,
+    pub subject: Subject,
+    pub skill: Skill,
+    pub evidence: Vec<Evidence>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub holder_binding: Option<HolderBinding>,
+    /// Operator or service carrying out the re-issuance, for the audit log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompromiseReport {
+    pub compromised_key: Vec<u8>,
+    pub compromised_at: DateTime<Utc>,
+    pub revoked: Vec<Uuid>,
+    pub reissuance_queue: Vec<ReissuanceRequest>,
+}
+
+/// Issuance time and proof template computed once per signing run.
+struct SigningEnvelope {
+    issued_at: DateTime<Utc>,
+    proof: Proof,
+}
 
 pub struct CredentialIssuer {
     issuer_info: Issuer,
-    keypair: CryptoKeyPair,
+    signer: Box<dyn Signer>,
+    status_list: StatusList,
+    validation_rules: ValidationRules,
+    endpoints: Vec<IssuerEndpoint>,
+    accreditations: Vec<Accreditation>,
+    pending_challenges: HashMap<Vec<u8>, IssuanceChallenge>,
+    event_handlers: Vec<Arc<dyn EventHandler>>,
+    subject_resolver: Option<Arc<dyn SubjectResolver>>,
+    duplicate_policy: DuplicatePolicy,
+    duplicate_store: Option<Arc<Mutex<dyn CredentialStore + Send>>>,
+    digest_salting: bool,
+    timestamp_policy: TimestampPolicy,
+    id_generator: Arc<dyn IdGenerator>,
+    clock: Arc<dyn Clock>,
+    hash_algorithm: HashAlgorithm,
+    payload_encoding: PayloadEncoding,
+    context_loader: Arc<dyn ContextLoader>,
+    export_contexts: Vec<String>,
+    certificate_chain: Vec<Certificate>,
+    blockchain_addresses: Vec<String>,
+    audit_log: Option<Arc<AuditLog>>,
 }
 
 impl CredentialIssuer {
     pub fn new(name: String, url: String) -> Self {
-        let keypair = CryptoKeyPair::generate();
+        Self::with_signer(name, url, Box::new(CryptoKeyPair::generate()))
+    }
+
+    /// Creates an issuer whose signing key is held by `signer`, e.g. a
+    /// remote KMS, instead of in process memory.
+    pub fn with_signer(name: String, url: String, signer: Box<dyn Signer>) -> Self {
+        Self::with_clock(name, url, signer, Arc::new(SystemClock))
+    }
+
+    /// Like `with_signer`, with the initial key activated at `clock`'s
+    /// current time rather than the system's, so simulated clocks set in
+    /// the past still find the key active.
+    pub fn with_clock(
+        name: String,
+        url: String,
+        signer: Box<dyn Signer>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
         let issuer_info = Issuer {
-            id: uuid::Uuid::new_v4(),
+            id: Uuid::new_v4(),
             name,
             url,
-            public_key: keypair.public_key(),
+            public_key: signer.public_key().into(),
+            key_history: vec![IssuerKey {
+                public_key: signer.public_key().into(),
+                activated_at: TimestampPolicy::default().normalize(clock.now()),
+                retired_at: None,
+                endorsement: None,
+            }],
+            unknown_fields: Default::default(),
         };
 
+        let status_list = StatusList::new(issuer_info.id, clock.now());
+
         Self {
             issuer_info,
-            keypair,
+            signer,
+            status_list,
+            validation_rules: ValidationRules::default(),
+            endpoints: Vec::new(),
+            accreditations: Vec::new(),
+            pending_challenges: HashMap::new(),
+            event_handlers: Vec::new(),
+            subject_resolver: None,
+            duplicate_policy: DuplicatePolicy::default(),
+            duplicate_store: None,
+            digest_salting: false,
+            timestamp_policy: TimestampPolicy::default(),
+            id_generator: Arc::new(IdStrategy::default()),
+            clock,
+            hash_algorithm: HashAlgorithm::default(),
+            payload_encoding: PayloadEncoding::default(),
+            context_loader: Arc::new(OfflineContextLoader::new()),
+            export_contexts: Vec::new(),
+            certificate_chain: Vec::new(),
+            blockchain_addresses: Vec::new(),
+            audit_log: None,
         }
     }
 
@@ -29,10 +204,45 @@ impl CredentialIssuer {
         issuer_info: Issuer,
         secret_key: &[u8],
     ) -> Result<Self, Box<dyn Error>> {
-        let keypair = CryptoKeyPair::from_secret_key(secret_key)?;
+        let signer = Box::new(CryptoKeyPair::from_secret_key(secret_key)?);
+        Self::from_existing_signer(issuer_info, signer)
+    }
+
+    /// Like `from_existing`, for an issuer whose key is held by `signer`,
+    /// e.g. a remote KMS. Fails if `signer` does not hold the issuer's
+    /// current key.
+    pub fn from_existing_signer(
+        issuer_info: Issuer,
+        signer: Box<dyn Signer>,
+    ) -> Result<Self, Box<dyn Error>> {
+        if issuer_info.public_key != signer.public_key() {
+            return Err("Signer does not hold the issuer's current key".into());
+        }
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let status_list = StatusList::new(issuer_info.id, clock.now());
         Ok(Self {
             issuer_info,
-            keypair,
+            signer,
+            status_list,
+            validation_rules: ValidationRules::default(),
+            endpoints: Vec::new(),
+            accreditations: Vec::new(),
+            pending_challenges: HashMap::new(),
+            event_handlers: Vec::new(),
+            subject_resolver: None,
+            duplicate_policy: DuplicatePolicy::default(),
+            duplicate_store: None,
+            digest_salting: false,
+            timestamp_policy: TimestampPolicy::default(),
+            id_generator: Arc::new(IdStrategy::default()),
+            clock,
+            hash_algorithm: HashAlgorithm::default(),
+            payload_encoding: PayloadEncoding::default(),
+            context_loader: Arc::new(OfflineContextLoader::new()),
+            export_contexts: Vec::new(),
+            certificate_chain: Vec::new(),
+            blockchain_addresses: Vec::new(),
+            audit_log: None,
         })
     }
 
@@ -43,32 +253,1141 @@ impl CredentialIssuer {
         evidence: Vec<Evidence>,
         expires_at: Option<DateTime<Utc>>,
     ) -> Result<Microcredential, Box<dyn Error>> {
-        let mut credential = Microcredential::new(
-            self.issuer_info.clone(),
+        self.issue_request(CredentialRequest {
             subject,
             skill,
             evidence,
+            valid_from: None,
             expires_at,
+            metadata: HashMap::new(),
+            attachments: Vec::new(),
+            holder_key: None,
+            actor: None,
+        })
+    }
+
+    /// Validates the request against the issuer's rules and signs it. A
+    /// failed validation is returned as a `ValidationReport` error.
+    pub fn issue_request(
+        &self,
+        request: CredentialRequest,
+    ) -> Result<Microcredential, Box<dyn Error>> {
+        let actor = request.actor.clone();
+        let credential = self.sign_request(request)?;
+        self.audit(&credential, AuditAction::Issued, actor.as_deref())?;
+        self.notify(|handler| handler.on_issued(&credential));
+        Ok(credential)
+    }
+
+    /// Like `issue_request`, but first looks in `store` for credentials the
+    /// subject already holds for the same skill at the same or a higher
+    /// level. These are returned alongside the credential, or fail issuance
+    /// under `DuplicatePolicy::Block`.
+    pub fn issue_checked(
+        &self,
+        request: CredentialRequest,
+        store: &dyn CredentialStore,
+    ) -> Result<(Microcredential, Vec<Duplicate>), Box<dyn Error>> {
+        let actor = request.actor.clone();
+        let mut credential = self.build_checked(request, None)?;
+        let duplicates = self.check_duplicates(&credential, store, None)?;
+        self.sign_credential(&mut credential)?;
+        self.audit(&credential, AuditAction::Issued, actor.as_deref())?;
+        self.notify(|handler| handler.on_issued(&credential));
+        Ok((credential, duplicates))
+    }
+
+    fn sign_request(&self, request: CredentialRequest) -> Result<Microcredential, Box<dyn Error>> {
+        let mut credential = self.build_checked(request, None)?;
+        self.sign_credential(&mut credential)?;
+        Ok(credential)
+    }
+
+    /// Renders what `request` would issue, without signing, storing or
+    /// recording it, for review before approval. Invalid requests fail with
+    /// the same `ValidationReport` as issuance.
+    pub fn preview_request(
+        &self,
+        request: &CredentialRequest,
+        template: &PreviewTemplate,
+        format: PreviewFormat,
+    ) -> Result<Preview, Box<dyn Error>> {
+        let mut credential = self.build_credential(request.clone())?;
+        credential.issued_at = self.clock.now();
+        credential.normalize_timestamps(&self.timestamp_policy);
+        Ok(preview::render(&credential, template, format)?)
+    }
+
+    /// Validates `request` and builds the unsigned credential.
+    fn build_credential(
+        &self,
+        mut request: CredentialRequest,
+    ) -> Result<Microcredential, Box<dyn Error>> {
+        let rules = &self.validation_rules;
+        let mut report = self.resolve_subject(&mut request.subject)?;
+        let rules_report = rules.validate(&request.subject, &request.skill, &request.evidence);
+        report.issues.extend(rules_report.issues);
+        rules.attachments.validate(&request.attachments, &mut report);
+        if request.holder_key.as_ref().is_some_and(|key| key.len() != 32) {
+            report.add_issue("holder_key", "must be a 32-byte Ed25519 public key");
+        }
+        if let (Some(valid_from), Some(expires_at)) = (request.valid_from, request.expires_at) {
+            if valid_from >= expires_at {
+                report.add_issue("valid_from", "must be before expires_at");
+            }
+        }
+        report.into_result()?;
+        let skill_version = rules
+            .skill_catalog
+            .as_ref()
+            .and_then(|catalog| catalog.get(&request.skill.id))
+            .map(|definition| definition.version);
+
+        let mut credential = Microcredential::new(
+            self.issuer_info.clone(),
+            request.subject,
+            request.skill,
+            request.evidence,
+            request.expires_at,
         );
+        credential.valid_from = request.valid_from;
+        credential.metadata = request.metadata;
+        if let Some(version) = skill_version {
+            credential
+                .metadata
+                .insert("skill_version".to_string(), version.to_string());
+        }
+        credential.attachments = request.attachments;
+        credential.holder_binding = request
+            .holder_key
+            .map(|public_key| HolderBinding {
+                public_key: public_key.into(),
+            });
+        Ok(credential)
+    }
+
+    /// Like `build_credential`, then checks the credential against the
+    /// duplicate store, if one is set. `replaces` is a credential the new
+    /// one supersedes, which does not count as a duplicate.
+    fn build_checked(
+        &self,
+        request: CredentialRequest,
+        replaces: Option<&Uuid>,
+    ) -> Result<Microcredential, Box<dyn Error>> {
+        let credential = self.build_credential(request)?;
+        if let Some(store) = &self.duplicate_store {
+            let store = store.lock().unwrap_or_else(|e| e.into_inner());
+            self.check_duplicates(&credential, &*store, replaces)?;
+        }
+        Ok(credential)
+    }
+
+    /// Credentials in `store` that `credential` duplicates, or the first of
+    /// them as an error under `DuplicatePolicy::Block`.
+    fn check_duplicates(
+        &self,
+        credential: &Microcredential,
+        store: &dyn CredentialStore,
+        replaces: Option<&Uuid>,
+    ) -> Result<Vec<Duplicate>, Box<dyn Error>> {
+        let mut held = self.held_by(store, &credential.subject.id)?;
+        held.retain(|held| Some(&held.id) != replaces);
+        let duplicates = duplicates::duplicates_of(credential, &held, self.clock.now());
+        if let (DuplicatePolicy::Block, Some(duplicate)) =
+            (self.duplicate_policy, duplicates.first())
+        {
+            return Err(Box::new(duplicate.clone()));
+        }
+        Ok(duplicates)
+    }
 
-        let credential_json = serde_json::to_vec(&credential)?;
-        let credential_hash = hash_credential(&credential_json);
-        let signature = self.keypair.sign(&credential_hash);
+    /// Issues a whole cohort: every request is validated and signed before
+    /// anything is written, then the credentials are stored all-or-nothing.
+    /// Under `DuplicatePolicy::Block` a request duplicating a stored
+    /// credential, or an earlier request in the batch, fails the batch.
+    pub fn issue_batch(
+        &self,
+        requests: Vec<CredentialRequest>,
+        store: &mut dyn CredentialStore,
+    ) -> Result<Vec<Microcredential>, BatchError> {
+        let actors: Vec<Option<String>> =
+            requests.iter().map(|request| request.actor.clone()).collect();
+        let credentials = requests
+            .into_iter()
+            .enumerate()
+            .map(|(index, request)| {
+                self.sign_request(request).map_err(|e| BatchError::Issuance {
+                    index,
+                    message: e.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if self.duplicate_policy == DuplicatePolicy::Block {
+            let mut held = Vec::new();
+            let mut subjects = HashSet::new();
+            for credential in &credentials {
+                if subjects.insert(credential.subject.id) {
+                    held.extend(self.held_by(store, &credential.subject.id)?);
+                }
+            }
+            let now = self.clock.now();
+            for (index, credential) in credentials.iter().enumerate() {
+                if let Some(duplicate) = duplicates::duplicates_of(credential, &held, now).first() {
+                    return Err(BatchError::Issuance {
+                        index,
+                        message: duplicate.to_string(),
+                    });
+                }
+                held.push(credential.clone());
+            }
+        }
+        self.store_issued(&credentials, &actors, store)?;
+        Ok(credentials)
+    }
+
+    /// Stores the credentials of a bulk run all-or-nothing, then records
+    /// them in the audit log and notifies event handlers. Returns them.
+    pub fn store_bulk(
+        &self,
+        outcome: BulkOutcome,
+        store: &mut dyn CredentialStore,
+    ) -> Result<Vec<Microcredential>, BatchError> {
+        let actors: Vec<Option<String>> = outcome
+            .credentials
+            .iter()
+            .map(|credential| outcome.actors.get(&credential.id).cloned())
+            .collect();
+        self.store_issued(&outcome.credentials, &actors, store)?;
+        Ok(outcome.credentials)
+    }
+
+    /// Issuance only counts once the credentials are stored, so that is
+    /// when they are audited and announced.
+    fn store_issued(
+        &self,
+        credentials: &[Microcredential],
+        actors: &[Option<String>],
+        store: &mut dyn CredentialStore,
+    ) -> Result<(), BatchError> {
+        store.insert_all(credentials)?;
+        for (credential, actor) in credentials.iter().zip(actors) {
+            self.audit(credential, AuditAction::Issued, actor.as_deref())
+                .map_err(BatchError::Audit)?;
+            self.notify(|handler| handler.on_issued(credential));
+        }
+        Ok(())
+    }
+
+    /// Validates and signs a large number of requests, e.g. a whole
+    /// enrollment intake, on `options.threads` workers. The issuance time
+    /// and proof are computed once per chunk, so a long run does not stamp
+    /// its last credentials with the time it started, and each worker
+    /// reuses one serialization buffer. Invalid requests, and under
+    /// `DuplicatePolicy::Block` requests repeating an earlier one in the
+    /// run, are reported by index without stopping the run. Nothing is
+    /// stored, audited or announced yet: pass the outcome to `store_bulk`.
+    pub fn issue_bulk(
+        &self,
+        requests: Vec<CredentialRequest>,
+        options: &BulkOptions,
+    ) -> BulkOutcome {
+        let actors: Vec<Option<String>> =
+            requests.iter().map(|request| request.actor.clone()).collect();
+        let chunk_size = options.chunk_size.max(1);
+        let mut chunks = Vec::new();
+        let mut requests = requests.into_iter();
+        let mut start = 0;
+        loop {
+            let chunk: Vec<_> = requests.by_ref().take(chunk_size).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            let len = chunk.len();
+            chunks.push((start, chunk));
+            start += len;
+        }
+
+        let results: Vec<_> = if options.threads <= 1 || chunks.len() <= 1 {
+            let mut buffer = Vec::new();
+            chunks
+                .into_iter()
+                .flat_map(|(start, chunk)| self.sign_chunk(start, chunk, &mut buffer))
+                .collect()
+        } else {
+            let queue = Mutex::new(chunks.into_iter());
+            let mut signed = thread::scope(|scope| {
+                let workers: Vec<_> = (0..options.threads)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            let mut buffer = Vec::new();
+                            let mut signed = Vec::new();
+                            loop {
+                                let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                                let Some((start, chunk)) = next else {
+                                    break signed;
+                                };
+                                let results = self.sign_chunk(start, chunk, &mut buffer);
+                                signed.push((start, results));
+                            }
+                        })
+                    })
+                    .collect();
+                workers
+                    .into_iter()
+                    .flat_map(|worker| worker.join().expect("bulk signing worker panicked"))
+                    .collect::<Vec<_>>()
+            });
+            signed.sort_by_key(|(start, _)| *start);
+            signed.into_iter().flat_map(|(_, results)| results).collect()
+        };
+
+        let now = self.clock.now();
+        let mut outcome = BulkOutcome::default();
+        for ((index, result), actor) in results.into_iter().enumerate().zip(actors) {
+            let checked = result.and_then(|credential| {
+                if self.duplicate_policy != DuplicatePolicy::Block {
+                    return Ok(credential);
+                }
+                match duplicates::duplicates_of(&credential, &outcome.credentials, now).first() {
+                    Some(duplicate) => Err(BulkFailure {
+                        index,
+                        message: duplicate.to_string(),
+                    }),
+                    None => Ok(credential),
+                }
+            });
+            match checked {
+                Ok(credential) => {
+                    if let Some(actor) = actor {
+                        outcome.actors.insert(credential.id, actor);
+                    }
+                    outcome.credentials.push(credential);
+                }
+                Err(failure) => outcome.failures.push(failure),
+            }
+        }
+        outcome
+    }
+
+    /// Starts the proof-of-possession handshake. The challenge is single use
+    /// and valid for ten minutes.
+    pub fn create_issuance_challenge(&mut self) -> IssuanceChallenge {
+        let now = self.clock.now();
+        self.pending_challenges
+            .retain(|_, challenge| !challenge.is_expired_at(now));
+        let challenge = IssuanceChallenge::new(self.issuer_info.id, now, Duration::minutes(10));
+        self.pending_challenges
+            .insert(challenge.nonce.clone(), challenge.clone());
+        challenge
+    }
+
+    /// Issues a credential bound to the key that signed the challenge.
+    pub fn issue_with_possession_proof(
+        &mut self,
+        mut request: CredentialRequest,
+        proof: &PossessionProof,
+    ) -> Result<Microcredential, Box<dyn Error>> {
+        let challenge = self
+            .pending_challenges
+            .remove(&proof.nonce)
+            .ok_or(ChallengeError::UnknownChallenge)?;
+        if challenge.is_expired_at(self.clock.now()) {
+            return Err(ChallengeError::ChallengeExpired.into());
+        }
+        if !proof.verify(&challenge) {
+            return Err(ChallengeError::InvalidProof.into());
+        }
+        if request
+            .holder_key
+            .as_ref()
+            .is_some_and(|key| *key != proof.holder_key)
+        {
+            return Err(ChallengeError::HolderKeyMismatch.into());
+        }
+
+        request.holder_key = Some(proof.holder_key.clone());
+        self.issue_request(request)
+    }
+
+    pub fn set_validation_rules(&mut self, rules: ValidationRules) {
+        self.validation_rules = rules;
+    }
+
+    pub fn set_id_strategy(&mut self, strategy: IdStrategy) {
+        self.id_generator = Arc::new(strategy);
+    }
+
+    /// Time source for issuance timestamps, challenges and key rotation.
+    /// The initial key was activated by the clock given at construction;
+    /// use `with_clock` to inject one from the start.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Digest used for new credentials, recorded in their `proof`.
+    pub fn set_hash_algorithm(&mut self, algorithm: HashAlgorithm) {
+        self.hash_algorithm = algorithm;
+    }
+
+    /// Encoding of the bytes signed for new credentials, recorded in their
+    /// `proof`.
+    pub fn set_payload_encoding(&mut self, encoding: PayloadEncoding) {
+        self.payload_encoding = encoding;
+    }
+
+    /// Gives every credential signed from now on a fresh random salt, so
+    /// `salting::salted_digest` can be published without exposing it to
+    /// dictionary attacks. Renewed and reissued credentials get new salts.
+    pub fn set_digest_salting(&mut self, enabled: bool) {
+        self.digest_salting = enabled;
+    }
+
+    /// Precision that timestamps are truncated to before signing, second
+    /// precision by default. Finer precisions only survive systems that
+    /// store timestamps at least as precisely.
+    pub fn set_timestamp_policy(&mut self, policy: TimestampPolicy) {
+        self.timestamp_policy = policy;
+    }
+
+    pub fn set_id_generator(&mut self, generator: Arc<dyn IdGenerator>) {
+        self.id_generator = generator;
+    }
+
+    /// Text form of `id` chosen by the id generator, e.g. a ULID string.
+    pub fn format_id(&self, id: &Uuid) -> String {
+        self.id_generator.format_id(id)
+    }
+
+    /// Parses an id written by `format_id`.
+    pub fn parse_id(&self, text: &str) -> Option<Uuid> {
+        self.id_generator.parse_id(text)
+    }
+
+    /// Id for evidence attached to new requests, from the same generator as
+    /// credential ids.
+    pub fn new_evidence_id(&self) -> Uuid {
+        self.id_generator.next_id(self.clock.now())
+    }
+
+    fn sign_credential(&self, credential: &mut Microcredential) -> Result<(), Box<dyn Error>> {
+        self.sign_with_envelope(credential, &self.signing_envelope(), &mut Vec::new())
+    }
+
+    /// Issuance time and proof shared by everything signed at this moment.
+    fn signing_envelope(&self) -> SigningEnvelope {
+        let now = self.clock.now();
+        let mut issued_at = self.timestamp_policy.normalize(now);
+        // Rotation rounds the new key's activation up; credentials signed
+        // before then in the same interval take the activation time, so they
+        // resolve to the key that signed them.
+        if let Some(key) = self.issuer_info.key_history.last() {
+            if issued_at < key.activated_at
+                && key.activated_at <= self.timestamp_policy.normalize_up(now)
+            {
+                issued_at = key.activated_at;
+            }
+        }
+        SigningEnvelope {
+            issued_at,
+            proof: Proof {
+                suite: SignatureSuite::Ed25519,
+                hash: self.hash_algorithm,
+                payload: self.payload_encoding,
+                created: Some(issued_at),
+                verification_method: Some(format!(
+                    "{}#{}",
+                    self.issuer_info.url,
+                    key_id(&self.signer.public_key())
+                )),
+                proof_purpose: ProofPurpose::AssertionMethod,
+            },
+        }
+    }
+
+    /// Signs `credential` within `envelope`, serializing into `buffer`.
+    fn sign_with_envelope(
+        &self,
+        credential: &mut Microcredential,
+        envelope: &SigningEnvelope,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        credential.issued_at = envelope.issued_at;
+        credential.normalize_timestamps(&self.timestamp_policy);
+        // Before salting, so content-derived ids do not depend on the salt.
+        credential.id = self.id_generator.credential_id(credential)?;
+        if self.digest_salting {
+            credential.add_metadata(
+                salting::SALT_METADATA_KEY.to_string(),
+                salting::generate_salt(),
+            );
+        }
+        credential.proof = Some(envelope.proof.clone());
+        credential.write_signing_payload(buffer)?;
+        let credential_hash = self.hash_algorithm.digest(buffer);
+        let signature = self.signer.sign(&credential_hash)?;
 
         credential.signature = Some(signature);
 
+        Ok(())
+    }
+
+    /// Signs the requests starting at `start` in one pass within a fresh
+    /// envelope, reusing `buffer`.
+    fn sign_chunk(
+        &self,
+        start: usize,
+        requests: Vec<CredentialRequest>,
+        buffer: &mut Vec<u8>,
+    ) -> Vec<Result<Microcredential, BulkFailure>> {
+        let envelope = self.signing_envelope();
+        let mut sign = |request| -> Result<Microcredential, Box<dyn Error>> {
+            let mut credential = self.build_checked(request, None)?;
+            self.sign_with_envelope(&mut credential, &envelope, buffer)?;
+            Ok(credential)
+        };
+        requests
+            .into_iter()
+            .enumerate()
+            .map(|(offset, request)| {
+                sign(request).map_err(|e| BulkFailure {
+                    index: start + offset,
+                    message: e.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Revokes every credential in `credentials` that was signed with
+    /// `compromised_key` at or after `compromised_at` and returns the
+    /// re-issuance work queue for them. The compromised key is retired as
+    /// of `compromised_at`.
+    ///
+    /// Rotate to a new key (`rotate_key` or `rotate_signer`) first: this
+    /// fails while the compromised key is still the signing key, since the
+    /// queue would be re-issued under it.
+    pub fn handle_key_compromise(
+        &mut self,
+        compromised_key: &[u8],
+        compromised_at: DateTime<Utc>,
+        credentials: &[Microcredential],
+    ) -> Result<CompromiseReport, Box<dyn Error>> {
+        if self.signer.public_key() == compromised_key {
+            return Err("Rotate away from the compromised key first".into());
+        }
+        // Issuance times are truncated, so anything signed in the same
+        // interval as the compromise is treated as compromised.
+        let compromised_at = self.timestamp_policy.normalize(compromised_at);
+        for key in &mut self.issuer_info.key_history {
+            if key.public_key == compromised_key
+                && key.retired_at.is_none_or(|retired_at| retired_at > compromised_at)
+            {
+                key.retired_at = Some(compromised_at);
+            }
+        }
+        let mut report = CompromiseReport {
+            compromised_key: compromised_key.to_vec(),
+            compromised_at,
+            revoked: Vec::new(),
+            reissuance_queue: Vec::new(),
+        };
+
+        for credential in credentials {
+            if credential.issuer.id != self.issuer_info.id
+                || credential.issuer.public_key != compromised_key
+                || credential.issued_at < compromised_at
+            {
+                continue;
+            }
+
+            let reason = format!(
+                "Signing key compromised as of {}",
+                compromised_at.to_rfc3339()
+            );
+            let now = self.clock.now();
+            if self.status_list.revoke(credential.id, reason.clone(), now).is_err() {
+                continue;
+            }
+            self.notify(|handler| handler.on_revoked(&credential.id, &reason));
+
+            report.revoked.push(credential.id);
+            report.reissuance_queue.push(ReissuanceRequest {
+                original_credential_id: credential.id,
+                subject: credential.subject.clone(),
+                skill: credential.skill.clone(),
+                evidence: credential.evidence.clone(),
+                expires_at: credential.expires_at,
+                metadata: credential.metadata.clone(),
+                attachments: credential.attachments.clone(),
+                holder_binding: credential.holder_binding.clone(),
+                actor: None,
+            });
+        }
+
+        Ok(report)
+    }
+
+    pub fn reissue_credential(
+        &self,
+        request: &ReissuanceRequest,
+    ) -> Result<Microcredential, Box<dyn Error>> {
+        let mut credential = self.build_checked(
+            CredentialRequest {
+                subject: request.subject.clone(),
+                skill: request.skill.clone(),
+                evidence: request.evidence.clone(),
+                valid_from: None,
+                expires_at: request.expires_at,
+                metadata: request.metadata.clone(),
+                attachments: request.attachments.clone(),
+                holder_key: request
+                    .holder_binding
+                    .as_ref()
+                    .map(|binding| binding.public_key.to_vec()),
+                actor: None,
+            },
+            Some(&request.original_credential_id),
+        )?;
+        credential.add_metadata(
+            "reissued_from".to_string(),
+            request.original_credential_id.to_string(),
+        );
+
+        self.sign_credential(&mut credential)?;
+        self.audit(
+            &credential,
+            AuditAction::Reissued {
+                original_id: request.original_credential_id,
+            },
+            request.actor.as_deref(),
+        )?;
+        self.notify(|handler| handler.on_issued(&credential));
+
         Ok(credential)
     }
 
+    /// Issues a fresh copy of one of this issuer's credentials with a new
+    /// expiry. Only active credentials whose signature verifies under this
+    /// issuer's keys can be renewed.
+    pub fn renew_credential(
+        &self,
+        credential: &Microcredential,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Microcredential, Box<dyn Error>> {
+        self.renew_request(credential, CredentialRequest::renewing(credential, expires_at))
+    }
+
+    /// Like `renew_credential`, with the renewed contents given as a request,
+    /// e.g. one edited and approved under dual control. The request goes
+    /// through the same validation as issuance and must keep the subject and
+    /// skill of `credential`.
+    pub fn renew_request(
+        &self,
+        credential: &Microcredential,
+        request: CredentialRequest,
+    ) -> Result<Microcredential, Box<dyn Error>> {
+        if credential.issuer.id != self.issuer_info.id {
+            return Err("Credential was not issued by this issuer".into());
+        }
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(self.issuer_info.clone());
+        verifier.verify_credential_checks(credential, Checks::SIGNATURE | Checks::TRUST)?;
+        match self.status_list.status_of(&credential.id) {
+            CredentialStatus::Active => {}
+            CredentialStatus::Suspended { .. } => return Err(StatusError::AlreadySuspended.into()),
+            CredentialStatus::Revoked { .. } => return Err(StatusError::AlreadyRevoked.into()),
+        }
+        if request.subject.id != credential.subject.id || request.skill.id != credential.skill.id {
+            return Err("A renewal must keep the subject and skill".into());
+        }
+
+        let actor = request.actor.clone();
+        let mut renewed = self.build_checked(request, Some(&credential.id))?;
+        renewed.add_metadata("renewed_from".to_string(), credential.id.to_string());
+
+        self.sign_credential(&mut renewed)?;
+        self.audit(
+            &renewed,
+            AuditAction::Renewed {
+                previous_id: credential.id,
+            },
+            actor.as_deref(),
+        )?;
+        self.notify(|handler| handler.on_renewed(&credential.id, &renewed));
+
+        Ok(renewed)
+    }
+
+    /// Retires the current signing key and activates a freshly generated one.
+    /// Returns the new public key.
+    pub fn rotate_key(&mut self) -> Vec<u8> {
+        self.rotate_signer(Box::new(CryptoKeyPair::generate()))
+    }
+
+    /// Like `rotate_key`, for keys created outside the process.
+    pub fn rotate_signer(&mut self, signer: Box<dyn Signer>) -> Vec<u8> {
+        // Rounded up, so credentials the old key signed earlier in the same
+        // interval still fall before its retirement.
+        let now = self.timestamp_policy.normalize_up(self.clock.now());
+        let old_public_key = self.signer.public_key();
+
+        if self.issuer_info.key_history.is_empty() {
+            self.issuer_info.key_history.push(IssuerKey {
+                public_key: old_public_key.into(),
+                activated_at: DateTime::<Utc>::MIN_UTC,
+                retired_at: Some(now),
+                endorsement: None,
+            });
+        } else {
+            for key in &mut self.issuer_info.key_history {
+                if key.retired_at.is_none() {
+                    key.retired_at = Some(now);
+                }
+            }
+        }
+
+        // A retiring signer that cannot sign, e.g. an unreachable KMS key,
+        // leaves its successor unendorsed.
+        let endorsement = self
+            .signer
+            .sign(&IssuerKey::succession_message(&signer.public_key()))
+            .ok();
+        self.issuer_info.key_history.push(IssuerKey {
+            public_key: signer.public_key().into(),
+            activated_at: now,
+            retired_at: None,
+            endorsement,
+        });
+        self.issuer_info.public_key = signer.public_key().into();
+        self.signer = signer;
+        // The chain certifies the retired key.
+        self.certificate_chain.clear();
+
+        self.issuer_info.public_key.to_vec()
+    }
+
+    pub fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) {
+        self.duplicate_policy = policy;
+    }
+
+    /// Checks every credential this issuer signs against `store` under the
+    /// duplicate policy, including those issued through queues, lifecycle
+    /// approvals and exchanges. `issue_checked` and `issue_batch` also check
+    /// the store they are given.
+    pub fn set_duplicate_store(&mut self, store: Arc<Mutex<dyn CredentialStore + Send>>) {
+        self.duplicate_store = Some(store);
+    }
+
+    /// Redundant credentials in `store`; see `duplicates::find_duplicates`.
+    /// Credentials this issuer has revoked are not counted.
+    pub fn find_duplicates(
+        &self,
+        store: &dyn CredentialStore,
+    ) -> Result<Vec<Duplicate>, StoreError> {
+        let held = self.held_credentials(store)?;
+        Ok(duplicates::find_duplicates(&held, self.clock.now()))
+    }
+
+    fn held_credentials(
+        &self,
+        store: &dyn CredentialStore,
+    ) -> Result<Vec<Microcredential>, StoreError> {
+        let mut held = Vec::new();
+        for id in store.ids()? {
+            if let CredentialStatus::Revoked { .. } = self.status_list.status_of(&id) {
+                continue;
+            }
+            if let Some(credential) = store.get(&id)? {
+                held.push(credential);
+            }
+        }
+        Ok(held)
+    }
+
+    /// Unrevoked credentials of one subject, from the store's subject index.
+    fn held_by(
+        &self,
+        store: &dyn CredentialStore,
+        subject_id: &Uuid,
+    ) -> Result<Vec<Microcredential>, StoreError> {
+        Ok(store
+            .subject_credentials(subject_id)?
+            .into_iter()
+            .filter(|credential| {
+                !matches!(
+                    self.status_list.status_of(&credential.id),
+                    CredentialStatus::Revoked { .. }
+                )
+            })
+            .collect())
+    }
+
+    pub fn add_event_handler(&mut self, handler: Arc<dyn EventHandler>) {
+        self.event_handlers.push(handler);
+    }
+
+    /// Takes subject names and emails from `resolver` at issuance. Requests
+    /// then only need the subject id; a name or email that is given must
+    /// match the directory, which catches typos before they are signed.
+    pub fn set_subject_resolver(&mut self, resolver: Arc<dyn SubjectResolver>) {
+        self.subject_resolver = Some(resolver);
+    }
+
+    /// Replaces `subject` with its directory entry, reporting unknown ids and
+    /// mismatched fields. Without a resolver the subject is kept as given.
+    fn resolve_subject(&self, subject: &mut Subject) -> Result<ValidationReport, Box<dyn Error>> {
+        let mut report = ValidationReport::default();
+        let Some(resolver) = &self.subject_resolver else {
+            return Ok(report);
+        };
+        let Some(entry) = resolver.resolve(&subject.id)? else {
+            report.add_issue("subject.id", "is not in the subject directory");
+            return Ok(report);
+        };
+        if !subject.name.is_empty() && subject.name != entry.name {
+            report.add_issue("subject.name", "does not match the subject directory");
+        }
+        if !subject.email.is_empty() && !subject.email.eq_ignore_ascii_case(&entry.email) {
+            report.add_issue("subject.email", "does not match the subject directory");
+        }
+        subject.name = entry.name;
+        subject.email = entry.email;
+        Ok(report)
+    }
+
+    /// Records every credential issued from now on in `log`, attributed to
+    /// the `actor` of each request. Issuance fails when the log cannot
+    /// persist its record.
+    pub fn set_audit_log(&mut self, log: Arc<AuditLog>) {
+        self.audit_log = Some(log);
+    }
+
+    /// The issuing policy in force, as recorded in audit records.
+    pub fn policy_snapshot(&self) -> Value {
+        let rules = &self.validation_rules;
+        let evidence_types: Vec<&str> = rules
+            .evidence_types
+            .definitions()
+            .map(|definition| definition.id.as_str())
+            .collect();
+        let skill_catalog: Option<HashMap<&str, u32>> = rules.skill_catalog.as_ref().map(|catalog| {
+            catalog
+                .definitions()
+                .map(|definition| (definition.id.as_str(), definition.version))
+                .collect()
+        });
+        json!({
+            "validation": {
+                "max_name_length": rules.max_name_length,
+                "max_description_length": rules.max_description_length,
+                "min_evidence": rules.min_evidence,
+                "max_evidence": rules.max_evidence,
+                "evidence_types": evidence_types,
+                "attachments": {
+                    "max_size": rules.attachments.max_size,
+                    "max_attachments": rules.attachments.max_attachments,
+                    "allowed_mime_types": rules.attachments.allowed_mime_types,
+                },
+                "skill_catalog": skill_catalog,
+                "level_scales": rules
+                    .level_scales
+                    .iter()
+                    .map(|scale| scale.id.as_str())
+                    .collect::<Vec<_>>(),
+            },
+            "hash_algorithm": self.hash_algorithm,
+            "payload_encoding": self.payload_encoding,
+            "digest_salting": self.digest_salting,
+            "certificate_chain": !self.certificate_chain.is_empty(),
+        })
+    }
+
+    fn audit(
+        &self,
+        credential: &Microcredential,
+        action: AuditAction,
+        actor: Option<&str>,
+    ) -> Result<(), AuditError> {
+        if let Some(log) = &self.audit_log {
+            log.record(
+                credential,
+                action,
+                actor.unwrap_or_default(),
+                self.policy_snapshot(),
+                self.clock.now(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn notify(&self, event: impl Fn(&dyn EventHandler)) {
+        for handler in &self.event_handlers {
+            event(handler.as_ref());
+        }
+    }
+
+    /// Readiness of this issuer: whether its signer is reachable, its
+    /// signing certificate (if any) is unexpired, and `store` is reachable.
+    pub fn health(
+        &self,
+        store: Option<&dyn CredentialStore>,
+        thresholds: &HealthThresholds,
+    ) -> HealthReport {
+        let now = self.clock.now();
+        let mut checks = vec![match self.signer.health_check() {
+            Ok(()) => HealthCheck::new("signer", HealthStatus::Healthy, "signer available"),
+            Err(e) => HealthCheck::new("signer", HealthStatus::Unhealthy, e.to_string()),
+        }];
+        checks.push(health::expiry_check(
+            "signing_key",
+            self.certificate_chain.first().map(|leaf| leaf.not_after),
+            now,
+            thresholds.key_expiry_warning,
+        ));
+        if let Some(store) = store {
+            checks.push(match store.ping() {
+                Ok(()) => HealthCheck::new("store", HealthStatus::Healthy, "store reachable"),
+                Err(e) => HealthCheck::new("store", HealthStatus::Unhealthy, e.to_string()),
+            });
+        }
+        HealthReport {
+            checked_at: now,
+            checks,
+        }
+    }
+
+    pub fn add_endpoint(&mut self, name: String, url: String) {
+        self.endpoints.push(IssuerEndpoint { name, url });
+    }
+
+    pub fn add_accreditation(&mut self, accreditation: Accreditation) {
+        self.accreditations.push(accreditation);
+    }
+
+    /// Sets the X.509 chain (leaf first) certifying the current signing key,
+    /// published in exported profiles. Rotating the key clears it.
+    pub fn set_certificate_chain(
+        &mut self,
+        chain: Vec<Certificate>,
+    ) -> Result<(), Box<dyn Error>> {
+        match chain.first() {
+            Some(leaf) if leaf.public_key() != self.signer.public_key() => {
+                Err("Leaf certificate does not certify the signing key".into())
+            }
+            _ => {
+                self.certificate_chain = chain;
+                Ok(())
+            }
+        }
+    }
+
+    /// Publishes an address Blockcerts batches are anchored from. Verifiers
+    /// only accept anchors sent from addresses listed in the profile.
+    pub fn add_blockchain_address(&mut self, address: String) {
+        if !self.blockchain_addresses.contains(&address) {
+            self.blockchain_addresses.push(address);
+        }
+    }
+
+    /// Produces a profile document signed with the current key, to hand to
+    /// verifiers for onboarding.
+    pub fn export_profile(&self) -> Result<IssuerProfile, Box<dyn Error>> {
+        let mut profile = IssuerProfile {
+            issuer: self.issuer_info.clone(),
+            endpoints: self.endpoints.clone(),
+            accreditations: self.accreditations.clone(),
+            certificate_chain: self.certificate_chain.iter().map(Certificate::to_pem).collect(),
+            blockchain_addresses: self.blockchain_addresses.clone(),
+            created_at: self.timestamp_policy.normalize(self.clock.now()),
+            signature: None,
+        };
+        let payload = profile.signing_payload()?;
+        profile.signature = Some(self.signer.sign(&hash_credential(&payload))?);
+        Ok(profile)
+    }
+
+    /// Sets the loader used to resolve contexts when signing Linked Data
+    /// exports. Every context added with `add_export_context` must resolve
+    /// through it.
+    pub fn set_context_loader(&mut self, loader: Arc<dyn ContextLoader>) {
+        self.context_loader = loader;
+    }
+
+    /// Appends `url` to the `@context` of every Linked Data export, e.g. a
+    /// partner context some verifiers require.
+    pub fn add_export_context(&mut self, url: &str) {
+        if !self.export_contexts.iter().any(|known| known == url) {
+            self.export_contexts.push(url.to_string());
+        }
+    }
+
+    /// Exports `credential` as a W3C Verifiable Credential with a Linked
+    /// Data proof from the current key.
+    pub fn export_linked_data(
+        &self,
+        credential: &Microcredential,
+        suite: LdSuite,
+    ) -> Result<Value, Box<dyn Error>> {
+        let mut document = ld::credential_document(credential);
+        for url in &self.export_contexts {
+            ld::add_context(&mut document, url)?;
+        }
+        ld::add_proof(
+            &mut document,
+            self.signer.as_ref(),
+            &LdProofOptions {
+                suite,
+                verification_method: format!(
+                    "{}#{}",
+                    did::did_web(&self.issuer_info.url),
+                    key_id(&self.signer.public_key())
+                ),
+                created: self.timestamp_policy.normalize(self.clock.now()),
+                purpose: ProofPurpose::AssertionMethod,
+            },
+            self.context_loader.as_ref(),
+        )?;
+        Ok(document)
+    }
+
+    /// Hashes `credentials` into a Blockcerts batch. Anchor the batch's
+    /// Merkle root, then take the certificates with their receipts from
+    /// `BlockcertsBatch::certificates`.
+    pub fn export_blockcerts(
+        &self,
+        credentials: &[Microcredential],
+    ) -> Result<BlockcertsBatch, Box<dyn Error>> {
+        let mut documents = Vec::new();
+        for credential in credentials {
+            let mut document = blockcerts::certificate_document(credential);
+            for url in &self.export_contexts {
+                ld::add_context(&mut document, url)?;
+            }
+            documents.push(document);
+        }
+        Ok(BlockcertsBatch::new(documents, self.context_loader.as_ref())?)
+    }
+
+    /// Open Badges 2.0 documents to host for `credential`, reflecting its
+    /// current status. Republish the assertion whenever the status changes.
+    pub fn export_open_badge(
+        &self,
+        credential: &Microcredential,
+        hosting: &BadgeHosting,
+    ) -> HostedBadge {
+        hosting.hosted_badge(credential, &self.status_list.status_of(&credential.id))
+    }
+
+    /// Exports a holder-bound `credential` as an ISO 18013-5 mdoc signed
+    /// with the current key, carrying the certificate chain if one is set.
+    pub fn export_mdoc(
+        &self,
+        credential: &Microcredential,
+    ) -> Result<MdocDocument, Box<dyn Error>> {
+        let x5chain = self
+            .certificate_chain
+            .iter()
+            .map(|certificate| certificate.der().to_vec())
+            .collect();
+        Ok(MdocDocument::issue(
+            credential,
+            self.signer.as_ref(),
+            x5chain,
+            self.clock.now(),
+        )?)
+    }
+
+    /// Describes what applicants must submit to obtain a credential for
+    /// `skill`, derived from the current validation rules.
+    pub fn credential_manifest(&self, skill: &Skill) -> CredentialManifest {
+        CredentialManifest::new(&self.issuer_info, skill, self.validation_rules.min_evidence)
+    }
+
+    /// Signs a certificate allowing `subject` to issue credentials under this
+    /// issuer's authority. Only certificates with `is_ca` may certify further.
+    pub fn certify_issuer(
+        &self,
+        subject: Issuer,
+        is_ca: bool,
+        path_len: Option<u32>,
+        constraints: IssuerConstraints,
+        not_after: DateTime<Utc>,
+    ) -> Result<IssuerCertificate, Box<dyn Error>> {
+        let mut certificate = IssuerCertificate {
+            id: Uuid::new_v4(),
+            subject,
+            issuer_id: self.issuer_info.id,
+            is_ca,
+            path_len,
+            constraints,
+            not_before: self.timestamp_policy.normalize(self.clock.now()),
+            not_after,
+            signature: None,
+        };
+        let payload = certificate.signing_payload()?;
+        certificate.signature = Some(self.signer.sign(&hash_credential(&payload))?);
+        Ok(certificate)
+    }
+
+    /// The current time by the issuer's clock.
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
     pub fn get_issuer_info(&self) -> &Issuer {
         &self.issuer_info
     }
 
     pub fn get_public_key(&self) -> Vec<u8> {
-        self.keypair.public_key()
+        self.signer.public_key()
     }
 
+    /// The signing key, for issuers that hold it in process.
+    ///
+    /// # Panics
+    ///
+    /// If the key is held by a remote signer such as a KMS.
+    #[deprecated(note = "panics for remote signers; use `try_get_secret_key`")]
     pub fn get_secret_key(&self) -> Vec<u8> {
-        self.keypair.secret_key()
+        self.try_get_secret_key()
+            .expect("signing key is not held in process")
+    }
+
+    /// `None` when the signing key is not held in process.
+    pub fn try_get_secret_key(&self) -> Option<Vec<u8>> {
+        self.signer.secret_key()
+    }
+
+    pub fn suspend_credential(
+        &mut self,
+        credential_id: &Uuid,
+        reason: String,
+    ) -> Result<(), StatusError> {
+        let now = self.clock.now();
+        self.status_list.suspend(*credential_id, reason, now)
+    }
+
+    pub fn reinstate_credential(&mut self, credential_id: &Uuid) -> Result<(), StatusError> {
+        let now = self.clock.now();
+        self.status_list.reinstate(credential_id, now)
+    }
+
+    pub fn revoke_credential(
+        &mut self,
+        credential_id: &Uuid,
+        reason: String,
+    ) -> Result<(), StatusError> {
+        let now = self.clock.now();
+        self.status_list.revoke(*credential_id, reason.clone(), now)?;
+        self.notify(|handler| handler.on_revoked(credential_id, &reason));
+        Ok(())
+    }
+
+    pub fn credential_status(&self, credential_id: &Uuid) -> CredentialStatus {
+        self.status_list.status_of(credential_id)
+    }
+
+    pub fn get_status_list(&self) -> &StatusList {
+        &self.status_list
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,216 @@
+//! JSON Web Key (RFC 7517, RFC 8037) form of Ed25519 keys, for JOSE
+//! tooling and JWKS endpoints.
+
+use crate::crypto::CryptoKeyPair;
+use crate::proof::key_id;
+use crate::Issuer;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum JwkError {
+    UnsupportedKeyType(String),
+    InvalidEncoding(String),
+    InvalidKeyLength(usize),
+    MissingPrivateKey,
+    KeyMismatch,
+}
+
+impl fmt::Display for JwkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JwkError::UnsupportedKeyType(kind) => write!(f, "Unsupported JWK key type {}", kind),
+            JwkError::InvalidEncoding(msg) => write!(f, "Invalid base64url: {}", msg),
+            JwkError::InvalidKeyLength(len) => {
+                write!(f, "Ed25519 keys must be 32 bytes, got {}", len)
+            }
+            JwkError::MissingPrivateKey => write!(f, "JWK has no private key"),
+            JwkError::KeyMismatch => write!(f, "JWK private key does not match its public key"),
+        }
+    }
+}
+
+impl Error for JwkError {}
+
+/// An `OKP` key on curve `Ed25519`. `d` is only present for private keys.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub crv: String,
+    pub x: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub d: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alg: Option<String>,
+    #[serde(rename = "use", default, skip_serializing_if = "Option::is_none")]
+    pub key_use: Option<String>,
+}
+
+impl Jwk {
+    /// Public JWK of `public_key`, with `kid` set to `proof::key_id`.
+    pub fn from_public_key(public_key: &[u8]) -> Result<Self, JwkError> {
+        if public_key.len() != 32 {
+            return Err(JwkError::InvalidKeyLength(public_key.len()));
+        }
+        Ok(Self {
+            kty: "OKP".to_string(),
+            crv: "Ed25519".to_string(),
+            x: URL_SAFE_NO_PAD.encode(public_key),
+            d: None,
+            kid: Some(key_id(public_key)),
+            alg: Some("EdDSA".to_string()),
+            key_use: Some("sig".to_string()),
+        })
+    }
+
+    /// Private JWK of `key_pair`. Handle the result like the secret key.
+    pub fn from_key_pair(key_pair: &CryptoKeyPair) -> Self {
+        let mut jwk = Self::from_public_key(&key_pair.public_key())
+            .expect("Ed25519 public keys are 32 bytes");
+        jwk.d = Some(URL_SAFE_NO_PAD.encode(key_pair.secret_key()));
+        jwk
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.d.is_some()
+    }
+
+    /// The same key without its private part.
+    pub fn to_public(&self) -> Self {
+        Self {
+            d: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn public_key(&self) -> Result<Vec<u8>, JwkError> {
+        self.check_type()?;
+        decode_key(&self.x)
+    }
+
+    /// Key pair of a private JWK, checking that `x` matches `d`.
+    pub fn to_key_pair(&self) -> Result<CryptoKeyPair, JwkError> {
+        let public_key = self.public_key()?;
+        let secret_key = decode_key(self.d.as_deref().ok_or(JwkError::MissingPrivateKey)?)?;
+        let key_pair = CryptoKeyPair::from_secret_key(&secret_key)
+            .map_err(|e| JwkError::InvalidEncoding(e.to_string()))?;
+        if key_pair.public_key() != public_key {
+            return Err(JwkError::KeyMismatch);
+        }
+        Ok(key_pair)
+    }
+
+    fn check_type(&self) -> Result<(), JwkError> {
+        if self.kty != "OKP" || self.crv != "Ed25519" {
+            return Err(JwkError::UnsupportedKeyType(format!(
+                "{} {}",
+                self.kty, self.crv
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// JWK Set (`{"keys": [...]}`) as served from a JWKS endpoint.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl JwkSet {
+    /// Public keys of `issuer`, current key first. Retired keys stay listed
+    /// so credentials signed before a rotation can still be checked.
+    pub fn for_issuer(issuer: &Issuer) -> Self {
+        let mut public_keys = vec![issuer.public_key.as_slice()];
+        for key in issuer.key_history.iter().rev() {
+            if !public_keys.contains(&key.public_key.as_slice()) {
+                public_keys.push(&key.public_key);
+            }
+        }
+        Self {
+            keys: public_keys
+                .into_iter()
+                .filter_map(|public_key| Jwk::from_public_key(public_key).ok())
+                .collect(),
+        }
+    }
+
+    pub fn find(&self, kid: &str) -> Option<&Jwk> {
+        self.keys.iter().find(|key| key.kid.as_deref() == Some(kid))
+    }
+}
+
+fn decode_key(value: &str) -> Result<Vec<u8>, JwkError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|e| JwkError::InvalidEncoding(e.to_string()))?;
+    if bytes.len() != 32 {
+        return Err(JwkError::InvalidKeyLength(bytes.len()));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuer::CredentialIssuer;
+
+    #[test]
+    fn test_rfc8037_key_round_trip() {
+        // RFC 8037, appendix A.1.
+        let jwk: Jwk = serde_json::from_str(
+            r#"{"kty":"OKP","crv":"Ed25519",
+                "d":"nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A",
+                "x":"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}"#,
+        )
+        .unwrap();
+        let key_pair = jwk.to_key_pair().unwrap();
+        assert_eq!(
+            Jwk::from_key_pair(&key_pair).to_public().x,
+            "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"
+        );
+
+        let public = Jwk::from_key_pair(&key_pair).to_public();
+        assert!(!public.is_private());
+        assert_eq!(
+            public.to_key_pair().err(),
+            Some(JwkError::MissingPrivateKey)
+        );
+        let json = serde_json::to_value(&public).unwrap();
+        assert_eq!(json["use"], "sig");
+        assert!(json.get("d").is_none());
+
+        let mut mismatched = jwk.clone();
+        mismatched.x = Jwk::from_key_pair(&CryptoKeyPair::generate()).x;
+        assert_eq!(mismatched.to_key_pair().err(), Some(JwkError::KeyMismatch));
+        let mut x25519 = jwk;
+        x25519.crv = "X25519".to_string();
+        assert!(matches!(
+            x25519.public_key(),
+            Err(JwkError::UnsupportedKeyType(_))
+        ));
+    }
+
+    #[test]
+    fn test_issuer_jwks_keeps_rotated_keys() {
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let old_key = issuer_service.get_public_key();
+        let new_key = issuer_service.rotate_key();
+
+        let jwks = JwkSet::for_issuer(issuer_service.get_issuer_info());
+        assert_eq!(jwks.keys.len(), 2);
+        assert_eq!(jwks.keys[0].public_key().unwrap(), new_key);
+        let old = jwks.find(&key_id(&old_key)).unwrap();
+        assert_eq!(old.public_key().unwrap(), old_key);
+        assert!(jwks.keys.iter().all(|key| !key.is_private()));
+    }
+}
@@ -0,0 +1,88 @@
+//! `Signer` implementations backed by remote key management services, so
+//! the issuer's private key never enters process memory.
+//!
+//! The backends only build and interpret API calls; the HTTP client, TLS and
+//! connection pooling are supplied by the application through
+//! [`HttpTransport`].
+
+use crate::signer::SignerError;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::Value;
+
+#[cfg(feature = "aws-kms")]
+mod aws;
+#[cfg(feature = "gcp-kms")]
+mod gcp;
+#[cfg(feature = "vault")]
+mod vault;
+
+#[cfg(feature = "aws-kms")]
+pub use aws::{AwsCredentials, AwsKmsSigner};
+#[cfg(feature = "gcp-kms")]
+pub use gcp::GcpKmsSigner;
+#[cfg(feature = "vault")]
+pub use vault::VaultTransitSigner;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub method: &'static str,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+pub trait HttpTransport: Send + Sync {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, SignerError>;
+}
+
+fn send_json(transport: &dyn HttpTransport, request: &HttpRequest) -> Result<Value, SignerError> {
+    let response = transport.send(request)?;
+    if !(200..300).contains(&response.status) {
+        return Err(SignerError::Remote {
+            status: response.status,
+            message: String::from_utf8_lossy(&response.body).into_owned(),
+        });
+    }
+    serde_json::from_slice(&response.body).map_err(|e| SignerError::InvalidResponse(e.to_string()))
+}
+
+fn json_body(value: &Value) -> Vec<u8> {
+    value.to_string().into_bytes()
+}
+
+fn string_at<'a>(value: &'a Value, pointer: &str) -> Result<&'a str, SignerError> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .ok_or_else(|| SignerError::InvalidResponse(format!("missing {}", pointer)))
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>, SignerError> {
+    STANDARD
+        .decode(value)
+        .map_err(|e| SignerError::InvalidResponse(e.to_string()))
+}
+
+/// Health check result for a key fetched from the service: it must still be
+/// the key the signer was connected with.
+fn check_public_key(pinned: &[u8], fetched: &[u8]) -> Result<(), SignerError> {
+    if pinned != fetched {
+        return Err(SignerError::InvalidResponse(
+            "the service returned a different public key".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Extracts the raw key from a DER `SubjectPublicKeyInfo` for Ed25519.
+#[cfg(any(feature = "aws-kms", feature = "gcp-kms"))]
+fn ed25519_from_spki(der: &[u8]) -> Result<Vec<u8>, SignerError> {
+    crate::pkcs8::public_key_from_der(der).map_err(|e| SignerError::InvalidResponse(e.to_string()))
+}
@@ -0,0 +1,318 @@
+use super::{
+    check_public_key, decode_base64, ed25519_from_spki, json_body, send_json, string_at,
+    HttpRequest, HttpTransport,
+};
+use crate::clock::{Clock, SystemClock};
+use crate::signer::{Signer, SignerError};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+/// Signs with an `ECC_NIST_EDWARDS25519` key in AWS KMS. Requests are
+/// authenticated with Signature Version 4.
+pub struct AwsKmsSigner<T> {
+    transport: T,
+    region: String,
+    key_id: String,
+    credentials: AwsCredentials,
+    public_key: Vec<u8>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<T: HttpTransport> AwsKmsSigner<T> {
+    pub fn connect(
+        transport: T,
+        region: &str,
+        key_id: &str,
+        credentials: AwsCredentials,
+    ) -> Result<Self, SignerError> {
+        Self::connect_with_clock(
+            transport,
+            region,
+            key_id,
+            credentials,
+            Arc::new(SystemClock),
+        )
+    }
+
+    /// Like `connect`, dating requests by `clock`. AWS rejects requests
+    /// dated more than a few minutes from its own time.
+    pub fn connect_with_clock(
+        transport: T,
+        region: &str,
+        key_id: &str,
+        credentials: AwsCredentials,
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self, SignerError> {
+        let mut signer = Self {
+            transport,
+            region: region.to_string(),
+            key_id: key_id.to_string(),
+            credentials,
+            public_key: Vec::new(),
+            clock,
+        };
+
+        signer.public_key = signer.fetch_public_key()?;
+        Ok(signer)
+    }
+
+    /// `GetPublicKey`, which also checks that the key is usable for signing.
+    fn fetch_public_key(&self) -> Result<Vec<u8>, SignerError> {
+        let response = self.call("GetPublicKey", json!({ "KeyId": self.key_id }))?;
+        let key_spec = string_at(&response, "/KeySpec")?;
+        if key_spec != "ECC_NIST_EDWARDS25519" {
+            return Err(SignerError::InvalidResponse(format!(
+                "unsupported key spec {}",
+                key_spec
+            )));
+        }
+        let der = decode_base64(string_at(&response, "/PublicKey")?)?;
+        ed25519_from_spki(&der)
+    }
+
+    fn call(&self, action: &str, body: Value) -> Result<Value, SignerError> {
+        let host = format!("kms.{}.amazonaws.com", self.region);
+        let body = json_body(&body);
+        let amz_date = self.clock.now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        let mut headers = vec![
+            (
+                "content-type".to_string(),
+                "application/x-amz-json-1.1".to_string(),
+            ),
+            ("host".to_string(), host.clone()),
+            ("x-amz-date".to_string(), amz_date.clone()),
+            (
+                "x-amz-target".to_string(),
+                format!("TrentService.{}", action),
+            ),
+        ];
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token".to_string(), token.clone()));
+        }
+        let authorization = sigv4_authorization(
+            &self.credentials,
+            &self.region,
+            "kms",
+            &amz_date,
+            &headers,
+            &body,
+        );
+        headers.push(("authorization".to_string(), authorization));
+
+        let request = HttpRequest {
+            method: "POST",
+            url: format!("https://{}/", host),
+            headers,
+            body,
+        };
+        send_json(&self.transport, &request)
+    }
+}
+
+impl<T: HttpTransport> Signer for AwsKmsSigner<T> {
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignerError> {
+        let response = self.call(
+            "Sign",
+            json!({
+                "KeyId": self.key_id,
+                "Message": STANDARD.encode(message),
+                "MessageType": "RAW",
+                "SigningAlgorithm": "ED25519_SHA_512",
+            }),
+        )?;
+        decode_base64(string_at(&response, "/Signature")?)
+    }
+
+    fn health_check(&self) -> Result<(), SignerError> {
+        check_public_key(&self.public_key, &self.fetch_public_key()?)
+    }
+}
+
+/// Authorization header for a `POST /` request. Every header in `headers`
+/// is signed; names must be lowercase, in any order.
+fn sigv4_authorization(
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    amz_date: &str,
+    headers: &[(String, String)],
+    body: &[u8],
+) -> String {
+    let mut headers: Vec<_> = headers.iter().collect();
+    headers.sort_by(|a, b| a.0.cmp(&b.0));
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let canonical_request = format!(
+        "POST\n/\n\n{}\n{}\n{}",
+        canonical_headers,
+        signed_headers,
+        hex::encode(Sha256::digest(body))
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let secret = format!("AWS4{}", credentials.secret_access_key);
+    let mut key = hmac_sha256(secret.as_bytes(), date.as_bytes());
+    for part in [region, service, "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id, scope, signed_headers, signature
+    )
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::HttpResponse;
+    use super::*;
+    use crate::crypto::{verify_signature, CryptoKeyPair};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FakeKms {
+        key: CryptoKeyPair,
+        signs: AtomicUsize,
+    }
+
+    impl HttpTransport for FakeKms {
+        fn send(&self, request: &HttpRequest) -> Result<HttpResponse, SignerError> {
+            assert_eq!(request.url, "https://kms.eu-west-1.amazonaws.com/");
+            let header = |name: &str| {
+                request
+                    .headers
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.clone())
+                    .unwrap()
+            };
+            assert!(header("authorization").contains(
+                "SignedHeaders=content-type;host;x-amz-date;x-amz-security-token;x-amz-target,"
+            ));
+            assert_eq!(header("x-amz-security-token"), "session-token");
+
+            let body: Value = serde_json::from_slice(&request.body).unwrap();
+            let response = match header("x-amz-target").as_str() {
+                "TrentService.GetPublicKey" => {
+                    let mut der = vec![
+                        0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+                    ];
+                    der.extend(self.key.public_key());
+                    json!({ "KeySpec": "ECC_NIST_EDWARDS25519", "PublicKey": STANDARD.encode(der) })
+                }
+                "TrentService.Sign" => {
+                    self.signs.fetch_add(1, Ordering::SeqCst);
+                    assert_eq!(body["SigningAlgorithm"], "ED25519_SHA_512");
+                    let message = STANDARD.decode(body["Message"].as_str().unwrap()).unwrap();
+                    json!({ "Signature": STANDARD.encode(self.key.sign(&message)) })
+                }
+                other => panic!("unexpected action {}", other),
+            };
+            Ok(HttpResponse {
+                status: 200,
+                body: response.to_string().into_bytes(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_aws_kms_signatures_verify() {
+        let key = CryptoKeyPair::generate();
+        let expected_key = key.public_key();
+        let mut signer = AwsKmsSigner::connect(
+            FakeKms {
+                key,
+                signs: AtomicUsize::new(0),
+            },
+            "eu-west-1",
+            "alias/issuer",
+            AwsCredentials {
+                access_key_id: "AKIDEXAMPLE".to_string(),
+                secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+                session_token: Some("session-token".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(signer.public_key(), expected_key);
+
+        let signature = signer.sign(b"payload").unwrap();
+        assert!(verify_signature(&expected_key, b"payload", &signature).unwrap());
+
+        signer.health_check().unwrap();
+        assert_eq!(signer.transport.signs.load(Ordering::SeqCst), 1);
+        signer.public_key = CryptoKeyPair::generate().public_key();
+        assert!(matches!(
+            signer.health_check(),
+            Err(SignerError::InvalidResponse(_))
+        ));
+    }
+
+    /// `post-sts-header-before` from the AWS SigV4 test suite.
+    #[test]
+    fn test_sigv4_known_answer_with_session_token() {
+        let token = "AQoDYXdzEPT//////////wEXAMPLEtc764bNrC9SAPBSM22wDOk4x4HIZ8j4FZTwdQWLWsKWHGBuF\
+                     qwAeMicRXmxfpSPfIeoIYRqTflfKD8YUuwthAx7mSEI/qkPpKPi/kMcGdQrmGdeehM4IC1NtBmU\
+                     pp2wUE8phUZampKsburEDy0KPkyQDYwT7WZ0wq5VSXDvp75YU9HFvlRd8Tx6q6fE8YQcHNVXAki\
+                     Y9q6d+xo0rKwT38xVqr7ZD0u0iPPkUL64lIZbqBAz+scqKmlzm8FDrypNC9Yjc8fPOLn9FX9KSY\
+                     vKTr4rvx3iSIlTJabIQwj2ICCR/oLxBA==";
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: Some(token.to_string()),
+        };
+        let headers = vec![
+            ("x-amz-security-token".to_string(), token.to_string()),
+            ("host".to_string(), "example.amazonaws.com".to_string()),
+            ("x-amz-date".to_string(), "20150830T123600Z".to_string()),
+        ];
+        assert_eq!(
+            sigv4_authorization(
+                &credentials,
+                "us-east-1",
+                "service",
+                "20150830T123600Z",
+                &headers,
+                b""
+            ),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date;x-amz-security-token, \
+             Signature=85d96828115b5dc0cfc3bd16ad9e210dd772bbebba041836c64533a82be05ead"
+        );
+    }
+}
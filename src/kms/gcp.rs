@@ -0,0 +1,168 @@
+use super::{
+    check_public_key, decode_base64, ed25519_from_spki, json_body, send_json, string_at,
+    HttpRequest, HttpTransport,
+};
+use crate::signer::{Signer, SignerError};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::json;
+
+const API_BASE: &str = "https://cloudkms.googleapis.com/v1";
+
+type TokenSource = Box<dyn Fn() -> Result<String, SignerError> + Send + Sync>;
+
+/// Signs with an `EC_SIGN_ED25519` key version in Google Cloud KMS.
+///
+/// `token_source` is called for every request so short-lived OAuth tokens
+/// can be refreshed by the application.
+pub struct GcpKmsSigner<T> {
+    transport: T,
+    key_version: String,
+    token_source: TokenSource,
+    public_key: Vec<u8>,
+}
+
+impl<T: HttpTransport> GcpKmsSigner<T> {
+    /// `key_version` is the full resource name, e.g.
+    /// `projects/p/locations/l/keyRings/r/cryptoKeys/k/cryptoKeyVersions/1`.
+    pub fn connect(
+        transport: T,
+        key_version: &str,
+        token_source: TokenSource,
+    ) -> Result<Self, SignerError> {
+        Ok(Self {
+            public_key: fetch_public_key(&transport, key_version, &token_source)?,
+            transport,
+            key_version: key_version.to_string(),
+            token_source,
+        })
+    }
+}
+
+impl<T: HttpTransport> Signer for GcpKmsSigner<T> {
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignerError> {
+        let request = HttpRequest {
+            method: "POST",
+            url: format!("{}/{}:asymmetricSign", API_BASE, self.key_version),
+            headers: vec![
+                authorization(&self.token_source)?,
+                ("Content-Type".to_string(), "application/json".to_string()),
+            ],
+            body: json_body(&json!({ "data": STANDARD.encode(message) })),
+        };
+        let response = send_json(&self.transport, &request)?;
+        decode_base64(string_at(&response, "/signature")?)
+    }
+
+    /// Fetches the version's public key, which fails once the version is
+    /// disabled or destroyed.
+    fn health_check(&self) -> Result<(), SignerError> {
+        let fetched = fetch_public_key(&self.transport, &self.key_version, &self.token_source)?;
+        check_public_key(&self.public_key, &fetched)
+    }
+}
+
+fn fetch_public_key(
+    transport: &dyn HttpTransport,
+    key_version: &str,
+    token_source: &TokenSource,
+) -> Result<Vec<u8>, SignerError> {
+    let request = HttpRequest {
+        method: "GET",
+        url: format!("{}/{}/publicKey", API_BASE, key_version),
+        headers: vec![authorization(token_source)?],
+        body: Vec::new(),
+    };
+    let response = send_json(transport, &request)?;
+
+    let algorithm = string_at(&response, "/algorithm")?;
+    if algorithm != "EC_SIGN_ED25519" {
+        return Err(SignerError::InvalidResponse(format!(
+            "unsupported key algorithm {}",
+            algorithm
+        )));
+    }
+    let pem = string_at(&response, "/pem")?;
+    let der = decode_base64(
+        &pem.lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect::<String>(),
+    )?;
+    ed25519_from_spki(&der)
+}
+
+fn authorization(token_source: &TokenSource) -> Result<(String, String), SignerError> {
+    Ok((
+        "Authorization".to_string(),
+        format!("Bearer {}", token_source()?),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::HttpResponse;
+    use super::*;
+    use crate::crypto::{verify_signature, CryptoKeyPair};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use serde_json::Value;
+
+    struct FakeKms {
+        key: CryptoKeyPair,
+        signs: AtomicUsize,
+    }
+
+    impl HttpTransport for FakeKms {
+        fn send(&self, request: &HttpRequest) -> Result<HttpResponse, SignerError> {
+            assert!(request
+                .headers
+                .contains(&("Authorization".to_string(), "Bearer ya29.token".to_string())));
+            let body = if request.url.ends_with("/publicKey") {
+                let mut der = vec![
+                    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+                ];
+                der.extend(self.key.public_key());
+                let pem = format!(
+                    "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+                    STANDARD.encode(der)
+                );
+                json!({ "pem": pem, "algorithm": "EC_SIGN_ED25519" })
+            } else {
+                assert!(request.url.ends_with("cryptoKeyVersions/1:asymmetricSign"));
+                self.signs.fetch_add(1, Ordering::SeqCst);
+                let body: Value = serde_json::from_slice(&request.body).unwrap();
+                let data = STANDARD.decode(body["data"].as_str().unwrap()).unwrap();
+                json!({ "signature": STANDARD.encode(self.key.sign(&data)) })
+            };
+            Ok(HttpResponse {
+                status: 200,
+                body: body.to_string().into_bytes(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_gcp_kms_signatures_verify() {
+        let key = CryptoKeyPair::generate();
+        let expected_key = key.public_key();
+        let signer = GcpKmsSigner::connect(
+            FakeKms {
+                key,
+                signs: AtomicUsize::new(0),
+            },
+            "projects/p/locations/global/keyRings/r/cryptoKeys/issuer/cryptoKeyVersions/1",
+            Box::new(|| Ok("ya29.token".to_string())),
+        )
+        .unwrap();
+        assert_eq!(signer.public_key(), expected_key);
+
+        let signature = signer.sign(b"payload").unwrap();
+        assert!(verify_signature(&expected_key, b"payload", &signature).unwrap());
+
+        signer.health_check().unwrap();
+        assert_eq!(signer.transport.signs.load(Ordering::SeqCst), 1);
+    }
+}
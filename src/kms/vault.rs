@@ -0,0 +1,172 @@
+use super::{
+    check_public_key, decode_base64, json_body, send_json, string_at, HttpRequest, HttpTransport,
+};
+use serde_json::Value;
+use crate::signer::{Signer, SignerError};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::json;
+
+/// Signs with an `ed25519` key in HashiCorp Vault's transit engine. The key
+/// version is pinned at connect time so a rotation in Vault cannot silently
+/// change the issuer's key.
+pub struct VaultTransitSigner<T> {
+    transport: T,
+    address: String,
+    token: String,
+    key_name: String,
+    key_version: u64,
+    public_key: Vec<u8>,
+}
+
+impl<T: HttpTransport> VaultTransitSigner<T> {
+    pub fn connect(
+        transport: T,
+        address: &str,
+        token: String,
+        key_name: &str,
+    ) -> Result<Self, SignerError> {
+        let address = address.trim_end_matches('/').to_string();
+        let response = read_key(&transport, &address, &token, key_name)?;
+        let key_version = response
+            .pointer("/data/latest_version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| SignerError::InvalidResponse("missing latest_version".to_string()))?;
+        let public_key = public_key_at(&response, key_version)?;
+
+        Ok(Self {
+            transport,
+            address,
+            token,
+            key_name: key_name.to_string(),
+            key_version,
+            public_key,
+        })
+    }
+}
+
+/// Reads the transit key's metadata, checking that it is an ed25519 key.
+fn read_key(
+    transport: &dyn HttpTransport,
+    address: &str,
+    token: &str,
+    key_name: &str,
+) -> Result<Value, SignerError> {
+    let request = HttpRequest {
+        method: "GET",
+        url: format!("{}/v1/transit/keys/{}", address, key_name),
+        headers: vec![("X-Vault-Token".to_string(), token.to_string())],
+        body: Vec::new(),
+    };
+    let response = send_json(transport, &request)?;
+    if string_at(&response, "/data/type")? != "ed25519" {
+        return Err(SignerError::InvalidResponse(format!(
+            "transit key {} is not ed25519",
+            key_name
+        )));
+    }
+    Ok(response)
+}
+
+fn public_key_at(response: &Value, key_version: u64) -> Result<Vec<u8>, SignerError> {
+    decode_base64(string_at(
+        response,
+        &format!("/data/keys/{}/public_key", key_version),
+    )?)
+}
+
+impl<T: HttpTransport> Signer for VaultTransitSigner<T> {
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignerError> {
+        let request = HttpRequest {
+            method: "POST",
+            url: format!("{}/v1/transit/sign/{}", self.address, self.key_name),
+            headers: vec![
+                ("X-Vault-Token".to_string(), self.token.clone()),
+                ("Content-Type".to_string(), "application/json".to_string()),
+            ],
+            body: json_body(&json!({
+                "input": STANDARD.encode(message),
+                "key_version": self.key_version,
+            })),
+        };
+        let response = send_json(&self.transport, &request)?;
+
+        // Signatures look like `vault:v1:<base64>`.
+        let signature = string_at(&response, "/data/signature")?;
+        let encoded = signature.rsplit(':').next().unwrap_or(signature);
+        decode_base64(encoded)
+    }
+
+    /// Reads the key's metadata; the pinned version must still be present,
+    /// i.e. not trimmed after a rotation in Vault.
+    fn health_check(&self) -> Result<(), SignerError> {
+        let response = read_key(&self.transport, &self.address, &self.token, &self.key_name)?;
+        check_public_key(&self.public_key, &public_key_at(&response, self.key_version)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::HttpResponse;
+    use super::*;
+    use crate::crypto::{verify_signature, CryptoKeyPair};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FakeVault {
+        key: CryptoKeyPair,
+        signs: AtomicUsize,
+    }
+
+    impl HttpTransport for FakeVault {
+        fn send(&self, request: &HttpRequest) -> Result<HttpResponse, SignerError> {
+            assert!(request
+                .headers
+                .contains(&("X-Vault-Token".to_string(), "s.token".to_string())));
+            let body = if request.url.ends_with("/v1/transit/keys/issuer") {
+                json!({"data": {
+                    "type": "ed25519",
+                    "latest_version": 2,
+                    "keys": {"2": {"public_key": STANDARD.encode(self.key.public_key())}},
+                }})
+            } else {
+                self.signs.fetch_add(1, Ordering::SeqCst);
+                let body: Value = serde_json::from_slice(&request.body).unwrap();
+                assert_eq!(body["key_version"], 2);
+                let input = STANDARD.decode(body["input"].as_str().unwrap()).unwrap();
+                let signature = STANDARD.encode(self.key.sign(&input));
+                json!({"data": {"signature": format!("vault:v2:{}", signature)}})
+            };
+            Ok(HttpResponse {
+                status: 200,
+                body: body.to_string().into_bytes(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_vault_transit_signatures_verify() {
+        let key = CryptoKeyPair::generate();
+        let expected_key = key.public_key();
+        let signer = VaultTransitSigner::connect(
+            FakeVault {
+                key,
+                signs: AtomicUsize::new(0),
+            },
+            "https://vault.internal:8200/",
+            "s.token".to_string(),
+            "issuer",
+        )
+        .unwrap();
+        assert_eq!(signer.public_key(), expected_key);
+        signer.health_check().unwrap();
+        assert_eq!(signer.transport.signs.load(Ordering::SeqCst), 0);
+
+        let signature = signer.sign(b"payload").unwrap();
+        assert!(verify_signature(&expected_key, b"payload", &signature).unwrap());
+        assert_eq!(signer.transport.signs.load(Ordering::SeqCst), 1);
+    }
+}
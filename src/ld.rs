@@ -0,0 +1,521 @@
+//! W3C Verifiable Credential export with Linked Data proofs
+//! (`Ed25519Signature2020` and the `eddsa-rdfc-2022` Data Integrity
+//! cryptosuite), so exported credentials verify in JSON-LD based verifier
+//! libraries.
+//!
+//! Both suites sign `SHA-256(canonical proof options) || SHA-256(canonical
+//! document)`, where canonical means RDFC-1.0 N-Quads of the JSON-LD.
+
+use crate::crypto::verify_signature;
+use crate::multibase;
+use crate::proof::ProofPurpose;
+use crate::signer::{Signer, SignerError};
+use crate::Microcredential;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+
+pub mod canon;
+pub mod context;
+pub mod expand;
+pub mod rdf;
+
+use context::{
+    ContextLoader, CREDENTIALS_V2, DATA_INTEGRITY_V2, ED25519_2020_V1, ISSUER_DEPENDENT_VOCAB,
+    OPEN_BADGES_V3,
+};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LdError {
+    UnknownContext(String),
+    InvalidContext(String),
+    InvalidDocument(String),
+    /// Properties that no context defines; JSON-LD processing drops them, so
+    /// they would not be covered by a proof.
+    UndefinedTerms(Vec<String>),
+    Unsupported(String),
+    CanonicalizationLimit,
+    MissingProof,
+    UnknownSuite(String),
+    InvalidProofValue,
+    Signer(SignerError),
+}
+
+impl fmt::Display for LdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LdError::UnknownContext(url) => write!(f, "Unknown context {}", url),
+            LdError::InvalidContext(msg) => write!(f, "Invalid context: {}", msg),
+            LdError::InvalidDocument(msg) => write!(f, "Invalid JSON-LD document: {}", msg),
+            LdError::UndefinedTerms(terms) => {
+                write!(f, "Terms not defined by any context: {}", terms.join(", "))
+            }
+            LdError::Unsupported(feature) => write!(f, "Unsupported JSON-LD feature: {}", feature),
+            LdError::CanonicalizationLimit => {
+                write!(f, "Canonicalization exceeded its complexity limit")
+            }
+            LdError::MissingProof => write!(f, "Document has no proof"),
+            LdError::UnknownSuite(suite) => write!(f, "Unknown proof suite {}", suite),
+            LdError::InvalidProofValue => write!(f, "Invalid proof value"),
+            LdError::Signer(e) => write!(f, "Signing failed: {}", e),
+        }
+    }
+}
+
+impl Error for LdError {}
+
+impl From<SignerError> for LdError {
+    fn from(e: SignerError) -> Self {
+        LdError::Signer(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LdSuite {
+    Ed25519Signature2020,
+    /// `DataIntegrityProof` with cryptosuite `eddsa-rdfc-2022`.
+    EddsaRdfc2022,
+}
+
+impl LdSuite {
+    pub fn context(&self) -> &'static str {
+        match self {
+            LdSuite::Ed25519Signature2020 => ED25519_2020_V1,
+            LdSuite::EddsaRdfc2022 => DATA_INTEGRITY_V2,
+        }
+    }
+
+    fn proof_fields(&self) -> Map<String, Value> {
+        let mut fields = Map::new();
+        match self {
+            LdSuite::Ed25519Signature2020 => {
+                fields.insert("type".to_string(), json!("Ed25519Signature2020"));
+            }
+            LdSuite::EddsaRdfc2022 => {
+                fields.insert("type".to_string(), json!("DataIntegrityProof"));
+                fields.insert("cryptosuite".to_string(), json!("eddsa-rdfc-2022"));
+            }
+        }
+        fields
+    }
+
+    pub fn from_proof(proof: &Value) -> Result<Self, LdError> {
+        match (
+            proof.get("type").and_then(Value::as_str),
+            proof.get("cryptosuite").and_then(Value::as_str),
+        ) {
+            (Some("Ed25519Signature2020"), _) => Ok(LdSuite::Ed25519Signature2020),
+            (Some("DataIntegrityProof"), Some("eddsa-rdfc-2022")) => Ok(LdSuite::EddsaRdfc2022),
+            (kind, cryptosuite) => Err(LdError::UnknownSuite(format!(
+                "{} {}",
+                kind.unwrap_or("?"),
+                cryptosuite.unwrap_or("")
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LdProofOptions {
+    pub suite: LdSuite,
+    pub verification_method: String,
+    pub created: DateTime<Utc>,
+    pub purpose: ProofPurpose,
+}
+
+/// RDF quads of a JSON-LD document, resolving remote contexts with
+/// `loader`.
+pub fn to_rdf(document: &Value, loader: &dyn ContextLoader) -> Result<Vec<rdf::Quad>, LdError> {
+    let mut rdf = expand::ToRdf::new(loader);
+    rdf.document(document)?;
+    Ok(rdf.quads)
+}
+
+/// RDFC-1.0 canonical N-Quads of a JSON-LD document.
+pub fn canonicalize(document: &Value, loader: &dyn ContextLoader) -> Result<String, LdError> {
+    canon::canonicalize(&to_rdf(document, loader)?)
+}
+
+/// Checks that every context of `document` resolves through `loader` and
+/// that every property is defined by one of them. Properties that only the
+/// VC 2.0 issuer-dependent `@vocab` covers count as undefined.
+pub fn validate_terms(document: &Value, loader: &dyn ContextLoader) -> Result<(), LdError> {
+    let mut rdf = expand::ToRdf::new(loader);
+    rdf.document(document)?;
+    let mut undefined = rdf.dropped;
+    undefined.extend(rdf.issuer_dependent);
+    if undefined.is_empty() {
+        Ok(())
+    } else {
+        undefined.sort();
+        undefined.dedup();
+        Err(LdError::UndefinedTerms(undefined))
+    }
+}
+
+/// Appends `url` to the `@context` of `document` unless already present.
+pub fn add_context(document: &mut Value, url: &str) -> Result<(), LdError> {
+    let object = document
+        .as_object_mut()
+        .ok_or_else(|| LdError::InvalidDocument("document must be an object".to_string()))?;
+    let url = json!(url);
+    match object.get_mut("@context") {
+        Some(Value::Array(contexts)) => {
+            if !contexts.contains(&url) {
+                contexts.push(url);
+            }
+        }
+        Some(other) => {
+            if *other != url {
+                *other = json!([other.clone(), url]);
+            }
+        }
+        None => {
+            object.insert("@context".to_string(), json!([url]));
+        }
+    }
+    Ok(())
+}
+
+/// Unsigned W3C VC (data model 2.0) form of `credential`, as an Open Badges
+/// 3.0 `OpenBadgeCredential`: the skill is the achievement, and the result
+/// points at the rubric criterion level for the skill level.
+pub fn credential_document(credential: &Microcredential) -> Value {
+    let id = urn(&credential.id);
+    let skill = &credential.skill;
+    let subject = &credential.subject;
+    let scale = format!("{}#level-scale", id);
+    let level = format!("{}#level-{}", id, skill.level.rank());
+    let identity = |kind: &str, value: &str| {
+        json!({
+            "type": "IdentityObject",
+            "identityType": kind,
+            "hashed": false,
+            "identityHash": value,
+        })
+    };
+    let mut document = json!({
+        "@context": [CREDENTIALS_V2, OPEN_BADGES_V3, extension_context()],
+        "id": id,
+        "type": ["VerifiableCredential", "OpenBadgeCredential"],
+        "name": skill.name,
+        "issuer": {
+            "id": credential.issuer.url,
+            "type": ["Profile"],
+            "name": credential.issuer.name,
+        },
+        "validFrom": xsd_date_time(credential.valid_from.as_ref().unwrap_or(&credential.issued_at)),
+        "awardedDate": xsd_date_time(&credential.issued_at),
+        "credentialSubject": {
+            "id": urn(&subject.id),
+            "type": ["AchievementSubject"],
+            "identifier": [
+                identity("name", &subject.name),
+                identity("emailAddress", &subject.email),
+            ],
+            "achievement": {
+                "type": ["Achievement"],
+                "humanCode": skill.id,
+                "name": skill.name,
+                "description": skill.description,
+                "resultDescription": [{
+                    "id": scale,
+                    "type": ["ResultDescription"],
+                    "name": skill.level.scale_id(),
+                    "resultType": "RubricCriterionLevel",
+                    "rubricCriterionLevel": [{
+                        "id": level,
+                        "type": ["RubricCriterionLevel"],
+                        "name": skill.level.label(),
+                        "level": skill.level.rank().to_string(),
+                    }],
+                }],
+            },
+            "result": [{
+                "type": ["Result"],
+                "resultDescription": scale,
+                "achievedLevel": level,
+            }],
+        },
+    });
+
+    let object = document.as_object_mut().expect("document is an object");
+    if let Some(expires_at) = &credential.expires_at {
+        object.insert("validUntil".to_string(), json!(xsd_date_time(expires_at)));
+    }
+    if !credential.evidence.is_empty() {
+        let evidence: Vec<Value> = credential
+            .evidence
+            .iter()
+            .map(|evidence| {
+                let mut item = json!({
+                    "id": urn(&evidence.id),
+                    "type": ["Evidence"],
+                    "name": evidence.name,
+                    "description": evidence.description,
+                    "evidenceType": evidence.evidence_type,
+                });
+                if evidence.url.contains(':') {
+                    item["url"] = json!(evidence.url);
+                }
+                item
+            })
+            .collect();
+        object.insert("evidence".to_string(), json!(evidence));
+    }
+    if !credential.metadata.is_empty() {
+        object.insert("metadata".to_string(), json!(credential.metadata));
+    }
+    document
+}
+
+/// Terms for the fields neither VC 2.0 nor Open Badges 3.0 defines. Custom
+/// evidence attributes and metadata are free-form, so they are signed as
+/// JSON literals.
+fn extension_context() -> Value {
+    json!({
+        "evidenceType": {
+            "@id": format!("{}evidenceType", ISSUER_DEPENDENT_VOCAB),
+            "@type": "@json",
+        },
+        "metadata": {
+            "@id": format!("{}metadata", ISSUER_DEPENDENT_VOCAB),
+            "@type": "@json",
+        },
+        "url": {"@id": "https://schema.org/url", "@type": "@id"},
+    })
+}
+
+/// Signs `document` with `signer` and attaches the proof. The suite's
+/// context is appended to `@context` if missing, unless the VC 2.0 context
+/// already defines the proof type. Documents with undefined
+/// terms are rejected, since those terms would not be signed.
+pub fn add_proof(
+    document: &mut Value,
+    signer: &dyn Signer,
+    options: &LdProofOptions,
+    loader: &dyn ContextLoader,
+) -> Result<(), LdError> {
+    if document.get("proof").is_some() {
+        return Err(LdError::Unsupported("multiple proofs".to_string()));
+    }
+    let defined = options.suite == LdSuite::EddsaRdfc2022
+        && match document.get("@context") {
+            Some(Value::Array(contexts)) => contexts.contains(&json!(CREDENTIALS_V2)),
+            Some(context) => *context == CREDENTIALS_V2,
+            None => false,
+        };
+    if !defined {
+        add_context(document, options.suite.context())?;
+    }
+    validate_terms(document, loader)?;
+
+    let mut proof = options.suite.proof_fields();
+    proof.insert(
+        "created".to_string(),
+        json!(xsd_date_time(&options.created)),
+    );
+    proof.insert(
+        "verificationMethod".to_string(),
+        json!(options.verification_method),
+    );
+    proof.insert("proofPurpose".to_string(), json!(options.purpose));
+    let mut proof = Value::Object(proof);
+
+    let signature = signer.sign(&verify_data(document, &proof, loader)?)?;
+    proof["proofValue"] = json!(multibase::encode_base58btc(&signature));
+    document["proof"] = proof;
+    Ok(())
+}
+
+/// The proof attached to `document`, without its `proofValue`.
+pub fn proof_options(document: &Value) -> Result<Value, LdError> {
+    let mut proof = document
+        .get("proof")
+        .cloned()
+        .ok_or(LdError::MissingProof)?;
+    if let Some(object) = proof.as_object_mut() {
+        object.remove("proofValue");
+    }
+    Ok(proof)
+}
+
+/// Checks the proof on `document` against `public_key`. The caller decides
+/// whether the proof's `verificationMethod` belongs to a trusted issuer.
+pub fn verify_proof(
+    document: &Value,
+    public_key: &[u8],
+    loader: &dyn ContextLoader,
+) -> Result<bool, LdError> {
+    let mut unsigned = document.clone();
+    let proof = unsigned
+        .as_object_mut()
+        .and_then(|object| object.remove("proof"))
+        .ok_or(LdError::MissingProof)?;
+    LdSuite::from_proof(&proof)?;
+    let signature = proof
+        .get("proofValue")
+        .and_then(Value::as_str)
+        .and_then(|value| multibase::decode(value).ok())
+        .ok_or(LdError::InvalidProofValue)?;
+    let options = proof_options(document)?;
+
+    let data = verify_data(&unsigned, &options, loader)?;
+    verify_signature(public_key, &data, &signature).map_err(|_| LdError::InvalidProofValue)
+}
+
+fn verify_data(
+    document: &Value,
+    proof_options: &Value,
+    loader: &dyn ContextLoader,
+) -> Result<Vec<u8>, LdError> {
+    let mut options = proof_options.clone();
+    options["@context"] = document.get("@context").cloned().unwrap_or(Value::Null);
+    let mut data = Sha256::digest(canonicalize(&options, loader)?.as_bytes()).to_vec();
+    data.extend(Sha256::digest(canonicalize(document, loader)?.as_bytes()));
+    Ok(data)
+}
+
+fn urn(id: &uuid::Uuid) -> String {
+    format!("urn:uuid:{}", id)
+}
+
+fn xsd_date_time(at: &DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::context::{OfflineContextLoader, CREDENTIALS_V1};
+    use super::*;
+    use crate::crypto::CryptoKeyPair;
+
+    #[test]
+    fn test_contexts_resolve_through_the_loader() {
+        let partner = "https://partner.example/contexts/v1";
+        let mut document = json!({
+            "@context": [CREDENTIALS_V1, partner],
+            "id": "urn:uuid:1",
+            "type": "VerifiableCredential",
+            "issuanceDate": "2024-01-01T00:00:00Z",
+            "credentialSubject": {"id": "did:example:alice", "cohort": "2024"},
+        });
+        let mut loader = OfflineContextLoader::new();
+        assert!(matches!(
+            validate_terms(&document, &loader),
+            Err(LdError::UnknownContext(url)) if url == partner
+        ));
+
+        loader
+            .insert(
+                partner,
+                json!({"@context": {"cohort": "https://partner.example/vocab#cohort"}}),
+            )
+            .unwrap();
+        validate_terms(&document, &loader).unwrap();
+
+        document["credentialSubject"]["nickname"] = json!("Al");
+        document["grade"] = json!("A");
+        assert!(matches!(
+            validate_terms(&document, &loader),
+            Err(LdError::UndefinedTerms(terms)) if terms == ["grade", "nickname"]
+        ));
+        let options = LdProofOptions {
+            suite: LdSuite::EddsaRdfc2022,
+            verification_method: "https://test.edu#key-1".to_string(),
+            created: Utc::now(),
+            purpose: ProofPurpose::AssertionMethod,
+        };
+        assert!(matches!(
+            add_proof(&mut document, &CryptoKeyPair::generate(), &options, &loader),
+            Err(LdError::UndefinedTerms(_))
+        ));
+    }
+
+    /// The `eddsa-rdfc-2022` test vector from the W3C Data Integrity EdDSA
+    /// Cryptosuites specification.
+    #[test]
+    fn test_eddsa_rdfc_2022_known_answer() {
+        let examples = "https://www.w3.org/ns/credentials/examples/v2";
+        let mut loader = OfflineContextLoader::new();
+        loader
+            .insert(
+                examples,
+                json!({"@context": {"@vocab": "https://www.w3.org/ns/credentials/examples#"}}),
+            )
+            .unwrap();
+        let mut document = json!({
+            "@context": [CREDENTIALS_V2, examples],
+            "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+            "type": ["VerifiableCredential", "AlumniCredential"],
+            "name": "Alumni Credential",
+            "description": "A minimum viable example of an Alumni Credential.",
+            "issuer": "https://vc.example/issuers/5678",
+            "validFrom": "2023-01-01T00:00:00Z",
+            "credentialSubject": {
+                "id": "did:example:abcdefgh",
+                "alumniOf": "The School of Examples"
+            }
+        });
+        let public_key = "z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2";
+        let secret_key = "z3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq";
+
+        let nquads = canonicalize(&document, &loader).unwrap();
+        assert_eq!(
+            nquads,
+            "<did:example:abcdefgh> <https://www.w3.org/ns/credentials/examples#alumniOf> \
+             \"The School of Examples\" .\n\
+             <urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> \
+             <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> \
+             <https://www.w3.org/2018/credentials#VerifiableCredential> .\n\
+             <urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> \
+             <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> \
+             <https://www.w3.org/ns/credentials/examples#AlumniCredential> .\n\
+             <urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> <https://schema.org/description> \
+             \"A minimum viable example of an Alumni Credential.\" .\n\
+             <urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> <https://schema.org/name> \
+             \"Alumni Credential\" .\n\
+             <urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> \
+             <https://www.w3.org/2018/credentials#credentialSubject> <did:example:abcdefgh> .\n\
+             <urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> \
+             <https://www.w3.org/2018/credentials#issuer> <https://vc.example/issuers/5678> .\n\
+             <urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33> \
+             <https://www.w3.org/2018/credentials#validFrom> \
+             \"2023-01-01T00:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n"
+        );
+        assert_eq!(
+            hex::encode(Sha256::digest(nquads.as_bytes())),
+            "517744132ae165a5349155bef0bb0cf2258fff99dfe1dbd914b938d775a36017"
+        );
+
+        let seed = multibase::decode(secret_key).unwrap();
+        let key = CryptoKeyPair::from_secret_key(&seed[2..]).unwrap();
+        assert_eq!(
+            key.public_key(),
+            multibase::decode_multikey(public_key).unwrap()
+        );
+        let options = LdProofOptions {
+            suite: LdSuite::EddsaRdfc2022,
+            verification_method: format!("did:key:{}#{}", public_key, public_key),
+            created: "2023-02-24T23:36:38Z".parse().unwrap(),
+            purpose: ProofPurpose::AssertionMethod,
+        };
+        add_proof(&mut document, &key, &options, &loader).unwrap();
+        assert_eq!(document["@context"], json!([CREDENTIALS_V2, examples]));
+        let data = verify_data(&document, &proof_options(&document).unwrap(), &loader).unwrap();
+        assert_eq!(
+            hex::encode(&data[..32]),
+            "bea7b7acfbad0126b135104024a5f1733e705108f42d59668b05c0c50004c6b0"
+        );
+        assert_eq!(
+            document["proof"]["proofValue"],
+            "z2YwC8z3ap7yx1nZYCg4L3j3ApHsF8kgPdSb5xoS1VR7vPG3F561B52hYnQF9\
+             iseabecm3ijx4K1FBTQsCZahKZme"
+        );
+        assert!(verify_proof(&document, &key.public_key(), &loader).unwrap());
+    }
+}
@@ -0,0 +1,368 @@
+//! RDF Dataset Canonicalization (RDFC-1.0, formerly URDNA2015).
+
+use super::rdf::{Quad, Term};
+use super::LdError;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+
+/// Bound on Hash N-Degree Quads invocations, so crafted graphs with many
+/// indistinguishable blank nodes cannot make canonicalization run forever.
+const MAX_NDEGREE_CALLS: usize = 4096;
+
+#[derive(Debug, Clone)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    issued: Vec<(String, String)>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            issued: Vec::new(),
+        }
+    }
+
+    fn get(&self, existing: &str) -> Option<&str> {
+        self.issued
+            .iter()
+            .find(|(old, _)| old == existing)
+            .map(|(_, new)| new.as_str())
+    }
+
+    fn issue(&mut self, existing: &str) -> String {
+        if let Some(id) = self.get(existing) {
+            return id.to_string();
+        }
+        let id = format!("{}{}", self.prefix, self.issued.len());
+        self.issued.push((existing.to_string(), id.clone()));
+        id
+    }
+}
+
+struct Canonicalizer<'a> {
+    quads: &'a [Quad],
+    quads_for: HashMap<String, Vec<usize>>,
+    first_degree: HashMap<String, String>,
+    canonical: IdentifierIssuer,
+    ndegree_calls: usize,
+}
+
+/// Canonical N-Quads for `quads`: blank nodes relabeled `_:c14n0`,
+/// `_:c14n1`, ... and lines sorted.
+pub fn canonicalize(quads: &[Quad]) -> Result<String, LdError> {
+    let mut quads_for: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, quad) in quads.iter().enumerate() {
+        for term in [&quad.subject, &quad.object]
+            .into_iter()
+            .chain(quad.graph.as_ref())
+        {
+            if let Some(label) = term.blank_label() {
+                let entry = quads_for.entry(label.to_string()).or_default();
+                if entry.last() != Some(&index) {
+                    entry.push(index);
+                }
+            }
+        }
+    }
+
+    let mut state = Canonicalizer {
+        quads,
+        quads_for,
+        first_degree: HashMap::new(),
+        canonical: IdentifierIssuer::new("c14n"),
+        ndegree_calls: 0,
+    };
+
+    let mut labels: Vec<String> = state.quads_for.keys().cloned().collect();
+    labels.sort();
+    let mut by_hash: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for label in labels {
+        let hash = state.hash_first_degree(&label);
+        by_hash.entry(hash).or_default().push(label);
+    }
+
+    let mut shared = Vec::new();
+    for (_, labels) in by_hash {
+        if labels.len() == 1 {
+            state.canonical.issue(&labels[0]);
+        } else {
+            shared.push(labels);
+        }
+    }
+
+    for labels in shared {
+        let mut results = Vec::new();
+        for label in &labels {
+            if state.canonical.get(label).is_some() {
+                continue;
+            }
+            let mut issuer = IdentifierIssuer::new("b");
+            issuer.issue(label);
+            results.push(state.hash_n_degree(label, issuer)?);
+        }
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, issuer) in results {
+            for (existing, _) in issuer.issued {
+                state.canonical.issue(&existing);
+            }
+        }
+    }
+
+    let mut lines: Vec<String> = quads
+        .iter()
+        .map(|quad| {
+            let mut quad = quad.clone();
+            for term in quad.terms_mut() {
+                if let Term::Blank(label) = term {
+                    *label = state.canonical.get(label).unwrap_or(label).to_string();
+                }
+            }
+            quad.to_nquads()
+        })
+        .collect();
+    lines.sort();
+    lines.dedup();
+    Ok(lines.concat())
+}
+
+impl Canonicalizer<'_> {
+    fn hash_first_degree(&mut self, label: &str) -> String {
+        if let Some(hash) = self.first_degree.get(label) {
+            return hash.clone();
+        }
+        let mut lines: Vec<String> = self.quads_for[label]
+            .iter()
+            .map(|index| {
+                let mut quad = self.quads[*index].clone();
+                for term in quad.terms_mut() {
+                    if let Term::Blank(other) = term {
+                        *other = if other == label { "a" } else { "z" }.to_string();
+                    }
+                }
+                quad.to_nquads()
+            })
+            .collect();
+        lines.sort();
+        let hash = sha256_hex(lines.concat().as_bytes());
+        self.first_degree.insert(label.to_string(), hash.clone());
+        hash
+    }
+
+    fn hash_related(
+        &mut self,
+        related: &str,
+        quad: &Quad,
+        issuer: &IdentifierIssuer,
+        position: &str,
+    ) -> String {
+        let identifier = match self.canonical.get(related).or_else(|| issuer.get(related)) {
+            Some(id) => format!("_:{}", id),
+            None => self.hash_first_degree(related),
+        };
+        let mut input = position.to_string();
+        if position != "g" {
+            input.push_str(&quad.predicate.to_nquads());
+        }
+        input.push_str(&identifier);
+        sha256_hex(input.as_bytes())
+    }
+
+    fn hash_n_degree(
+        &mut self,
+        label: &str,
+        mut issuer: IdentifierIssuer,
+    ) -> Result<(String, IdentifierIssuer), LdError> {
+        self.ndegree_calls += 1;
+        if self.ndegree_calls > MAX_NDEGREE_CALLS {
+            return Err(LdError::CanonicalizationLimit);
+        }
+
+        let mut related_by_hash: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for index in self.quads_for[label].clone() {
+            let quad = &self.quads[index];
+            let positions = [("s", &quad.subject), ("o", &quad.object)]
+                .into_iter()
+                .chain(quad.graph.as_ref().map(|graph| ("g", graph)));
+            for (position, term) in positions {
+                match term.blank_label() {
+                    Some(related) if related != label => {
+                        let hash = self.hash_related(related, quad, &issuer, position);
+                        related_by_hash
+                            .entry(hash)
+                            .or_default()
+                            .push(related.to_string());
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut data = String::new();
+        for (hash, related) in related_by_hash {
+            data.push_str(&hash);
+            let mut chosen_path = String::new();
+            let mut chosen_issuer = None;
+
+            'permutations: for permutation in permutations(&related) {
+                let mut issuer_copy = issuer.clone();
+                let mut path = String::new();
+                let mut recursion = Vec::new();
+                for node in &permutation {
+                    match self.canonical.get(node) {
+                        Some(id) => path.push_str(&format!("_:{}", id)),
+                        None => {
+                            if issuer_copy.get(node).is_none() {
+                                recursion.push(node.clone());
+                            }
+                            path.push_str(&format!("_:{}", issuer_copy.issue(node)));
+                        }
+                    }
+                    if worse(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+                for node in recursion {
+                    let (result_hash, result_issuer) =
+                        self.hash_n_degree(&node, issuer_copy.clone())?;
+                    path.push_str(&format!("_:{}", issuer_copy.issue(&node)));
+                    path.push_str(&format!("<{}>", result_hash));
+                    issuer_copy = result_issuer;
+                    if worse(&path, &chosen_path) {
+                        continue 'permutations;
+                    }
+                }
+                if chosen_path.is_empty() || path < chosen_path {
+                    chosen_path = path;
+                    chosen_issuer = Some(issuer_copy);
+                }
+            }
+
+            data.push_str(&chosen_path);
+            if let Some(chosen) = chosen_issuer {
+                issuer = chosen;
+            }
+        }
+        Ok((sha256_hex(data.as_bytes()), issuer))
+    }
+}
+
+fn worse(path: &str, chosen: &str) -> bool {
+    !chosen.is_empty() && path.len() >= chosen.len() && path > chosen
+}
+
+fn permutations(items: &[String]) -> Vec<Vec<String>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+    let mut all = Vec::new();
+    for index in 0..items.len() {
+        let mut rest = items.to_vec();
+        let first = rest.remove(index);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, first.clone());
+            all.push(permutation);
+        }
+    }
+    all
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad(subject: Term, object: Term) -> Quad {
+        Quad {
+            subject,
+            predicate: Term::Iri("http://example.org/vocab#next".to_string()),
+            object,
+            graph: None,
+        }
+    }
+
+    fn blank(label: &str) -> Term {
+        Term::Blank(label.to_string())
+    }
+
+    #[test]
+    fn test_canonical_form_ignores_input_labels() {
+        let single = [quad(
+            blank("x"),
+            Term::literal("a", super::super::rdf::XSD_STRING),
+        )];
+        assert_eq!(
+            canonicalize(&single).unwrap(),
+            "_:c14n0 <http://example.org/vocab#next> \"a\" .\n"
+        );
+
+        // A cycle of three indistinguishable nodes needs the n-degree hash.
+        let cycle = |labels: [&str; 3]| {
+            vec![
+                quad(blank(labels[0]), blank(labels[1])),
+                quad(blank(labels[1]), blank(labels[2])),
+                quad(blank(labels[2]), blank(labels[0])),
+            ]
+        };
+        let first = canonicalize(&cycle(["a", "b", "c"])).unwrap();
+        let mut reordered = cycle(["q", "p", "r"]);
+        reordered.reverse();
+        assert_eq!(first, canonicalize(&reordered).unwrap());
+        assert_eq!(first.lines().count(), 3);
+        for label in ["_:c14n0", "_:c14n1", "_:c14n2"] {
+            assert!(first.contains(label));
+        }
+    }
+
+    #[test]
+    fn test_rdfc_spec_examples() {
+        let term = |text: &str| match text.strip_prefix("_:") {
+            Some(label) => Term::Blank(label.to_string()),
+            None => Term::Iri(format!("http://example.com/#{}", text)),
+        };
+        let dataset = |lines: &[[&str; 3]]| {
+            lines
+                .iter()
+                .map(|[s, p, o]| Quad {
+                    subject: term(s),
+                    predicate: term(p),
+                    object: term(o),
+                    graph: None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let unique = dataset(&[
+            ["p", "q", "_:e0"],
+            ["p", "r", "_:e1"],
+            ["_:e0", "s", "u"],
+            ["_:e1", "t", "u"],
+        ]);
+        assert_eq!(
+            canonicalize(&unique).unwrap(),
+            "<http://example.com/#p> <http://example.com/#q> _:c14n0 .\n\
+             <http://example.com/#p> <http://example.com/#r> _:c14n1 .\n\
+             _:c14n0 <http://example.com/#s> <http://example.com/#u> .\n\
+             _:c14n1 <http://example.com/#t> <http://example.com/#u> .\n"
+        );
+
+        let shared = dataset(&[
+            ["p", "q", "_:e0"],
+            ["p", "q", "_:e1"],
+            ["_:e0", "p", "_:e2"],
+            ["_:e1", "p", "_:e3"],
+            ["_:e2", "r", "_:e3"],
+        ]);
+        assert_eq!(
+            canonicalize(&shared).unwrap(),
+            "<http://example.com/#p> <http://example.com/#q> _:c14n2 .\n\
+             <http://example.com/#p> <http://example.com/#q> _:c14n3 .\n\
+             _:c14n0 <http://example.com/#r> _:c14n1 .\n\
+             _:c14n2 <http://example.com/#p> _:c14n1 .\n\
+             _:c14n3 <http://example.com/#p> _:c14n0 .\n"
+        );
+    }
+}
@@ -0,0 +1,825 @@
+//! Active context processing for the subset of JSON-LD 1.1 used by
+//! credentials: term definitions, `@vocab`, compact IRIs, and type- and
+//! property-scoped contexts. `@base`, `@import`, reverse properties and
+//! language maps are not supported.
+
+use super::LdError;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub const CREDENTIALS_V1: &str = "https://www.w3.org/2018/credentials/v1";
+pub const ED25519_2020_V1: &str = "https://w3id.org/security/suites/ed25519-2020/v1";
+pub const DATA_INTEGRITY_V2: &str = "https://w3id.org/security/data-integrity/v2";
+pub const CREDENTIALS_V2: &str = "https://www.w3.org/ns/credentials/v2";
+pub const OPEN_BADGES_V3: &str = "https://purl.imsglobal.org/spec/ob/v3p0/context-3.0.3.json";
+/// Where the VC 2.0 context maps terms no other context defines.
+pub const ISSUER_DEPENDENT_VOCAB: &str = "https://www.w3.org/ns/credentials/issuer-dependent#";
+/// Blockcerts and Chainpoint terms. Open Badges 2.0 certificates pair it
+/// with the Open Badges context, which is not bundled.
+pub const BLOCKCERTS_V2: &str = "https://w3id.org/blockcerts/v2";
+
+/// Remote contexts may include further remote contexts; this bounds the
+/// nesting so cyclic references fail instead of recursing forever.
+const MAX_CONTEXT_DEPTH: usize = 16;
+
+/// Resolves context URLs to context documents.
+pub trait ContextLoader: Send + Sync {
+    fn load(&self, url: &str) -> Result<Arc<Value>, LdError>;
+}
+
+/// Context loader that never touches the network: it serves the contexts
+/// bundled with the crate plus documents cached with `insert`. Verifiers
+/// should pin every context they accept this way rather than fetching
+/// contexts named by the credential.
+#[derive(Debug, Clone)]
+pub struct OfflineContextLoader {
+    documents: HashMap<String, Arc<Value>>,
+}
+
+impl OfflineContextLoader {
+    pub fn new() -> Self {
+        let documents = [
+            CREDENTIALS_V1,
+            CREDENTIALS_V2,
+            ED25519_2020_V1,
+            DATA_INTEGRITY_V2,
+            OPEN_BADGES_V3,
+            BLOCKCERTS_V2,
+        ]
+        .into_iter()
+        .filter_map(|url| {
+            let document = serde_json::from_str(bundled_document(url)?).ok()?;
+            Some((url.to_string(), Arc::new(document)))
+        })
+        .collect();
+        Self { documents }
+    }
+
+    /// Caches `document` (a JSON object with an `@context` entry) for `url`,
+    /// replacing any earlier document for the same URL.
+    pub fn insert(&mut self, url: &str, document: Value) -> Result<(), LdError> {
+        if document.get("@context").is_none() {
+            return Err(LdError::InvalidContext(format!("{} has no @context", url)));
+        }
+        self.documents.insert(url.to_string(), Arc::new(document));
+        Ok(())
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.documents.contains_key(url)
+    }
+}
+
+impl Default for OfflineContextLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextLoader for OfflineContextLoader {
+    fn load(&self, url: &str) -> Result<Arc<Value>, LdError> {
+        self.documents
+            .get(url)
+            .cloned()
+            .ok_or_else(|| LdError::UnknownContext(url.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermDefinition {
+    /// Absolute IRI or keyword.
+    pub id: String,
+    /// `@id`, `@vocab`, `@json` or a datatype IRI.
+    pub type_mapping: Option<String>,
+    pub container: Vec<String>,
+    pub scoped_context: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub terms: HashMap<String, TermDefinition>,
+    pub vocab: Option<String>,
+    /// Context to restore when entering a nested node object, set by
+    /// non-propagating (type-scoped) contexts.
+    pub previous: Option<Box<Context>>,
+}
+
+impl Context {
+    /// Applies `local` on top of this context.
+    pub fn process(
+        &self,
+        local: &Value,
+        propagate: bool,
+        loader: &dyn ContextLoader,
+    ) -> Result<Context, LdError> {
+        let mut result = self.clone();
+        if !propagate && result.previous.is_none() {
+            result.previous = Some(Box::new(self.clone()));
+        }
+        result.apply(local, loader, 0)?;
+        Ok(result)
+    }
+
+    fn apply(
+        &mut self,
+        local: &Value,
+        loader: &dyn ContextLoader,
+        depth: usize,
+    ) -> Result<(), LdError> {
+        if depth > MAX_CONTEXT_DEPTH {
+            return Err(LdError::InvalidContext(
+                "context nesting too deep".to_string(),
+            ));
+        }
+        match local {
+            Value::Array(items) => {
+                for item in items {
+                    self.apply(item, loader, depth)?;
+                }
+            }
+            Value::Null => {
+                self.terms.clear();
+                self.vocab = None;
+            }
+            Value::String(url) => {
+                let document = loader.load(url)?;
+                let context = document
+                    .get("@context")
+                    .ok_or_else(|| LdError::InvalidContext(format!("{} has no @context", url)))?;
+                self.apply(context, loader, depth + 1)?;
+            }
+            Value::Object(map) => {
+                if let Some(import) = map.get("@import") {
+                    return Err(LdError::Unsupported(format!("@import {}", import)));
+                }
+                match map.get("@vocab") {
+                    Some(Value::String(vocab)) => {
+                        self.vocab = Some(self.expand_iri(vocab, true).ok_or_else(|| {
+                            LdError::InvalidContext(format!("invalid @vocab {}", vocab))
+                        })?);
+                    }
+                    Some(Value::Null) => self.vocab = None,
+                    _ => {}
+                }
+                let mut defined = HashMap::new();
+                for term in map.keys().filter(|key| !key.starts_with('@')) {
+                    self.define(map, term, &mut defined)?;
+                }
+            }
+            other => {
+                return Err(LdError::InvalidContext(format!(
+                    "unexpected context value {}",
+                    other
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn define(
+        &mut self,
+        local: &Map<String, Value>,
+        term: &str,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<(), LdError> {
+        match defined.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => {
+                return Err(LdError::InvalidContext(format!(
+                    "cyclic definition of {}",
+                    term
+                )))
+            }
+            None => {
+                defined.insert(term.to_string(), false);
+            }
+        }
+
+        let value = &local[term];
+        let definition = match value {
+            Value::Null => None,
+            Value::String(id) => Some(TermDefinition {
+                id: self.expand_local(local, defined, id)?,
+                type_mapping: None,
+                container: Vec::new(),
+                scoped_context: None,
+            }),
+            Value::Object(map) => {
+                let id = match map.get("@id") {
+                    Some(Value::String(id)) => Some(self.expand_local(local, defined, id)?),
+                    Some(Value::Null) => None,
+                    _ if term.contains(':') => Some(self.expand_local(local, defined, term)?),
+                    _ => Some(
+                        self.vocab
+                            .as_ref()
+                            .map(|vocab| format!("{}{}", vocab, term))
+                            .ok_or_else(|| {
+                                LdError::InvalidContext(format!("{} has no @id", term))
+                            })?,
+                    ),
+                };
+                let type_mapping = match map.get("@type") {
+                    Some(Value::String(kind)) if kind.starts_with('@') => Some(kind.clone()),
+                    Some(Value::String(kind)) => Some(self.expand_local(local, defined, kind)?),
+                    _ => None,
+                };
+                let container = match map.get("@container") {
+                    Some(Value::String(container)) => vec![container.clone()],
+                    Some(Value::Array(items)) => items
+                        .iter()
+                        .filter_map(|item| item.as_str().map(str::to_string))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                id.map(|id| TermDefinition {
+                    id,
+                    type_mapping,
+                    container,
+                    scoped_context: map.get("@context").cloned(),
+                })
+            }
+            other => {
+                return Err(LdError::InvalidContext(format!(
+                    "invalid definition of {}: {}",
+                    term, other
+                )))
+            }
+        };
+
+        match definition {
+            Some(definition) => {
+                self.terms.insert(term.to_string(), definition);
+            }
+            None => {
+                self.terms.remove(term);
+            }
+        }
+        defined.insert(term.to_string(), true);
+        Ok(())
+    }
+
+    /// Expands `value` while defining the local terms it depends on first.
+    fn expand_local(
+        &mut self,
+        local: &Map<String, Value>,
+        defined: &mut HashMap<String, bool>,
+        value: &str,
+    ) -> Result<String, LdError> {
+        let dependency = value.split_once(':').map_or(value, |(prefix, _)| prefix);
+        if local.contains_key(dependency) && defined.get(dependency) != Some(&true) {
+            self.define(local, dependency, defined)?;
+        }
+        self.expand_iri(value, true)
+            .filter(|iri| iri.starts_with('@') || iri.contains(':'))
+            .ok_or_else(|| LdError::InvalidContext(format!("cannot expand {}", value)))
+    }
+
+    /// Expands a term, compact IRI or absolute IRI. Terms and `@vocab` are
+    /// only consulted when `vocab` is set, i.e. for property names, types
+    /// and `@vocab`-typed values.
+    pub fn expand_iri(&self, value: &str, vocab: bool) -> Option<String> {
+        if value.starts_with('@') {
+            return Some(value.to_string());
+        }
+        if vocab {
+            if let Some(definition) = self.terms.get(value) {
+                return Some(definition.id.clone());
+            }
+        }
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            return Some(match self.terms.get(prefix) {
+                Some(definition) => format!("{}{}", definition.id, suffix),
+                None => value.to_string(),
+            });
+        }
+        match (&self.vocab, vocab) {
+            (Some(vocab), true) => Some(format!("{}{}", vocab, value)),
+            _ => None,
+        }
+    }
+}
+
+/// Context documents shipped with the crate. The VC 2.0 and Open Badges 3.0
+/// documents only carry the term definitions microcredentials use; terms
+/// they leave out fall back to the VC 2.0 issuer-dependent `@vocab`.
+fn bundled_document(url: &str) -> Option<&'static str> {
+    match url {
+        CREDENTIALS_V1 => Some(CREDENTIALS_V1_DOCUMENT),
+        CREDENTIALS_V2 => Some(CREDENTIALS_V2_DOCUMENT),
+        ED25519_2020_V1 => Some(ED25519_2020_V1_DOCUMENT),
+        DATA_INTEGRITY_V2 => Some(DATA_INTEGRITY_V2_DOCUMENT),
+        OPEN_BADGES_V3 => Some(OPEN_BADGES_V3_DOCUMENT),
+        BLOCKCERTS_V2 => Some(BLOCKCERTS_V2_DOCUMENT),
+        _ => None,
+    }
+}
+
+const CREDENTIALS_V1_DOCUMENT: &str = r#"{
+  "@context": {
+    "@version": 1.1,
+    "@protected": true,
+    "id": "@id",
+    "type": "@type",
+    "VerifiableCredential": {
+      "@id": "https://www.w3.org/2018/credentials#VerifiableCredential",
+      "@context": {
+        "@version": 1.1,
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "cred": "https://www.w3.org/2018/credentials#",
+        "sec": "https://w3id.org/security#",
+        "xsd": "http://www.w3.org/2001/XMLSchema#",
+        "credentialSchema": {"@id": "cred:credentialSchema", "@type": "@id"},
+        "credentialStatus": {"@id": "cred:credentialStatus", "@type": "@id"},
+        "credentialSubject": {"@id": "cred:credentialSubject", "@type": "@id"},
+        "evidence": {"@id": "cred:evidence", "@type": "@id"},
+        "expirationDate": {"@id": "cred:expirationDate", "@type": "xsd:dateTime"},
+        "holder": {"@id": "cred:holder", "@type": "@id"},
+        "issued": {"@id": "cred:issued", "@type": "xsd:dateTime"},
+        "issuer": {"@id": "cred:issuer", "@type": "@id"},
+        "issuanceDate": {"@id": "cred:issuanceDate", "@type": "xsd:dateTime"},
+        "proof": {"@id": "sec:proof", "@type": "@id", "@container": "@graph"},
+        "refreshService": {"@id": "cred:refreshService", "@type": "@id"},
+        "termsOfUse": {"@id": "cred:termsOfUse", "@type": "@id"},
+        "validFrom": {"@id": "cred:validFrom", "@type": "xsd:dateTime"},
+        "validUntil": {"@id": "cred:validUntil", "@type": "xsd:dateTime"}
+      }
+    },
+    "VerifiablePresentation": {
+      "@id": "https://www.w3.org/2018/credentials#VerifiablePresentation",
+      "@context": {
+        "@version": 1.1,
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "cred": "https://www.w3.org/2018/credentials#",
+        "sec": "https://w3id.org/security#",
+        "holder": {"@id": "cred:holder", "@type": "@id"},
+        "proof": {"@id": "sec:proof", "@type": "@id", "@container": "@graph"},
+        "verifiableCredential": {
+          "@id": "cred:verifiableCredential",
+          "@type": "@id",
+          "@container": "@graph"
+        }
+      }
+    }
+  }
+}"#;
+
+const CREDENTIALS_V2_DOCUMENT: &str = r#"{
+  "@context": {
+    "@protected": true,
+    "id": "@id",
+    "type": "@type",
+    "description": "https://schema.org/description",
+    "name": "https://schema.org/name",
+    "VerifiableCredential": {
+      "@id": "https://www.w3.org/2018/credentials#VerifiableCredential",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "credentialSchema": {
+          "@id": "https://www.w3.org/2018/credentials#credentialSchema",
+          "@type": "@id"
+        },
+        "credentialStatus": {
+          "@id": "https://www.w3.org/2018/credentials#credentialStatus",
+          "@type": "@id"
+        },
+        "credentialSubject": {
+          "@id": "https://www.w3.org/2018/credentials#credentialSubject",
+          "@type": "@id"
+        },
+        "description": "https://schema.org/description",
+        "evidence": {"@id": "https://www.w3.org/2018/credentials#evidence", "@type": "@id"},
+        "issuer": {"@id": "https://www.w3.org/2018/credentials#issuer", "@type": "@id"},
+        "name": "https://schema.org/name",
+        "proof": {"@id": "https://w3id.org/security#proof", "@type": "@id", "@container": "@graph"},
+        "refreshService": {
+          "@id": "https://www.w3.org/2018/credentials#refreshService",
+          "@type": "@id"
+        },
+        "termsOfUse": {"@id": "https://www.w3.org/2018/credentials#termsOfUse", "@type": "@id"},
+        "validFrom": {
+          "@id": "https://www.w3.org/2018/credentials#validFrom",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        },
+        "validUntil": {
+          "@id": "https://www.w3.org/2018/credentials#validUntil",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        }
+      }
+    },
+    "VerifiablePresentation": {
+      "@id": "https://www.w3.org/2018/credentials#VerifiablePresentation",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "holder": {"@id": "https://www.w3.org/2018/credentials#holder", "@type": "@id"},
+        "proof": {"@id": "https://w3id.org/security#proof", "@type": "@id", "@container": "@graph"},
+        "verifiableCredential": {
+          "@id": "https://www.w3.org/2018/credentials#verifiableCredential",
+          "@type": "@id",
+          "@container": "@graph"
+        }
+      }
+    },
+    "DataIntegrityProof": {
+      "@id": "https://w3id.org/security#DataIntegrityProof",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "challenge": "https://w3id.org/security#challenge",
+        "created": {
+          "@id": "http://purl.org/dc/terms/created",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        },
+        "domain": "https://w3id.org/security#domain",
+        "expires": {
+          "@id": "https://w3id.org/security#expiration",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        },
+        "nonce": "https://w3id.org/security#nonce",
+        "previousProof": {
+          "@id": "https://w3id.org/security#previousProof",
+          "@type": "@id"
+        },
+        "proofPurpose": {
+          "@id": "https://w3id.org/security#proofPurpose",
+          "@type": "@vocab",
+          "@context": {
+            "@protected": true,
+            "id": "@id",
+            "type": "@type",
+            "assertionMethod": {
+              "@id": "https://w3id.org/security#assertionMethod",
+              "@type": "@id",
+              "@container": "@set"
+            },
+            "authentication": {
+              "@id": "https://w3id.org/security#authenticationMethod",
+              "@type": "@id",
+              "@container": "@set"
+            }
+          }
+        },
+        "cryptosuite": {
+          "@id": "https://w3id.org/security#cryptosuite",
+          "@type": "https://w3id.org/security#cryptosuiteString"
+        },
+        "proofValue": {
+          "@id": "https://w3id.org/security#proofValue",
+          "@type": "https://w3id.org/security#multibase"
+        },
+        "verificationMethod": {
+          "@id": "https://w3id.org/security#verificationMethod",
+          "@type": "@id"
+        }
+      }
+    },
+    "@vocab": "https://www.w3.org/ns/credentials/issuer-dependent#"
+  }
+}"#;
+
+const ED25519_2020_V1_DOCUMENT: &str = r#"{
+  "@context": {
+    "id": "@id",
+    "type": "@type",
+    "@protected": true,
+    "proof": {
+      "@id": "https://w3id.org/security#proof",
+      "@type": "@id",
+      "@container": "@graph"
+    },
+    "Ed25519Signature2020": {
+      "@id": "https://w3id.org/security#Ed25519Signature2020",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "challenge": "https://w3id.org/security#challenge",
+        "created": {
+          "@id": "http://purl.org/dc/terms/created",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        },
+        "domain": "https://w3id.org/security#domain",
+        "expires": {
+          "@id": "https://w3id.org/security#expiration",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        },
+        "nonce": "https://w3id.org/security#nonce",
+        "proofPurpose": {
+          "@id": "https://w3id.org/security#proofPurpose",
+          "@type": "@vocab",
+          "@context": {
+            "@protected": true,
+            "id": "@id",
+            "type": "@type",
+            "assertionMethod": {
+              "@id": "https://w3id.org/security#assertionMethod",
+              "@type": "@id",
+              "@container": "@set"
+            },
+            "authentication": {
+              "@id": "https://w3id.org/security#authenticationMethod",
+              "@type": "@id",
+              "@container": "@set"
+            }
+          }
+        },
+        "proofValue": {
+          "@id": "https://w3id.org/security#proofValue",
+          "@type": "https://w3id.org/security#multibase"
+        },
+        "verificationMethod": {
+          "@id": "https://w3id.org/security#verificationMethod",
+          "@type": "@id"
+        }
+      }
+    }
+  }
+}"#;
+
+const DATA_INTEGRITY_V2_DOCUMENT: &str = r#"{
+  "@context": {
+    "id": "@id",
+    "type": "@type",
+    "@protected": true,
+    "proof": {
+      "@id": "https://w3id.org/security#proof",
+      "@type": "@id",
+      "@container": "@graph"
+    },
+    "DataIntegrityProof": {
+      "@id": "https://w3id.org/security#DataIntegrityProof",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "challenge": "https://w3id.org/security#challenge",
+        "created": {
+          "@id": "http://purl.org/dc/terms/created",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        },
+        "domain": "https://w3id.org/security#domain",
+        "expires": {
+          "@id": "https://w3id.org/security#expiration",
+          "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+        },
+        "nonce": "https://w3id.org/security#nonce",
+        "previousProof": {
+          "@id": "https://w3id.org/security#previousProof",
+          "@type": "@id"
+        },
+        "proofPurpose": {
+          "@id": "https://w3id.org/security#proofPurpose",
+          "@type": "@vocab",
+          "@context": {
+            "@protected": true,
+            "id": "@id",
+            "type": "@type",
+            "assertionMethod": {
+              "@id": "https://w3id.org/security#assertionMethod",
+              "@type": "@id",
+              "@container": "@set"
+            },
+            "authentication": {
+              "@id": "https://w3id.org/security#authenticationMethod",
+              "@type": "@id",
+              "@container": "@set"
+            }
+          }
+        },
+        "cryptosuite": {
+          "@id": "https://w3id.org/security#cryptosuite",
+          "@type": "https://w3id.org/security#cryptosuiteString"
+        },
+        "proofValue": {
+          "@id": "https://w3id.org/security#proofValue",
+          "@type": "https://w3id.org/security#multibase"
+        },
+        "verificationMethod": {
+          "@id": "https://w3id.org/security#verificationMethod",
+          "@type": "@id"
+        }
+      }
+    }
+  }
+}"#;
+
+const OPEN_BADGES_V3_DOCUMENT: &str = r#"{
+  "@context": {
+    "id": "@id",
+    "type": "@type",
+    "xsd": "https://www.w3.org/2001/XMLSchema#",
+    "OpenBadgeCredential": {
+      "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#OpenBadgeCredential"
+    },
+    "Achievement": {
+      "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#Achievement",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "xsd": "https://www.w3.org/2001/XMLSchema#",
+        "achievementType": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#achievementType",
+          "@type": "xsd:string"
+        },
+        "criteria": {"@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#Criteria"},
+        "humanCode": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#humanCode",
+          "@type": "xsd:string"
+        },
+        "resultDescription": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#ResultDescription",
+          "@container": "@set"
+        }
+      }
+    },
+    "AchievementSubject": {
+      "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#AchievementSubject",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "achievement": {"@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#achievement"},
+        "identifier": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#identifier",
+          "@container": "@set"
+        },
+        "result": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#result",
+          "@container": "@set"
+        }
+      }
+    },
+    "Criteria": {"@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#Criteria"},
+    "Evidence": {
+      "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#Evidence",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "xsd": "https://www.w3.org/2001/XMLSchema#",
+        "genre": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#genre",
+          "@type": "xsd:string"
+        },
+        "narrative": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#narrative",
+          "@type": "xsd:string"
+        }
+      }
+    },
+    "IdentityObject": {
+      "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#IdentityObject",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "xsd": "https://www.w3.org/2001/XMLSchema#",
+        "hashed": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#hashed",
+          "@type": "xsd:boolean"
+        },
+        "identityHash": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#identityHash",
+          "@type": "xsd:string"
+        },
+        "identityType": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#identityType",
+          "@type": "xsd:string"
+        },
+        "salt": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#salt",
+          "@type": "xsd:string"
+        }
+      }
+    },
+    "Profile": {"@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#Profile"},
+    "Result": {
+      "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#Result",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "achievedLevel": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#achievedLevel",
+          "@type": "@id"
+        },
+        "resultDescription": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#resultDescription",
+          "@type": "@id"
+        }
+      }
+    },
+    "ResultDescription": {
+      "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#ResultDescription",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "xsd": "https://www.w3.org/2001/XMLSchema#",
+        "resultType": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#resultType",
+          "@type": "xsd:string"
+        },
+        "rubricCriterionLevel": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#rubricCriterionLevel",
+          "@container": "@set"
+        }
+      }
+    },
+    "RubricCriterionLevel": {
+      "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#RubricCriterionLevel",
+      "@context": {
+        "@protected": true,
+        "id": "@id",
+        "type": "@type",
+        "xsd": "https://www.w3.org/2001/XMLSchema#",
+        "level": {
+          "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#level",
+          "@type": "xsd:string"
+        }
+      }
+    },
+    "awardedDate": {
+      "@id": "https://purl.imsglobal.org/spec/vc/ob/vocab.html#awardedDate",
+      "@type": "xsd:dateTime"
+    }
+  }
+}"#;
+
+const BLOCKCERTS_V2_DOCUMENT: &str = r#"{
+  "@context": {
+    "id": "@id",
+    "type": "@type",
+    "bc": "https://w3id.org/blockcerts#",
+    "obi": "https://w3id.org/openbadges#",
+    "cp": "https://w3id.org/chainpoint#",
+    "schema": "http://schema.org/",
+    "sec": "https://w3id.org/security#",
+    "xsd": "http://www.w3.org/2001/XMLSchema#",
+
+    "MerkleProof2017": "sec:MerkleProof2017",
+
+    "RecipientProfile": "bc:RecipientProfile",
+    "SignatureLine": "bc:SignatureLine",
+
+    "recipientProfile": "bc:recipientProfile",
+    "signatureLines": "bc:signatureLines",
+    "introductionUrl": { "@id": "bc:introductionUrl", "@type": "@id" },
+
+    "subtitle": "bc:subtitle",
+
+    "jobTitle": "schema:jobTitle",
+
+    "creator": { "@id": "dc:creator", "@type": "@id" },
+    "expires": {
+      "@id": "sec:expiration",
+      "@type": "xsd:dateTime"
+    },
+    "revoked": {
+      "@id": "sec:expiration",
+      "@type": "xsd:dateTime"
+    },
+    "CryptographicKey": "sec:Key",
+    "signature": "sec:signature",
+
+    "verification": "bc:verification",
+    "publicKeys": "bc:publicKeys",
+
+    "ChainpointSHA256v2": "cp:ChainpointSHA256v2",
+    "BTCOpReturn": "cp:BTCOpReturn",
+    "targetHash": "cp:targetHash",
+    "merkleRoot": "cp:merkleRoot",
+    "proof": "cp:proof",
+    "anchors": "cp:anchors",
+    "sourceId": "cp:sourceId",
+    "right": "cp:right",
+    "left": "cp:left"
+  },
+  "obi:validation": [
+    {
+      "obi:validatesType": "RecipientProfile",
+      "obi:validationSchema": "https://w3id.org/blockcerts/schema/2.0/recipientSchema.json"
+    },
+    {
+      "obi:validatesType": "SignatureLine",
+      "obi:validationSchema": "https://w3id.org/blockcerts/schema/2.0/signatureLineSchema.json"
+    },
+    {
+      "obi:validatesType": "MerkleProof2017",
+      "obi:validationSchema": "https://w3id.org/blockcerts/schema/2.0/merkleProof2017Schema.json"
+    }
+  ]
+}"#;
@@ -0,0 +1,344 @@
+//! Deserialization of a JSON-LD document straight to RDF quads, without
+//! materializing the expanded form.
+
+use super::context::{Context, ContextLoader, TermDefinition, ISSUER_DEPENDENT_VOCAB};
+use super::rdf::{
+    Quad, Term, RDF_JSON, RDF_TYPE, XSD_BOOLEAN, XSD_DOUBLE, XSD_INTEGER, XSD_STRING,
+};
+use super::LdError;
+use crate::crypto::canonical_json;
+use serde_json::{Map, Value};
+
+pub struct ToRdf<'a> {
+    pub quads: Vec<Quad>,
+    /// Properties that did not expand to an IRI and were dropped, as
+    /// JSON-LD processors silently do.
+    pub dropped: Vec<String>,
+    /// Properties no context defines that VC 2.0 maps into its
+    /// issuer-dependent vocabulary instead of dropping them.
+    pub issuer_dependent: Vec<String>,
+    blank_nodes: usize,
+    loader: &'a dyn ContextLoader,
+}
+
+impl<'a> ToRdf<'a> {
+    pub fn new(loader: &'a dyn ContextLoader) -> Self {
+        Self {
+            quads: Vec::new(),
+            dropped: Vec::new(),
+            issuer_dependent: Vec::new(),
+            blank_nodes: 0,
+            loader,
+        }
+    }
+
+    pub fn document(&mut self, document: &Value) -> Result<(), LdError> {
+        let context = Context::default();
+        match document {
+            Value::Object(map) => {
+                self.node(map, &context, None, &None)?;
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.document(item)?;
+                }
+            }
+            _ => {
+                return Err(LdError::InvalidDocument(
+                    "document must be an object".to_string(),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    fn new_blank(&mut self) -> Term {
+        self.blank_nodes += 1;
+        Term::Blank(format!("b{}", self.blank_nodes - 1))
+    }
+
+    /// `property_scoped` is the scoped context of the property this node is
+    /// the value of; unlike type-scoped contexts it applies inside the node.
+    fn node(
+        &mut self,
+        map: &Map<String, Value>,
+        context: &Context,
+        property_scoped: Option<&Value>,
+        graph: &Option<Term>,
+    ) -> Result<Term, LdError> {
+        let mut context = context.clone();
+        if let Some(previous) = context.previous.take() {
+            context = *previous;
+        }
+        if let Some(scoped) = property_scoped {
+            context = context.process(scoped, true, self.loader)?;
+        }
+        if let Some(local) = map.get("@context") {
+            context = context.process(local, true, self.loader)?;
+        }
+
+        let keyword = |key: &str| {
+            context
+                .expand_iri(key, true)
+                .filter(|iri| iri.starts_with('@'))
+        };
+        let mut types: Vec<&str> = Vec::new();
+        for (key, value) in map {
+            if keyword(key).as_deref() == Some("@type") {
+                for value in as_array(value) {
+                    types.push(value.as_str().ok_or_else(|| {
+                        LdError::InvalidDocument(format!("{} must be a string", key))
+                    })?);
+                }
+            }
+        }
+
+        let mut sorted_types = types.clone();
+        sorted_types.sort();
+        let mut type_context = context.clone();
+        for kind in sorted_types {
+            if let Some(scoped) = scoped_context(context.terms.get(kind)) {
+                type_context = type_context.process(scoped, false, self.loader)?;
+            }
+        }
+
+        let subject = match map
+            .iter()
+            .find(|(key, _)| keyword(key).as_deref() == Some("@id"))
+        {
+            Some((_, Value::String(id))) => reference(&type_context, id, false)?,
+            Some((key, _)) => {
+                return Err(LdError::InvalidDocument(format!(
+                    "{} must be a string",
+                    key
+                )))
+            }
+            None => self.new_blank(),
+        };
+
+        for kind in types {
+            let iri = type_context
+                .expand_iri(kind, true)
+                .filter(|iri| iri.contains(':'))
+                .ok_or_else(|| LdError::InvalidDocument(format!("undefined type {}", kind)))?;
+            self.quads.push(Quad {
+                subject: subject.clone(),
+                predicate: Term::Iri(RDF_TYPE.to_string()),
+                object: Term::Iri(iri),
+                graph: graph.clone(),
+            });
+        }
+
+        for (key, value) in map {
+            if key == "@context" {
+                continue;
+            }
+            let iri = match type_context.expand_iri(key, true) {
+                Some(iri) if iri == "@id" || iri == "@type" => continue,
+                Some(iri) if iri.starts_with('@') => {
+                    return Err(LdError::Unsupported(format!("keyword {}", iri)))
+                }
+                Some(iri) if iri.contains(':') && !iri.starts_with("_:") => iri,
+                _ => {
+                    self.dropped.push(key.clone());
+                    continue;
+                }
+            };
+            let definition = type_context.terms.get(key);
+            if definition.is_none() && iri.starts_with(ISSUER_DEPENDENT_VOCAB) {
+                self.issuer_dependent.push(key.clone());
+            }
+            for item in as_array(value) {
+                if let Some(object) = self.object(item, definition, &type_context, graph)? {
+                    self.quads.push(Quad {
+                        subject: subject.clone(),
+                        predicate: Term::Iri(iri.clone()),
+                        object,
+                        graph: graph.clone(),
+                    });
+                }
+            }
+        }
+        Ok(subject)
+    }
+
+    fn object(
+        &mut self,
+        value: &Value,
+        definition: Option<&TermDefinition>,
+        context: &Context,
+        graph: &Option<Term>,
+    ) -> Result<Option<Term>, LdError> {
+        let scoped = scoped_context(definition);
+        let value_context = match scoped {
+            Some(scoped) => context.process(scoped, true, self.loader)?,
+            None => context.clone(),
+        };
+        let type_mapping = definition.and_then(|definition| definition.type_mapping.as_deref());
+        if type_mapping == Some("@json") {
+            let json =
+                canonical_json(value).map_err(|e| LdError::InvalidDocument(e.to_string()))?;
+            return Ok(Some(Term::literal(
+                String::from_utf8_lossy(&json),
+                RDF_JSON,
+            )));
+        }
+        let datatype = type_mapping.filter(|kind| !kind.starts_with('@'));
+
+        Ok(Some(match value {
+            Value::Null => return Ok(None),
+            Value::Object(map) if map.contains_key("@value") => value_object(map, &value_context)?,
+            Value::Object(map) if map.contains_key("@list") => {
+                return Err(LdError::Unsupported("@list".to_string()))
+            }
+            Value::Object(map) => {
+                let is_graph = definition
+                    .is_some_and(|definition| definition.container.iter().any(|c| c == "@graph"));
+                if is_graph {
+                    let name = self.new_blank();
+                    self.node(map, context, scoped, &Some(name.clone()))?;
+                    name
+                } else {
+                    self.node(map, context, scoped, graph)?
+                }
+            }
+            Value::String(text) => match type_mapping {
+                Some("@id") => reference(&value_context, text, false)?,
+                Some("@vocab") => reference(&value_context, text, true)?,
+                _ => Term::literal(text.clone(), datatype.unwrap_or(XSD_STRING)),
+            },
+            Value::Bool(flag) => Term::literal(flag.to_string(), datatype.unwrap_or(XSD_BOOLEAN)),
+            Value::Number(number) => {
+                let integer = number
+                    .as_i64()
+                    .map(|n| n.to_string())
+                    .or_else(|| number.as_u64().map(|n| n.to_string()));
+                match integer {
+                    Some(integer) if datatype != Some(XSD_DOUBLE) => {
+                        Term::literal(integer, datatype.unwrap_or(XSD_INTEGER))
+                    }
+                    _ => Term::literal(
+                        canonical_double(number.as_f64().unwrap_or(f64::NAN)),
+                        datatype.unwrap_or(XSD_DOUBLE),
+                    ),
+                }
+            }
+            Value::Array(_) => return Err(LdError::Unsupported("nested arrays".to_string())),
+        }))
+    }
+}
+
+fn value_object(map: &Map<String, Value>, context: &Context) -> Result<Term, LdError> {
+    let datatype = match map.get("@type").and_then(Value::as_str) {
+        Some(kind) => context
+            .expand_iri(kind, true)
+            .ok_or_else(|| LdError::InvalidDocument(format!("undefined type {}", kind)))?,
+        None => XSD_STRING.to_string(),
+    };
+    let value = match &map["@value"] {
+        Value::String(text) => text.clone(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Number(number) => number.to_string(),
+        other => {
+            return Err(LdError::InvalidDocument(format!(
+                "invalid @value {}",
+                other
+            )))
+        }
+    };
+    Ok(Term::Literal {
+        value,
+        datatype,
+        language: map
+            .get("@language")
+            .and_then(Value::as_str)
+            .map(str::to_lowercase),
+    })
+}
+
+fn reference(context: &Context, value: &str, vocab: bool) -> Result<Term, LdError> {
+    match value.strip_prefix("_:") {
+        Some(label) => Ok(Term::Blank(format!("u{}", label))),
+        None => context
+            .expand_iri(value, vocab)
+            .filter(|iri| iri.contains(':'))
+            .map(Term::Iri)
+            .ok_or_else(|| LdError::InvalidDocument(format!("{} is not an absolute IRI", value))),
+    }
+}
+
+fn scoped_context(definition: Option<&TermDefinition>) -> Option<&Value> {
+    definition.and_then(|definition| definition.scoped_context.as_ref())
+}
+
+fn as_array(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        other => vec![other],
+    }
+}
+
+/// XSD canonical form for doubles, e.g. `1.5E0`.
+fn canonical_double(value: f64) -> String {
+    let formatted = format!("{:.15E}", value);
+    match formatted.split_once('E') {
+        Some((mantissa, exponent)) => {
+            let mut mantissa = mantissa.trim_end_matches('0').to_string();
+            if mantissa.ends_with('.') {
+                mantissa.push('0');
+            }
+            format!("{}E{}", mantissa, exponent)
+        }
+        None => formatted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::context::OfflineContextLoader;
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_type_scoped_contexts_do_not_propagate() {
+        let document = json!({
+            "@context": [
+                "https://www.w3.org/2018/credentials/v1",
+                {"ex": "https://example.org/", "name": "ex:name", "score": "ex:score"}
+            ],
+            "id": "urn:uuid:1",
+            "type": "VerifiableCredential",
+            "issuanceDate": "2024-01-01T00:00:00Z",
+            "credentialSubject": {
+                "id": "did:example:alice",
+                "name": "Alice",
+                "score": 1.5,
+                // Defined only inside VerifiableCredential's scoped context.
+                "issuer": "https://example.org/issuer"
+            },
+            "unknownTerm": true
+        });
+        let loader = OfflineContextLoader::new();
+        let mut rdf = ToRdf::new(&loader);
+        rdf.document(&document).unwrap();
+        let lines: Vec<String> = rdf.quads.iter().map(Quad::to_nquads).collect();
+
+        assert!(lines.contains(
+            &"<urn:uuid:1> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> \
+              <https://www.w3.org/2018/credentials#VerifiableCredential> .\n"
+                .to_string()
+        ));
+        assert!(lines.contains(
+            &"<urn:uuid:1> <https://www.w3.org/2018/credentials#issuanceDate> \
+              \"2024-01-01T00:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n"
+                .to_string()
+        ));
+        assert!(lines.contains(
+            &"<did:example:alice> <https://example.org/score> \
+              \"1.5E0\"^^<http://www.w3.org/2001/XMLSchema#double> .\n"
+                .to_string()
+        ));
+        assert_eq!(rdf.quads.len(), 5);
+        assert_eq!(rdf.dropped, vec!["issuer", "unknownTerm"]);
+    }
+}
@@ -0,0 +1,109 @@
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+pub const RDF_JSON: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON";
+pub const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+pub const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+pub const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+pub const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Term {
+    Iri(String),
+    /// Label without the `_:` prefix.
+    Blank(String),
+    Literal {
+        value: String,
+        datatype: String,
+        language: Option<String>,
+    },
+}
+
+impl Term {
+    pub fn literal(value: impl Into<String>, datatype: &str) -> Self {
+        Term::Literal {
+            value: value.into(),
+            datatype: datatype.to_string(),
+            language: None,
+        }
+    }
+
+    pub fn blank_label(&self) -> Option<&str> {
+        match self {
+            Term::Blank(label) => Some(label),
+            _ => None,
+        }
+    }
+
+    pub fn to_nquads(&self) -> String {
+        match self {
+            Term::Iri(iri) => format!("<{}>", iri),
+            Term::Blank(label) => format!("_:{}", label),
+            Term::Literal {
+                value,
+                datatype,
+                language,
+            } => {
+                let mut out = format!("\"{}\"", escape(value));
+                if let Some(language) = language {
+                    out.push('@');
+                    out.push_str(language);
+                } else if datatype != XSD_STRING {
+                    out.push_str(&format!("^^<{}>", datatype));
+                }
+                out
+            }
+        }
+    }
+}
+
+/// A triple in the default graph (`graph: None`) or a named graph.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Quad {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+    pub graph: Option<Term>,
+}
+
+impl Quad {
+    /// One canonical N-Quads line, including the trailing newline.
+    pub fn to_nquads(&self) -> String {
+        let mut line = format!(
+            "{} {} {} ",
+            self.subject.to_nquads(),
+            self.predicate.to_nquads(),
+            self.object.to_nquads()
+        );
+        if let Some(graph) = &self.graph {
+            line.push_str(&graph.to_nquads());
+            line.push(' ');
+        }
+        line.push_str(".\n");
+        line
+    }
+
+    pub fn terms_mut(&mut self) -> impl Iterator<Item = &mut Term> {
+        [&mut self.subject, &mut self.object]
+            .into_iter()
+            .chain(self.graph.as_mut())
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if c <= '\u{1f}' || c == '\u{7f}' => {
+                out.push_str(&format!("\\u{:04X}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
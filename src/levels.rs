@@ -0,0 +1,134 @@
+use crate::SkillLevel;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_SCALE_ID: &str = "default";
+
+/// An ordinal proficiency scale. Levels are ranked by their position,
+/// starting at 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelScale {
+    pub id: String,
+    pub name: String,
+    pub levels: Vec<String>,
+}
+
+impl LevelScale {
+    pub fn custom(id: String, name: String, levels: Vec<String>) -> Self {
+        Self { id, name, levels }
+    }
+
+    /// The built-in Beginner → Expert scale used by the plain `SkillLevel`
+    /// variants.
+    pub fn default_scale() -> Self {
+        Self::from_labels(
+            DEFAULT_SCALE_ID,
+            "Default proficiency scale",
+            &["Beginner", "Intermediate", "Advanced", "Expert"],
+        )
+    }
+
+    /// European Qualifications Framework levels 1–8.
+    pub fn eqf() -> Self {
+        Self::from_labels(
+            "eqf",
+            "European Qualifications Framework",
+            &[
+                "EQF 1", "EQF 2", "EQF 3", "EQF 4", "EQF 5", "EQF 6", "EQF 7", "EQF 8",
+            ],
+        )
+    }
+
+    /// Revised Bloom's taxonomy, cognitive process dimension.
+    pub fn blooms() -> Self {
+        Self::from_labels(
+            "blooms",
+            "Bloom's taxonomy",
+            &[
+                "Remember",
+                "Understand",
+                "Apply",
+                "Analyze",
+                "Evaluate",
+                "Create",
+            ],
+        )
+    }
+
+    /// The default, EQF and Bloom's scales.
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::default_scale(), Self::eqf(), Self::blooms()]
+    }
+
+    fn from_labels(id: &str, name: &str, labels: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            levels: labels.iter().map(|label| label.to_string()).collect(),
+        }
+    }
+
+    pub fn level(&self, label: &str) -> Option<SkillLevel> {
+        let index = self.levels.iter().position(|level| level == label)?;
+        self.level_at(index as u32 + 1)
+    }
+
+    pub fn level_at(&self, rank: u32) -> Option<SkillLevel> {
+        if self.id == DEFAULT_SCALE_ID {
+            return match rank {
+                1 => Some(SkillLevel::Beginner),
+                2 => Some(SkillLevel::Intermediate),
+                3 => Some(SkillLevel::Advanced),
+                4 => Some(SkillLevel::Expert),
+                _ => None,
+            };
+        }
+        let label = self.levels.get(rank.checked_sub(1)? as usize)?;
+        Some(SkillLevel::Scaled {
+            scale: self.id.clone(),
+            rank,
+            label: label.clone(),
+        })
+    }
+
+    /// Whether `level` belongs to this scale with a consistent rank and label.
+    pub fn contains(&self, level: &SkillLevel) -> bool {
+        level.scale_id() == self.id
+            && self
+                .levels
+                .get((level.rank() as usize).wrapping_sub(1))
+                .is_some_and(|label| label == level.label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_levels_order_within_a_scale_only() {
+        let eqf = LevelScale::eqf();
+        let eqf5 = eqf.level("EQF 5").unwrap();
+        let eqf6 = eqf.level("EQF 6").unwrap();
+        assert_eq!(eqf5.compare(&eqf6), Some(Ordering::Less));
+        assert!(eqf6.meets(&eqf5));
+        assert!(eqf.contains(&eqf5));
+
+        let apply = LevelScale::blooms().level("Apply").unwrap();
+        assert_eq!(apply.rank(), 3);
+        assert_eq!(apply.compare(&eqf5), None);
+        assert!(!apply.meets(&eqf5));
+
+        let default_scale = LevelScale::default_scale();
+        assert!(matches!(default_scale.level("Advanced"), Some(SkillLevel::Advanced)));
+        assert!(SkillLevel::Expert.meets(&SkillLevel::Intermediate));
+        assert!(default_scale.contains(&SkillLevel::Beginner));
+
+        let beyond = SkillLevel::Scaled {
+            scale: "eqf".to_string(),
+            rank: 9,
+            label: "EQF 9".to_string(),
+        };
+        assert!(!eqf.contains(&beyond));
+    }
+}
@@ -1,5 +1,9 @@
+use crate::clock::Clock;
+use crate::multibase::Multikey;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -8,7 +12,96 @@ pub struct Issuer {
     pub id: Uuid,
     pub name: String,
     pub url: String,
-    pub public_key: Vec<u8>,
+    pub public_key: Multikey,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_history: Vec<IssuerKey>,
+    #[serde(flatten)]
+    pub unknown_fields: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssuerKey {
+    pub public_key: Multikey,
+    pub activated_at: DateTime<Utc>,
+    pub retired_at: Option<DateTime<Utc>>,
+    /// The previous key's signature over `succession_message` for this key,
+    /// so verifiers that trust the previous key can follow a rotation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endorsement: Option<Vec<u8>>,
+}
+
+impl IssuerKey {
+    /// The key as a multikey string (`z6Mk...`).
+    pub fn public_key_multibase(&self) -> String {
+        multibase::encode_multikey(&self.public_key)
+    }
+
+    pub fn is_active_at(&self, at: DateTime<Utc>) -> bool {
+        self.activated_at <= at && self.retired_at.is_none_or(|retired_at| at < retired_at)
+    }
+
+    /// What a retiring key signs to endorse its successor `public_key`.
+    pub fn succession_message(public_key: &[u8]) -> Vec<u8> {
+        [b"microcred-key-succession:".as_slice(), public_key].concat()
+    }
+}
+
+impl Issuer {
+    /// The current key as a multikey string (`z6Mk...`).
+    pub fn public_key_multibase(&self) -> String {
+        multibase::encode_multikey(&self.public_key)
+    }
+
+    /// DID document publishing this issuer's keys, see
+    /// `DidDocument::for_issuer`.
+    pub fn did_document(&self) -> did::DidDocument {
+        did::DidDocument::for_issuer(self)
+    }
+
+    /// JWK Set of this issuer's keys, see `JwkSet::for_issuer`.
+    pub fn jwks(&self) -> jwk::JwkSet {
+        jwk::JwkSet::for_issuer(self)
+    }
+
+    /// Returns the public key that was active at `at`. Issuers without a key
+    /// history only have their current key.
+    pub fn key_at(&self, at: DateTime<Utc>) -> Option<&[u8]> {
+        if self.key_history.is_empty() {
+            return Some(&self.public_key);
+        }
+        self.key_history
+            .iter()
+            .find(|key| key.is_active_at(at))
+            .map(|key| key.public_key.as_slice())
+    }
+
+    /// Whether the current key descends from `trusted_key` through rotations
+    /// in the key history, each endorsed by the key before it.
+    pub fn succeeds(&self, trusted_key: &[u8]) -> bool {
+        let Some(start) = self
+            .key_history
+            .iter()
+            .position(|key| key.public_key == trusted_key)
+        else {
+            return false;
+        };
+        let chain = &self.key_history[start..];
+        let endorsed = chain.windows(2).all(|pair| {
+            let message = IssuerKey::succession_message(&pair[1].public_key);
+            pair[1].endorsement.as_ref().is_some_and(|endorsement| {
+                crypto::verify_signature(&pair[0].public_key, &message, endorsement)
+                    .unwrap_or(false)
+            })
+        });
+        endorsed && chain.last().is_some_and(|key| key.public_key == self.public_key)
+    }
+
+    /// Like `key_at`, but only if the key active at `at` has id `key_id`
+    /// (see `proof::key_id`).
+    pub fn key_with_id(&self, key_id: &str, at: DateTime<Utc>) -> Option<&[u8]> {
+        self.key_at(at)
+            .filter(|public_key| proof::key_id(public_key) == key_id)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +109,8 @@ pub struct Subject {
     pub id: Uuid,
     pub name: String,
     pub email: String,
+    #[serde(flatten)]
+    pub unknown_fields: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,14 +119,69 @@ pub struct Skill {
     pub name: String,
     pub description: String,
     pub level: SkillLevel,
+    #[serde(flatten)]
+    pub unknown_fields: Map<String, Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum SkillLevel {
     Beginner,
     Intermediate,
     Advanced,
     Expert,
+    /// A level on another framework (see `levels::LevelScale`), e.g. EQF 1–8
+    /// or Bloom's taxonomy. `rank` starts at 1 for the lowest level.
+    Scaled {
+        scale: String,
+        rank: u32,
+        label: String,
+    },
+}
+
+impl SkillLevel {
+    pub fn scale_id(&self) -> &str {
+        match self {
+            SkillLevel::Scaled { scale, .. } => scale,
+            _ => levels::DEFAULT_SCALE_ID,
+        }
+    }
+
+    pub fn rank(&self) -> u32 {
+        match self {
+            SkillLevel::Beginner => 1,
+            SkillLevel::Intermediate => 2,
+            SkillLevel::Advanced => 3,
+            SkillLevel::Expert => 4,
+            SkillLevel::Scaled { rank, .. } => *rank,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            SkillLevel::Beginner => "Beginner",
+            SkillLevel::Intermediate => "Intermediate",
+            SkillLevel::Advanced => "Advanced",
+            SkillLevel::Expert => "Expert",
+            SkillLevel::Scaled { label, .. } => label,
+        }
+    }
+
+    /// Orders two levels on the same scale. Levels from different scales are
+    /// not comparable.
+    pub fn compare(&self, other: &SkillLevel) -> Option<Ordering> {
+        if self.scale_id() != other.scale_id() {
+            return None;
+        }
+        Some(self.rank().cmp(&other.rank()))
+    }
+
+    pub fn meets(&self, minimum: &SkillLevel) -> bool {
+        matches!(
+            self.compare(minimum),
+            Some(Ordering::Greater | Ordering::Equal)
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,14 +191,27 @@ pub struct Evidence {
     pub description: String,
     pub url: String,
     pub evidence_type: EvidenceType,
+    #[serde(flatten)]
+    pub unknown_fields: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum EvidenceType {
     Project,
     Assessment,
     Portfolio,
     Certification,
+    /// A type registered in an `evidence::EvidenceTypeRegistry`, with
+    /// attributes checked against its definition.
+    Custom {
+        type_id: String,
+        #[serde(default)]
+        attributes: Map<String, Value>,
+    },
+    /// Free-text type from releases before the evidence type registry.
+    /// Still read, and written back unchanged so old signatures verify.
+    #[deprecated(note = "register the type and use `EvidenceType::Custom`")]
     Other(String),
 }
 
@@ -60,9 +223,46 @@ pub struct Microcredential {
     pub skill: Skill,
     pub evidence: Vec<Evidence>,
     pub issued_at: DateTime<Utc>,
+    /// Start of validity when it differs from issuance, e.g. a credential
+    /// issued ahead of the programme it certifies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<DateTime<Utc>>,
     pub expires_at: Option<DateTime<Utc>>,
     pub metadata: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<attachment::Attachment>,
+    #[serde(rename = "cnf", default, skip_serializing_if = "Option::is_none")]
+    pub holder_binding: Option<HolderBinding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<proof::Proof>,
     pub signature: Option<Vec<u8>>,
+    #[serde(flatten)]
+    pub unknown_fields: Map<String, Value>,
+}
+
+/// Confirmation (`cnf`) claim: the holder key the credential is bound to.
+/// Verifiers can demand proof of possession of this key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HolderBinding {
+    pub public_key: Multikey,
+}
+
+impl HolderBinding {
+    pub fn from_multikey(multikey: &str) -> Result<Self, multibase::MultibaseError> {
+        Ok(Self {
+            public_key: multibase::decode_multikey(multikey)?.into(),
+        })
+    }
+
+    /// The holder key as a multikey string (`z6Mk...`).
+    pub fn public_key_multibase(&self) -> String {
+        multibase::encode_multikey(&self.public_key)
+    }
+
+    /// `did:key` identifier of the holder key.
+    pub fn did(&self) -> String {
+        did::did_key(&self.public_key)
+    }
 }
 
 impl Microcredential {
@@ -80,32 +280,252 @@ impl Microcredential {
             skill,
             evidence,
             issued_at: Utc::now(),
+            valid_from: None,
             expires_at,
             metadata: HashMap::new(),
+            attachments: Vec::new(),
+            holder_binding: None,
+            proof: None,
             signature: None,
+            unknown_fields: Map::new(),
         }
     }
 
-    pub fn is_expired(&self) -> bool {
+    pub fn is_expired(&self, clock: &dyn Clock) -> bool {
+        self.is_expired_at(clock.now())
+    }
+
+    /// Expiry check against an explicit time, e.g. from a `clock::Clock`.
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
         if let Some(expires_at) = self.expires_at {
-            Utc::now() > expires_at
+            now > expires_at
         } else {
             false
         }
     }
 
-    pub fn is_valid(&self) -> bool {
-        !self.is_expired() && self.signature.is_some()
+    pub fn is_valid(&self, clock: &dyn Clock) -> bool {
+        !self.is_expired(clock) && self.signature.is_some()
     }
 
     pub fn add_metadata(&mut self, key: String, value: String) {
         self.metadata.insert(key, value);
     }
+
+    /// Bytes covered by the issuer signature: the credential without its
+    /// signature, as canonical JSON. Unknown fields are included, so
+    /// credentials from newer crate versions keep verifying.
+    pub fn signing_payload(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        match self.proof.as_ref().map(|proof| proof.payload) {
+            Some(proof::PayloadEncoding::Cbor) => cbor::to_canonical_vec(&unsigned),
+            _ => crypto::canonical_json(&unsigned),
+        }
+    }
+
+    /// `signing_payload` written into `buffer`, which is cleared first, so
+    /// bulk signing can reuse one allocation per worker.
+    pub(crate) fn write_signing_payload(
+        &self,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), serde_json::Error> {
+        buffer.clear();
+        let cbor = matches!(
+            self.proof.as_ref().map(|proof| proof.payload),
+            Some(proof::PayloadEncoding::Cbor)
+        );
+        if self.signature.is_some() || cbor {
+            buffer.extend(self.signing_payload()?);
+            return Ok(());
+        }
+        crypto::write_canonical_json(self, buffer)
+    }
+
+    /// `signing_payload` as signed by releases that serialized public keys
+    /// as byte arrays rather than multikeys.
+    pub(crate) fn byte_key_signing_payload(&self) -> Result<Vec<u8>, serde_json::Error> {
+        multibase::with_byte_array_keys(|| self.signing_payload())
+    }
+
+    /// Struct-ordered payload signed by releases before canonical JSON was
+    /// introduced, which also wrote public keys as byte arrays.
+    pub(crate) fn legacy_signing_payload(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        multibase::with_byte_array_keys(|| serde_json::to_vec(&unsigned))
+    }
+
+    /// Structural invariants that hold for every well-formed credential,
+    /// independent of any issuer's `ValidationRules`: consistent dates and
+    /// keys, unique evidence ids, and a signing payload that survives a JSON
+    /// round trip. Downstream pipelines can property-test against it with
+    /// the generators in `arbitrary`. Issuers may sign credentials that have
+    /// already expired, so `expires_at` is not checked against `issued_at`.
+    pub fn invariants(&self) -> validation::ValidationReport {
+        let mut report = validation::ValidationReport::default();
+        if !validation::is_valid_email(&self.subject.email) {
+            report.add_issue("subject.email", "is not a valid email address");
+        }
+        if self.skill.level.rank() == 0 {
+            report.add_issue("skill.level", "rank must start at 1");
+        } else if let Some(scale) = levels::LevelScale::builtin()
+            .into_iter()
+            .find(|scale| scale.id == self.skill.level.scale_id())
+        {
+            if !scale.contains(&self.skill.level) {
+                report.add_issue("skill.level", format!("is not a level of {}", scale.name));
+            }
+        }
+        if self.valid_from.is_some_and(|at| at < self.issued_at) {
+            report.add_issue("valid_from", "must not be before issued_at");
+        }
+        if let (Some(valid_from), Some(expires_at)) = (self.valid_from, self.expires_at) {
+            if valid_from >= expires_at {
+                report.add_issue("valid_from", "must be before expires_at");
+            }
+        }
+        if self.issuer.key_at(self.issued_at).is_none() {
+            report.add_issue("issued_at", "no issuer key is active at this time");
+        }
+        for (index, key) in self.issuer.key_history.iter().enumerate() {
+            if key.retired_at.is_some_and(|at| at < key.activated_at) {
+                report.add_issue(
+                    format!("issuer.key_history[{}].retired_at", index),
+                    "is before activated_at",
+                );
+            }
+        }
+        let mut evidence_ids = std::collections::HashSet::new();
+        for (index, evidence) in self.evidence.iter().enumerate() {
+            if !evidence_ids.insert(evidence.id) {
+                report.add_issue(format!("evidence[{}].id", index), "is not unique");
+            }
+        }
+        if self
+            .holder_binding
+            .as_ref()
+            .is_some_and(|binding| binding.public_key.len() != 32)
+        {
+            report.add_issue("cnf.public_key", "must be a 32-byte Ed25519 public key");
+        }
+        if self.signature.as_ref().is_some_and(|signature| signature.len() != 64) {
+            report.add_issue("signature", "must be a 64-byte Ed25519 signature");
+        }
+        let round_trip = serde_json::to_string(self)
+            .and_then(|json| serde_json::from_str::<Microcredential>(&json))
+            .and_then(|parsed| Ok(parsed.signing_payload()? == self.signing_payload()?));
+        if !round_trip.unwrap_or(false) {
+            report.add_issue("signing_payload", "changes in a JSON round trip");
+        }
+        report
+    }
+
+    /// Truncates every signed timestamp to the precision of `policy`.
+    pub fn normalize_timestamps(&mut self, policy: &timestamps::TimestampPolicy) {
+        self.issued_at = policy.normalize(self.issued_at);
+        self.valid_from = self.valid_from.map(|at| policy.normalize(at));
+        self.expires_at = self.expires_at.map(|at| policy.normalize(at));
+        for key in &mut self.issuer.key_history {
+            key.activated_at = policy.normalize(key.activated_at);
+            key.retired_at = key.retired_at.map(|at| policy.normalize(at));
+        }
+        if let Some(proof) = &mut self.proof {
+            proof.created = proof.created.map(|at| policy.normalize(at));
+        }
+    }
+
+    /// Checks that every signed timestamp is within the precision of
+    /// `policy`, naming the first field that is not.
+    pub fn check_timestamps(
+        &self,
+        policy: &timestamps::TimestampPolicy,
+    ) -> Result<(), timestamps::TimestampError> {
+        let mut fields = vec![
+            ("issued_at".to_string(), Some(self.issued_at)),
+            ("valid_from".to_string(), self.valid_from),
+            ("expires_at".to_string(), self.expires_at),
+            (
+                "proof.created".to_string(),
+                self.proof.as_ref().and_then(|proof| proof.created),
+            ),
+        ];
+        for (index, key) in self.issuer.key_history.iter().enumerate() {
+            let prefix = format!("issuer.key_history[{}].", index);
+            fields.push((format!("{}activated_at", prefix), Some(key.activated_at)));
+            fields.push((format!("{}retired_at", prefix), key.retired_at));
+        }
+        match fields
+            .into_iter()
+            .find(|(_, at)| at.is_some_and(|at| !policy.is_normalized(&at)))
+        {
+            Some((field, _)) => Err(timestamps::TimestampError::ExcessPrecision(field)),
+            None => Ok(()),
+        }
+    }
 }
 
+pub mod analytics;
+pub mod anonymize;
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+pub mod attestation;
+pub mod attachment;
+pub mod audit;
+pub mod blockcerts;
+pub mod builder;
+pub mod bulk;
+pub mod catalog;
+pub mod cbor;
+pub mod challenge;
+pub mod clock;
 pub mod crypto;
+pub mod did;
+#[cfg(feature = "didcomm")]
+pub mod didcomm;
+pub mod directory;
+pub mod duplicates;
+pub mod equivalence;
+pub mod evidence;
+pub mod events;
+pub mod exchange;
+pub mod health;
+pub mod hierarchy;
+pub mod ids;
+pub mod import;
 pub mod issuer;
+pub mod jwk;
+#[cfg(any(feature = "aws-kms", feature = "gcp-kms", feature = "vault"))]
+pub mod kms;
+pub mod ld;
+pub mod levels;
+pub mod lifecycle;
+pub mod linkedin;
+pub mod localization;
+pub mod manifest;
+pub mod mdoc;
+pub mod multibase;
+pub mod offline;
+pub mod openbadges;
+pub mod parse;
+pub mod pkcs8;
+pub mod presentation;
+pub mod preview;
+pub mod profile;
+pub mod proof;
+pub mod queue;
+pub mod salting;
+pub mod signer;
+pub mod status;
+pub mod store;
+pub mod testvectors;
+pub mod timestamps;
+pub mod tokens;
+pub mod validation;
 pub mod verifier;
+#[cfg(feature = "wallet")]
+pub mod wallet;
+pub mod x509;
 
 #[cfg(test)]
 mod tests {
@@ -116,6 +536,8 @@ mod tests {
 
     #[test]
     fn test_credential_creation() {
+        use crate::clock::SystemClock;
+
         let issuer_service = CredentialIssuer::new(
             "Test University".to_string(),
             "https://test.edu".to_string(),
@@ -125,6 +547,7 @@ mod tests {
             id: Uuid::new_v4(),
             name: "Test Student".to_string(),
             email: "test@example.com".to_string(),
+            unknown_fields: Default::default(),
         };
 
         let skill = Skill {
@@ -132,6 +555,7 @@ mod tests {
             name: "Test Skill".to_string(),
             description: "A test skill".to_string(),
             level: SkillLevel::Intermediate,
+            unknown_fields: Default::default(),
         };
 
         let evidence = vec![Evidence {
@@ -140,14 +564,15 @@ mod tests {
             description: "Test evidence description".to_string(),
             url: "https://example.com/evidence".to_string(),
             evidence_type: EvidenceType::Project,
+            unknown_fields: Default::default(),
         }];
 
         let credential = issuer_service
             .issue_credential(subject, skill, evidence, None)
             .unwrap();
 
-        assert!(credential.is_valid());
-        assert!(!credential.is_expired());
+        assert!(credential.is_valid(&SystemClock));
+        assert!(!credential.is_expired(&SystemClock));
         assert!(credential.signature.is_some());
     }
 
@@ -162,6 +587,7 @@ mod tests {
             id: Uuid::new_v4(),
             name: "Test Student".to_string(),
             email: "test@example.com".to_string(),
+            unknown_fields: Default::default(),
         };
 
         let skill = Skill {
@@ -169,6 +595,7 @@ mod tests {
             name: "Test Skill".to_string(),
             description: "A test skill".to_string(),
             level: SkillLevel::Advanced,
+            unknown_fields: Default::default(),
         };
 
         let evidence = vec![Evidence {
@@ -177,6 +604,7 @@ mod tests {
             description: "Test evidence description".to_string(),
             url: "https://example.com/evidence".to_string(),
             evidence_type: EvidenceType::Assessment,
+            unknown_fields: Default::default(),
         }];
 
         let credential = issuer_service
@@ -193,6 +621,8 @@ mod tests {
 
     #[test]
     fn test_expired_credential() {
+        use crate::clock::SystemClock;
+
         let issuer_service = CredentialIssuer::new(
             "Test University".to_string(),
             "https://test.edu".to_string(),
@@ -202,6 +632,7 @@ mod tests {
             id: Uuid::new_v4(),
             name: "Test Student".to_string(),
             email: "test@example.com".to_string(),
+            unknown_fields: Default::default(),
         };
 
         let skill = Skill {
@@ -209,6 +640,7 @@ mod tests {
             name: "Test Skill".to_string(),
             description: "A test skill".to_string(),
             level: SkillLevel::Expert,
+            unknown_fields: Default::default(),
         };
 
         let evidence = vec![Evidence {
@@ -217,6 +649,7 @@ mod tests {
             description: "Test evidence description".to_string(),
             url: "https://example.com/evidence".to_string(),
             evidence_type: EvidenceType::Certification,
+            unknown_fields: Default::default(),
         }];
 
         let past_time = Utc::now() - Duration::days(1);
@@ -224,8 +657,8 @@ mod tests {
             .issue_credential(subject, skill, evidence, Some(past_time))
             .unwrap();
 
-        assert!(credential.is_expired());
-        assert!(!credential.is_valid());
+        assert!(credential.is_expired(&SystemClock));
+        assert!(!credential.is_valid(&SystemClock));
 
         let mut verifier = CredentialVerifier::new();
         verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
@@ -233,4 +666,3349 @@ mod tests {
         let verification_result = verifier.verify_credential(&credential);
         assert!(verification_result.is_err());
     }
-}
\ No newline at end of file
+
+    fn sample_subject() -> Subject {
+        Subject {
+            id: Uuid::new_v4(),
+            name: "Test Student".to_string(),
+            email: "test@example.com".to_string(),
+            unknown_fields: Default::default(),
+        }
+    }
+
+    fn sample_skill() -> Skill {
+        Skill {
+            id: "test-skill".to_string(),
+            name: "Test Skill".to_string(),
+            description: "A test skill".to_string(),
+            level: SkillLevel::Intermediate,
+            unknown_fields: Default::default(),
+        }
+    }
+
+    /// A request for the sample subject and skill, without evidence.
+    fn sample_request() -> crate::issuer::CredentialRequest {
+        crate::builder::CredentialBuilder::new()
+            .subject(sample_subject())
+            .skill(sample_skill())
+            .build()
+            .unwrap()
+    }
+
+    fn sample_credential(issuer_service: &CredentialIssuer) -> Microcredential {
+        sample_credential_expiring(issuer_service, None)
+    }
+
+    fn sample_credential_expiring(
+        issuer_service: &CredentialIssuer,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Microcredential {
+        let evidence = vec![Evidence {
+            id: Uuid::new_v4(),
+            name: "Test Evidence".to_string(),
+            description: "Test evidence description".to_string(),
+            url: "https://example.com/evidence".to_string(),
+            evidence_type: EvidenceType::Project,
+            unknown_fields: Default::default(),
+        }];
+
+        issuer_service
+            .issue_credential(sample_subject(), sample_skill(), evidence, expires_at)
+            .unwrap()
+    }
+
+    /// An unsigned credential, for checks that compare credentials without
+    /// verifying them.
+    fn unsigned_credential(issuer_id: Uuid, subject: &Subject, skill: Skill) -> Microcredential {
+        let issuer = Issuer {
+            id: issuer_id,
+            name: "Test University".to_string(),
+            url: "https://test.edu".to_string(),
+            public_key: vec![0; 32].into(),
+            key_history: Vec::new(),
+            unknown_fields: Default::default(),
+        };
+        Microcredential::new(issuer, subject.clone(), skill, Vec::new(), None)
+    }
+
+    #[test]
+    fn test_suspension_and_revocation() {
+        use crate::status::StatusError;
+        use crate::verifier::VerificationError;
+
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let credential = sample_credential(&issuer_service);
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+
+        issuer_service
+            .suspend_credential(&credential.id, "Integrity review".to_string())
+            .unwrap();
+        verifier.update_status_list(issuer_service.get_status_list().clone());
+        assert!(matches!(
+            verifier.verify_credential(&credential),
+            Err(VerificationError::SuspendedCredential(_))
+        ));
+
+        issuer_service.reinstate_credential(&credential.id).unwrap();
+        verifier.update_status_list(issuer_service.get_status_list().clone());
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        issuer_service
+            .revoke_credential(&credential.id, "Misconduct".to_string())
+            .unwrap();
+        verifier.update_status_list(issuer_service.get_status_list().clone());
+        assert!(matches!(
+            verifier.verify_credential(&credential),
+            Err(VerificationError::RevokedCredential(_))
+        ));
+        assert_eq!(
+            issuer_service.reinstate_credential(&credential.id),
+            Err(StatusError::AlreadyRevoked)
+        );
+    }
+
+    #[test]
+    fn test_key_compromise_response() {
+        use crate::clock::{Clock, ManualClock};
+        use std::sync::Arc;
+
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        issuer_service.set_clock(clock.clone());
+        let before = sample_credential(&issuer_service);
+        clock.advance(Duration::seconds(1));
+        let compromised_at = clock.now();
+        let after = sample_credential(&issuer_service);
+
+        let compromised_key = issuer_service.get_public_key();
+        let mut old_verifier = CredentialVerifier::new();
+        old_verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        // Re-issuing under the compromised key would be pointless.
+        assert!(issuer_service
+            .handle_key_compromise(
+                &compromised_key,
+                compromised_at,
+                std::slice::from_ref(&after),
+            )
+            .is_err());
+        assert!(issuer_service.credential_status(&after.id).is_active());
+
+        clock.advance(Duration::seconds(1));
+        let new_key = issuer_service.rotate_key();
+        let report = issuer_service
+            .handle_key_compromise(
+                &compromised_key,
+                compromised_at,
+                &[before.clone(), after.clone()],
+            )
+            .unwrap();
+
+        assert_eq!(report.revoked, vec![after.id]);
+        assert_eq!(report.reissuance_queue.len(), 1);
+        assert!(issuer_service.credential_status(&before.id).is_active());
+        assert!(!issuer_service.credential_status(&after.id).is_active());
+
+        let reissued = issuer_service
+            .reissue_credential(&report.reissuance_queue[0])
+            .unwrap();
+        assert_eq!(
+            reissued.metadata.get("reissued_from"),
+            Some(&after.id.to_string())
+        );
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        verifier.update_status_list(issuer_service.get_status_list().clone());
+        assert!(verifier.verify_credential(&reissued).unwrap());
+        assert!(verifier.verify_credential(&after).is_err());
+        assert!(verifier.verify_credential(&before).unwrap());
+
+        // Signed by the new key only.
+        assert_eq!(reissued.issuer.public_key, new_key);
+        let digest = crypto::hash_credential(&reissued.signing_payload().unwrap());
+        let signature = reissued.signature.as_ref().unwrap();
+        assert!(crypto::verify_signature(&new_key, &digest, signature).unwrap());
+        assert!(!crypto::verify_signature(&compromised_key, &digest, signature).unwrap());
+        assert!(old_verifier.verify_credential(&reissued).is_err());
+        // Without the revocation list, the retired key still rejects `after`.
+        let mut unrevoked = CredentialVerifier::new();
+        unrevoked.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(unrevoked.verify_credential(&after).is_err());
+    }
+
+    #[test]
+    fn test_key_rotation_keeps_old_credentials_verifiable() {
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let old_credential = sample_credential(&issuer_service);
+        let old_key = issuer_service.get_public_key();
+
+        let new_key = issuer_service.rotate_key();
+        assert_ne!(old_key, new_key);
+        let new_credential = sample_credential(&issuer_service);
+
+        let issuer_info = issuer_service.get_issuer_info();
+        assert_eq!(issuer_info.key_history.len(), 2);
+        assert_eq!(issuer_info.key_at(old_credential.issued_at), Some(&old_key[..]));
+        assert_eq!(issuer_info.key_at(new_credential.issued_at), Some(&new_key[..]));
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_info.clone());
+        assert!(verifier.verify_credential(&old_credential).unwrap());
+        assert!(verifier.verify_credential(&new_credential).unwrap());
+    }
+
+    #[test]
+    fn test_unknown_fields_survive_roundtrip_and_verify() {
+        use crate::crypto::{canonical_json, hash_credential, CryptoKeyPair};
+
+        let issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let credential = sample_credential(&issuer_service);
+
+        // Simulate a credential signed by a newer release with extra fields.
+        let mut value = serde_json::to_value(&credential).unwrap();
+        value["signature"] = serde_json::Value::Null;
+        value["holder_binding"] = serde_json::json!({ "method": "did:key" });
+        value["skill"]["framework"] = serde_json::json!("EQF");
+        let secret_key = issuer_service.try_get_secret_key().unwrap();
+        let keypair = CryptoKeyPair::from_secret_key(&secret_key).unwrap();
+        let signature = keypair.sign(&hash_credential(&canonical_json(&value).unwrap()));
+        value["signature"] = serde_json::json!(signature);
+
+        let parsed: Microcredential = serde_json::from_value(value).unwrap();
+        assert!(parsed.unknown_fields.contains_key("holder_binding"));
+        assert!(parsed.skill.unknown_fields.contains_key("framework"));
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(verifier.verify_credential(&parsed).unwrap());
+
+        let reserialized = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(reserialized["skill"]["framework"], "EQF");
+    }
+
+    #[test]
+    fn test_issuer_restored_from_existing_signer() {
+        use crate::crypto::CryptoKeyPair;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let issuer_info = issuer_service.get_issuer_info().clone();
+        let key = issuer_service.try_get_secret_key().unwrap();
+        let credential = sample_credential(&issuer_service);
+
+        let other_key = Box::new(CryptoKeyPair::generate());
+        assert!(CredentialIssuer::from_existing_signer(issuer_info.clone(), other_key).is_err());
+        let signer = Box::new(CryptoKeyPair::from_secret_key(&key).unwrap());
+        let restored = CredentialIssuer::from_existing_signer(issuer_info, signer).unwrap();
+        let renewed = restored.renew_credential(&credential, None).unwrap();
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(restored.get_issuer_info().clone());
+        assert!(verifier.verify_credential(&renewed).unwrap());
+    }
+
+    #[test]
+    fn test_issue_credential_rejects_invalid_input() {
+        use crate::validation::ValidationReport;
+
+        let issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let subject = Subject {
+            id: Uuid::new_v4(),
+            name: "Test Student".to_string(),
+            email: "not-an-email".to_string(),
+            unknown_fields: Default::default(),
+        };
+        let skill = Skill {
+            id: "test-skill".to_string(),
+            name: "Test Skill".to_string(),
+            description: "A test skill".to_string(),
+            level: SkillLevel::Beginner,
+            unknown_fields: Default::default(),
+        };
+
+        let err = issuer_service
+            .issue_credential(subject, skill, Vec::new(), None)
+            .unwrap_err();
+        let report = err.downcast_ref::<ValidationReport>().unwrap();
+        assert_eq!(report.issues[0].field, "subject.email");
+    }
+
+    #[test]
+    fn test_verifier_policy_minimum_skill_level() {
+        use crate::levels::LevelScale;
+        use crate::verifier::{VerificationError, VerifierPolicy};
+
+        let issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let credential = sample_credential(&issuer_service);
+
+        let mut verifier = CredentialVerifier::with_policy(VerifierPolicy {
+            min_skill_level: Some(SkillLevel::Intermediate),
+            ..Default::default()
+        });
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        verifier.set_policy(VerifierPolicy {
+            min_skill_level: LevelScale::eqf().level("EQF 5"),
+            ..Default::default()
+        });
+        assert!(matches!(
+            verifier.verify_credential(&credential),
+            Err(VerificationError::InsufficientSkillLevel)
+        ));
+
+        // An issuer with a stretched EQF cannot slip past an EQF minimum.
+        let mut stretched = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let mut eqf = LevelScale::eqf();
+        eqf.levels.push("EQF 9".to_string());
+        stretched.set_validation_rules(validation::ValidationRules {
+            level_scales: vec![eqf.clone()],
+            ..Default::default()
+        });
+        let mut request = builder::CredentialBuilder::new()
+            .subject(credential.subject.clone())
+            .skill(credential.skill.clone())
+            .build()
+            .unwrap();
+        request.skill.level = eqf.level("EQF 9").unwrap();
+        let beyond = stretched.issue_request(request).unwrap();
+        verifier.add_trusted_issuer(stretched.get_issuer_info().clone());
+        assert!(matches!(
+            verifier.verify_credential(&beyond),
+            Err(VerificationError::InvalidSkillLevel(_))
+        ));
+    }
+
+    #[test]
+    fn test_issuer_profile_onboarding() {
+        use crate::profile::Accreditation;
+        use crate::verifier::VerificationError;
+
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        issuer_service.add_endpoint(
+            "status".to_string(),
+            "https://test.edu/status".to_string(),
+        );
+        issuer_service.add_accreditation(Accreditation {
+            name: "Regional accreditation".to_string(),
+            accrediting_body: "Accreditation Board".to_string(),
+            url: None,
+            valid_until: None,
+        });
+        let credential = sample_credential(&issuer_service);
+
+        let profile = issuer_service.export_profile().unwrap();
+        let json = serde_json::to_string(&profile).unwrap();
+        let received: profile::IssuerProfile = serde_json::from_str(&json).unwrap();
+        assert_eq!(received.key_fingerprint().len(), 64);
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.import_issuer_profile(&received).unwrap();
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        let mut tampered = received.clone();
+        tampered.issuer.url = "https://evil.example".to_string();
+        assert!(matches!(
+            CredentialVerifier::new().import_issuer_profile(&tampered),
+            Err(VerificationError::InvalidSignature)
+        ));
+
+        // A validly self-signed profile under someone else's key cannot
+        // take over the trusted issuer's id.
+        let impostor_key = crypto::CryptoKeyPair::generate();
+        let mut hijack = received.clone();
+        hijack.issuer.public_key = impostor_key.public_key().into();
+        hijack.signature = Some(
+            impostor_key.sign(&crypto::hash_credential(&hijack.signing_payload().unwrap())),
+        );
+        assert!(hijack.verify_self_signature());
+        assert!(matches!(
+            verifier.import_issuer_profile(&hijack),
+            Err(VerificationError::InvalidSignature)
+        ));
+        assert!(verifier.verify_credential(&credential).unwrap());
+        // Re-importing the issuer's own profile is fine.
+        verifier.import_issuer_profile(&received).unwrap();
+
+        // After a rotation the retired key vouches for its successor.
+        issuer_service.rotate_key();
+        let rotated = issuer_service.export_profile().unwrap();
+        assert!(!rotated.verify_signature(&received.issuer.public_key));
+        verifier.import_issuer_profile(&rotated).unwrap();
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        // An unendorsed key appended to the history does not.
+        let mut hijack = rotated.clone();
+        hijack.issuer.public_key = impostor_key.public_key().into();
+        hijack.issuer.key_history.push(IssuerKey {
+            public_key: impostor_key.public_key().into(),
+            activated_at: Utc::now(),
+            retired_at: None,
+            endorsement: Some(vec![0; 64]),
+        });
+        hijack.signature = Some(
+            impostor_key.sign(&crypto::hash_credential(&hijack.signing_payload().unwrap())),
+        );
+        assert!(matches!(
+            verifier.import_issuer_profile(&hijack),
+            Err(VerificationError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_multi_credential_presentation() {
+        use crate::crypto::CryptoKeyPair;
+        use crate::presentation::Presentation;
+
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let first = sample_credential(&issuer_service);
+        let second = sample_credential(&issuer_service);
+        issuer_service
+            .revoke_credential(&second.id, "Issued in error".to_string())
+            .unwrap();
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+
+        let holder = CryptoKeyPair::generate();
+        let presentation = Presentation::create(
+            vec![first.clone(), second.clone()],
+            &holder,
+            "https://jobs.example.com".to_string(),
+            None,
+            Utc::now(),
+        )
+        .unwrap();
+        assert!(verifier.verify_presentation(&presentation).is_valid());
+
+        verifier.update_status_list(issuer_service.get_status_list().clone());
+        let report = verifier.verify_presentation(&presentation);
+        assert!(report.holder_proof_valid);
+        assert!(!report.is_valid());
+        let failed: Vec<Uuid> = report.failures().map(|check| check.credential_id).collect();
+        assert_eq!(failed, vec![second.id]);
+
+        let mut swapped = presentation.clone();
+        swapped.credentials.pop();
+        assert!(!verifier.verify_presentation(&swapped).holder_proof_valid);
+    }
+
+    #[test]
+    fn test_presentation_audience_binding() {
+        use crate::crypto::CryptoKeyPair;
+        use crate::presentation::Presentation;
+        use crate::verifier::VerifierPolicy;
+
+        let issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let credential = sample_credential(&issuer_service);
+        let holder = CryptoKeyPair::generate();
+        let presentation = Presentation::create(
+            vec![credential],
+            &holder,
+            "https://jobs.example.com".to_string(),
+            Some("jobs.example.com".to_string()),
+            Utc::now(),
+        )
+        .unwrap();
+
+        let mut intended = CredentialVerifier::with_policy(VerifierPolicy {
+            audience: Some("https://jobs.example.com".to_string()),
+            domain: Some("jobs.example.com".to_string()),
+            ..Default::default()
+        });
+        intended.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(intended.verify_presentation(&presentation).is_valid());
+
+        let mut other_site = CredentialVerifier::with_policy(VerifierPolicy {
+            audience: Some("https://other.example.org".to_string()),
+            ..Default::default()
+        });
+        other_site.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        let report = other_site.verify_presentation(&presentation);
+        assert!(report.holder_proof_valid);
+        assert!(!report.audience_valid);
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_holder_bound_credential() {
+        use crate::builder::CredentialBuilder;
+        use crate::challenge::possession_message;
+        use crate::crypto::CryptoKeyPair;
+        use crate::presentation::Presentation;
+        use crate::verifier::{VerificationError, VerifierPolicy};
+
+        let issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let holder = CryptoKeyPair::generate();
+        let template = sample_credential(&issuer_service);
+        let request = CredentialBuilder::new()
+            .subject(template.subject.clone())
+            .skill(template.skill.clone())
+            .holder_key(holder.public_key())
+            .build()
+            .unwrap();
+        let credential = issuer_service.issue_request(request).unwrap();
+        assert_eq!(
+            credential.holder_binding.as_ref().unwrap().public_key,
+            holder.public_key()
+        );
+
+        let mut verifier = CredentialVerifier::with_policy(VerifierPolicy {
+            require_holder_binding: true,
+            ..Default::default()
+        });
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+
+        let (verifier_id, challenge) = ("https://jobs.example.com", b"verifier-nonce-123");
+        let message = possession_message(verifier_id, challenge);
+        assert!(verifier
+            .verify_possession(&credential, verifier_id, challenge, &holder.sign(&message))
+            .unwrap());
+        // Neither a bare signature over the challenge nor one meant for
+        // another verifier proves possession.
+        for signature in [holder.sign(challenge), holder.sign(&message)] {
+            let other = "https://other.example";
+            assert!(matches!(
+                verifier.verify_possession(&credential, other, challenge, &signature),
+                Err(VerificationError::HolderBindingMismatch)
+            ));
+        }
+        let thief = CryptoKeyPair::generate();
+        assert!(matches!(
+            verifier.verify_possession(&credential, verifier_id, challenge, &thief.sign(&message)),
+            Err(VerificationError::HolderBindingMismatch)
+        ));
+
+        let audience = "https://jobs.example.com".to_string();
+        let stolen = Presentation::create(
+            vec![credential.clone()],
+            &thief,
+            audience.clone(),
+            None,
+            Utc::now(),
+        )
+        .unwrap();
+        assert!(!verifier.verify_presentation(&stolen).is_valid());
+        let unbound =
+            Presentation::create(vec![template], &holder, audience, None, Utc::now()).unwrap();
+        assert!(matches!(
+            verifier.verify_presentation(&unbound).credential_checks[0].result,
+            Err(VerificationError::MissingHolderBinding)
+        ));
+    }
+
+    #[test]
+    fn test_issuance_requires_proof_of_possession() {
+        use crate::builder::CredentialBuilder;
+        use crate::challenge::{ChallengeError, PossessionProof};
+        use crate::crypto::CryptoKeyPair;
+
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let template = sample_credential(&issuer_service);
+        let request = CredentialBuilder::new()
+            .subject(template.subject.clone())
+            .skill(template.skill.clone())
+            .build()
+            .unwrap();
+        let holder = CryptoKeyPair::generate();
+
+        let challenge = issuer_service.create_issuance_challenge();
+        let proof = PossessionProof::create(&challenge, &holder);
+        let credential = issuer_service
+            .issue_with_possession_proof(request.clone(), &proof)
+            .unwrap();
+        assert_eq!(
+            credential.holder_binding.unwrap().public_key,
+            holder.public_key()
+        );
+
+        let replay = issuer_service
+            .issue_with_possession_proof(request.clone(), &proof)
+            .unwrap_err();
+        assert_eq!(
+            replay.downcast_ref::<ChallengeError>(),
+            Some(&ChallengeError::UnknownChallenge)
+        );
+
+        let challenge = issuer_service.create_issuance_challenge();
+        let mut forged = PossessionProof::create(&challenge, &CryptoKeyPair::generate());
+        forged.holder_key = holder.public_key();
+        let err = issuer_service
+            .issue_with_possession_proof(request, &forged)
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ChallengeError>(),
+            Some(&ChallengeError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn test_event_handlers_observe_lifecycle() {
+        use crate::events::EventHandler;
+        use crate::verifier::VerificationError;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct Recorder {
+            events: Mutex<Vec<String>>,
+        }
+
+        impl EventHandler for Recorder {
+            fn on_issued(&self, _: &Microcredential) {
+                self.events.lock().unwrap().push("issued".to_string());
+            }
+
+            fn on_revoked(&self, _: &Uuid, reason: &str) {
+                self.events.lock().unwrap().push(format!("revoked: {}", reason));
+            }
+
+            fn on_renewed(&self, previous_id: &Uuid, renewed: &Microcredential) {
+                assert_eq!(renewed.metadata["renewed_from"], previous_id.to_string());
+                self.events.lock().unwrap().push("renewed".to_string());
+            }
+
+            fn on_verified(&self, _: &Microcredential, result: &Result<bool, VerificationError>) {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push(format!("verified: {}", result.is_ok()));
+            }
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        issuer_service.add_event_handler(recorder.clone());
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        verifier.add_event_handler(recorder.clone());
+
+        let credential = sample_credential(&issuer_service);
+        let renewed = issuer_service
+            .renew_credential(&credential, Some(Utc::now() + Duration::days(365)))
+            .unwrap();
+        verifier.verify_credential(&renewed).unwrap();
+        issuer_service
+            .revoke_credential(&credential.id, "Superseded".to_string())
+            .unwrap();
+        assert!(issuer_service.renew_credential(&credential, None).is_err());
+
+        assert_eq!(
+            *recorder.events.lock().unwrap(),
+            vec!["issued", "renewed", "verified: true", "revoked: Superseded"]
+        );
+    }
+
+    #[test]
+    fn test_renewal_is_checked_like_issuance() {
+        use crate::lifecycle::{LifecycleError, LifecycleRecord};
+        use crate::validation::ValidationRules;
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = sample_credential(&issuer_service);
+
+        let mut tampered = credential.clone();
+        tampered.skill.name = "Forged Skill".to_string();
+        assert!(issuer_service.renew_credential(&tampered, None).is_err());
+
+        issuer_service
+            .suspend_credential(&credential.id, "Under review".to_string())
+            .unwrap();
+        assert!(issuer_service.renew_credential(&credential, None).is_err());
+        issuer_service.reinstate_credential(&credential.id).unwrap();
+
+        issuer_service.set_validation_rules(ValidationRules {
+            min_evidence: 2,
+            ..Default::default()
+        });
+        assert!(issuer_service.renew_credential(&credential, None).is_err());
+        issuer_service.set_validation_rules(ValidationRules::default());
+
+        let mut record = LifecycleRecord::renewal(credential.clone(), None, "clerk".to_string());
+        record.submit(&issuer_service, "clerk").unwrap();
+        assert_eq!(
+            record.approve(&issuer_service, "clerk").unwrap_err(),
+            LifecycleError::ApproverIsDrafter
+        );
+        let renewed = record.approve(&issuer_service, "registrar").unwrap();
+        assert_eq!(renewed.metadata["renewed_from"], credential.id.to_string());
+    }
+
+    #[test]
+    fn test_issuer_hierarchy_pins_only_the_root() {
+        use crate::hierarchy::IssuerConstraints;
+        use crate::verifier::VerificationError;
+
+        let root = CredentialIssuer::new(
+            "Accreditation Board".to_string(),
+            "https://board.example.org".to_string(),
+        );
+        let university = CredentialIssuer::new(
+            "Example University".to_string(),
+            "https://example.edu".to_string(),
+        );
+        let department = CredentialIssuer::new(
+            "Computer Science".to_string(),
+            "https://cs.example.edu".to_string(),
+        );
+        let rogue = CredentialIssuer::new(
+            "Rogue Department".to_string(),
+            "https://rogue.example.com".to_string(),
+        );
+        let not_after = Utc::now() + Duration::days(365);
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_root(root.get_issuer_info().clone());
+        let intermediate = root
+            .certify_issuer(
+                university.get_issuer_info().clone(),
+                true,
+                Some(0),
+                IssuerConstraints {
+                    permitted_domains: vec!["example.edu".to_string()],
+                    allowed_skill_domains: vec!["cs.".to_string()],
+                },
+                not_after,
+            )
+            .unwrap();
+        verifier.add_issuer_certificate(intermediate);
+        for leaf in [&department, &rogue] {
+            let certificate = university
+                .certify_issuer(
+                    leaf.get_issuer_info().clone(),
+                    false,
+                    None,
+                    IssuerConstraints::default(),
+                    not_after,
+                )
+                .unwrap();
+            verifier.add_issuer_certificate(certificate);
+        }
+
+        let mut credential = sample_credential(&department);
+        assert!(matches!(
+            verifier.verify_credential(&credential),
+            Err(VerificationError::CertificateConstraintViolation(_))
+        ));
+
+        credential = department
+            .issue_credential(
+                credential.subject.clone(),
+                Skill {
+                    id: "cs.rust".to_string(),
+                    ..credential.skill.clone()
+                },
+                Vec::new(),
+                None,
+            )
+            .unwrap();
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        let rogue_credential = rogue
+            .issue_credential(
+                credential.subject.clone(),
+                credential.skill.clone(),
+                Vec::new(),
+                None,
+            )
+            .unwrap();
+        assert!(matches!(
+            verifier.verify_credential(&rogue_credential),
+            Err(VerificationError::CertificateConstraintViolation(_))
+        ));
+
+        let uncertified = CredentialIssuer::new(
+            "Unknown".to_string(),
+            "https://unknown.example.edu".to_string(),
+        );
+        assert!(matches!(
+            verifier.verify_credential(&sample_credential(&uncertified)),
+            Err(VerificationError::TrustedIssuerNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_cbor_signing_payload() {
+        use crate::verifier::VerificationError;
+
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        issuer_service.set_payload_encoding(proof::PayloadEncoding::Cbor);
+        let mut credential = sample_credential(&issuer_service);
+        assert_eq!(
+            credential.proof.as_ref().map(|proof| proof.payload),
+            Some(proof::PayloadEncoding::Cbor)
+        );
+
+        let cbor = credential.signing_payload().unwrap();
+        let mut unsigned = credential.clone();
+        unsigned.signature = None;
+        assert!(cbor.len() < crypto::canonical_json(&unsigned).unwrap().len());
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        credential.skill.name = "Python".to_string();
+        assert!(matches!(
+            verifier.verify_credential(&credential),
+            Err(VerificationError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_linked_data_export_verifies() {
+        use crate::ld::LdSuite;
+        use crate::verifier::VerificationError;
+
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let credential = sample_credential(&issuer_service);
+        let document = issuer_service
+            .export_linked_data(&credential, LdSuite::EddsaRdfc2022)
+            .unwrap();
+        assert_eq!(document["proof"]["cryptosuite"], "eddsa-rdfc-2022");
+        let method = document["proof"]["verificationMethod"].as_str().unwrap();
+        let did_document = issuer_service.get_issuer_info().did_document();
+        assert!(did_document.verification_method(method).is_some());
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(verifier.verify_linked_data(&document).unwrap());
+
+        let mut tampered = document.clone();
+        tampered["credentialSubject"]["name"] = serde_json::json!("Mallory");
+        assert!(matches!(
+            verifier.verify_linked_data(&tampered),
+            Err(VerificationError::InvalidSignature)
+        ));
+
+        issuer_service
+            .revoke_credential(&credential.id, "Issued in error".to_string())
+            .unwrap();
+        verifier.update_status_list(issuer_service.get_status_list().clone());
+        assert!(matches!(
+            verifier.verify_linked_data(&document),
+            Err(VerificationError::RevokedCredential(_))
+        ));
+    }
+
+    #[test]
+    fn test_linked_data_export_contexts() {
+        use crate::ld::context::OfflineContextLoader;
+        use crate::ld::LdSuite;
+        use crate::verifier::{VerificationError, VerifierPolicy};
+        use std::sync::Arc;
+
+        let partner = "https://partner.example/contexts/v1";
+        let mut loader = OfflineContextLoader::new();
+        loader
+            .insert(partner, serde_json::json!({"@context": {"@version": 1.1}}))
+            .unwrap();
+        let loader = Arc::new(loader);
+
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        issuer_service.add_export_context(partner);
+        let credential = sample_credential(&issuer_service);
+        assert!(issuer_service
+            .export_linked_data(&credential, LdSuite::Ed25519Signature2020)
+            .is_err());
+        issuer_service.set_context_loader(loader.clone());
+        let document = issuer_service
+            .export_linked_data(&credential, LdSuite::Ed25519Signature2020)
+            .unwrap();
+        assert_eq!(document["@context"][3], partner);
+
+        let mut verifier = CredentialVerifier::with_policy(VerifierPolicy {
+            require_defined_terms: true,
+            ..Default::default()
+        });
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(matches!(
+            verifier.verify_linked_data(&document),
+            Err(VerificationError::UnsupportedProof(_))
+        ));
+        verifier.set_context_loader(loader);
+        assert!(verifier.verify_linked_data(&document).unwrap());
+
+        let mut extended = document.clone();
+        extended["credentialSubject"]["nickname"] = serde_json::json!("Al");
+        assert!(matches!(
+            verifier.verify_linked_data(&extended),
+            Err(VerificationError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_public_keys_serialize_as_multikeys() {
+        let key = crypto::CryptoKeyPair::generate();
+        let issuer_service = CredentialIssuer::with_signer(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+            Box::new(crypto::CryptoKeyPair::from_secret_key(&key.secret_key()).unwrap()),
+        );
+        let mut credential = sample_credential(&issuer_service);
+        let json = serde_json::to_value(&credential).unwrap();
+        let multikey = multibase::encode_multikey(&key.public_key());
+        assert_eq!(json["issuer"]["public_key"], multikey);
+        assert_eq!(json["issuer"]["key_history"][0]["public_key"], multikey);
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        // Releases that wrote keys as byte arrays signed that form.
+        let hash = credential.proof.clone().unwrap_or_default().hash;
+        let payload = credential.byte_key_signing_payload().unwrap();
+        credential.signature = Some(key.sign(&hash.digest(&payload)));
+        let stored: Microcredential = serde_json::from_str(
+            &multibase::with_byte_array_keys(|| serde_json::to_string(&credential)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stored.issuer.public_key, key.public_key());
+        assert!(verifier.verify_credential(&stored).unwrap());
+    }
+
+    #[test]
+    fn test_holder_key_accepts_multikey() {
+        use crate::crypto::CryptoKeyPair;
+        use crate::issuer::CredentialRequest;
+
+        let issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let holder = CryptoKeyPair::generate();
+        let multikey = multibase::encode_multikey(&holder.public_key());
+        let request: CredentialRequest = serde_json::from_value(serde_json::json!({
+            "subject": {"id": Uuid::new_v4(), "name": "Alice", "email": "alice@example.com"},
+            "skill": {
+                "id": "rust",
+                "name": "Rust",
+                "description": "Rust programming",
+                "level": "Advanced"
+            },
+            "evidence": [],
+            "expires_at": null,
+            "holder_key": multikey,
+        }))
+        .unwrap();
+        assert_eq!(request.holder_key, Some(holder.public_key()));
+
+        let credential = issuer_service.issue_request(request).unwrap();
+        let binding = credential.holder_binding.unwrap();
+        assert_eq!(binding.public_key_multibase(), multikey);
+        assert_eq!(binding.did(), format!("did:key:{}", multikey));
+        assert_eq!(HolderBinding::from_multikey(&multikey).unwrap(), binding);
+        assert!(issuer_service
+            .get_issuer_info()
+            .public_key_multibase()
+            .starts_with("z6Mk"));
+    }
+
+    #[test]
+    fn test_x509_bound_issuer_profile() {
+        use crate::clock::ManualClock;
+        use crate::verifier::VerificationError;
+        use crate::x509::tests::{CHAIN_PEM, LEAF_SECRET_KEY, ROOT_PEM};
+        use crate::x509::Certificate;
+        use chrono::TimeZone;
+        use std::sync::Arc;
+
+        let secret_key = hex::decode(LEAF_SECRET_KEY).unwrap();
+        let mut issuer_service = CredentialIssuer::with_signer(
+            "Test University".to_string(),
+            "https://credentials.test.edu".to_string(),
+            Box::new(crypto::CryptoKeyPair::from_secret_key(&secret_key).unwrap()),
+        );
+        let chain = Certificate::chain_from_pem(CHAIN_PEM).unwrap();
+        assert!(issuer_service
+            .set_certificate_chain(chain[1..].to_vec())
+            .is_err());
+        issuer_service.set_certificate_chain(chain).unwrap();
+        let profile = issuer_service.export_profile().unwrap();
+        assert_eq!(profile.certificate_chain.len(), 2);
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.set_clock(Arc::new(ManualClock::new(
+            Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap(),
+        )));
+        assert!(matches!(
+            verifier.import_x509_profile(&profile),
+            Err(VerificationError::InvalidCertificateChain(_))
+        ));
+        verifier.add_x509_root(Certificate::from_pem(ROOT_PEM).unwrap());
+        // The certificate names Test University at credentials.test.edu, not
+        // whoever holds a copy of the key.
+        let mut impostor = profile.clone();
+        impostor.issuer.url = "https://evil.example".to_string();
+        impostor.signature = Some(
+            crypto::CryptoKeyPair::from_secret_key(&secret_key)
+                .unwrap()
+                .sign(&crypto::hash_credential(&impostor.signing_payload().unwrap())),
+        );
+        assert!(matches!(
+            verifier.import_x509_profile(&impostor),
+            Err(VerificationError::InvalidCertificateChain(_))
+        ));
+        // Nor does it let the key take over another trusted issuer's id.
+        let mut other = CredentialIssuer::new(
+            "Other College".to_string(),
+            "https://other.edu".to_string(),
+        )
+        .get_issuer_info()
+        .clone();
+        other.id = profile.issuer.id;
+        verifier.add_trusted_issuer(other);
+        assert!(matches!(
+            verifier.import_x509_profile(&profile),
+            Err(VerificationError::InvalidCertificateChain(_))
+        ));
+        verifier.remove_trusted_issuer(&profile.issuer.id);
+        let leaf = verifier.import_x509_profile(&profile).unwrap();
+        assert_eq!(leaf.subject_organization().as_deref(), Some("Test University"));
+        let credential = sample_credential(&issuer_service);
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        // The chain also goes into the mdoc's x5chain header.
+        let holder = crypto::CryptoKeyPair::generate();
+        let request = builder::CredentialBuilder::new()
+            .subject(credential.subject.clone())
+            .skill(credential.skill.clone())
+            .holder_key(holder.public_key())
+            .expires_at(Utc.with_ymd_and_hms(2031, 1, 1, 0, 0, 0).unwrap())
+            .build()
+            .unwrap();
+        let bound = issuer_service.issue_request(request).unwrap();
+        let mdoc = issuer_service.export_mdoc(&bound).unwrap();
+        assert_eq!(mdoc.x5chain.len(), 2);
+        assert!(mdoc.verify(leaf.public_key()));
+        assert!(issuer_service.export_mdoc(&credential).is_err());
+
+        // A rotated key is no longer covered by the certificate.
+        issuer_service.rotate_key();
+        let rotated = issuer_service.export_profile().unwrap();
+        assert!(rotated.certificate_chain.is_empty());
+        assert!(matches!(
+            verifier.import_x509_profile(&rotated),
+            Err(VerificationError::InvalidCertificateChain(_))
+        ));
+    }
+
+    #[test]
+    fn test_blockcerts_batch_verification() {
+        use crate::blockcerts::{Anchor, AnchorLookup, AnchorTransaction};
+        use crate::verifier::VerificationError;
+
+        struct Anchored(&'static str, Vec<u8>);
+
+        impl AnchorLookup for Anchored {
+            fn transaction(
+                &self,
+                _anchor: &Anchor,
+                _issuer_addresses: &[String],
+            ) -> Result<AnchorTransaction, String> {
+                Ok(AnchorTransaction {
+                    data: self.1.clone(),
+                    sender: self.0.to_string(),
+                })
+            }
+        }
+
+        const ISSUER_ADDRESS: &str = "mgCNaPM3TFhh8Yn6X6VcEGd3RGfGNeUtrh";
+
+        let mut issuer_service = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let credentials = vec![
+            sample_credential(&issuer_service),
+            sample_credential(&issuer_service),
+            sample_credential(&issuer_service),
+        ];
+        issuer_service.add_blockchain_address(ISSUER_ADDRESS.to_string());
+        let batch = issuer_service.export_blockcerts(&credentials).unwrap();
+        let anchor = Anchor {
+            source_id: "2f1c".to_string(),
+            kind: "BTCOpReturn".to_string(),
+            chain: Some("bitcoinTestnet".to_string()),
+        };
+        let certificates = batch.certificates(&[anchor]);
+        let chain = Anchored(ISSUER_ADDRESS, batch.merkle_root().to_vec());
+
+        let mut verifier = CredentialVerifier::new();
+        assert!(matches!(
+            verifier.verify_blockcerts(&certificates[2], &chain),
+            Err(VerificationError::TrustedIssuerNotFound)
+        ));
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        // Trusted, but no published addresses: no anchor can be accepted.
+        assert!(matches!(
+            verifier.verify_blockcerts(&certificates[2], &chain),
+            Err(VerificationError::InvalidSignature)
+        ));
+        verifier
+            .import_issuer_profile(&issuer_service.export_profile().unwrap())
+            .unwrap();
+        let receipt = verifier.verify_blockcerts(&certificates[2], &chain).unwrap();
+        assert_eq!(receipt.proof.len(), 1);
+
+        issuer_service
+            .revoke_credential(&credentials[2].id, "Issued in error".to_string())
+            .unwrap();
+        verifier.update_status_list(issuer_service.get_status_list().clone());
+        assert!(matches!(
+            verifier.verify_blockcerts(&certificates[2], &chain),
+            Err(VerificationError::RevokedCredential(_))
+        ));
+        assert!(matches!(
+            verifier.verify_blockcerts(&certificates[0], &Anchored(ISSUER_ADDRESS, vec![0; 32])),
+            Err(VerificationError::InvalidSignature)
+        ));
+        let forged = Anchored("n2eMqTT929pb1RDNuqEnxdaLau1rxy3efi", batch.merkle_root().to_vec());
+        assert!(matches!(
+            verifier.verify_blockcerts(&certificates[0], &forged),
+            Err(VerificationError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_issuer_audit_trail() {
+        use crate::audit::{AuditAction, AuditLog};
+        use crate::issuer::CredentialRequest;
+        use crate::lifecycle::LifecycleRecord;
+        use std::sync::Arc;
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let log = Arc::new(AuditLog::new());
+        issuer_service.set_audit_log(log.clone());
+
+        let request = CredentialRequest {
+            actor: Some("registrar@test.edu".to_string()),
+            ..sample_request()
+        };
+        let credential = issuer_service.issue_request(request).unwrap();
+        let renewed = issuer_service.renew_credential(&credential, None).unwrap();
+
+        let records = log.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].credential_id, credential.id);
+        assert_eq!(records[0].actor, "registrar@test.edu");
+        assert_eq!(records[1].actor, "");
+        assert_eq!(records[0].policy, issuer_service.policy_snapshot());
+        assert_eq!(records[1].credential_id, renewed.id);
+        assert_eq!(
+            records[1].action,
+            AuditAction::Renewed {
+                previous_id: credential.id
+            }
+        );
+        assert_eq!(records[1].previous_hash, records[0].hash);
+        log.verify().unwrap();
+
+        // Shared issuers attribute each operation to its own actor, and
+        // lifecycle approvals to the approver.
+        let shared = Arc::new(issuer_service);
+        let mut record = LifecycleRecord::draft(sample_request(), "clerk".to_string());
+        record.submit(&shared, "clerk").unwrap();
+        record.approve(&shared, "dean").unwrap();
+        assert_eq!(log.records()[2].actor, "dean");
+    }
+
+    #[test]
+    fn test_audit_log_writes_through_to_its_sink() {
+        use crate::audit::{AuditError, AuditLog, AuditRecord, AuditSink, JsonlFileSink};
+        use std::sync::Arc;
+
+        struct Unavailable;
+
+        impl AuditSink for Unavailable {
+            fn append(&self, _record: &AuditRecord) -> Result<(), AuditError> {
+                Err(AuditError::Sink("disk full".to_string()))
+            }
+        }
+
+        let path = std::env::temp_dir().join(format!("audit-{}.jsonl", Uuid::new_v4()));
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let sink = JsonlFileSink::open(&path).unwrap();
+        let log = Arc::new(AuditLog::new().with_sink(Box::new(sink)));
+        issuer_service.set_audit_log(log.clone());
+        sample_credential(&issuer_service);
+        sample_credential(&issuer_service);
+        let persisted = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(AuditLog::from_jsonl(&persisted).unwrap().head(), log.head());
+
+        // Nothing is issued that could not be recorded.
+        let log = Arc::new(AuditLog::new().with_sink(Box::new(Unavailable)));
+        issuer_service.set_audit_log(log.clone());
+        assert!(issuer_service.issue_request(sample_request()).is_err());
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn test_expiry_grace_and_valid_from() {
+        use crate::clock::ManualClock;
+        use crate::verifier::{VerificationError, VerificationWarning, VerifierPolicy};
+        use chrono::{Duration, TimeZone};
+        use std::sync::Arc;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let valid_from = Utc.with_ymd_and_hms(2031, 9, 1, 0, 0, 0).unwrap();
+        let expires_at = Utc.with_ymd_and_hms(2032, 9, 1, 0, 0, 0).unwrap();
+        let credential = sample_credential(&issuer_service);
+        let request = builder::CredentialBuilder::new()
+            .subject(credential.subject.clone())
+            .skill(credential.skill.clone())
+            .valid_from(valid_from)
+            .expires_at(expires_at)
+            .build()
+            .unwrap();
+        let credential = issuer_service.issue_request(request).unwrap();
+
+        let mut verifier = CredentialVerifier::with_policy(VerifierPolicy {
+            expiry_grace: Some(Duration::days(30)),
+            enforce_valid_from: true,
+            ..Default::default()
+        });
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        let clock = Arc::new(ManualClock::new(valid_from - Duration::hours(1)));
+        verifier.set_clock(clock.clone());
+        assert!(matches!(
+            verifier.verify_credential(&credential),
+            Err(VerificationError::NotYetValid(at)) if at == valid_from
+        ));
+
+        clock.set(valid_from);
+        assert!(verifier
+            .verify_credential_with_warnings(&credential)
+            .unwrap()
+            .is_empty());
+
+        clock.set(expires_at + Duration::days(29));
+        assert!(verifier.verify_credential(&credential).unwrap());
+        assert_eq!(
+            verifier.verify_credential_with_warnings(&credential).unwrap(),
+            vec![VerificationWarning::ExpiredWithinGrace {
+                expired_at: expires_at
+            }]
+        );
+
+        clock.set(expires_at + Duration::days(31));
+        assert!(matches!(
+            verifier.verify_credential(&credential),
+            Err(VerificationError::ExpiredCredential)
+        ));
+    }
+
+    #[test]
+    fn test_partial_verification() {
+        use crate::status::StatusList;
+        use crate::verifier::{Checks, VerificationError};
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = sample_credential(&issuer_service);
+        let mut verifier = CredentialVerifier::new();
+
+        // Untrusted issuer: the signature still checks out against its key.
+        assert!(verifier
+            .verify_credential_checks(&credential, Checks::SIGNATURE | Checks::VALIDITY)
+            .unwrap());
+        assert!(matches!(
+            verifier.verify_credential_checks(&credential, Checks::TRUST),
+            Err(VerificationError::TrustedIssuerNotFound)
+        ));
+
+        let mut tampered = credential.clone();
+        tampered.subject.name = "Someone Else".to_string();
+        assert!(matches!(
+            verifier.verify_credential_checks(&tampered, Checks::SIGNATURE),
+            Err(VerificationError::InvalidSignature)
+        ));
+        assert!(verifier
+            .verify_credential_checks(&tampered, Checks::VALIDITY | Checks::STATUS)
+            .unwrap());
+
+        let mut status_list = StatusList::new(issuer_service.get_issuer_info().id, Utc::now());
+        status_list
+            .revoke(credential.id, "Issued in error".to_string(), Utc::now())
+            .unwrap();
+        verifier.update_status_list(status_list);
+        assert!(matches!(
+            verifier.verify_credential_checks(&credential, Checks::STATUS),
+            Err(VerificationError::RevokedCredential(_))
+        ));
+        assert!(verifier
+            .verify_credential_checks(&credential, Checks::NONE)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_full_verification_reports_every_failure() {
+        use crate::clock::ManualClock;
+        use crate::verifier::{Checks, VerificationError, VerifierPolicy};
+        use chrono::{Duration, TimeZone};
+        use std::sync::Arc;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let expires_at = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let mut credential = issuer_service
+            .issue_credential(
+                sample_credential(&issuer_service).subject,
+                Skill {
+                    id: "rust".to_string(),
+                    name: "Rust".to_string(),
+                    description: "Rust programming".to_string(),
+                    level: SkillLevel::Beginner,
+                    unknown_fields: Default::default(),
+                },
+                Vec::new(),
+                Some(expires_at),
+            )
+            .unwrap();
+        let mut verifier = CredentialVerifier::with_policy(VerifierPolicy {
+            min_skill_level: Some(SkillLevel::Advanced),
+            ..Default::default()
+        });
+        verifier.set_clock(Arc::new(ManualClock::new(expires_at + Duration::days(1))));
+
+        let report = verifier.verify_credential_full(&credential);
+        assert!(!report.is_valid());
+        assert!(matches!(
+            report.failures.as_slice(),
+            [
+                VerificationError::ExpiredCredential,
+                VerificationError::TrustedIssuerNotFound,
+                VerificationError::InsufficientSkillLevel,
+            ]
+        ));
+        assert!(report.to_string().contains(
+            "failed verification: Credential has expired; Issuer is not in the trusted list; "
+        ));
+
+        credential.signature = None;
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        verifier.set_policy(VerifierPolicy::default());
+        let report = verifier.verify_credential_full(&credential);
+        assert!(matches!(
+            report.failures.as_slice(),
+            [
+                VerificationError::ExpiredCredential,
+                VerificationError::MissingSignature
+            ]
+        ));
+        // Trust is judged on the issuer alone, so the missing signature is
+        // reported once, by the signature check.
+        assert!(verifier
+            .verify_credential_checks(&credential, Checks::TRUST)
+            .unwrap());
+        verifier.remove_trusted_issuer(&issuer_service.get_issuer_info().id);
+        let report = verifier.verify_credential_full(&credential);
+        assert!(matches!(
+            report.failures.as_slice(),
+            [
+                VerificationError::ExpiredCredential,
+                VerificationError::TrustedIssuerNotFound,
+                VerificationError::MissingSignature
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_verifier_attestation() {
+        use crate::attestation::AttestationError;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = sample_credential(&issuer_service);
+        let mut verifier = CredentialVerifier::new();
+        assert!(matches!(
+            verifier.attest_credential(&credential),
+            Err(AttestationError::NoSigner)
+        ));
+
+        let verifier_key = crypto::CryptoKeyPair::generate();
+        verifier.set_attestation_signer(
+            "https://verify.example.org",
+            Box::new(crypto::CryptoKeyPair::from_secret_key(&verifier_key.secret_key()).unwrap()),
+        );
+        let attestation = verifier.attest_credential(&credential).unwrap();
+        assert!(!attestation.valid);
+        assert_eq!(attestation.failures, vec!["Issuer is not in the trusted list"]);
+
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        let attestation = verifier.attest_credential(&credential).unwrap();
+        assert!(attestation.valid && attestation.failures.is_empty());
+        assert_eq!(attestation.verifier_key, verifier_key.public_key());
+        assert!(attestation.verify_signature());
+        assert!(attestation.covers(&credential));
+
+        let mut other = credential.clone();
+        other.subject.name = "Someone Else".to_string();
+        assert!(!attestation.covers(&other));
+        // Same claims, different signature: not the credential that was
+        // checked.
+        let mut resigned = credential.clone();
+        resigned.signature = Some(vec![0; 64]);
+        assert!(!attestation.covers(&resigned));
+
+        let mut forged = attestation.clone();
+        forged.valid = false;
+        assert!(!forged.verify_signature());
+    }
+
+    #[test]
+    fn test_offline_verification_pack() {
+        use crate::clock::ManualClock;
+        use crate::offline::{OfflinePack, OfflinePackError};
+        use crate::status::StatusList;
+        use crate::verifier::{Checks, VerificationError, VerificationWarning, VerifierPolicy};
+        use chrono::Duration;
+        use std::sync::Arc;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = sample_credential(&issuer_service);
+        let revoked = sample_credential(&issuer_service);
+
+        let mut central = CredentialVerifier::new();
+        central.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        let mut status_list = StatusList::new(issuer_service.get_issuer_info().id, Utc::now());
+        status_list
+            .revoke(revoked.id, "Issued in error".to_string(), Utc::now())
+            .unwrap();
+        central.update_status_list(status_list);
+        let publisher = crypto::CryptoKeyPair::generate();
+        let mut pack = central.export_offline_pack(Duration::days(7));
+        pack.sign(&publisher).unwrap();
+        let pack = OfflinePack::from_json(&pack.to_json().unwrap()).unwrap();
+
+        let mut field = CredentialVerifier::new();
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        field.set_clock(clock.clone());
+        let impostor = crypto::CryptoKeyPair::generate();
+        assert!(matches!(
+            field.load_offline_pack(&pack, &impostor.public_key()),
+            Err(OfflinePackError::InvalidSignature)
+        ));
+        field
+            .load_offline_pack(&pack, &publisher.public_key())
+            .unwrap();
+        assert!(field
+            .verify_credential_with_warnings(&credential)
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            field.verify_credential(&revoked),
+            Err(VerificationError::RevokedCredential(_))
+        ));
+
+        clock.advance(Duration::days(8));
+        assert!(matches!(
+            field.verify_credential(&credential),
+            Err(VerificationError::OfflinePackExpired(at)) if at == pack.valid_until
+        ));
+        assert!(matches!(
+            field.verify_credential_checks(&credential, Checks::STATUS),
+            Err(VerificationError::OfflinePackExpired(_))
+        ));
+        field.set_policy(VerifierPolicy {
+            offline_pack_grace: Some(Duration::days(2)),
+            ..Default::default()
+        });
+        assert_eq!(
+            field.verify_credential_with_warnings(&credential).unwrap(),
+            vec![VerificationWarning::StaleOfflinePack {
+                valid_until: pack.valid_until
+            }]
+        );
+        clock.advance(Duration::days(2));
+        assert!(field.verify_credential(&credential).is_err());
+        assert!(matches!(
+            field.load_offline_pack(&pack, &publisher.public_key()),
+            Err(OfflinePackError::Expired(_))
+        ));
+    }
+
+    #[test]
+    fn test_issuance_against_skill_catalog() {
+        use crate::catalog::{SkillCatalog, SkillDefinition};
+        use crate::levels::LevelScale;
+        use crate::validation::{ValidationReport, ValidationRules};
+
+        let mut catalog = SkillCatalog::new();
+        catalog.publish(SkillDefinition::new(
+            "test-skill".to_string(),
+            "Test Skill".to_string(),
+            "A test skill".to_string(),
+            LevelScale::default_scale(),
+        ));
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        issuer_service.set_validation_rules(ValidationRules {
+            skill_catalog: Some(catalog.clone()),
+            ..Default::default()
+        });
+
+        let credential = sample_credential(&issuer_service);
+        assert_eq!(credential.metadata["skill_version"], "1");
+        assert_eq!(
+            issuer_service.policy_snapshot()["validation"]["skill_catalog"]["test-skill"],
+            1
+        );
+
+        let err = issuer_service
+            .issue_credential(
+                credential.subject.clone(),
+                Skill {
+                    id: "free-form".to_string(),
+                    name: "Free Form".to_string(),
+                    description: "Not in the catalog".to_string(),
+                    level: SkillLevel::Expert,
+                    unknown_fields: Default::default(),
+                },
+                Vec::new(),
+                None,
+            )
+            .unwrap_err();
+        let report = err.downcast_ref::<ValidationReport>().unwrap();
+        assert_eq!(report.issues[0].field, "skill.id");
+    }
+
+    #[test]
+    fn test_unsigned_preview() {
+        use crate::preview::{PreviewError, PreviewFormat, PreviewTemplate};
+        use crate::validation::ValidationReport;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let template = sample_credential(&issuer_service);
+        let request = builder::CredentialBuilder::new()
+            .subject(template.subject.clone())
+            .skill(template.skill.clone())
+            .build()
+            .unwrap();
+
+        let preview = issuer_service
+            .preview_request(&request, &PreviewTemplate::default(), PreviewFormat::Json)
+            .unwrap();
+        assert_eq!(preview.media_type(), "application/json");
+        let document: serde_json::Value = serde_json::from_slice(&preview.content).unwrap();
+        assert_eq!(document["display"]["title"], "Test Skill");
+        assert_eq!(document["display"]["lines"][0], "Awarded to Test Student");
+        assert!(document["credential"]["signature"].is_null());
+
+        let svg = issuer_service
+            .preview_request(&request, &PreviewTemplate::default(), PreviewFormat::Svg)
+            .unwrap();
+        let svg = String::from_utf8(svg.content).unwrap();
+        assert!(svg.starts_with("<svg") && svg.contains("PREVIEW - NOT SIGNED"));
+        let pdf = issuer_service
+            .preview_request(&request, &PreviewTemplate::default(), PreviewFormat::Pdf)
+            .unwrap();
+        assert!(pdf.content.starts_with(b"%PDF-1.4") && pdf.content.ends_with(b"%%EOF\n"));
+
+        let template = PreviewTemplate {
+            title: "{{skill.nme}}".to_string(),
+            ..Default::default()
+        };
+        let err = issuer_service
+            .preview_request(&request, &template, PreviewFormat::Svg)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PreviewError>(),
+            Some(PreviewError::UnknownPlaceholder(name)) if name == "skill.nme"
+        ));
+
+        let mut invalid = request.clone();
+        invalid.subject.email = "not an email".to_string();
+        let err = issuer_service
+            .preview_request(&invalid, &PreviewTemplate::default(), PreviewFormat::Json)
+            .unwrap_err();
+        assert!(err.downcast_ref::<ValidationReport>().is_some());
+    }
+
+    #[test]
+    fn test_subjects_resolved_from_directory() {
+        use crate::directory::InMemoryDirectory;
+        use crate::validation::ValidationReport;
+        use std::sync::Arc;
+
+        let enrolled = Subject {
+            id: Uuid::new_v4(),
+            name: "Zoë Lindqvist".to_string(),
+            email: "zoe.lindqvist@test.edu".to_string(),
+            unknown_fields: Default::default(),
+        };
+        let mut directory = InMemoryDirectory::new();
+        directory.insert(enrolled.clone());
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        issuer_service.set_subject_resolver(Arc::new(directory));
+        let skill = Skill {
+            id: "rust".to_string(),
+            name: "Rust".to_string(),
+            description: "Rust programming".to_string(),
+            level: SkillLevel::Advanced,
+            unknown_fields: Default::default(),
+        };
+
+        let by_id = Subject {
+            id: enrolled.id,
+            name: String::new(),
+            email: String::new(),
+            unknown_fields: Default::default(),
+        };
+        let credential = issuer_service
+            .issue_credential(by_id.clone(), skill.clone(), Vec::new(), None)
+            .unwrap();
+        assert_eq!(credential.subject.name, "Zoë Lindqvist");
+        assert_eq!(credential.subject.email, "zoe.lindqvist@test.edu");
+
+        let typo = Subject {
+            name: "Zoe Lindqvist".to_string(),
+            ..by_id.clone()
+        };
+        let unknown = Subject {
+            id: Uuid::new_v4(),
+            ..by_id
+        };
+        for (subject, field) in [(typo, "subject.name"), (unknown, "subject.id")] {
+            let err = issuer_service
+                .issue_credential(subject, skill.clone(), Vec::new(), None)
+                .unwrap_err();
+            let report = err.downcast_ref::<ValidationReport>().unwrap();
+            assert_eq!(report.issues[0].field, field);
+        }
+    }
+
+    #[test]
+    fn test_duplicate_credentials_detected_at_issuance() {
+        use crate::duplicates::{Duplicate, DuplicatePolicy};
+        use crate::issuer::CredentialRequest;
+        use crate::store::{CredentialStore, InMemoryStore};
+        use std::collections::HashMap;
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let held = sample_credential(&issuer_service);
+        let mut store = InMemoryStore::new();
+        store.insert(&held).unwrap();
+
+        let request = |level: SkillLevel| CredentialRequest {
+            subject: held.subject.clone(),
+            skill: Skill {
+                level,
+                ..held.skill.clone()
+            },
+            evidence: Vec::new(),
+            valid_from: None,
+            expires_at: None,
+            metadata: HashMap::new(),
+            attachments: Vec::new(),
+            holder_key: None,
+            actor: None,
+        };
+        let (repeat, warnings) = issuer_service
+            .issue_checked(request(SkillLevel::Beginner), &store)
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].existing_id, held.id);
+        let (_, warnings) = issuer_service
+            .issue_checked(request(SkillLevel::Expert), &store)
+            .unwrap();
+        assert!(warnings.is_empty());
+
+        store.insert(&repeat).unwrap();
+        let found = issuer_service.find_duplicates(&store).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].credential_id, repeat.id);
+
+        issuer_service.set_duplicate_policy(DuplicatePolicy::Block);
+        let err = issuer_service
+            .issue_checked(request(SkillLevel::Intermediate), &store)
+            .unwrap_err();
+        assert!(err.downcast_ref::<Duplicate>().is_some());
+
+        issuer_service
+            .revoke_credential(&held.id, "Issued in error".to_string())
+            .unwrap();
+        store.remove(&repeat.id).unwrap();
+        assert!(issuer_service
+            .issue_checked(request(SkillLevel::Intermediate), &store)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_store_checks_every_issuance_path() {
+        use crate::duplicates::{Duplicate, DuplicatePolicy};
+        use crate::lifecycle::{LifecycleError, LifecycleRecord};
+        use crate::store::{CredentialStore, InMemoryStore};
+        use std::sync::{Arc, Mutex};
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let store = Arc::new(Mutex::new(InMemoryStore::new()));
+        issuer_service.set_duplicate_policy(DuplicatePolicy::Block);
+        issuer_service.set_duplicate_store(store.clone());
+
+        let request = sample_request();
+        let held = issuer_service.issue_request(request.clone()).unwrap();
+        store.lock().unwrap().insert(&held).unwrap();
+        let err = issuer_service.issue_request(request.clone()).unwrap_err();
+        assert!(err.downcast_ref::<Duplicate>().is_some());
+
+        let mut record = LifecycleRecord::draft(request, "clerk".to_string());
+        record.submit(&issuer_service, "clerk").unwrap();
+        assert!(matches!(
+            record.approve(&issuer_service, "registrar"),
+            Err(LifecycleError::Issuance(_))
+        ));
+
+        // A renewal replaces the credential it renews.
+        assert!(issuer_service.renew_credential(&held, None).is_ok());
+    }
+
+    #[test]
+    fn test_salted_digests_resist_dictionary_matching() {
+        use crate::salting::{matches_digest, salted_digest, SALT_METADATA_KEY};
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let unsalted = sample_credential(&issuer_service);
+        assert!(salted_digest(&unsalted).is_err());
+
+        issuer_service.set_digest_salting(true);
+        let credential = sample_credential(&issuer_service);
+        let salt = credential.metadata[SALT_METADATA_KEY].clone();
+        let digest = salted_digest(&credential).unwrap();
+        assert!(matches_digest(&credential, &digest));
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        let renewed = issuer_service.renew_credential(&credential, None).unwrap();
+        assert_ne!(renewed.metadata[SALT_METADATA_KEY], salt);
+
+        let mut guessed = credential.clone();
+        guessed.metadata.remove(SALT_METADATA_KEY);
+        let unsalted_hash = crate::crypto::hash_credential(&guessed.signing_payload().unwrap());
+        assert_ne!(hex::encode(unsalted_hash), digest);
+
+        let mut tampered = credential.clone();
+        tampered.subject.name = "Mallory".to_string();
+        assert!(!matches_digest(&tampered, &digest));
+    }
+
+    #[test]
+    fn test_timestamps_survive_lower_precision_storage() {
+        use crate::clock::{Clock, ManualClock};
+        use crate::timestamps::{TimestampError, TimestampPolicy};
+        use std::sync::Arc;
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let clock = Arc::new(ManualClock::new(
+            Utc::now() + Duration::nanoseconds(123_456_789),
+        ));
+        issuer_service.set_clock(clock.clone());
+        let credential = sample_credential(&issuer_service);
+        assert!(credential.check_timestamps(&TimestampPolicy::default()).is_ok());
+        assert_eq!(credential.issued_at.timestamp_subsec_nanos(), 0);
+
+        // A store that keeps milliseconds writes the timestamps back out
+        // with a fraction; the signed values are unchanged.
+        let mut json = serde_json::to_value(&credential).unwrap();
+        let issued_at = json["issued_at"].as_str().unwrap().replace('Z', ".000Z");
+        json["issued_at"] = serde_json::json!(issued_at);
+        let stored: Microcredential = serde_json::from_value(json).unwrap();
+
+        // Rotating within the same second keeps both keys' credentials
+        // resolvable.
+        issuer_service.rotate_key();
+        clock.advance(Duration::milliseconds(10));
+        let rotated = sample_credential(&issuer_service);
+        let mut verifier = CredentialVerifier::new();
+        verifier.set_clock(clock.clone());
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(verifier.verify_credential(&stored).unwrap());
+        assert!(verifier.verify_credential(&rotated).unwrap());
+        assert!(rotated.issued_at > clock.now() - Duration::seconds(1));
+
+        let mut precise = credential.clone();
+        precise.expires_at = Some(clock.now());
+        assert!(matches!(
+            precise.check_timestamps(&TimestampPolicy::default()),
+            Err(TimestampError::ExcessPrecision(field)) if field == "expires_at"
+        ));
+    }
+
+    #[test]
+    fn test_issuer_and_verifier_health() {
+        use crate::clock::{Clock, ManualClock};
+        use crate::health::{HealthStatus, HealthThresholds};
+        use crate::signer::{Signer, SignerError};
+        use crate::store::InMemoryStore;
+        use std::sync::Arc;
+
+        struct UnreachableHsm(Vec<u8>);
+
+        impl Signer for UnreachableHsm {
+            fn public_key(&self) -> Vec<u8> {
+                self.0.clone()
+            }
+
+            fn sign(&self, _message: &[u8]) -> Result<Vec<u8>, SignerError> {
+                Err(SignerError::Transport("connection refused".to_string()))
+            }
+
+            fn health_check(&self) -> Result<(), SignerError> {
+                Err(SignerError::Transport("connection refused".to_string()))
+            }
+        }
+
+        let thresholds = HealthThresholds::default();
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let store = InMemoryStore::new();
+        let report = issuer_service.health(Some(&store), &thresholds);
+        assert_eq!(report.status(), HealthStatus::Healthy);
+        assert!(report.check("store").is_some());
+
+        let public_key = issuer_service.get_public_key();
+        issuer_service.rotate_signer(Box::new(UnreachableHsm(public_key)));
+        let report = issuer_service.health(None, &thresholds);
+        assert!(!report.is_ready());
+        assert_eq!(
+            report.check("signer").unwrap().message,
+            "Signer unreachable: connection refused"
+        );
+
+        let mut verifier = CredentialVerifier::new();
+        assert_eq!(
+            verifier.health(&thresholds).check("trust_list").unwrap().status,
+            HealthStatus::Unhealthy
+        );
+        let clock = Arc::new(ManualClock::new(Utc::now()));
+        verifier.set_clock(clock.clone());
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        verifier.update_status_list(issuer_service.get_status_list().clone());
+        assert_eq!(verifier.health(&thresholds).status(), HealthStatus::Healthy);
+
+        clock.advance(Duration::days(2));
+        let report = verifier.health(&thresholds);
+        assert!(report.is_ready());
+        assert_eq!(
+            report.check("status_lists").unwrap().status,
+            HealthStatus::Degraded
+        );
+        assert_eq!(report.checked_at, clock.now());
+    }
+
+    #[test]
+    fn test_invariants_catch_malformed_credentials() {
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let mut credential = sample_credential(&issuer_service);
+        assert!(credential.invariants().is_valid());
+
+        credential.evidence.push(credential.evidence[0].clone());
+        credential.expires_at = Some(credential.issued_at);
+        credential.valid_from = credential.expires_at;
+        credential.signature = Some(vec![0; 12]);
+        let fields: Vec<_> = credential
+            .invariants()
+            .issues
+            .into_iter()
+            .map(|issue| issue.field)
+            .collect();
+        assert_eq!(fields, ["valid_from", "evidence[1].id", "signature"]);
+
+        // Whatever the issuer signs holds the invariants, expired or not.
+        let yesterday = Utc::now() - Duration::days(1);
+        let expired = sample_credential_expiring(&issuer_service, Some(yesterday));
+        assert!(expired.invariants().is_valid());
+
+        let mut credential = sample_credential(&issuer_service);
+        credential.skill.level = SkillLevel::Scaled {
+            scale: "eqf".to_string(),
+            rank: 9,
+            label: "EQF 9".to_string(),
+        };
+        credential.issued_at -= Duration::days(1);
+        credential.valid_from = Some(credential.issued_at - Duration::seconds(1));
+        let fields: Vec<_> = credential
+            .invariants()
+            .issues
+            .into_iter()
+            .map(|issue| issue.field)
+            .collect();
+        assert_eq!(fields, ["skill.level", "valid_from", "issued_at"]);
+    }
+
+    #[test]
+    fn test_shared_verifier_across_threads() {
+        use crate::verifier::SharedVerifier;
+        use std::sync::Arc;
+        use std::thread;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = Arc::new(sample_credential(&issuer_service));
+        let shared = Arc::new(SharedVerifier::default());
+        let before = shared.snapshot();
+        assert!(shared.verify_credential(&credential).is_err());
+
+        let writer = {
+            let shared = shared.clone();
+            let issuer = issuer_service.get_issuer_info().clone();
+            thread::spawn(move || shared.add_trusted_issuer(issuer))
+        };
+        writer.join().unwrap();
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let (shared, credential) = (shared.clone(), credential.clone());
+                thread::spawn(move || shared.verify_credential(&credential).unwrap())
+            })
+            .collect();
+        for reader in readers {
+            assert!(reader.join().unwrap());
+        }
+
+        assert!(before.verify_credential(&credential).is_err());
+        shared.remove_trusted_issuer(&issuer_service.get_issuer_info().id);
+        assert!(!shared.verify_credential_full(&credential).is_valid());
+    }
+
+    #[test]
+    fn test_bulk_issuance_keeps_order_and_reports_failures() {
+        use crate::audit::AuditLog;
+        use crate::bulk::BulkOptions;
+        use crate::clock::Clock;
+        use crate::crypto::CryptoKeyPair;
+        use crate::duplicates::DuplicatePolicy;
+        use crate::issuer::CredentialRequest;
+        use crate::store::{CredentialStore, InMemoryStore};
+        use std::collections::{BTreeSet, HashMap};
+        use std::sync::{Arc, Mutex};
+
+        /// Moves a second forward on every reading.
+        struct TickingClock(Mutex<DateTime<Utc>>);
+
+        impl Clock for TickingClock {
+            fn now(&self) -> DateTime<Utc> {
+                let mut now = self.0.lock().unwrap();
+                *now += Duration::seconds(1);
+                *now
+            }
+        }
+
+        let start = "2020-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut issuer_service = CredentialIssuer::with_clock(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+            Box::new(CryptoKeyPair::generate()),
+            Arc::new(TickingClock(Mutex::new(start))),
+        );
+        let template = sample_credential(&issuer_service);
+        let requests: Vec<_> = (0..10)
+            .map(|index| CredentialRequest {
+                subject: Subject {
+                    id: Uuid::new_v4(),
+                    email: if index == 4 {
+                        "not an email".to_string()
+                    } else {
+                        format!("student{}@test.edu", index)
+                    },
+                    ..template.subject.clone()
+                },
+                skill: template.skill.clone(),
+                evidence: Vec::new(),
+                valid_from: None,
+                expires_at: None,
+                metadata: HashMap::new(),
+                attachments: Vec::new(),
+                holder_key: None,
+                actor: None,
+            })
+            .collect();
+        let subjects: Vec<_> = requests.iter().map(|request| request.subject.id).collect();
+
+        let log = Arc::new(AuditLog::new());
+        issuer_service.set_audit_log(log.clone());
+        let options = BulkOptions {
+            threads: 3,
+            chunk_size: 2,
+        };
+        let outcome = issuer_service.issue_bulk(requests.clone(), &options);
+        assert!(!outcome.is_complete());
+        assert_eq!(outcome.failures.len(), 1);
+        assert_eq!(outcome.failures[0].index, 4);
+        assert_eq!(outcome.credentials.len(), 9);
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        let expected: Vec<_> = subjects
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != 4)
+            .map(|(_, id)| *id)
+            .collect();
+        let issued: Vec<_> = outcome
+            .credentials
+            .iter()
+            .map(|credential| credential.subject.id)
+            .collect();
+        assert_eq!(issued, expected);
+        for credential in &outcome.credentials {
+            assert!(verifier.verify_credential(credential).unwrap());
+        }
+        // One issuance time per chunk of two.
+        assert_eq!(
+            outcome.credentials[0].issued_at,
+            outcome.credentials[1].issued_at
+        );
+        let stamps: BTreeSet<_> = outcome.credentials.iter().map(|c| c.issued_at).collect();
+        assert_eq!(stamps.len(), 5);
+
+        // Nothing is recorded until the run is stored.
+        assert!(log.is_empty());
+        let mut store = InMemoryStore::new();
+        let stored = issuer_service.store_bulk(outcome, &mut store).unwrap();
+        assert_eq!(store.ids().unwrap().len(), 9);
+        assert_eq!(log.len(), stored.len());
+
+        // Under Block, a request repeating an earlier one in the run fails.
+        issuer_service.set_duplicate_policy(DuplicatePolicy::Block);
+        let repeated = vec![requests[0].clone(), requests[1].clone(), requests[0].clone()];
+        let outcome = issuer_service.issue_bulk(repeated, &options);
+        assert_eq!(outcome.credentials.len(), 2);
+        assert_eq!(outcome.failures.len(), 1);
+        assert_eq!(outcome.failures[0].index, 2);
+    }
+
+    #[test]
+    fn test_anonymized_export_keeps_subjects_apart_without_pii() {
+        use crate::anonymize::{AnonymizationPolicy, Anonymizer};
+        use crate::store::{CredentialStore, InMemoryStore};
+        use chrono::Datelike;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let first = sample_credential(&issuer_service);
+        let mut second = first.clone();
+        second.id = Uuid::new_v4();
+        let mut other = first.clone();
+        other.id = Uuid::new_v4();
+        other.subject.id = Uuid::new_v4();
+        other.subject.email = "other@example.com".to_string();
+        let mut store = InMemoryStore::new();
+        store.insert_all(&[first.clone(), second, other]).unwrap();
+
+        let key = Anonymizer::generate_key();
+        let anonymizer = Anonymizer::new(key, AnonymizationPolicy::default());
+        let export = anonymizer.export(&store, Utc::now()).unwrap();
+        assert_eq!(export.records.len(), 3);
+        let subjects: std::collections::HashSet<_> = export
+            .records
+            .iter()
+            .map(|record| record.subject_id.clone().unwrap())
+            .collect();
+        assert_eq!(subjects.len(), 2);
+        assert!(export.records.iter().all(|record| record.subject_email.is_none()));
+        assert_eq!(export.records[0].issued_at.day(), 1);
+        assert_eq!(export.records[0].issued_at.month(), first.issued_at.month());
+
+        let json = export.to_json();
+        let csv = export.to_csv();
+        for pii in [
+            first.subject.name.as_str(),
+            first.subject.email.as_str(),
+            &first.subject.id.to_string(),
+            &first.id.to_string(),
+        ] {
+            assert!(!json.contains(pii));
+            assert!(!csv.contains(pii));
+        }
+        assert_eq!(csv.lines().count(), 4);
+
+        let again = Anonymizer::new(key, AnonymizationPolicy::default())
+            .export(&store, Utc::now())
+            .unwrap();
+        assert_eq!(again.records, export.records);
+    }
+
+    #[test]
+    fn test_verification_report_localized_for_holder() {
+        use crate::localization::{Catalog, Localizer};
+        use std::sync::Arc;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = sample_credential(&issuer_service);
+        let report = CredentialVerifier::new().verify_credential_full(&credential);
+        assert!(!report.is_valid());
+
+        let localizer = Localizer::default();
+        let french = localizer.report(&report, "fr-CA");
+        assert!(!french.valid);
+        assert_eq!(
+            french.summary,
+            format!("La vérification du justificatif {} a échoué", credential.id)
+        );
+        assert_eq!(french.failures[0].key, "trusted_issuer_not_found");
+        assert_eq!(
+            french.failures[0].message,
+            "L'émetteur ne figure pas dans la liste de confiance"
+        );
+        assert_eq!(
+            localizer.report(&report, "es").failures[0].message,
+            "El emisor no está en la lista de confianza"
+        );
+        assert_eq!(
+            localizer.report(&report, "de").failures[0].message,
+            "Issuer is not in the trusted list"
+        );
+
+        let mut catalog = Catalog::builtin();
+        catalog.insert(
+            "de",
+            "trusted_issuer_not_found",
+            "Der Aussteller ist nicht vertrauenswürdig",
+        );
+        let german = Localizer::new(Arc::new(catalog)).report(&report, "de-AT");
+        assert_eq!(
+            german.failures[0].message,
+            "Der Aussteller ist nicht vertrauenswürdig"
+        );
+        assert_eq!(
+            german.summary,
+            format!("Credential {} failed verification", credential.id)
+        );
+    }
+
+    #[cfg(feature = "wallet")]
+    struct FixedWalletSigner;
+
+    #[cfg(feature = "wallet")]
+    impl crate::wallet::PassSigner for FixedWalletSigner {
+        fn sign_manifest(&self, manifest: &[u8]) -> Result<Vec<u8>, crate::signer::SignerError> {
+            use sha1::{Digest, Sha1};
+
+            Ok(Sha1::digest(manifest).to_vec())
+        }
+    }
+
+    #[cfg(feature = "wallet")]
+    impl crate::wallet::JwtSigner for FixedWalletSigner {
+        fn sign(&self, _message: &[u8]) -> Result<Vec<u8>, crate::signer::SignerError> {
+            Ok(vec![1, 2, 3])
+        }
+    }
+
+    #[cfg(feature = "wallet")]
+    fn sample_branding() -> crate::wallet::WalletBranding {
+        crate::wallet::WalletBranding {
+            organization_name: "Test University".to_string(),
+            background_color: [0, 51, 102],
+            foreground_color: [255, 255, 255],
+            label_color: [200, 200, 200],
+            icon_png: b"\x89PNG icon".to_vec(),
+            logo_png: None,
+            logo_url: Some("https://test.edu/logo.png".to_string()),
+            verification_url: "https://verify.test.edu/c/{id}".to_string(),
+        }
+    }
+
+    #[cfg(feature = "wallet")]
+    #[test]
+    fn test_pkpass_archive_is_signed_over_its_manifest() {
+        use crate::wallet::{pkpass, PkPassConfig, WalletBranding, WalletError};
+        use sha1::{Digest, Sha1};
+        use std::io::{Cursor, Read};
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = sample_credential(&issuer_service);
+        let branding = sample_branding();
+        let config = PkPassConfig {
+            pass_type_identifier: "pass.edu.test.credential".to_string(),
+            team_identifier: "ABCDE12345".to_string(),
+        };
+        let archive = pkpass(&credential, &branding, &config, &FixedWalletSigner).unwrap();
+
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+        let mut read = |name: &str| {
+            let mut bytes = Vec::new();
+            zip.by_name(name).unwrap().read_to_end(&mut bytes).unwrap();
+            bytes
+        };
+        let pass: Value = serde_json::from_slice(&read("pass.json")).unwrap();
+        assert_eq!(
+            pass["barcodes"][0]["message"],
+            format!("https://verify.test.edu/c/{}", credential.id)
+        );
+        assert_eq!(pass["backgroundColor"], "rgb(0, 51, 102)");
+        let manifest: Value = serde_json::from_slice(&read("manifest.json")).unwrap();
+        let icon_digest = hex::encode(Sha1::digest(&branding.icon_png));
+        assert_eq!(manifest["icon.png"], icon_digest);
+        assert_eq!(
+            read("signature"),
+            Sha1::digest(read("manifest.json")).to_vec()
+        );
+
+        let no_icon = WalletBranding {
+            icon_png: Vec::new(),
+            ..branding
+        };
+        assert!(matches!(
+            pkpass(&credential, &no_icon, &config, &FixedWalletSigner),
+            Err(WalletError::MissingIcon)
+        ));
+    }
+
+    #[cfg(feature = "wallet")]
+    #[test]
+    fn test_google_wallet_jwt_claims() {
+        use crate::wallet::{google_save_url, google_wallet_jwt, GoogleWalletConfig};
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = sample_credential(&issuer_service);
+        let config = GoogleWalletConfig {
+            issuer_id: "3388000000012345".to_string(),
+            class_suffix: "microcredential".to_string(),
+            service_account_email: "wallet@test-edu.iam.gserviceaccount.com".to_string(),
+        };
+        let jwt = google_wallet_jwt(
+            &credential,
+            &sample_branding(),
+            &config,
+            &FixedWalletSigner,
+            Utc::now(),
+        )
+        .unwrap();
+        let parts: Vec<&str> = jwt.split('.').collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[2], "AQID");
+        let claims: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[1]).unwrap()).unwrap();
+        let object = &claims["payload"]["genericObjects"][0];
+        assert_eq!(claims["typ"], "savetowallet");
+        assert_eq!(object["classId"], "3388000000012345.microcredential");
+        assert_eq!(object["state"], "ACTIVE");
+        assert_eq!(object["hexBackgroundColor"], "#003366");
+        assert!(google_save_url(&jwt).ends_with(&jwt));
+    }
+
+    #[test]
+    fn test_audit_log_detects_tampering() {
+        use crate::audit::{verify_chain, AuditAction, AuditError, AuditLog};
+        use serde_json::json;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let log = AuditLog::new();
+        for _ in 0..3 {
+            log.record(
+                &sample_credential(&issuer_service),
+                AuditAction::Issued,
+                "registrar",
+                json!({"max_evidence": 50}),
+                Utc::now(),
+            )
+            .unwrap();
+        }
+        log.verify().unwrap();
+        let exported = log.to_jsonl().unwrap();
+        assert_eq!(AuditLog::from_jsonl(&exported).unwrap().head(), log.head());
+
+        let mut records = log.records();
+        records[1].actor = "someone else".to_string();
+        assert_eq!(verify_chain(&records), Err(AuditError::HashMismatch(1)));
+
+        let mut records = log.records();
+        records.remove(1);
+        assert_eq!(verify_chain(&records), Err(AuditError::SequenceGap(2)));
+
+        // A record from another log is intact on its own but not linked.
+        let other = AuditLog::new();
+        for _ in 0..2 {
+            other.record(
+                &sample_credential(&issuer_service),
+                AuditAction::Issued,
+                "registrar",
+                json!({}),
+                Utc::now(),
+            )
+            .unwrap();
+        }
+        let mut records = log.records();
+        records[1] = other.records()[1].clone();
+        assert_eq!(verify_chain(&records), Err(AuditError::BrokenLink(1)));
+    }
+
+    #[test]
+    fn test_blockcerts_receipts_verify_against_anchor() {
+        use crate::blockcerts::{
+            self, Anchor, AnchorLookup, AnchorTransaction, BlockcertsBatch, BlockcertsError,
+        };
+        use crate::ld::context::OfflineContextLoader;
+        use serde_json::json;
+
+        /// Transactions by id: sender and anchored root.
+        struct Chain(Vec<(String, String, [u8; 32])>);
+
+        impl AnchorLookup for Chain {
+            fn transaction(
+                &self,
+                anchor: &Anchor,
+                _issuer_addresses: &[String],
+            ) -> Result<AnchorTransaction, String> {
+                self.0
+                    .iter()
+                    .find(|(tx, _, _)| *tx == anchor.source_id)
+                    .map(|(_, sender, root)| AnchorTransaction {
+                        data: root.to_vec(),
+                        sender: sender.clone(),
+                    })
+                    .ok_or_else(|| format!("unknown transaction {}", anchor.source_id))
+            }
+        }
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let documents: Vec<Value> = (0..5)
+            .map(|_| blockcerts::certificate_document(&sample_credential(&issuer_service)))
+            .collect();
+        let loader = OfflineContextLoader::new();
+        let batch = BlockcertsBatch::new(documents, &loader).unwrap();
+        let anchor = Anchor {
+            source_id: "tx1".to_string(),
+            kind: "BTCOpReturn".to_string(),
+            chain: Some("bitcoinTestnet".to_string()),
+        };
+        let addresses = vec!["mgCNaPM3TFhh8Yn6X6VcEGd3RGfGNeUtrh".to_string()];
+        let chain = Chain(vec![(
+            "tx1".to_string(),
+            addresses[0].clone(),
+            batch.merkle_root(),
+        )]);
+
+        let certificates = batch.certificates(std::slice::from_ref(&anchor));
+        assert_eq!(certificates.len(), 5);
+        for certificate in &certificates {
+            blockcerts::verify(certificate, &loader, &chain, &addresses).unwrap();
+        }
+
+        let mut tampered = certificates[4].clone();
+        tampered["credentialSubject"]["name"] = json!("Mallory");
+        assert!(matches!(
+            blockcerts::verify(&tampered, &loader, &chain, &addresses),
+            Err(BlockcertsError::TargetHashMismatch)
+        ));
+        let other_root = Chain(vec![("tx1".to_string(), addresses[0].clone(), [0; 32])]);
+        assert!(matches!(
+            blockcerts::verify(&certificates[0], &loader, &other_root, &addresses),
+            Err(BlockcertsError::AnchorMismatch(_))
+        ));
+        // The right root, anchored by someone else.
+        let forged = Chain(vec![(
+            "tx1".to_string(),
+            "n2eMqTT929pb1RDNuqEnxdaLau1rxy3efi".to_string(),
+            batch.merkle_root(),
+        )]);
+        assert!(matches!(
+            blockcerts::verify(&certificates[0], &loader, &forged, &addresses),
+            Err(BlockcertsError::UnknownSender(_))
+        ));
+        assert!(matches!(
+            blockcerts::verify(&certificates[0], &loader, &chain, &[]),
+            Err(BlockcertsError::UnknownSender(_))
+        ));
+    }
+
+    #[test]
+    fn test_simulated_time_drives_expiry() {
+        use crate::clock::ManualClock;
+        use crate::crypto::CryptoKeyPair;
+        use crate::verifier::VerificationError;
+        use std::sync::Arc;
+
+        let start = "2020-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let clock = Arc::new(ManualClock::new(start));
+
+        let issuer_service = CredentialIssuer::with_clock(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+            Box::new(CryptoKeyPair::generate()),
+            clock.clone(),
+        );
+        assert_eq!(
+            issuer_service.get_issuer_info().key_history[0].activated_at,
+            start
+        );
+        let mut verifier = CredentialVerifier::new();
+        verifier.set_clock(clock.clone());
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+
+        let credential =
+            sample_credential_expiring(&issuer_service, Some(start + Duration::days(30)));
+        assert_eq!(credential.issued_at, start);
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        clock.advance(Duration::days(31));
+        assert!(matches!(
+            verifier.verify_credential(&credential),
+            Err(VerificationError::ExpiredCredential)
+        ));
+    }
+
+    #[cfg(feature = "didcomm")]
+    #[test]
+    fn test_didcomm_anoncrypt_roundtrip_for_each_recipient() {
+        use crate::didcomm::{
+            pack_anoncrypt, unpack_anoncrypt, DidCommError, KeyAgreementKey, Message, MessageKind,
+        };
+
+        let issuer_did = "did:example:issuer".to_string();
+        let wallet_key = KeyAgreementKey::generate("did:example:wallet#key-1".to_string());
+        let mediator_key = KeyAgreementKey::generate("did:example:mediator#key-1".to_string());
+        let offer = Message::offer_credential(
+            issuer_did,
+            "did:example:wallet".to_string(),
+            &sample_skill(),
+            Utc::now(),
+        )
+        .unwrap();
+
+        let packed =
+            pack_anoncrypt(&offer, &[wallet_key.recipient(), mediator_key.recipient()]).unwrap();
+        for key in [&wallet_key, &mediator_key] {
+            let unpacked = unpack_anoncrypt(&packed, key).unwrap();
+            assert_eq!(unpacked, offer);
+            assert_eq!(unpacked.kind(), Some(MessageKind::OfferCredential));
+            let offered: Skill = unpacked.attachment("microcred/skill@v1").unwrap();
+            assert_eq!(offered.id, "test-skill");
+        }
+
+        let outsider = KeyAgreementKey::generate("did:example:wallet#key-1".to_string());
+        assert_eq!(
+            unpack_anoncrypt(&packed, &outsider).unwrap_err(),
+            DidCommError::DecryptionFailed
+        );
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        use crate::duplicates::{duplicates_of, find_duplicates};
+
+        let now = Utc::now();
+        let subject = sample_subject();
+        let credential = |level, age| {
+            let skill = Skill {
+                level,
+                ..sample_skill()
+            };
+            let mut credential = unsigned_credential(Uuid::from_u128(1), &subject, skill);
+            credential.issued_at = now - Duration::days(age);
+            credential
+        };
+        let first = credential(SkillLevel::Intermediate, 2);
+        let repeat = credential(SkillLevel::Intermediate, 1);
+        let advanced = credential(SkillLevel::Advanced, 0);
+        let mut expired = credential(SkillLevel::Expert, 9);
+        expired.expires_at = Some(now - Duration::days(3));
+
+        let all = vec![first.clone(), repeat.clone(), advanced.clone(), expired];
+        let found = find_duplicates(&all, now);
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            (found[0].credential_id, found[0].existing_id),
+            (first.id, advanced.id)
+        );
+        assert_eq!(
+            (found[1].credential_id, found[1].existing_id),
+            (repeat.id, first.id)
+        );
+        assert!(duplicates_of(&advanced, &all, now).is_empty());
+
+        let mut elsewhere = credential(SkillLevel::Expert, 0);
+        elsewhere.issuer.id = Uuid::new_v4();
+        assert!(duplicates_of(&elsewhere, &all, now).is_empty());
+        assert_eq!(duplicates_of(&repeat, &[elsewhere], now), Vec::new());
+    }
+
+    #[test]
+    fn test_compare_across_issuers_and_scales() {
+        use crate::catalog::{Alignment, SkillCatalog, SkillDefinition};
+        use crate::equivalence::{EquivalenceEvaluator, SkillBasis};
+        use crate::levels::LevelScale;
+
+        fn catalog(skill_id: &str, name: &str, scale: LevelScale, codes: &[&str]) -> SkillCatalog {
+            let mut definition =
+                SkillDefinition::new(skill_id.to_string(), name.to_string(), String::new(), scale);
+            for code in codes {
+                definition = definition.alignment(Alignment {
+                    framework: "ESCO".to_string(),
+                    target_name: code.to_string(),
+                    target_url: format!("http://data.europa.eu/esco/skill/{}", code),
+                    target_code: Some(code.to_string()),
+                });
+            }
+            let mut catalog = SkillCatalog::new();
+            catalog.publish(definition);
+            catalog
+        }
+
+        let credential = |issuer_id, skill_id: &str, name: &str, level| {
+            let skill = Skill {
+                id: skill_id.to_string(),
+                name: name.to_string(),
+                level,
+                ..sample_skill()
+            };
+            unsigned_credential(issuer_id, &sample_subject(), skill)
+        };
+
+        let (university, college) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut evaluator = EquivalenceEvaluator::new();
+        evaluator.add_catalog(
+            university,
+            catalog("welding-201", "Welding", LevelScale::eqf(), &["s1", "s2"]),
+        );
+        evaluator.add_catalog(
+            college,
+            catalog(
+                "mig",
+                "MIG welding",
+                LevelScale::default_scale(),
+                &["s1", "s2"],
+            ),
+        );
+
+        let eqf6 = LevelScale::eqf().level("EQF 6").unwrap();
+        let presented = credential(university, "welding-201", "Welding", eqf6);
+        let target = credential(college, "mig", "MIG welding", SkillLevel::Advanced);
+        let matched = evaluator.compare(&presented, &target);
+        assert_eq!(matched.basis, SkillBasis::Alignment);
+        assert_eq!(matched.skill_score, 1.0);
+        assert_eq!(matched.shared_alignments.len(), 2);
+        assert_eq!(matched.level_score, Some(1.0));
+        assert!(matched.is_equivalent(0.8));
+
+        let beginner = credential(college, "mig", "MIG welding", SkillLevel::Beginner);
+        let matched = evaluator.compare(&beginner, &presented);
+        assert_eq!(matched.meets_level, Some(false));
+        assert!(!matched.is_equivalent(0.1));
+        // Exceeding the target level is not penalized.
+        let matched = evaluator.compare(&presented, &beginner);
+        assert_eq!(matched.level_score, Some(1.0));
+        assert!(matched.is_equivalent(0.8));
+
+        // Credentials are compared by the skill version they were issued
+        // against, not the catalog's current one.
+        let mut revised = catalog("welding-201", "Welding", LevelScale::eqf(), &["s1", "s2"]);
+        revised.publish(SkillDefinition::new(
+            "welding-201".to_string(),
+            "Welding".to_string(),
+            String::new(),
+            LevelScale::eqf(),
+        ));
+        evaluator.add_catalog(university, revised);
+        let mut issued_v1 = presented.clone();
+        issued_v1.add_metadata("skill_version".to_string(), "1".to_string());
+        assert_eq!(evaluator.compare(&issued_v1, &target).basis, SkillBasis::Alignment);
+        assert_eq!(evaluator.compare(&presented, &target).basis, SkillBasis::Name);
+
+        let unaligned = credential(Uuid::new_v4(), "tig", "TIG welding", SkillLevel::Advanced);
+        let matched = evaluator.compare(&unaligned, &target);
+        assert_eq!(matched.basis, SkillBasis::Name);
+        assert!((matched.score - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_exchange_persists_and_resumes() {
+        use crate::builder::CredentialBuilder;
+        use crate::exchange::{ExchangeError, ExchangeState, IssuanceExchange};
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let skill = sample_skill();
+        let evidence = Evidence {
+            id: Uuid::new_v4(),
+            name: "Final exam".to_string(),
+            description: "Passed the final exam".to_string(),
+            url: "https://test.edu/exams/1".to_string(),
+            evidence_type: EvidenceType::Assessment,
+            unknown_fields: Default::default(),
+        };
+        let expires_at: DateTime<Utc> = "2030-01-01T00:00:00Z".parse().unwrap();
+        let mut exchange = IssuanceExchange::offer(
+            &issuer_service,
+            skill.clone(),
+            vec![evidence.clone()],
+            Some(expires_at),
+            None,
+        );
+        assert_eq!(
+            exchange.acknowledge(&issuer_service),
+            Err(ExchangeError::InvalidTransition {
+                state: "offer_sent",
+                action: "acknowledge"
+            })
+        );
+
+        let request = CredentialBuilder::new()
+            .subject(sample_subject())
+            .skill(Skill {
+                level: SkillLevel::Expert,
+                ..skill
+            })
+            .metadata("grade".to_string(), "A+".to_string())
+            .build()
+            .unwrap();
+        exchange.receive_request(&issuer_service, request).unwrap();
+
+        let saved = serde_json::to_string(&exchange).unwrap();
+        let mut resumed: IssuanceExchange = serde_json::from_str(&saved).unwrap();
+        assert_eq!(resumed.state.name(), "request_received");
+
+        let credential = resumed.issue(&issuer_service).unwrap();
+        assert_eq!(credential.skill.level, SkillLevel::Intermediate);
+        assert!(credential.metadata.is_empty());
+        assert_eq!(credential.evidence[0].id, evidence.id);
+        assert_eq!(credential.expires_at, Some(expires_at));
+        let credential_id = credential.id;
+        resumed.acknowledge(&issuer_service).unwrap();
+        assert!(matches!(
+            resumed.state,
+            ExchangeState::Acknowledged { credential_id: id } if id == credential_id
+        ));
+        assert!(resumed
+            .abandon(&issuer_service, "late".to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn test_content_derived_ids_are_stable() {
+        use crate::ids::IdStrategy;
+        use crate::store::{CredentialStore, InMemoryStore, StoreError};
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        issuer_service.set_id_strategy(IdStrategy::content_derived("https://test.edu"));
+        let request = sample_request();
+
+        let first = issuer_service.issue_request(request.clone()).unwrap();
+        issuer_service.rotate_key();
+        let second = issuer_service.issue_request(request.clone()).unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.id.get_version_num(), 5);
+
+        // Salts differ per signing, so they are not part of the id.
+        issuer_service.set_digest_salting(true);
+        let salted = issuer_service.issue_request(request.clone()).unwrap();
+        assert_eq!(salted.id, first.id);
+        issuer_service.set_digest_salting(false);
+
+        let mut other = request;
+        other.subject.name = "Other Student".to_string();
+        assert_ne!(issuer_service.issue_request(other).unwrap().id, first.id);
+
+        let mut store = InMemoryStore::new();
+        store.insert(&first).unwrap();
+        assert_eq!(store.insert(&second), Err(StoreError::Duplicate(first.id)));
+    }
+
+    #[test]
+    fn test_linked_data_proofs_round_trip() {
+        use crate::crypto::CryptoKeyPair;
+        use crate::ld::context::{OfflineContextLoader, ISSUER_DEPENDENT_VOCAB};
+        use crate::ld::{
+            add_proof, canonicalize, credential_document, validate_terms, verify_proof,
+            LdProofOptions, LdSuite,
+        };
+        use crate::proof::ProofPurpose;
+        use serde_json::json;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = sample_credential(&issuer_service);
+        let loader = OfflineContextLoader::new();
+        let document = credential_document(&credential);
+        validate_terms(&document, &loader).unwrap();
+        let nquads = canonicalize(&document, &loader).unwrap();
+        let id = format!("urn:uuid:{}", credential.id);
+        assert!(nquads.contains(&format!(
+            "<{}#level-2> <https://purl.imsglobal.org/spec/vc/ob/vocab.html#level> \
+             \"2\"^^<https://www.w3.org/2001/XMLSchema#string> .",
+            id
+        )));
+        assert!(nquads.contains(&format!(
+            "<https://purl.imsglobal.org/spec/vc/ob/vocab.html#achievedLevel> <{}#level-2> .",
+            id
+        )));
+        assert!(nquads.contains(&format!(
+            "<{}> <https://www.w3.org/2018/credentials#issuer> <https://test.edu> .",
+            id
+        )));
+        // Only the evidence type falls back to the issuer-dependent vocabulary.
+        assert!(nquads
+            .lines()
+            .filter(|line| line.contains(ISSUER_DEPENDENT_VOCAB))
+            .all(|line| line.contains("evidenceType")));
+
+        let key = CryptoKeyPair::generate();
+        for suite in [LdSuite::Ed25519Signature2020, LdSuite::EddsaRdfc2022] {
+            let mut signed = document.clone();
+            add_proof(
+                &mut signed,
+                &key,
+                &LdProofOptions {
+                    suite,
+                    verification_method: "https://test.edu#key-1".to_string(),
+                    created: Utc::now(),
+                    purpose: ProofPurpose::AssertionMethod,
+                },
+                &loader,
+            )
+            .unwrap();
+            let contexts = signed["@context"].as_array().unwrap();
+            let suite_context = json!(suite.context());
+            // VC 2.0 defines DataIntegrityProof itself.
+            assert_eq!(
+                contexts.contains(&suite_context),
+                suite == LdSuite::Ed25519Signature2020
+            );
+            assert!(verify_proof(&signed, &key.public_key(), &loader).unwrap());
+
+            // Key order and whitespace do not matter, content does.
+            let reparsed: Value =
+                serde_json::from_str(&serde_json::to_string_pretty(&signed).unwrap()).unwrap();
+            assert!(verify_proof(&reparsed, &key.public_key(), &loader).unwrap());
+            let mut tampered = signed.clone();
+            tampered["credentialSubject"]["result"][0]["achievedLevel"] =
+                json!(format!("{}#level-3", id));
+            assert!(!verify_proof(&tampered, &key.public_key(), &loader).unwrap());
+            let mut tampered = signed;
+            tampered["proof"]["created"] = json!("2000-01-01T00:00:00Z");
+            assert!(!verify_proof(&tampered, &key.public_key(), &loader).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_dual_control_lifecycle() {
+        use crate::lifecycle::{LifecycleError, LifecycleRecord, LifecycleState};
+        use crate::status::CredentialStatus;
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let request = sample_request();
+
+        let mut record = LifecycleRecord::draft(request.clone(), "clerk".to_string());
+        assert_eq!(
+            record.approve(&issuer_service, "registrar").unwrap_err(),
+            LifecycleError::InvalidTransition {
+                state: "draft",
+                action: "approve"
+            }
+        );
+        record.update_draft(request, "editor").unwrap();
+        record.submit(&issuer_service, "coordinator").unwrap();
+        assert_eq!(
+            record.approve(&issuer_service, "clerk").unwrap_err(),
+            LifecycleError::ApproverIsDrafter
+        );
+        assert_eq!(
+            record.approve(&issuer_service, "editor").unwrap_err(),
+            LifecycleError::ApproverIsEditor
+        );
+        assert_eq!(
+            record.approve(&issuer_service, "coordinator").unwrap_err(),
+            LifecycleError::ApproverIsSubmitter
+        );
+        assert!(record.credential().is_none());
+
+        let credential_id = record.approve(&issuer_service, "registrar").unwrap().id;
+        record
+            .suspend(&mut issuer_service, "registrar", "Under review".to_string())
+            .unwrap();
+        record
+            .revoke(&mut issuer_service, "registrar", "Misconduct".to_string())
+            .unwrap();
+        assert!(matches!(
+            issuer_service.credential_status(&credential_id),
+            CredentialStatus::Revoked { .. }
+        ));
+        assert!(record.reinstate(&mut issuer_service, "registrar").is_err());
+
+        // A hand-edited store can claim an issued record without its
+        // credential.
+        let mut json = serde_json::to_value(&record).unwrap();
+        json["state"] = serde_json::json!({"state": "issued"});
+        json["credential"] = serde_json::Value::Null;
+        let mut damaged: LifecycleRecord = serde_json::from_value(json).unwrap();
+        assert_eq!(damaged.state(), &LifecycleState::Issued);
+        assert_eq!(
+            damaged
+                .revoke(&mut issuer_service, "registrar", "Misconduct".to_string())
+                .unwrap_err(),
+            LifecycleError::MissingCredential
+        );
+
+        let steps: Vec<&str> = record.history().iter().map(|t| t.to.as_str()).collect();
+        assert_eq!(
+            steps,
+            vec!["pending_approval", "issued", "suspended", "revoked"]
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_follows_the_issuer_clock() {
+        use crate::clock::{Clock, ManualClock};
+        use crate::lifecycle::{LifecycleRecord, LifecycleState};
+        use std::sync::Arc;
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let start = Utc::now() - Duration::days(30);
+        let clock = Arc::new(ManualClock::new(start));
+        issuer_service.set_clock(clock.clone());
+
+        let mut request = sample_request();
+        request.expires_at = Some(start + Duration::days(7));
+        let mut record = LifecycleRecord::draft(request, "clerk".to_string());
+        record.submit(&issuer_service, "clerk").unwrap();
+        record.approve(&issuer_service, "registrar").unwrap();
+        assert!(!record.expire_if_due(&issuer_service));
+
+        clock.advance(Duration::days(8));
+        assert!(record.expire_if_due(&issuer_service));
+        assert_eq!(record.state(), &LifecycleState::Expired);
+        assert_eq!(record.history()[0].at, start);
+        assert_eq!(record.history().last().unwrap().at, clock.now());
+    }
+
+    #[test]
+    fn test_manifest_describes_required_inputs() {
+        use crate::manifest::CredentialManifest;
+        use crate::validation::ValidationRules;
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        issuer_service.set_validation_rules(ValidationRules {
+            min_evidence: 1,
+            ..Default::default()
+        });
+
+        let manifest = issuer_service.credential_manifest(&sample_skill());
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(
+            json["output_descriptors"][0]["display"]["title"]["text"],
+            "Test Skill"
+        );
+        let roundtrip: CredentialManifest = serde_json::from_value(json).unwrap();
+        assert_eq!(roundtrip, manifest);
+
+        let report = manifest.check_application(&serde_json::to_value(sample_request()).unwrap());
+        let fields: Vec<&str> = report.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, vec!["$.evidence"]);
+
+        // Holder keys are accepted as multikey strings or byte arrays.
+        let mut application = serde_json::to_value(sample_request()).unwrap();
+        application["evidence"] = serde_json::json!([{}]);
+        for (holder_key, valid) in [
+            (serde_json::json!("z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp"), true),
+            (serde_json::json!([1, 2, 3]), true),
+            (serde_json::json!(42), false),
+        ] {
+            application["holder_key"] = holder_key;
+            assert_eq!(manifest.check_application(&application).is_valid(), valid);
+        }
+    }
+
+    #[test]
+    fn test_mdoc_items_are_signed_and_selectively_disclosable() {
+        use crate::cbor::Item;
+        use crate::crypto::CryptoKeyPair;
+        use crate::mdoc::{MdocDocument, MdocError};
+        use chrono::TimeZone;
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let holder = CryptoKeyPair::generate();
+        let mut credential =
+            sample_credential_expiring(&issuer_service, Some(Utc::now() + Duration::days(365)));
+        let key = CryptoKeyPair::generate();
+        let signed = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        assert!(matches!(
+            MdocDocument::issue(&credential, &key, Vec::new(), signed),
+            Err(MdocError::MissingDeviceKey)
+        ));
+        credential.holder_binding = Some(HolderBinding {
+            public_key: holder.public_key().into(),
+        });
+
+        let mdoc = MdocDocument::issue(&credential, &key, Vec::new(), signed).unwrap();
+        assert!(mdoc.verify(&key.public_key()));
+        assert!(!mdoc.verify(&holder.public_key()));
+        assert_eq!(mdoc.items[10].element_identifier, "skill_level_rank");
+        assert_eq!(mdoc.items[10].element_value, Item::Unsigned(2));
+
+        let disclosed = mdoc.disclose(&["skill_name", "skill_level"]);
+        assert_eq!(disclosed.items.len(), 2);
+        assert!(disclosed.verify(&key.public_key()));
+        let mut forged = disclosed.clone();
+        forged.items[0].element_value = Item::text("Haskell");
+        assert!(!forged.verify(&key.public_key()));
+
+        let encoded = mdoc.to_cbor();
+        // Map of two entries whose first key is "docType".
+        assert_eq!(&encoded[..9], b"\xa2\x67docType");
+        let mso = mdoc.mso.to_item().to_vec();
+        assert!(mso
+            .windows(22)
+            .any(|window| window == b"\xc0\x742024-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_hosted_badge_verification() {
+        use crate::openbadges::{
+            BadgeFetcher, BadgeHosting, HostedBadge, HostedBadgeVerifier, OpenBadgeError,
+        };
+        use crate::status::CredentialStatus;
+        use serde_json::json;
+        use std::sync::Arc;
+
+        struct Hosted(HashMap<String, Value>);
+
+        impl Hosted {
+            fn new(badge: &HostedBadge) -> Self {
+                let documents = [&badge.assertion, &badge.badge_class, &badge.issuer]
+                    .into_iter()
+                    .map(|document| {
+                        (
+                            document["id"].as_str().unwrap().to_string(),
+                            document.clone(),
+                        )
+                    })
+                    .collect();
+                Self(documents)
+            }
+        }
+
+        impl BadgeFetcher for Hosted {
+            fn fetch(&self, url: &str) -> Result<Value, String> {
+                self.0.get(url).cloned().ok_or_else(|| "404".to_string())
+            }
+        }
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credential = sample_credential(&issuer_service);
+        let hosting = BadgeHosting::new("https://badges.test.edu/");
+        let badge = hosting.hosted_badge(&credential, &CredentialStatus::Active);
+        let url = hosting.assertion_url(&credential);
+        assert_eq!(
+            badge.badge_class["issuer"],
+            "https://badges.test.edu/issuer"
+        );
+
+        let verifier = HostedBadgeVerifier::new(Arc::new(Hosted::new(&badge)));
+        let verified = verifier.verify(&url, Some("Test@Example.com")).unwrap();
+        assert_eq!(verified.badge_class["name"], "Test Skill");
+        assert_eq!(
+            verifier.verify(&url, Some("bob@example.com")).err(),
+            Some(OpenBadgeError::RecipientMismatch)
+        );
+
+        let revoked = hosting.hosted_badge(
+            &credential,
+            &CredentialStatus::Revoked {
+                reason: "Academic misconduct".to_string(),
+                revoked_at: Utc::now(),
+            },
+        );
+        let verifier = HostedBadgeVerifier::new(Arc::new(Hosted::new(&revoked)));
+        assert_eq!(
+            verifier.verify(&url, None).err(),
+            Some(OpenBadgeError::Revoked("Academic misconduct".to_string()))
+        );
+
+        // An assertion copied to another host is not the issuer's, even if
+        // it names that host as allowed.
+        let mut copied = badge.clone();
+        copied.assertion["id"] = json!("https://evil.example/assertions/1");
+        copied.assertion["verification"]["allowedOrigins"] = json!("https://evil.example");
+        let verifier = HostedBadgeVerifier::new(Arc::new(Hosted::new(&copied)));
+        assert!(matches!(
+            verifier.verify("https://evil.example/assertions/1", None),
+            Err(OpenBadgeError::OriginMismatch(_))
+        ));
+
+        // The issuer profile can narrow hosting to a path.
+        let mut narrowed = badge.clone();
+        narrowed.issuer["verification"] =
+            json!({ "startsWith": "https://badges.test.edu/verified/" });
+        let verifier = HostedBadgeVerifier::new(Arc::new(Hosted::new(&narrowed)));
+        assert!(matches!(
+            verifier.verify(&url, None),
+            Err(OpenBadgeError::OriginMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_hash_algorithm_is_recorded_and_honoured() {
+        use crate::proof::{HashAlgorithm, Proof};
+        use crate::verifier::VerificationError;
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+
+        let default = sample_credential(&issuer_service);
+        assert_eq!(default.proof.as_ref().unwrap().hash, HashAlgorithm::Sha256);
+        assert!(verifier.verify_credential(&default).unwrap());
+
+        issuer_service.set_hash_algorithm(HashAlgorithm::Blake3);
+        let mut credential = sample_credential(&issuer_service);
+        let json = serde_json::to_value(&credential).unwrap();
+        assert_eq!(json["proof"]["hash"], "blake3");
+        assert!(verifier.verify_credential(&credential).unwrap());
+
+        // The algorithm is covered by the signature.
+        credential.proof = Some(Proof {
+            hash: HashAlgorithm::Sha512,
+            ..Default::default()
+        });
+        assert!(matches!(
+            verifier.verify_credential(&credential),
+            Err(VerificationError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_mixed_proofs_across_key_rotation() {
+        use crate::proof::{key_id, HashAlgorithm, PayloadEncoding, ProofPurpose, SignatureSuite};
+        use crate::verifier::VerificationError;
+
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+
+        let old = sample_credential(&issuer_service);
+        let old_key = issuer_service.get_issuer_info().public_key.clone();
+        let proof = old.proof.clone().unwrap();
+        assert_eq!(proof.suite, SignatureSuite::Ed25519);
+        assert_eq!(proof.created, Some(old.issued_at));
+        assert_eq!(
+            proof.verification_method,
+            Some(format!("https://test.edu#{}", key_id(&old_key)))
+        );
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        issuer_service.rotate_key();
+        issuer_service.set_hash_algorithm(HashAlgorithm::Blake3);
+        issuer_service.set_payload_encoding(PayloadEncoding::Cbor);
+        let new = sample_credential(&issuer_service);
+        assert_eq!(
+            new.proof.as_ref().unwrap().key_id(),
+            Some(key_id(&issuer_service.get_issuer_info().public_key).as_str())
+        );
+
+        let mut verifier = CredentialVerifier::new();
+        verifier.add_trusted_issuer(issuer_service.get_issuer_info().clone());
+        assert!(verifier.verify_credential(&old).unwrap());
+        assert!(verifier.verify_credential(&new).unwrap());
+
+        let mut forged = new.clone();
+        forged.proof.as_mut().unwrap().verification_method =
+            old.proof.clone().unwrap().verification_method;
+        assert!(matches!(
+            verifier.verify_credential(&forged),
+            Err(VerificationError::NoActiveIssuerKey)
+        ));
+
+        let mut authentication = new;
+        authentication.proof.as_mut().unwrap().proof_purpose = ProofPurpose::Authentication;
+        assert!(matches!(
+            verifier.verify_credential(&authentication),
+            Err(VerificationError::UnsupportedProof(_))
+        ));
+    }
+
+    #[test]
+    fn test_queue_retries_then_dead_letters() {
+        use crate::queue::{Delivery, IssuanceQueue, JobStatus, RetryPolicy};
+        use crate::store::InMemoryStore;
+        use std::error::Error;
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::{Arc, Mutex};
+        use std::time::{Duration, Instant};
+
+        struct FlakyDelivery {
+            failures: AtomicU32,
+        }
+
+        impl Delivery for FlakyDelivery {
+            fn deliver(&self, _: &Microcredential) -> Result<(), Box<dyn Error + Send + Sync>> {
+                let remaining = self.failures.load(Ordering::SeqCst);
+                if remaining > 0 {
+                    self.failures.store(remaining - 1, Ordering::SeqCst);
+                    return Err("mail server unavailable".into());
+                }
+                Ok(())
+            }
+        }
+
+        /// Waits for `job` to leave the queued and retrying states.
+        fn settled(queue: &IssuanceQueue, job: &Uuid) -> Option<JobStatus> {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            loop {
+                match queue.status(job) {
+                    Some(JobStatus::Queued | JobStatus::Retrying { .. })
+                        if Instant::now() < deadline =>
+                    {
+                        std::thread::sleep(Duration::from_millis(1))
+                    }
+                    status => return status,
+                }
+            }
+        }
+
+        let issuer_service = Arc::new(CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        ));
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            multiplier: 2,
+            max_backoff: Duration::from_millis(10),
+        };
+        assert_eq!(policy.backoff(3), Duration::from_millis(4));
+
+        let store = Arc::new(Mutex::new(InMemoryStore::new()));
+        let queue = IssuanceQueue::start(
+            Arc::clone(&issuer_service),
+            Arc::clone(&store),
+            FlakyDelivery {
+                failures: AtomicU32::new(2),
+            },
+            policy.clone(),
+        );
+        let job = queue.enqueue(sample_request());
+        assert!(matches!(
+            settled(&queue, &job),
+            Some(JobStatus::Delivered { .. })
+        ));
+        queue.shutdown();
+        assert_eq!(store.lock().unwrap().len(), 1);
+
+        let queue = IssuanceQueue::start(
+            issuer_service,
+            Arc::clone(&store),
+            FlakyDelivery {
+                failures: AtomicU32::new(u32::MAX),
+            },
+            policy,
+        );
+        let job = queue.enqueue(sample_request());
+        let mut invalid = sample_request();
+        invalid.subject.email = "invalid".to_string();
+        let failed = queue.enqueue(invalid);
+        assert!(matches!(
+            settled(&queue, &failed),
+            Some(JobStatus::Failed { .. })
+        ));
+        assert!(matches!(
+            settled(&queue, &job),
+            Some(JobStatus::DeadLettered { .. })
+        ));
+        let dead_letters = queue.take_dead_letters();
+        queue.shutdown();
+
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].job_id, job);
+        assert_eq!(dead_letters[0].attempts, 3);
+        assert_eq!(store.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_issue_batch_is_all_or_nothing() {
+        use crate::store::{BatchError, CredentialStore, InMemoryStore, StoreError};
+
+        struct FailingStore {
+            inner: InMemoryStore,
+            fail_after: usize,
+        }
+
+        impl CredentialStore for FailingStore {
+            fn insert(&mut self, credential: &Microcredential) -> Result<(), StoreError> {
+                if self.inner.len() == self.fail_after {
+                    return Err(StoreError::Backend("disk full".to_string()));
+                }
+                self.inner.insert(credential)
+            }
+
+            fn get(&self, id: &Uuid) -> Result<Option<Microcredential>, StoreError> {
+                self.inner.get(id)
+            }
+
+            fn remove(&mut self, id: &Uuid) -> Result<Option<Microcredential>, StoreError> {
+                self.inner.remove(id)
+            }
+
+            fn ids(&self) -> Result<Vec<Uuid>, StoreError> {
+                self.inner.ids()
+            }
+        }
+
+        let issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+
+        let mut store = InMemoryStore::new();
+        let issued = issuer_service
+            .issue_batch(vec![sample_request(), sample_request()], &mut store)
+            .unwrap();
+        assert_eq!(store.len(), 2);
+        assert!(store.get(&issued[0].id).unwrap().is_some());
+
+        let subject = issued[0].subject.id;
+        let ids = |credentials: Vec<Microcredential>| {
+            credentials.iter().map(|credential| credential.id).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(store.subject_credentials(&subject).unwrap()), vec![issued[0].id]);
+        let mut scanned = FailingStore {
+            inner: InMemoryStore::new(),
+            fail_after: usize::MAX,
+        };
+        scanned.insert_all(&issued).unwrap();
+        assert_eq!(ids(scanned.subject_credentials(&subject).unwrap()), vec![issued[0].id]);
+        scanned.inner.remove(&issued[0].id).unwrap();
+        assert!(scanned.inner.subject_credentials(&subject).unwrap().is_empty());
+
+        let mut invalid = sample_request();
+        invalid.subject.email = "not-an-email".to_string();
+        let err = issuer_service
+            .issue_batch(vec![sample_request(), invalid], &mut store)
+            .unwrap_err();
+        assert!(matches!(err, BatchError::Issuance { index: 1, .. }));
+        assert_eq!(store.len(), 2);
+
+        let mut failing = FailingStore {
+            inner: InMemoryStore::new(),
+            fail_after: 2,
+        };
+        let cohort = vec![sample_request(), sample_request(), sample_request()];
+        let err = issuer_service.issue_batch(cohort, &mut failing).unwrap_err();
+        assert!(matches!(err, BatchError::Store(StoreError::Backend(_))));
+        assert!(failing.inner.is_empty());
+    }
+
+    #[cfg(feature = "redis")]
+    #[test]
+    #[ignore = "needs a Redis server in REDIS_URL"]
+    fn test_redis_store_round_trip() {
+        use crate::store::{CredentialStore, RedisStatusCache, RedisStore, StoreError};
+
+        let url = std::env::var("REDIS_URL").expect("REDIS_URL is not set");
+        let prefix = format!("microcred-test-{}:", Uuid::new_v4());
+        let mut store = RedisStore::connect(&url, &prefix).unwrap();
+        let mut issuer_service =
+            CredentialIssuer::new("Test University".to_string(), "https://test.edu".to_string());
+        let credentials: Vec<Microcredential> =
+            (0..3).map(|_| sample_credential(&issuer_service)).collect();
+
+        let repeated = vec![credentials[1].clone(), credentials[1].clone()];
+        assert_eq!(
+            store.insert_all(&repeated),
+            Err(StoreError::Duplicate(credentials[1].id))
+        );
+        assert!(store.ids().unwrap().is_empty());
+
+        store.insert(&credentials[0]).unwrap();
+        assert_eq!(
+            store.insert_all(&credentials),
+            Err(StoreError::Duplicate(credentials[0].id))
+        );
+        assert_eq!(store.ids().unwrap(), vec![credentials[0].id]);
+        store.insert_all(&credentials[1..]).unwrap();
+        assert_eq!(store.ids().unwrap().len(), 3);
+        let held = store.subject_credentials(&credentials[1].subject.id).unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].id, credentials[1].id);
+        for credential in &credentials {
+            assert!(store.remove(&credential.id).unwrap().is_some());
+        }
+
+        let cache = RedisStatusCache::connect(&url, &prefix, Some(60)).unwrap();
+        issuer_service
+            .revoke_credential(&credentials[0].id, "Issued in error".to_string())
+            .unwrap();
+        cache.put(issuer_service.get_status_list()).unwrap();
+        let issuer_id = issuer_service.get_issuer_info().id;
+        let cached = cache.get(&issuer_id).unwrap().unwrap();
+        assert!(!cached.status_of(&credentials[0].id).is_active());
+        cache.invalidate(&issuer_id).unwrap();
+        assert!(cache.get(&issuer_id).unwrap().is_none());
+    }
+
+}
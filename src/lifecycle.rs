@@ -0,0 +1,335 @@
+use crate::issuer::{CredentialIssuer, CredentialRequest};
+use crate::status::StatusError;
+use crate::Microcredential;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum LifecycleState {
+    Draft,
+    PendingApproval,
+    Issued,
+    Suspended { reason: String },
+    Revoked { reason: String },
+    Expired,
+}
+
+impl LifecycleState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LifecycleState::Draft => "draft",
+            LifecycleState::PendingApproval => "pending_approval",
+            LifecycleState::Issued => "issued",
+            LifecycleState::Suspended { .. } => "suspended",
+            LifecycleState::Revoked { .. } => "revoked",
+            LifecycleState::Expired => "expired",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleTransition {
+    pub from: String,
+    pub to: String,
+    pub actor: String,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum LifecycleError {
+    InvalidTransition {
+        state: &'static str,
+        action: &'static str,
+    },
+    ApproverIsDrafter,
+    ApproverIsEditor,
+    ApproverIsSubmitter,
+    /// An issued record has lost its credential, e.g. in a hand-edited
+    /// store.
+    MissingCredential,
+    Issuance(String),
+    Status(StatusError),
+}
+
+impl fmt::Display for LifecycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LifecycleError::InvalidTransition { state, action } => {
+                write!(f, "Cannot {} a credential that is {}", action, state)
+            }
+            LifecycleError::ApproverIsDrafter => {
+                write!(f, "Approver must not be the drafter")
+            }
+            LifecycleError::ApproverIsEditor => {
+                write!(f, "Approver must not have edited the draft")
+            }
+            LifecycleError::ApproverIsSubmitter => {
+                write!(f, "Approver must not be the submitter")
+            }
+            LifecycleError::MissingCredential => {
+                write!(f, "Record has no issued credential")
+            }
+            LifecycleError::Issuance(msg) => write!(f, "Issuance failed: {}", msg),
+            LifecycleError::Status(err) => write!(f, "Status update failed: {}", err),
+        }
+    }
+}
+
+impl Error for LifecycleError {}
+
+impl From<StatusError> for LifecycleError {
+    fn from(err: StatusError) -> Self {
+        LifecycleError::Status(err)
+    }
+}
+
+/// A credential tracked from draft to end of life. Nothing is signed until
+/// a second person approves the draft: someone who neither drafted, last
+/// edited nor submitted it. Fields are only changed through transitions, so
+/// the record can be persisted and reloaded without weakening that rule.
+/// Transition times come from the issuer's clock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleRecord {
+    id: Uuid,
+    request: CredentialRequest,
+    drafted_by: String,
+    #[serde(default)]
+    last_edited_by: Option<String>,
+    #[serde(default)]
+    submitted_by: Option<String>,
+    state: LifecycleState,
+    /// The credential a renewal draft replaces once approved.
+    #[serde(default)]
+    renews: Option<Microcredential>,
+    credential: Option<Microcredential>,
+    history: Vec<LifecycleTransition>,
+}
+
+impl LifecycleRecord {
+    pub fn draft(request: CredentialRequest, drafted_by: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            request,
+            drafted_by,
+            last_edited_by: None,
+            submitted_by: None,
+            state: LifecycleState::Draft,
+            renews: None,
+            credential: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// A draft renewing `credential` until `expires_at`, which is signed
+    /// through `CredentialIssuer::renew_request` under the same dual control
+    /// as a new credential.
+    pub fn renewal(
+        credential: Microcredential,
+        expires_at: Option<DateTime<Utc>>,
+        drafted_by: String,
+    ) -> Self {
+        let request = CredentialRequest::renewing(&credential, expires_at);
+        Self {
+            renews: Some(credential),
+            ..Self::draft(request, drafted_by)
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn request(&self) -> &CredentialRequest {
+        &self.request
+    }
+
+    pub fn drafted_by(&self) -> &str {
+        &self.drafted_by
+    }
+
+    pub fn last_edited_by(&self) -> Option<&str> {
+        self.last_edited_by.as_deref()
+    }
+
+    pub fn submitted_by(&self) -> Option<&str> {
+        self.submitted_by.as_deref()
+    }
+
+    pub fn state(&self) -> &LifecycleState {
+        &self.state
+    }
+
+    pub fn renews(&self) -> Option<&Microcredential> {
+        self.renews.as_ref()
+    }
+
+    pub fn credential(&self) -> Option<&Microcredential> {
+        self.credential.as_ref()
+    }
+
+    pub fn history(&self) -> &[LifecycleTransition] {
+        &self.history
+    }
+
+    pub fn update_draft(
+        &mut self,
+        request: CredentialRequest,
+        actor: &str,
+    ) -> Result<(), LifecycleError> {
+        self.expect(matches!(self.state, LifecycleState::Draft), "edit")?;
+        self.request = request;
+        self.last_edited_by = Some(actor.to_string());
+        Ok(())
+    }
+
+    pub fn submit(&mut self, issuer: &CredentialIssuer, actor: &str) -> Result<(), LifecycleError> {
+        self.expect(matches!(self.state, LifecycleState::Draft), "submit")?;
+        self.submitted_by = Some(actor.to_string());
+        self.transition(LifecycleState::PendingApproval, actor, issuer.now());
+        Ok(())
+    }
+
+    /// Sends the draft back for changes.
+    pub fn reject(
+        &mut self,
+        issuer: &CredentialIssuer,
+        approver: &str,
+    ) -> Result<(), LifecycleError> {
+        self.expect(
+            matches!(self.state, LifecycleState::PendingApproval),
+            "reject",
+        )?;
+        self.transition(LifecycleState::Draft, approver, issuer.now());
+        Ok(())
+    }
+
+    pub fn approve(
+        &mut self,
+        issuer: &CredentialIssuer,
+        approver: &str,
+    ) -> Result<&Microcredential, LifecycleError> {
+        self.expect(
+            matches!(self.state, LifecycleState::PendingApproval),
+            "approve",
+        )?;
+        if approver == self.drafted_by {
+            return Err(LifecycleError::ApproverIsDrafter);
+        }
+        if self.last_edited_by.as_deref() == Some(approver) {
+            return Err(LifecycleError::ApproverIsEditor);
+        }
+        if self.submitted_by.as_deref() == Some(approver) {
+            return Err(LifecycleError::ApproverIsSubmitter);
+        }
+
+        let request = CredentialRequest {
+            actor: Some(approver.to_string()),
+            ..self.request.clone()
+        };
+        let credential = match &self.renews {
+            Some(previous) => issuer.renew_request(previous, request),
+            None => issuer.issue_request(request),
+        }
+        .map_err(|e| LifecycleError::Issuance(e.to_string()))?;
+        self.transition(LifecycleState::Issued, approver, issuer.now());
+        Ok(self.credential.insert(credential))
+    }
+
+    pub fn suspend(
+        &mut self,
+        issuer: &mut CredentialIssuer,
+        actor: &str,
+        reason: String,
+    ) -> Result<(), LifecycleError> {
+        self.expire_if_due(issuer);
+        self.expect(matches!(self.state, LifecycleState::Issued), "suspend")?;
+        issuer.suspend_credential(&self.credential_id()?, reason.clone())?;
+        self.transition(LifecycleState::Suspended { reason }, actor, issuer.now());
+        Ok(())
+    }
+
+    pub fn reinstate(
+        &mut self,
+        issuer: &mut CredentialIssuer,
+        actor: &str,
+    ) -> Result<(), LifecycleError> {
+        self.expect(
+            matches!(self.state, LifecycleState::Suspended { .. }),
+            "reinstate",
+        )?;
+        issuer.reinstate_credential(&self.credential_id()?)?;
+        self.transition(LifecycleState::Issued, actor, issuer.now());
+        self.expire_if_due(issuer);
+        Ok(())
+    }
+
+    pub fn revoke(
+        &mut self,
+        issuer: &mut CredentialIssuer,
+        actor: &str,
+        reason: String,
+    ) -> Result<(), LifecycleError> {
+        self.expect(
+            matches!(
+                self.state,
+                LifecycleState::Issued | LifecycleState::Suspended { .. }
+            ),
+            "revoke",
+        )?;
+        issuer.revoke_credential(&self.credential_id()?, reason.clone())?;
+        self.transition(LifecycleState::Revoked { reason }, actor, issuer.now());
+        Ok(())
+    }
+
+    /// Moves an issued or suspended credential to `Expired` once its expiry
+    /// has passed by the issuer's clock. Returns whether the state changed.
+    pub fn expire_if_due(&mut self, issuer: &CredentialIssuer) -> bool {
+        let now = issuer.now();
+        let active = matches!(
+            self.state,
+            LifecycleState::Issued | LifecycleState::Suspended { .. }
+        );
+        let expired = self
+            .credential
+            .as_ref()
+            .is_some_and(|c| c.is_expired_at(now));
+        if active && expired {
+            self.transition(LifecycleState::Expired, "system", now);
+        }
+        active && expired
+    }
+
+    fn credential_id(&self) -> Result<Uuid, LifecycleError> {
+        self.credential
+            .as_ref()
+            .map(|credential| credential.id)
+            .ok_or(LifecycleError::MissingCredential)
+    }
+
+    fn expect(&self, allowed: bool, action: &'static str) -> Result<(), LifecycleError> {
+        if allowed {
+            Ok(())
+        } else {
+            Err(LifecycleError::InvalidTransition {
+                state: self.state.name(),
+                action,
+            })
+        }
+    }
+
+    fn transition(&mut self, state: LifecycleState, actor: &str, at: DateTime<Utc>) {
+        self.history.push(LifecycleTransition {
+            from: self.state.name().to_string(),
+            to: state.name().to_string(),
+            actor: actor.to_string(),
+            at,
+        });
+        self.state = state;
+    }
+}
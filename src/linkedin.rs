@@ -0,0 +1,106 @@
+//! LinkedIn "Add to Profile" links, which open the add-certification form
+//! prefilled with a credential.
+
+use crate::Microcredential;
+use chrono::Datelike;
+
+const ADD_TO_PROFILE: &str = "https://www.linkedin.com/profile/add";
+
+/// Add-certification URL for `credential`, linking back to
+/// `verification_url`. With the issuer's LinkedIn company page id as
+/// `organization_id` the entry shows the organization's logo; otherwise
+/// the issuer name is used.
+pub fn add_to_profile_url(
+    credential: &Microcredential,
+    verification_url: &str,
+    organization_id: Option<u64>,
+) -> String {
+    let mut params = vec![
+        ("startTask", "CERTIFICATION_NAME".to_string()),
+        ("name", credential.skill.name.clone()),
+    ];
+    match organization_id {
+        Some(id) => params.push(("organizationId", id.to_string())),
+        None => params.push(("organizationName", credential.issuer.name.clone())),
+    }
+    params.push(("issueYear", credential.issued_at.year().to_string()));
+    params.push(("issueMonth", credential.issued_at.month().to_string()));
+    if let Some(expires_at) = credential.expires_at {
+        params.push(("expirationYear", expires_at.year().to_string()));
+        params.push(("expirationMonth", expires_at.month().to_string()));
+    }
+    params.push(("certUrl", verification_url.to_string()));
+    params.push(("certId", credential.id.to_string()));
+
+    let query: Vec<String> = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, encode_component(value)))
+        .collect();
+    format!("{}?{}", ADD_TO_PROFILE, query.join("&"))
+}
+
+/// Percent-encodes everything except RFC 3986 unreserved characters.
+fn encode_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Issuer, Skill, SkillLevel, Subject};
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_add_to_profile_url() {
+        let mut credential = Microcredential::new(
+            Issuer {
+                id: Uuid::new_v4(),
+                name: "Test University & Co".to_string(),
+                url: "https://test.edu".to_string(),
+                public_key: vec![0; 32].into(),
+                key_history: Vec::new(),
+                unknown_fields: Default::default(),
+            },
+            Subject {
+                id: Uuid::new_v4(),
+                name: "Alice".to_string(),
+                email: "alice@example.com".to_string(),
+                unknown_fields: Default::default(),
+            },
+            Skill {
+                id: "rust".to_string(),
+                name: "Rust Programming".to_string(),
+                description: "Rust".to_string(),
+                level: SkillLevel::Advanced,
+                unknown_fields: Default::default(),
+            },
+            Vec::new(),
+            Some(Utc.with_ymd_and_hms(2027, 3, 1, 0, 0, 0).unwrap()),
+        );
+        credential.issued_at = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+
+        let url = add_to_profile_url(&credential, "https://verify.test.edu/c?id=1", None);
+        assert!(url.starts_with(
+            "https://www.linkedin.com/profile/add?startTask=CERTIFICATION_NAME\
+             &name=Rust%20Programming&organizationName=Test%20University%20%26%20Co\
+             &issueYear=2026&issueMonth=10&expirationYear=2027&expirationMonth=3\
+             &certUrl=https%3A%2F%2Fverify.test.edu%2Fc%3Fid%3D1&certId="
+        ));
+        assert!(url.ends_with(&credential.id.to_string()));
+
+        credential.expires_at = None;
+        let url = add_to_profile_url(&credential, "https://verify.test.edu", Some(1035));
+        assert!(url.contains("&organizationId=1035&"));
+        assert!(!url.contains("organizationName") && !url.contains("expiration"));
+    }
+}
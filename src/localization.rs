@@ -0,0 +1,579 @@
+//! Verification messages in the holder's language. Templates are looked up
+//! in a `MessageCatalog` by message key: the `analytics::failure_kind` of an
+//! error, a warning kind, or `report_valid` / `report_invalid` for report
+//! summaries. The built-in `Catalog` has English, French and Spanish; apps
+//! can add locales to it or implement the trait over their own resources.
+//!
+//! Templates may use `{detail}` (e.g. a revocation reason, which is not
+//! translated), `{time}` and `{credential_id}`. Times are written by the
+//! catalog for the locale whose template was used, falling back to RFC 3339.
+
+use crate::analytics::failure_kind;
+use crate::verifier::{VerificationError, VerificationReport, VerificationWarning};
+use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub trait MessageCatalog: Send + Sync {
+    /// The template for `key` in `locale`, a lowercase language tag such as
+    /// `fr` or `pt-br`.
+    fn template(&self, locale: &str, key: &str) -> Option<String>;
+
+    /// `at` as written in `locale`, for `{time}`. `None` falls back to
+    /// RFC 3339.
+    fn format_time(&self, locale: &str, at: &DateTime<Utc>) -> Option<String> {
+        let _ = (locale, at);
+        None
+    }
+}
+
+const EN: &[(&str, &str)] = &[
+    ("serialization_error", "Serialization error: {detail}"),
+    ("invalid_signature", "Invalid signature"),
+    ("expired_credential", "Credential has expired"),
+    ("missing_signature", "Credential is not signed"),
+    (
+        "trusted_issuer_not_found",
+        "Issuer is not in the trusted list",
+    ),
+    (
+        "no_active_issuer_key",
+        "No issuer key was active at the time of issuance",
+    ),
+    (
+        "insufficient_skill_level",
+        "Skill level does not meet the required minimum",
+    ),
+    ("invalid_skill_level", "Skill level {detail} is not on its scale"),
+    (
+        "missing_holder_binding",
+        "Credential is not bound to a holder key",
+    ),
+    (
+        "holder_binding_mismatch",
+        "Presenter does not hold the credential's bound key",
+    ),
+    ("suspended_credential", "Credential is suspended: {detail}"),
+    (
+        "revoked_credential",
+        "Credential has been revoked: {detail}",
+    ),
+    (
+        "certificate_constraint_violation",
+        "Issuer certificate constraint violated: {detail}",
+    ),
+    ("unsupported_proof", "Unsupported proof: {detail}"),
+    (
+        "invalid_certificate_chain",
+        "Invalid X.509 certificate chain: {detail}",
+    ),
+    ("not_yet_valid", "Credential is not valid until {time}"),
+    (
+        "offline_pack_expired",
+        "Offline verification data expired at {time}",
+    ),
+    (
+        "expired_within_grace",
+        "Credential expired at {time} and is accepted within the grace period",
+    ),
+    (
+        "stale_offline_pack",
+        "Offline verification data expired at {time}",
+    ),
+    ("report_valid", "Credential {credential_id} is valid"),
+    (
+        "report_invalid",
+        "Credential {credential_id} failed verification",
+    ),
+];
+
+const FR: &[(&str, &str)] = &[
+    ("serialization_error", "Erreur de sérialisation : {detail}"),
+    ("invalid_signature", "Signature invalide"),
+    ("expired_credential", "Le justificatif a expiré"),
+    ("missing_signature", "Le justificatif n'est pas signé"),
+    (
+        "trusted_issuer_not_found",
+        "L'émetteur ne figure pas dans la liste de confiance",
+    ),
+    (
+        "no_active_issuer_key",
+        "Aucune clé de l'émetteur n'était active au moment de l'émission",
+    ),
+    (
+        "insufficient_skill_level",
+        "Le niveau de compétence n'atteint pas le minimum requis",
+    ),
+    (
+        "invalid_skill_level",
+        "Le niveau de compétence {detail} ne figure pas sur son échelle",
+    ),
+    (
+        "missing_holder_binding",
+        "Le justificatif n'est lié à aucune clé de titulaire",
+    ),
+    (
+        "holder_binding_mismatch",
+        "Le présentateur ne détient pas la clé liée au justificatif",
+    ),
+    (
+        "suspended_credential",
+        "Le justificatif est suspendu : {detail}",
+    ),
+    (
+        "revoked_credential",
+        "Le justificatif a été révoqué : {detail}",
+    ),
+    (
+        "certificate_constraint_violation",
+        "Contrainte du certificat de l'émetteur non respectée : {detail}",
+    ),
+    ("unsupported_proof", "Preuve non prise en charge : {detail}"),
+    (
+        "invalid_certificate_chain",
+        "Chaîne de certificats X.509 invalide : {detail}",
+    ),
+    (
+        "not_yet_valid",
+        "Le justificatif n'est pas valide avant le {time}",
+    ),
+    (
+        "offline_pack_expired",
+        "Les données de vérification hors ligne ont expiré le {time}",
+    ),
+    (
+        "expired_within_grace",
+        "Le justificatif a expiré le {time} et est accepté pendant la période de grâce",
+    ),
+    (
+        "stale_offline_pack",
+        "Les données de vérification hors ligne ont expiré le {time}",
+    ),
+    ("report_valid", "Le justificatif {credential_id} est valide"),
+    (
+        "report_invalid",
+        "La vérification du justificatif {credential_id} a échoué",
+    ),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("serialization_error", "Error de serialización: {detail}"),
+    ("invalid_signature", "Firma no válida"),
+    ("expired_credential", "La credencial ha caducado"),
+    ("missing_signature", "La credencial no está firmada"),
+    (
+        "trusted_issuer_not_found",
+        "El emisor no está en la lista de confianza",
+    ),
+    (
+        "no_active_issuer_key",
+        "Ninguna clave del emisor estaba activa en el momento de la emisión",
+    ),
+    (
+        "insufficient_skill_level",
+        "El nivel de competencia no alcanza el mínimo requerido",
+    ),
+    (
+        "invalid_skill_level",
+        "El nivel de competencia {detail} no pertenece a su escala",
+    ),
+    (
+        "missing_holder_binding",
+        "La credencial no está vinculada a una clave del titular",
+    ),
+    (
+        "holder_binding_mismatch",
+        "Quien la presenta no posee la clave vinculada a la credencial",
+    ),
+    (
+        "suspended_credential",
+        "La credencial está suspendida: {detail}",
+    ),
+    (
+        "revoked_credential",
+        "La credencial ha sido revocada: {detail}",
+    ),
+    (
+        "certificate_constraint_violation",
+        "Se ha infringido una restricción del certificado del emisor: {detail}",
+    ),
+    ("unsupported_proof", "Prueba no compatible: {detail}"),
+    (
+        "invalid_certificate_chain",
+        "Cadena de certificados X.509 no válida: {detail}",
+    ),
+    (
+        "not_yet_valid",
+        "La credencial no es válida hasta el {time}",
+    ),
+    (
+        "offline_pack_expired",
+        "Los datos de verificación sin conexión caducaron el {time}",
+    ),
+    (
+        "expired_within_grace",
+        "La credencial caducó el {time} y se acepta dentro del período de gracia",
+    ),
+    (
+        "stale_offline_pack",
+        "Los datos de verificación sin conexión caducaron el {time}",
+    ),
+    ("report_valid", "La credencial {credential_id} es válida"),
+    (
+        "report_invalid",
+        "La verificación de la credencial {credential_id} ha fallado",
+    ),
+];
+
+const EN_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const FR_MONTHS: [&str; 12] = [
+    "janvier",
+    "février",
+    "mars",
+    "avril",
+    "mai",
+    "juin",
+    "juillet",
+    "août",
+    "septembre",
+    "octobre",
+    "novembre",
+    "décembre",
+];
+
+const ES_MONTHS: [&str; 12] = [
+    "enero",
+    "febrero",
+    "marzo",
+    "abril",
+    "mayo",
+    "junio",
+    "julio",
+    "agosto",
+    "septiembre",
+    "octubre",
+    "noviembre",
+    "diciembre",
+];
+
+/// Times in the built-in locales, always in UTC.
+fn builtin_time(locale: &str, at: &DateTime<Utc>) -> Option<String> {
+    let (day, year, clock) = (at.day(), at.year(), at.format("%H:%M"));
+    let month = at.month0() as usize;
+    match locale {
+        "en" => Some(format!("{} {}, {}, {} UTC", EN_MONTHS[month], day, year, clock)),
+        "fr" => Some(format!("{} {} {} à {} UTC", day, FR_MONTHS[month], year, clock)),
+        "es" => Some(format!("{} de {} de {}, {} UTC", day, ES_MONTHS[month], year, clock)),
+        _ => None,
+    }
+}
+
+/// Templates held in memory, per locale and key.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    templates: HashMap<String, HashMap<String, String>>,
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// English, French and Spanish templates for every message key.
+    pub fn builtin() -> Self {
+        let mut catalog = Self::new();
+        for (locale, templates) in [("en", EN), ("fr", FR), ("es", ES)] {
+            for (key, template) in templates {
+                catalog.insert(locale, key, template);
+            }
+        }
+        catalog
+    }
+
+    /// Adds or replaces one template, e.g. to reword a built-in message or
+    /// to add a locale.
+    pub fn insert(&mut self, locale: &str, key: &str, template: &str) {
+        self.templates
+            .entry(locale.to_lowercase())
+            .or_default()
+            .insert(key.to_string(), template.to_string());
+    }
+
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<_> = self.templates.keys().map(String::as_str).collect();
+        locales.sort_unstable();
+        locales
+    }
+}
+
+impl MessageCatalog for Catalog {
+    fn template(&self, locale: &str, key: &str) -> Option<String> {
+        self.templates.get(locale)?.get(key).cloned()
+    }
+
+    /// English, French and Spanish dates; other locales use RFC 3339.
+    fn format_time(&self, locale: &str, at: &DateTime<Utc>) -> Option<String> {
+        builtin_time(locale, at)
+    }
+}
+
+/// A localized message with the key it was rendered from, so apps can also
+/// react to the cause.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalizedMessage {
+    pub key: &'static str,
+    pub message: String,
+}
+
+/// A `VerificationReport` rendered for display.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocalizedReport {
+    pub valid: bool,
+    pub summary: String,
+    pub failures: Vec<LocalizedMessage>,
+    pub warnings: Vec<LocalizedMessage>,
+}
+
+/// Renders verification results from a catalog. A locale like `fr-CA` falls
+/// back to `fr`, then to the fallback locale (`en` by default), then to the
+/// English `Display` text.
+#[derive(Clone)]
+pub struct Localizer {
+    catalog: Arc<dyn MessageCatalog>,
+    fallback_locale: String,
+}
+
+impl Localizer {
+    pub fn new(catalog: Arc<dyn MessageCatalog>) -> Self {
+        Self {
+            catalog,
+            fallback_locale: "en".to_string(),
+        }
+    }
+
+    pub fn set_fallback_locale(&mut self, locale: &str) {
+        self.fallback_locale = locale.to_lowercase();
+    }
+
+    pub fn error(&self, err: &VerificationError, locale: &str) -> String {
+        let (detail, time) = match err {
+            VerificationError::SerializationError(detail)
+            | VerificationError::SuspendedCredential(detail)
+            | VerificationError::RevokedCredential(detail)
+            | VerificationError::CertificateConstraintViolation(detail)
+            | VerificationError::InvalidSkillLevel(detail)
+            | VerificationError::UnsupportedProof(detail)
+            | VerificationError::InvalidCertificateChain(detail) => (detail.as_str(), None),
+            VerificationError::NotYetValid(at) | VerificationError::OfflinePackExpired(at) => {
+                ("", Some(at))
+            }
+            _ => ("", None),
+        };
+        self.render(locale, failure_kind(err), &[("detail", detail)], time)
+            .unwrap_or_else(|| err.to_string())
+    }
+
+    pub fn warning(&self, warning: &VerificationWarning, locale: &str) -> String {
+        let time = match warning {
+            VerificationWarning::ExpiredWithinGrace { expired_at } => expired_at,
+            VerificationWarning::StaleOfflinePack { valid_until } => valid_until,
+        };
+        self.render(locale, warning_kind(warning), &[], Some(time))
+            .unwrap_or_else(|| warning.to_string())
+    }
+
+    pub fn report(&self, report: &VerificationReport, locale: &str) -> LocalizedReport {
+        let key = if report.is_valid() {
+            "report_valid"
+        } else {
+            "report_invalid"
+        };
+        let credential_id = report.credential_id.to_string();
+        let summary = self
+            .render(locale, key, &[("credential_id", &credential_id)], None)
+            .unwrap_or_else(|| {
+                if report.is_valid() {
+                    format!("Credential {} is valid", credential_id)
+                } else {
+                    format!("Credential {} failed verification", credential_id)
+                }
+            });
+        LocalizedReport {
+            valid: report.is_valid(),
+            summary,
+            failures: report
+                .failures
+                .iter()
+                .map(|err| LocalizedMessage {
+                    key: failure_kind(err),
+                    message: self.error(err, locale),
+                })
+                .collect(),
+            warnings: report
+                .warnings
+                .iter()
+                .map(|warning| LocalizedMessage {
+                    key: warning_kind(warning),
+                    message: self.warning(warning, locale),
+                })
+                .collect(),
+        }
+    }
+
+    /// Fills in the first template found for `key`, in one pass so values
+    /// that contain placeholders (e.g. a revocation reason) stay as they are.
+    /// `{time}` is formatted for the locale the template came from.
+    fn render(
+        &self,
+        locale: &str,
+        key: &str,
+        values: &[(&str, &str)],
+        time: Option<&DateTime<Utc>>,
+    ) -> Option<String> {
+        let (locale, template) = candidates(locale)
+            .into_iter()
+            .chain(std::iter::once(self.fallback_locale.clone()))
+            .find_map(|locale| Some((locale.clone(), self.catalog.template(&locale, key)?)))?;
+        let time = time.map(|at| {
+            self.catalog
+                .format_time(&locale, at)
+                .unwrap_or_else(|| at.to_rfc3339())
+        });
+
+        let mut text = String::with_capacity(template.len());
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let value = rest.find('}').and_then(|end| {
+                let name = &rest[1..end];
+                let value = match name {
+                    "time" => time.as_deref(),
+                    _ => values.iter().find(|(key, _)| *key == name).map(|(_, v)| *v),
+                };
+                Some((value?, end))
+            });
+            match value {
+                Some((value, end)) => {
+                    text.push_str(value);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        text.push_str(rest);
+        Some(text)
+    }
+}
+
+impl Default for Localizer {
+    fn default() -> Self {
+        Self::new(Arc::new(Catalog::builtin()))
+    }
+}
+
+/// Stable snake_case name for a verification warning.
+pub fn warning_kind(warning: &VerificationWarning) -> &'static str {
+    match warning {
+        VerificationWarning::ExpiredWithinGrace { .. } => "expired_within_grace",
+        VerificationWarning::StaleOfflinePack { .. } => "stale_offline_pack",
+    }
+}
+
+/// `fr_CA` becomes `["fr-ca", "fr"]`.
+fn candidates(locale: &str) -> Vec<String> {
+    let locale = locale.trim().to_lowercase().replace('_', "-");
+    let mut candidates = Vec::new();
+    let mut tag = locale.as_str();
+    while !tag.is_empty() {
+        candidates.push(tag.to_string());
+        tag = tag.rsplit_once('-').map_or("", |(prefix, _)| prefix);
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_builtin_locales_cover_every_key() {
+        assert_eq!(EN.len(), FR.len());
+        assert_eq!(EN.len(), ES.len());
+        let catalog = Catalog::builtin();
+        assert_eq!(catalog.locales(), ["en", "es", "fr"]);
+        for (key, _) in EN {
+            for locale in ["fr", "es"] {
+                assert!(
+                    catalog.template(locale, key).is_some(),
+                    "{} {}",
+                    locale,
+                    key
+                );
+            }
+        }
+
+        // English templates match the `Display` text, apart from times.
+        let localizer = Localizer::default();
+        for err in [
+            VerificationError::InvalidSignature,
+            VerificationError::RevokedCredential("fraud".to_string()),
+        ] {
+            assert_eq!(localizer.error(&err, "en"), err.to_string());
+        }
+        assert_eq!(candidates("pt_BR"), ["pt-br", "pt"]);
+    }
+
+    #[test]
+    fn test_times_are_formatted_per_locale() {
+        let localizer = Localizer::default();
+        let at = "2026-03-01T09:30:15Z".parse::<DateTime<Utc>>().unwrap();
+        let err = VerificationError::NotYetValid(at);
+        assert_eq!(
+            localizer.error(&err, "en-GB"),
+            "Credential is not valid until March 1, 2026, 09:30 UTC"
+        );
+        assert_eq!(
+            localizer.error(&err, "fr"),
+            "Le justificatif n'est pas valide avant le 1 mars 2026 à 09:30 UTC"
+        );
+        assert_eq!(
+            localizer.error(&err, "es"),
+            "La credencial no es válida hasta el 1 de marzo de 2026, 09:30 UTC"
+        );
+
+        let mut catalog = Catalog::builtin();
+        catalog.insert("de", "not_yet_valid", "Gültig ab {time}");
+        let localizer = Localizer::new(Arc::new(catalog));
+        assert_eq!(
+            localizer.error(&err, "de"),
+            "Gültig ab 2026-03-01T09:30:15+00:00"
+        );
+
+        // Values are not substituted again.
+        let err = VerificationError::RevokedCredential("{time} {detail}".to_string());
+        assert_eq!(
+            localizer.error(&err, "en"),
+            "Credential has been revoked: {time} {detail}"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use microcred_rs::{
-    issuer::CredentialIssuer, verifier::CredentialVerifier, Evidence, EvidenceType, Skill,
-    SkillLevel, Subject,
+    clock::SystemClock, issuer::CredentialIssuer, verifier::CredentialVerifier, Evidence,
+    EvidenceType, Skill, SkillLevel, Subject,
 };
 use uuid::Uuid;
 
@@ -18,6 +18,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         id: Uuid::new_v4(),
         name: "Alice Developer".to_string(),
         email: "alice@example.com".to_string(),
+        unknown_fields: Default::default(),
     };
 
     let skill = Skill {
@@ -25,6 +26,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         name: "Rust Programming".to_string(),
         description: "Proficiency in Rust programming language".to_string(),
         level: SkillLevel::Advanced,
+        unknown_fields: Default::default(),
     };
 
     let evidence = vec![
@@ -34,6 +36,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             description: "Built a high-performance web server using Tokio".to_string(),
             url: "https://github.com/alice/rust-webserver".to_string(),
             evidence_type: EvidenceType::Project,
+            unknown_fields: Default::default(),
         },
         Evidence {
             id: Uuid::new_v4(),
@@ -41,6 +44,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             description: "Passed advanced Rust programming assessment".to_string(),
             url: "https://assessments.rust-university.edu/alice/cert-123".to_string(),
             evidence_type: EvidenceType::Assessment,
+            unknown_fields: Default::default(),
         },
     ];
 
@@ -50,7 +54,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Skill: {} (Level: {:?})", credential.skill.name, credential.skill.level);
     println!("Evidence count: {}", credential.evidence.len());
     println!("Credential ID: {}", credential.id);
-    println!("Is valid: {}", credential.is_valid());
+    println!("Is valid: {}", credential.is_valid(&SystemClock));
 
     let mut verifier = CredentialVerifier::new();
     verifier.add_trusted_issuer(issuer.get_issuer_info().clone());
@@ -0,0 +1,267 @@
+use crate::validation::ValidationReport;
+use crate::{Issuer, Skill};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub const MANIFEST_SPEC_VERSION: &str =
+    "https://identity.foundation/credential-manifest/spec/v1.0.0/";
+
+/// DIF Credential Manifest: which credential an issuer offers and which
+/// inputs an applicant has to supply, so wallets can render the form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CredentialManifest {
+    pub id: String,
+    pub spec_version: String,
+    pub issuer: ManifestIssuer,
+    pub output_descriptors: Vec<OutputDescriptor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presentation_definition: Option<PresentationDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestIssuer {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputDescriptor {
+    pub id: String,
+    pub schema: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<OutputDisplay>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputDisplay {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<DisplayProperty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<DisplayProperty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<DisplayProperty>,
+}
+
+/// Either literal `text`, or a JSONPath into the issued credential with a
+/// `fallback` for wallets that cannot resolve it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayProperty {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<String>,
+}
+
+impl DisplayProperty {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            path: Vec::new(),
+            text: Some(text.into()),
+            fallback: None,
+        }
+    }
+
+    pub fn path(path: impl Into<String>, fallback: impl Into<String>) -> Self {
+        Self {
+            path: vec![path.into()],
+            text: None,
+            fallback: Some(fallback.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresentationDefinition {
+    pub id: String,
+    pub input_descriptors: Vec<InputDescriptor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputDescriptor {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    pub constraints: Constraints,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Constraints {
+    pub fields: Vec<FieldConstraint>,
+}
+
+/// One required input. `filter` is a JSON Schema fragment; only `type`,
+/// `minItems` and `oneOf` are enforced by `check_application`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldConstraint {
+    pub path: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+impl FieldConstraint {
+    pub fn new(path: &str, filter: Value) -> Self {
+        Self {
+            path: vec![path.to_string()],
+            filter: Some(filter),
+            optional: false,
+        }
+    }
+
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+}
+
+impl CredentialManifest {
+    pub fn new(issuer: &Issuer, skill: &Skill, min_evidence: usize) -> Self {
+        let mut input_descriptors = vec![InputDescriptor {
+            id: "subject".to_string(),
+            name: Some("Applicant".to_string()),
+            purpose: Some("Identifies the person the credential is issued to".to_string()),
+            constraints: Constraints {
+                fields: vec![
+                    FieldConstraint::new("$.subject.name", json!({"type": "string"})),
+                    FieldConstraint::new(
+                        "$.subject.email",
+                        json!({"type": "string", "format": "email"}),
+                    ),
+                ],
+            },
+        }];
+        if min_evidence > 0 {
+            input_descriptors.push(InputDescriptor {
+                id: "evidence".to_string(),
+                name: Some("Evidence".to_string()),
+                purpose: Some(format!("At least {} piece(s) of evidence", min_evidence)),
+                constraints: Constraints {
+                    fields: vec![FieldConstraint::new(
+                        "$.evidence",
+                        json!({"type": "array", "minItems": min_evidence}),
+                    )],
+                },
+            });
+        }
+        input_descriptors.push(InputDescriptor {
+            id: "holder_key".to_string(),
+            name: Some("Holder key".to_string()),
+            purpose: Some("Binds the credential to a key held by the applicant".to_string()),
+            // A multikey string, or the legacy array of key bytes.
+            constraints: Constraints {
+                fields: vec![FieldConstraint::new(
+                    "$.holder_key",
+                    json!({"oneOf": [
+                        {"type": "string", "pattern": "^z"},
+                        {"type": "array", "items": {"type": "integer"}},
+                    ]}),
+                )
+                .optional()],
+            },
+        });
+
+        Self {
+            id: format!("{}:{}", issuer.url.trim_end_matches('/'), skill.id),
+            spec_version: MANIFEST_SPEC_VERSION.to_string(),
+            issuer: ManifestIssuer {
+                id: format!("urn:uuid:{}", issuer.id),
+                name: issuer.name.clone(),
+            },
+            output_descriptors: vec![OutputDescriptor {
+                id: skill.id.clone(),
+                schema: "Microcredential".to_string(),
+                name: Some(skill.name.clone()),
+                description: Some(skill.description.clone()),
+                display: Some(OutputDisplay {
+                    title: Some(DisplayProperty::text(skill.name.clone())),
+                    subtitle: Some(DisplayProperty::path("$.subject.name", "Holder")),
+                    description: Some(DisplayProperty::text(skill.description.clone())),
+                }),
+            }],
+            presentation_definition: Some(PresentationDefinition {
+                id: format!("{}-application", skill.id),
+                input_descriptors,
+            }),
+        }
+    }
+
+    /// Checks an application (a `CredentialRequest` as JSON) against the
+    /// required inputs.
+    pub fn check_application(&self, application: &Value) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let Some(definition) = &self.presentation_definition else {
+            return report;
+        };
+
+        for field in definition
+            .input_descriptors
+            .iter()
+            .flat_map(|d| d.constraints.fields.iter())
+        {
+            let name = field.path.first().map(String::as_str).unwrap_or("$");
+            let value = field.path.iter().find_map(|p| resolve_path(application, p));
+            match (value, &field.filter) {
+                (None, _) if field.optional => {}
+                (None, _) => report.add_issue(name, "is required"),
+                (Some(value), Some(filter)) => check_filter(name, value, filter, &mut report),
+                (Some(_), None) => {}
+            }
+        }
+        report
+    }
+}
+
+fn resolve_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let rest = path.strip_prefix('$')?;
+    let mut current = value;
+    for segment in rest.split('.').filter(|s| !s.is_empty()) {
+        current = current.get(segment)?;
+    }
+    (!current.is_null()).then_some(current)
+}
+
+fn check_filter(name: &str, value: &Value, filter: &Value, report: &mut ValidationReport) {
+    if let Some(alternatives) = filter.get("oneOf").and_then(Value::as_array) {
+        let matching = alternatives
+            .iter()
+            .filter(|alternative| {
+                let mut alternative_report = ValidationReport::default();
+                check_filter(name, value, alternative, &mut alternative_report);
+                alternative_report.is_valid()
+            })
+            .count();
+        if matching != 1 {
+            report.add_issue(name, "must match exactly one of the allowed forms");
+        }
+        return;
+    }
+    let type_matches = match filter.get("type").and_then(Value::as_str) {
+        Some("string") => value.is_string(),
+        Some("array") => value.is_array(),
+        Some("object") => value.is_object(),
+        Some("number") => value.is_number(),
+        Some("boolean") => value.is_boolean(),
+        _ => true,
+    };
+    if !type_matches {
+        report.add_issue(name, format!("must be of type {}", filter["type"]));
+        return;
+    }
+    if let (Some(min), Some(items)) = (
+        filter.get("minItems").and_then(Value::as_u64),
+        value.as_array(),
+    ) {
+        if (items.len() as u64) < min {
+            report.add_issue(name, format!("requires at least {} entries", min));
+        }
+    }
+}
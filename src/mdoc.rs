@@ -0,0 +1,326 @@
+//! ISO/IEC 18013-5 mdoc export: the credential's claims as issuer-signed
+//! items under a Mobile Security Object (MSO) signed with COSE_Sign1, for
+//! provisioning into mobile-document wallets.
+
+use crate::cbor::Item;
+use crate::crypto::verify_signature;
+use crate::signer::{Signer, SignerError};
+use crate::Microcredential;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+
+pub const DOC_TYPE: &str = "org.microcred.credential.1";
+pub const NAMESPACE: &str = "org.microcred.credential.1";
+
+/// COSE algorithm identifier for EdDSA.
+const COSE_ALG_EDDSA: i64 = -8;
+/// COSE header label of the X.509 chain (`x5chain`).
+const COSE_X5CHAIN: i64 = 33;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MdocError {
+    /// The MSO must name a device key; only holder-bound credentials can be
+    /// exported.
+    MissingDeviceKey,
+    /// The MSO must state how long it is valid.
+    MissingExpiry,
+    Signer(SignerError),
+}
+
+impl fmt::Display for MdocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MdocError::MissingDeviceKey => write!(f, "Credential is not bound to a holder key"),
+            MdocError::MissingExpiry => write!(f, "Credential has no expiry date"),
+            MdocError::Signer(e) => write!(f, "Signing failed: {}", e),
+        }
+    }
+}
+
+impl Error for MdocError {}
+
+impl From<SignerError> for MdocError {
+    fn from(e: SignerError) -> Self {
+        MdocError::Signer(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IssuerSignedItem {
+    pub digest_id: u64,
+    /// Salt that keeps undisclosed values from being guessed from digests.
+    pub random: Vec<u8>,
+    pub element_identifier: String,
+    pub element_value: Item,
+}
+
+impl IssuerSignedItem {
+    /// `IssuerSignedItemBytes`, the form that is digested and transmitted.
+    pub fn to_item(&self) -> Item {
+        Item::embedded(&Item::Map(vec![
+            (Item::text("digestID"), Item::Unsigned(self.digest_id)),
+            (Item::text("random"), Item::Bytes(self.random.clone())),
+            (
+                Item::text("elementIdentifier"),
+                Item::text(&self.element_identifier),
+            ),
+            (Item::text("elementValue"), self.element_value.clone()),
+        ]))
+    }
+
+    pub fn digest(&self) -> Vec<u8> {
+        Sha256::digest(self.to_item().to_vec()).to_vec()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MobileSecurityObject {
+    pub doc_type: String,
+    /// Digest of each item in `NAMESPACE`, by digest id.
+    pub value_digests: Vec<(u64, Vec<u8>)>,
+    pub device_key: Vec<u8>,
+    pub signed: DateTime<Utc>,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: DateTime<Utc>,
+}
+
+impl MobileSecurityObject {
+    pub fn to_item(&self) -> Item {
+        let digests = self
+            .value_digests
+            .iter()
+            .map(|(id, digest)| (Item::Unsigned(*id), Item::Bytes(digest.clone())))
+            .collect();
+        // COSE_Key: kty OKP, crv Ed25519, x.
+        let device_key = Item::Map(vec![
+            (Item::int(1), Item::int(1)),
+            (Item::int(-1), Item::int(6)),
+            (Item::int(-2), Item::Bytes(self.device_key.clone())),
+        ]);
+        Item::Map(vec![
+            (Item::text("version"), Item::text("1.0")),
+            (Item::text("digestAlgorithm"), Item::text("SHA-256")),
+            (
+                Item::text("valueDigests"),
+                Item::Map(vec![(Item::text(NAMESPACE), Item::Map(digests))]),
+            ),
+            (
+                Item::text("deviceKeyInfo"),
+                Item::Map(vec![(Item::text("deviceKey"), device_key)]),
+            ),
+            (Item::text("docType"), Item::text(&self.doc_type)),
+            (
+                Item::text("validityInfo"),
+                Item::Map(vec![
+                    (Item::text("signed"), tdate(&self.signed)),
+                    (Item::text("validFrom"), tdate(&self.valid_from)),
+                    (Item::text("validUntil"), tdate(&self.valid_until)),
+                ]),
+            ),
+        ])
+    }
+}
+
+/// An issuer-signed mdoc (`Document` with `docType` and `issuerSigned`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdocDocument {
+    pub items: Vec<IssuerSignedItem>,
+    pub mso: MobileSecurityObject,
+    /// DER certificates for the `x5chain` header, leaf first.
+    pub x5chain: Vec<Vec<u8>>,
+    pub signature: Vec<u8>,
+}
+
+impl MdocDocument {
+    /// Builds and signs the mdoc of a holder-bound credential with an
+    /// expiry date. `x5chain` certifies `signer`'s key, if available.
+    pub fn issue(
+        credential: &Microcredential,
+        signer: &dyn Signer,
+        x5chain: Vec<Vec<u8>>,
+        signed: DateTime<Utc>,
+    ) -> Result<Self, MdocError> {
+        let device_key = credential
+            .holder_binding
+            .as_ref()
+            .ok_or(MdocError::MissingDeviceKey)?
+            .public_key
+            .to_vec();
+        let valid_until = credential.expires_at.ok_or(MdocError::MissingExpiry)?;
+
+        let items: Vec<IssuerSignedItem> = elements(credential)
+            .into_iter()
+            .enumerate()
+            .map(|(index, (identifier, value))| {
+                let mut random = vec![0u8; 16];
+                OsRng.fill_bytes(&mut random);
+                IssuerSignedItem {
+                    digest_id: index as u64,
+                    random,
+                    element_identifier: identifier.to_string(),
+                    element_value: value,
+                }
+            })
+            .collect();
+        let mso = MobileSecurityObject {
+            doc_type: DOC_TYPE.to_string(),
+            value_digests: items
+                .iter()
+                .map(|item| (item.digest_id, item.digest()))
+                .collect(),
+            device_key,
+            signed,
+            valid_from: credential.issued_at,
+            valid_until,
+        };
+
+        let mut document = Self {
+            items,
+            mso,
+            x5chain,
+            signature: Vec::new(),
+        };
+        document.signature = signer.sign(&document.sig_structure())?;
+        Ok(document)
+    }
+
+    /// Checks the COSE_Sign1 signature and that every item matches its
+    /// digest in the MSO.
+    pub fn verify(&self, public_key: &[u8]) -> bool {
+        let signed =
+            verify_signature(public_key, &self.sig_structure(), &self.signature).unwrap_or(false);
+        signed
+            && self.items.iter().all(|item| {
+                self.mso
+                    .value_digests
+                    .iter()
+                    .any(|(id, digest)| *id == item.digest_id && *digest == item.digest())
+            })
+    }
+
+    /// Copy disclosing only the elements named in `identifiers`.
+    pub fn disclose(&self, identifiers: &[&str]) -> Self {
+        let mut disclosed = self.clone();
+        disclosed
+            .items
+            .retain(|item| identifiers.contains(&item.element_identifier.as_str()));
+        disclosed
+    }
+
+    /// CBOR encoding of the `Document`.
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let name_spaces = Item::Map(vec![(
+            Item::text(NAMESPACE),
+            Item::Array(self.items.iter().map(IssuerSignedItem::to_item).collect()),
+        )]);
+        let issuer_auth = Item::Array(vec![
+            Item::Bytes(protected_header()),
+            self.unprotected_header(),
+            Item::Bytes(self.payload()),
+            Item::Bytes(self.signature.clone()),
+        ]);
+        Item::Map(vec![
+            (Item::text("docType"), Item::text(&self.mso.doc_type)),
+            (
+                Item::text("issuerSigned"),
+                Item::Map(vec![
+                    (Item::text("nameSpaces"), name_spaces),
+                    (Item::text("issuerAuth"), issuer_auth),
+                ]),
+            ),
+        ])
+        .to_vec()
+    }
+
+    /// `MobileSecurityObjectBytes`, the COSE_Sign1 payload.
+    fn payload(&self) -> Vec<u8> {
+        Item::embedded(&self.mso.to_item()).to_vec()
+    }
+
+    fn unprotected_header(&self) -> Item {
+        let mut header = Vec::new();
+        match self.x5chain.as_slice() {
+            [] => {}
+            [leaf] => header.push((Item::int(COSE_X5CHAIN), Item::Bytes(leaf.clone()))),
+            chain => header.push((
+                Item::int(COSE_X5CHAIN),
+                Item::Array(chain.iter().cloned().map(Item::Bytes).collect()),
+            )),
+        }
+        Item::Map(header)
+    }
+
+    fn sig_structure(&self) -> Vec<u8> {
+        Item::Array(vec![
+            Item::text("Signature1"),
+            Item::Bytes(protected_header()),
+            Item::Bytes(Vec::new()),
+            Item::Bytes(self.payload()),
+        ])
+        .to_vec()
+    }
+}
+
+fn protected_header() -> Vec<u8> {
+    Item::Map(vec![(Item::int(1), Item::int(COSE_ALG_EDDSA))]).to_vec()
+}
+
+fn elements(credential: &Microcredential) -> Vec<(&'static str, Item)> {
+    let skill = &credential.skill;
+    let mut elements = vec![
+        ("credential_id", Item::text(credential.id.to_string())),
+        ("issuer_name", Item::text(&credential.issuer.name)),
+        ("issuer_url", Item::text(&credential.issuer.url)),
+        ("subject_id", Item::text(credential.subject.id.to_string())),
+        ("subject_name", Item::text(&credential.subject.name)),
+        ("subject_email", Item::text(&credential.subject.email)),
+        ("skill_id", Item::text(&skill.id)),
+        ("skill_name", Item::text(&skill.name)),
+        ("skill_description", Item::text(&skill.description)),
+        ("skill_level", Item::text(skill.level.label())),
+        (
+            "skill_level_rank",
+            Item::Unsigned(skill.level.rank() as u64),
+        ),
+        ("issue_date", full_date(&credential.issued_at)),
+    ];
+    if let Some(expires_at) = &credential.expires_at {
+        elements.push(("expiry_date", full_date(expires_at)));
+    }
+    if !credential.evidence.is_empty() {
+        let evidence = credential
+            .evidence
+            .iter()
+            .map(|evidence| {
+                Item::Map(vec![
+                    (Item::text("name"), Item::text(&evidence.name)),
+                    (Item::text("description"), Item::text(&evidence.description)),
+                    (Item::text("url"), Item::text(&evidence.url)),
+                ])
+            })
+            .collect();
+        elements.push(("evidence", Item::Array(evidence)));
+    }
+    elements
+}
+
+/// `tdate`: RFC 3339 text without fractional seconds, tag 0.
+fn tdate(at: &DateTime<Utc>) -> Item {
+    Item::Tag(
+        0,
+        Box::new(Item::text(at.to_rfc3339_opts(SecondsFormat::Secs, true))),
+    )
+}
+
+/// `full-date`: RFC 3339 date, tag 1004.
+fn full_date(at: &DateTime<Utc>) -> Item {
+    Item::Tag(
+        1004,
+        Box::new(Item::text(at.format("%Y-%m-%d").to_string())),
+    )
+}
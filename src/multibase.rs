@@ -0,0 +1,312 @@
+use serde::Deserialize;
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Varint-encoded multicodec prefix of an Ed25519 public key (`0xed`).
+pub const ED25519_PUB_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MultibaseError {
+    UnsupportedBase(char),
+    InvalidCharacter(char),
+    Empty,
+    UnsupportedKeyType,
+    InvalidKeyLength(usize),
+}
+
+impl fmt::Display for MultibaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MultibaseError::UnsupportedBase(prefix) => {
+                write!(f, "Unsupported multibase prefix '{}'", prefix)
+            }
+            MultibaseError::InvalidCharacter(c) => write!(f, "Invalid character '{}'", c),
+            MultibaseError::Empty => write!(f, "Empty multibase string"),
+            MultibaseError::UnsupportedKeyType => write!(f, "Multikey is not an Ed25519 key"),
+            MultibaseError::InvalidKeyLength(len) => {
+                write!(f, "Ed25519 public key must be 32 bytes, got {}", len)
+            }
+        }
+    }
+}
+
+impl Error for MultibaseError {}
+
+/// Multibase base58btc form: `z` followed by Bitcoin-alphabet base58.
+pub fn encode_base58btc(bytes: &[u8]) -> String {
+    format!("z{}", base58_encode(bytes))
+}
+
+/// Decodes a multibase string. Only base58btc (`z`) is supported.
+pub fn decode(text: &str) -> Result<Vec<u8>, MultibaseError> {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some('z') => base58_decode(chars.as_str()),
+        Some(prefix) => Err(MultibaseError::UnsupportedBase(prefix)),
+        None => Err(MultibaseError::Empty),
+    }
+}
+
+/// Multikey form of an Ed25519 public key, e.g. `z6Mk...`: the multicodec
+/// prefix and the key bytes, in multibase base58btc.
+pub fn encode_multikey(public_key: &[u8]) -> String {
+    let mut bytes = ED25519_PUB_MULTICODEC.to_vec();
+    bytes.extend_from_slice(public_key);
+    encode_base58btc(&bytes)
+}
+
+/// Raw Ed25519 public key of a multikey string.
+pub fn decode_multikey(text: &str) -> Result<Vec<u8>, MultibaseError> {
+    let bytes = decode(text)?;
+    let public_key = bytes
+        .strip_prefix(&ED25519_PUB_MULTICODEC[..])
+        .ok_or(MultibaseError::UnsupportedKeyType)?;
+    if public_key.len() != 32 {
+        return Err(MultibaseError::InvalidKeyLength(public_key.len()));
+    }
+    Ok(public_key.to_vec())
+}
+
+/// Serde helper for optional public key fields that accept either a
+/// multikey string or the legacy array of key bytes.
+pub fn deserialize_optional_key<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<EncodedKey>::deserialize(deserializer)?
+        .map(EncodedKey::into_bytes)
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum EncodedKey {
+    Multikey(String),
+    Bytes(Vec<u8>),
+}
+
+impl EncodedKey {
+    fn into_bytes(self) -> Result<Vec<u8>, MultibaseError> {
+        match self {
+            EncodedKey::Multikey(text) => decode_multikey(&text),
+            EncodedKey::Bytes(bytes) => Ok(bytes),
+        }
+    }
+}
+
+/// Ed25519 public key that serializes as a multikey string (`z6Mk...`)
+/// and derefs to the raw key bytes. Deserialization also accepts the byte
+/// arrays earlier releases wrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Multikey(Vec<u8>);
+
+impl Multikey {
+    pub fn new(public_key: Vec<u8>) -> Self {
+        Self(public_key)
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl fmt::Display for Multikey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&encode_multikey(&self.0))
+    }
+}
+
+impl std::ops::Deref for Multikey {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Multikey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for Multikey {
+    fn from(public_key: Vec<u8>) -> Self {
+        Self(public_key)
+    }
+}
+
+impl From<Multikey> for Vec<u8> {
+    fn from(key: Multikey) -> Self {
+        key.0
+    }
+}
+
+impl PartialEq<Vec<u8>> for Multikey {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<[u8]> for Multikey {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&[u8]> for Multikey {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Multikey> for Vec<u8> {
+    fn eq(&self, other: &Multikey) -> bool {
+        *self == other.0
+    }
+}
+
+impl serde::Serialize for Multikey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if BYTE_ARRAY_KEYS.with(Cell::get) {
+            return serializer.collect_seq(&self.0);
+        }
+        serializer.serialize_str(&encode_multikey(&self.0))
+    }
+}
+
+thread_local! {
+    static BYTE_ARRAY_KEYS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` with every `Multikey` serializing as a byte array, the form
+/// earlier releases signed, so their signatures can still be checked.
+pub(crate) fn with_byte_array_keys<T>(f: impl FnOnce() -> T) -> T {
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            BYTE_ARRAY_KEYS.with(|flag| flag.set(false));
+        }
+    }
+    BYTE_ARRAY_KEYS.with(|flag| flag.set(true));
+    let _reset = Reset;
+    f()
+}
+
+impl<'de> serde::Deserialize<'de> for Multikey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        EncodedKey::deserialize(deserializer)?
+            .into_bytes()
+            .map(Multikey)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+pub fn base58_encode(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    // Little-endian base58 digits of the big-endian input.
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for byte in &bytes[zeros..] {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat_n('1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|digit| BASE58_ALPHABET[*digit as usize] as char),
+        )
+        .collect()
+}
+
+pub fn base58_decode(text: &str) -> Result<Vec<u8>, MultibaseError> {
+    let zeros = text.chars().take_while(|c| *c == '1').count();
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len());
+    for c in text.chars().skip(zeros) {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|a| *a as char == c)
+            .ok_or(MultibaseError::InvalidCharacter(c))? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut decoded = vec![0u8; zeros];
+    decoded.extend(bytes.iter().rev());
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base58btc_round_trip() {
+        assert_eq!(base58_encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(base58_encode(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd]), "11233QC4");
+        assert_eq!(encode_base58btc(b""), "z");
+        for bytes in [&b""[..], &[0, 0, 1], &[255; 64]] {
+            assert_eq!(decode(&encode_base58btc(bytes)).unwrap(), bytes);
+        }
+        assert_eq!(decode("z0"), Err(MultibaseError::InvalidCharacter('0')));
+        assert_eq!(decode("mAQID"), Err(MultibaseError::UnsupportedBase('m')));
+    }
+
+    #[test]
+    fn test_multikey_round_trip() {
+        // did:key test vector from the W3C did:key specification.
+        let multikey = "z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp";
+        let public_key = decode_multikey(multikey).unwrap();
+        assert_eq!(public_key.len(), 32);
+        assert_eq!(encode_multikey(&public_key), multikey);
+
+        assert_eq!(
+            decode_multikey(&encode_base58btc(&[0xe7, 0x01, 2])),
+            Err(MultibaseError::UnsupportedKeyType)
+        );
+        assert_eq!(
+            decode_multikey(&encode_multikey(&[1; 31])),
+            Err(MultibaseError::InvalidKeyLength(31))
+        );
+    }
+
+    #[test]
+    fn test_multikey_serde() {
+        let key = Multikey::new(vec![7; 32]);
+        let json = serde_json::to_value(&key).unwrap();
+        assert_eq!(json, serde_json::json!(encode_multikey(&[7; 32])));
+        assert_eq!(serde_json::from_value::<Multikey>(json).unwrap(), key);
+        // Byte arrays written by earlier releases still read.
+        let legacy = serde_json::to_value(vec![7u8; 32]).unwrap();
+        assert_eq!(serde_json::from_value::<Multikey>(legacy).unwrap(), key);
+        assert!(serde_json::from_value::<Multikey>(serde_json::json!("z6Mk")).is_err());
+        assert_eq!(
+            with_byte_array_keys(|| serde_json::to_value(&key)).unwrap(),
+            serde_json::to_value(vec![7u8; 32]).unwrap()
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Offline verification packs: everything a verifier needs to check
+//! credentials without network access for a bounded period. The pack is
+//! signed by whoever publishes it, typically a central verifier whose
+//! trust configuration is maintained online.
+
+use crate::crypto::{canonical_json, hash_credential, verify_signature};
+use crate::hierarchy::IssuerCertificate;
+use crate::signer::{Signer, SignerError};
+use crate::status::StatusList;
+use crate::Issuer;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum OfflinePackError {
+    MissingSignature,
+    InvalidSignature,
+    /// The pack was only valid until this time.
+    Expired(DateTime<Utc>),
+    InvalidContext(String),
+    Signer(SignerError),
+    Serialization(String),
+}
+
+impl fmt::Display for OfflinePackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OfflinePackError::MissingSignature => write!(f, "Offline pack is not signed"),
+            OfflinePackError::InvalidSignature => write!(f, "Invalid offline pack signature"),
+            OfflinePackError::Expired(valid_until) => {
+                write!(f, "Offline pack expired at {}", valid_until.to_rfc3339())
+            }
+            OfflinePackError::InvalidContext(msg) => write!(f, "Invalid context: {}", msg),
+            OfflinePackError::Signer(e) => write!(f, "Signing failed: {}", e),
+            OfflinePackError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+        }
+    }
+}
+
+impl Error for OfflinePackError {}
+
+impl From<SignerError> for OfflinePackError {
+    fn from(e: SignerError) -> Self {
+        OfflinePackError::Signer(e)
+    }
+}
+
+/// Trust list, revocation snapshot and JSON-LD contexts as of `created_at`,
+/// usable until `valid_until`. Load it with
+/// `CredentialVerifier::load_offline_pack`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflinePack {
+    pub created_at: DateTime<Utc>,
+    pub valid_until: DateTime<Utc>,
+    pub trusted_issuers: Vec<Issuer>,
+    #[serde(default)]
+    pub trusted_roots: Vec<Issuer>,
+    #[serde(default)]
+    pub certificates: Vec<IssuerCertificate>,
+    /// Status lists as of `created_at`; revocations after that are not
+    /// seen until the next pack.
+    #[serde(default)]
+    pub status_lists: Vec<StatusList>,
+    /// Context documents by URL, in addition to the bundled ones.
+    #[serde(default)]
+    pub contexts: BTreeMap<String, Value>,
+    pub signature: Option<Vec<u8>>,
+}
+
+impl OfflinePack {
+    pub fn insert_context(&mut self, url: &str, document: Value) {
+        self.contexts.insert(url.to_string(), document);
+    }
+
+    pub fn signing_payload(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        canonical_json(&unsigned)
+    }
+
+    pub fn sign(&mut self, signer: &dyn Signer) -> Result<(), OfflinePackError> {
+        let payload = self
+            .signing_payload()
+            .map_err(|e| OfflinePackError::Serialization(e.to_string()))?;
+        self.signature = Some(signer.sign(&hash_credential(&payload))?);
+        Ok(())
+    }
+
+    /// Checks the publisher's signature and that the pack is still valid at
+    /// `now`.
+    pub fn verify(&self, publisher_key: &[u8], now: DateTime<Utc>) -> Result<(), OfflinePackError> {
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or(OfflinePackError::MissingSignature)?;
+        let payload = self
+            .signing_payload()
+            .map_err(|e| OfflinePackError::Serialization(e.to_string()))?;
+        if !verify_signature(publisher_key, &hash_credential(&payload), signature).unwrap_or(false)
+        {
+            return Err(OfflinePackError::InvalidSignature);
+        }
+        if now > self.valid_until {
+            return Err(OfflinePackError::Expired(self.valid_until));
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<String, OfflinePackError> {
+        serde_json::to_string(self).map_err(|e| OfflinePackError::Serialization(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, OfflinePackError> {
+        serde_json::from_str(json).map_err(|e| OfflinePackError::Serialization(e.to_string()))
+    }
+}
@@ -0,0 +1,600 @@
+use crate::timestamps::{TimestampError, TimestampPolicy};
+use crate::Microcredential;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+/// How strictly incoming credential JSON is checked.
+///
+/// `Strict` is meant for issuance pipelines and rejects anything unexpected.
+/// `Lenient` is meant for importing third-party credentials: it repairs what
+/// it can and reports every repair as a warning. Invalid ids are rejected in
+/// both modes, since any replacement would silently change what the
+/// credential refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum ParseMode {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+/// Bounds on untrusted input, checked before anything is deserialized into
+/// the credential model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLimits {
+    pub max_payload_bytes: usize,
+    /// Deepest nesting of objects and arrays.
+    pub max_depth: usize,
+    /// Longest string or object key, in bytes. Embedded attachment data is
+    /// only bounded by `max_payload_bytes`.
+    pub max_string_length: usize,
+    pub max_evidence: usize,
+    pub max_metadata_keys: usize,
+    pub max_attachments: usize,
+    /// Precision signed timestamps must stay within. Strict parsing rejects
+    /// finer timestamps when the policy does; lenient parsing warns, since
+    /// truncating them would break the signature.
+    pub timestamp_policy: TimestampPolicy,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_payload_bytes: 8 * 1024 * 1024,
+            max_depth: 32,
+            max_string_length: 64 * 1024,
+            max_evidence: 100,
+            max_metadata_keys: 100,
+            max_attachments: 10,
+            timestamp_policy: TimestampPolicy::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseWarning {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+    InvalidJson {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    UnknownField {
+        path: String,
+    },
+    InvalidDate {
+        path: String,
+        value: String,
+    },
+    InvalidUuid {
+        path: String,
+        value: String,
+    },
+    InvalidStructure(String),
+    /// The input exceeds the `ParseLimits` field named by `limit`.
+    LimitExceeded {
+        limit: &'static str,
+        path: String,
+    },
+    /// A timestamp violates `ParseLimits::timestamp_policy`.
+    Timestamp(TimestampError),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidJson {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "Invalid JSON at line {}, column {}: {}",
+                line, column, message
+            ),
+            ParseError::UnknownField { path } => write!(f, "Unknown field: {}", path),
+            ParseError::InvalidDate { path, value } => {
+                write!(f, "Invalid RFC 3339 timestamp at {}: {}", path, value)
+            }
+            ParseError::InvalidUuid { path, value } => {
+                write!(f, "Invalid UUID at {}: {}", path, value)
+            }
+            ParseError::InvalidStructure(msg) => write!(f, "Invalid credential structure: {}", msg),
+            ParseError::LimitExceeded { limit, path } if path.is_empty() => {
+                write!(f, "Credential exceeds {}", limit)
+            }
+            ParseError::LimitExceeded { limit, path } => {
+                write!(f, "Credential exceeds {} at {}", limit, path)
+            }
+            ParseError::Timestamp(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<TimestampError> for ParseError {
+    fn from(e: TimestampError) -> Self {
+        ParseError::Timestamp(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedCredential {
+    pub credential: Microcredential,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Parses with the default `ParseLimits`.
+pub fn parse_credential(input: &str, mode: ParseMode) -> Result<ParsedCredential, ParseError> {
+    parse_credential_with_limits(input, mode, &ParseLimits::default())
+}
+
+/// Limits are enforced in both modes: oversized input is never repaired.
+pub fn parse_credential_with_limits(
+    input: &str,
+    mode: ParseMode,
+    limits: &ParseLimits,
+) -> Result<ParsedCredential, ParseError> {
+    if input.len() > limits.max_payload_bytes {
+        return Err(ParseError::LimitExceeded {
+            limit: "max_payload_bytes",
+            path: String::new(),
+        });
+    }
+    // Rejected before parsing, so deep nesting never reaches the parser.
+    check_depth(input, limits.max_depth)?;
+    let mut value: Value = serde_json::from_str(input).map_err(|e| ParseError::InvalidJson {
+        line: e.line(),
+        column: e.column(),
+        message: e.to_string(),
+    })?;
+    check_sizes(&value, limits)?;
+
+    let mut checker = FieldChecker {
+        mode,
+        policy: limits.timestamp_policy,
+        warnings: Vec::new(),
+    };
+    checker.check_credential(&mut value)?;
+
+    let credential: Microcredential =
+        serde_json::from_value(value).map_err(|e| ParseError::InvalidStructure(e.to_string()))?;
+
+    for (path, fields) in unknown_field_sets(&credential) {
+        for key in fields.keys() {
+            let path = format!("{}{}", path, key);
+            match mode {
+                ParseMode::Strict => return Err(ParseError::UnknownField { path }),
+                ParseMode::Lenient => checker.warn(&path, "unknown field retained"),
+            }
+        }
+    }
+
+    match credential.check_timestamps(&limits.timestamp_policy) {
+        Err(TimestampError::ExcessPrecision(path))
+            if mode == ParseMode::Lenient || !limits.timestamp_policy.reject_excess_precision =>
+        {
+            checker.warn(&path, "timestamp finer than the timestamp policy")
+        }
+        result => result?,
+    }
+
+    Ok(ParsedCredential {
+        credential,
+        warnings: checker.warnings,
+    })
+}
+
+/// Scans raw JSON for the nesting depth, skipping brackets inside strings.
+fn check_depth(input: &str, max_depth: usize) -> Result<(), ParseError> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for byte in input.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > max_depth {
+                    return Err(ParseError::LimitExceeded {
+                        limit: "max_depth",
+                        path: String::new(),
+                    });
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_sizes(value: &Value, limits: &ParseLimits) -> Result<(), ParseError> {
+    let counts = [
+        ("evidence", "max_evidence", limits.max_evidence),
+        ("metadata", "max_metadata_keys", limits.max_metadata_keys),
+        ("attachments", "max_attachments", limits.max_attachments),
+    ];
+    for (key, limit, max) in counts {
+        let count = match value.get(key) {
+            Some(Value::Array(items)) => items.len(),
+            Some(Value::Object(entries)) => entries.len(),
+            _ => 0,
+        };
+        if count > max {
+            return Err(ParseError::LimitExceeded {
+                limit,
+                path: key.to_string(),
+            });
+        }
+    }
+    check_strings(value, "", limits.max_string_length)
+}
+
+fn check_strings(value: &Value, path: &str, max_length: usize) -> Result<(), ParseError> {
+    let too_long = |path: &str| ParseError::LimitExceeded {
+        limit: "max_string_length",
+        path: path.to_string(),
+    };
+    match value {
+        Value::String(text) if text.len() > max_length && !is_attachment_data(path) => {
+            Err(too_long(path))
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                check_strings(item, &format!("{}[{}]", path, index), max_length)?;
+            }
+            Ok(())
+        }
+        Value::Object(entries) => {
+            for (key, item) in entries {
+                let path = match path {
+                    "" => key.clone(),
+                    _ => format!("{}.{}", path, key),
+                };
+                if key.len() > max_length {
+                    return Err(too_long(&path));
+                }
+                check_strings(item, &path, max_length)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn is_attachment_data(path: &str) -> bool {
+    path.starts_with("attachments[") && path.ends_with("].content.Embedded.data")
+}
+
+fn unknown_field_sets(credential: &Microcredential) -> Vec<(String, &Map<String, Value>)> {
+    let mut sets = vec![
+        (String::new(), &credential.unknown_fields),
+        ("issuer.".to_string(), &credential.issuer.unknown_fields),
+        ("subject.".to_string(), &credential.subject.unknown_fields),
+        ("skill.".to_string(), &credential.skill.unknown_fields),
+    ];
+    for (index, evidence) in credential.evidence.iter().enumerate() {
+        sets.push((format!("evidence[{}].", index), &evidence.unknown_fields));
+    }
+    for (index, attachment) in credential.attachments.iter().enumerate() {
+        sets.push((
+            format!("attachments[{}].", index),
+            &attachment.unknown_fields,
+        ));
+    }
+    sets
+}
+
+struct FieldChecker {
+    mode: ParseMode,
+    policy: TimestampPolicy,
+    warnings: Vec<ParseWarning>,
+}
+
+impl FieldChecker {
+    fn warn(&mut self, path: &str, message: &str) {
+        self.warnings.push(ParseWarning {
+            path: path.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn check_credential(&mut self, value: &mut Value) -> Result<(), ParseError> {
+        let Some(object) = value.as_object_mut() else {
+            return Err(ParseError::InvalidStructure(
+                "credential must be a JSON object".to_string(),
+            ));
+        };
+
+        self.check_uuid(object, "", "id")?;
+        self.check_date(object, "", "issued_at", true)?;
+        self.check_date(object, "", "valid_from", false)?;
+        self.check_date(object, "", "expires_at", false)?;
+
+        if self.mode == ParseMode::Lenient {
+            let defaults = [
+                ("metadata", Value::Object(Map::new())),
+                ("evidence", Value::Array(Vec::new())),
+            ];
+            for (key, default) in defaults {
+                if !object.contains_key(key) {
+                    object.insert(key.to_string(), default);
+                    self.warn(key, "missing field defaulted to empty");
+                }
+            }
+        }
+
+        if let Some(Value::Object(issuer)) = object.get_mut("issuer") {
+            self.check_uuid(issuer, "issuer.", "id")?;
+            if let Some(Value::Array(keys)) = issuer.get_mut("key_history") {
+                for (index, key) in keys.iter_mut().enumerate() {
+                    if let Value::Object(key) = key {
+                        let prefix = format!("issuer.key_history[{}].", index);
+                        self.check_date(key, &prefix, "activated_at", true)?;
+                        self.check_date(key, &prefix, "retired_at", false)?;
+                    }
+                }
+            }
+        }
+        if let Some(Value::Object(subject)) = object.get_mut("subject") {
+            self.check_uuid(subject, "subject.", "id")?;
+        }
+        if let Some(Value::Array(evidence)) = object.get_mut("evidence") {
+            for (index, item) in evidence.iter_mut().enumerate() {
+                if let Value::Object(item) = item {
+                    self.check_uuid(item, &format!("evidence[{}].", index), "id")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_uuid(
+        &self,
+        object: &Map<String, Value>,
+        prefix: &str,
+        key: &str,
+    ) -> Result<(), ParseError> {
+        let Some(value) = object.get(key) else {
+            return Ok(());
+        };
+        if value.as_str().is_some_and(|s| Uuid::parse_str(s).is_ok()) {
+            return Ok(());
+        }
+        Err(ParseError::InvalidUuid {
+            path: format!("{}{}", prefix, key),
+            value: value.to_string(),
+        })
+    }
+
+    fn check_date(
+        &mut self,
+        object: &mut Map<String, Value>,
+        prefix: &str,
+        key: &str,
+        required: bool,
+    ) -> Result<(), ParseError> {
+        let Some(value) = object.get_mut(key) else {
+            return Ok(());
+        };
+        if value.is_null() && !required {
+            return Ok(());
+        }
+        if value
+            .as_str()
+            .is_some_and(|s| s.parse::<DateTime<Utc>>().is_ok())
+        {
+            return Ok(());
+        }
+
+        let path = format!("{}{}", prefix, key);
+        if self.mode == ParseMode::Strict {
+            return Err(ParseError::InvalidDate {
+                path,
+                value: value.to_string(),
+            });
+        }
+
+        if let Some(parsed) = parse_date_leniently(value) {
+            *value = Value::String(self.policy.format(&parsed));
+            self.warn(&path, "non-RFC 3339 timestamp normalized");
+            Ok(())
+        } else if !required {
+            *value = Value::Null;
+            self.warn(&path, "unparseable timestamp dropped");
+            Ok(())
+        } else {
+            Err(ParseError::InvalidDate {
+                path,
+                value: value.to_string(),
+            })
+        }
+    }
+}
+
+fn parse_date_leniently(value: &Value) -> Option<DateTime<Utc>> {
+    if let Some(seconds) = value.as_i64() {
+        return DateTime::from_timestamp(seconds, 0);
+    }
+    let text = value.as_str()?.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc2822(text) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(parsed) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(parsed.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuer::CredentialIssuer;
+    use crate::{Evidence, EvidenceType, Skill, SkillLevel, Subject};
+
+    fn sample_json() -> Value {
+        let issuer = CredentialIssuer::new(
+            "Test University".to_string(),
+            "https://test.edu".to_string(),
+        );
+        let subject = Subject {
+            id: Uuid::new_v4(),
+            name: "Test Student".to_string(),
+            email: "test@example.com".to_string(),
+            unknown_fields: Default::default(),
+        };
+        let skill = Skill {
+            id: "test-skill".to_string(),
+            name: "Test Skill".to_string(),
+            description: "A test skill".to_string(),
+            level: SkillLevel::Beginner,
+            unknown_fields: Default::default(),
+        };
+        let evidence = vec![Evidence {
+            id: Uuid::new_v4(),
+            name: "Test Evidence".to_string(),
+            description: "Test evidence description".to_string(),
+            url: "https://example.com/evidence".to_string(),
+            evidence_type: EvidenceType::Project,
+            unknown_fields: Default::default(),
+        }];
+        let credential = issuer
+            .issue_credential(subject, skill, evidence, None)
+            .unwrap();
+        serde_json::to_value(&credential).unwrap()
+    }
+
+    #[test]
+    fn test_strict_mode_reports_precise_errors() {
+        let mut value = sample_json();
+        value["evidence"][0]["id"] = Value::String("not-a-uuid".to_string());
+        for mode in [ParseMode::Strict, ParseMode::Lenient] {
+            let err = parse_credential(&value.to_string(), mode).unwrap_err();
+            assert!(
+                matches!(err, ParseError::InvalidUuid { ref path, .. } if path == "evidence[0].id")
+            );
+        }
+
+        let mut value = sample_json();
+        value["skill"]["framework"] = Value::String("EQF".to_string());
+        let err = parse_credential(&value.to_string(), ParseMode::Strict).unwrap_err();
+        assert!(matches!(err, ParseError::UnknownField { ref path } if path == "skill.framework"));
+
+        // Older credentials carry sub-second timestamps; they are only
+        // rejected when the policy asks for it.
+        let mut value = sample_json();
+        value["valid_from"] = Value::String("2026-03-01T09:30:15.250Z".to_string());
+        let parsed = parse_credential(&value.to_string(), ParseMode::Strict).unwrap();
+        assert_eq!(parsed.warnings[0].path, "valid_from");
+        let limits = ParseLimits {
+            timestamp_policy: TimestampPolicy {
+                reject_excess_precision: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = parse_credential_with_limits(&value.to_string(), ParseMode::Strict, &limits)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::Timestamp(TimestampError::ExcessPrecision(ref path)) if path == "valid_from"
+        ));
+        let parsed = parse_credential(&value.to_string(), ParseMode::Lenient).unwrap();
+        assert_eq!(parsed.warnings[0].path, "valid_from");
+
+        let err = parse_credential("{\"id\": ", ParseMode::Strict).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidJson { line: 1, .. }));
+    }
+
+    #[test]
+    fn test_lenient_mode_repairs_with_warnings() {
+        let mut value = sample_json();
+        value["expires_at"] = Value::String("2030-06-30".to_string());
+        value["skill"]["framework"] = Value::String("EQF".to_string());
+        value.as_object_mut().unwrap().remove("metadata");
+
+        let parsed = parse_credential(&value.to_string(), ParseMode::Lenient).unwrap();
+        assert_eq!(
+            parsed.credential.expires_at.unwrap().to_rfc3339(),
+            "2030-06-30T00:00:00+00:00"
+        );
+        let paths: Vec<&str> = parsed.warnings.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, vec!["expires_at", "metadata", "skill.framework"]);
+    }
+
+    #[test]
+    fn test_limits_reject_hostile_input() {
+        let limits = ParseLimits {
+            max_string_length: 1024,
+            ..Default::default()
+        };
+        let limit_of =
+            |input: &str| match parse_credential_with_limits(input, ParseMode::Lenient, &limits) {
+                Err(ParseError::LimitExceeded { limit, path }) => Some((limit, path)),
+                _ => None,
+            };
+
+        let nested = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert_eq!(limit_of(&nested), Some(("max_depth", String::new())));
+        // Brackets inside strings do not count towards the depth.
+        let mut value = sample_json();
+        value["skill"]["name"] = Value::String("[\"{".repeat(40));
+        assert_eq!(limit_of(&value.to_string()), None);
+
+        let mut value = sample_json();
+        value["evidence"][0]["description"] = Value::String("x".repeat(2000));
+        assert_eq!(
+            limit_of(&value.to_string()),
+            Some(("max_string_length", "evidence[0].description".to_string()))
+        );
+
+        let mut value = sample_json();
+        let evidence = value["evidence"][0].clone();
+        value["evidence"] = Value::Array(vec![evidence; 101]);
+        assert_eq!(
+            limit_of(&value.to_string()),
+            Some(("max_evidence", "evidence".to_string()))
+        );
+
+        let mut value = sample_json();
+        value["attachments"] = serde_json::to_value(vec![crate::attachment::Attachment::embedded(
+            "seal.png".to_string(),
+            "image/png".to_string(),
+            vec![0; 4096],
+        )])
+        .unwrap();
+        assert!(
+            parse_credential_with_limits(&value.to_string(), ParseMode::Strict, &limits).is_ok()
+        );
+        assert_eq!(
+            limit_of(&format!("{}{}", value, " ".repeat(9 * 1024 * 1024))),
+            Some(("max_payload_bytes", String::new()))
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use crate::crypto::{canonical_json, hash_credential, verify_signature, CryptoKeyPair};
+use crate::verifier::VerificationError;
+use crate::Microcredential;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Several credentials bundled under a single holder proof, e.g. all the
+/// prerequisites for one job application.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Presentation {
+    pub id: Uuid,
+    /// Relying party the presentation is intended for.
+    pub audience: String,
+    pub domain: Option<String>,
+    pub holder_key: Vec<u8>,
+    pub credentials: Vec<Microcredential>,
+    pub created_at: DateTime<Utc>,
+    pub proof: Option<Vec<u8>>,
+}
+
+impl Presentation {
+    pub fn create(
+        credentials: Vec<Microcredential>,
+        holder: &CryptoKeyPair,
+        audience: String,
+        domain: Option<String>,
+        created_at: DateTime<Utc>,
+    ) -> Result<Self, serde_json::Error> {
+        let mut presentation = Self {
+            id: Uuid::new_v4(),
+            audience,
+            domain,
+            holder_key: holder.public_key(),
+            credentials,
+            created_at,
+            proof: None,
+        };
+        let payload = presentation.signing_payload()?;
+        presentation.proof = Some(holder.sign(&hash_credential(&payload)));
+        Ok(presentation)
+    }
+
+    pub fn signing_payload(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let mut unsigned = self.clone();
+        unsigned.proof = None;
+        canonical_json(&unsigned)
+    }
+
+    pub fn verify_holder_proof(&self) -> bool {
+        let Some(proof) = &self.proof else {
+            return false;
+        };
+        let Ok(payload) = self.signing_payload() else {
+            return false;
+        };
+        verify_signature(&self.holder_key, &hash_credential(&payload), proof).unwrap_or(false)
+    }
+}
+
+#[derive(Debug)]
+pub struct CredentialCheck {
+    pub credential_id: Uuid,
+    pub result: Result<bool, VerificationError>,
+}
+
+/// Outcome of checking a presentation: the holder binding plus every
+/// included credential.
+#[derive(Debug)]
+pub struct PresentationReport {
+    pub presentation_id: Uuid,
+    pub holder_proof_valid: bool,
+    pub audience_valid: bool,
+    pub credential_checks: Vec<CredentialCheck>,
+}
+
+impl PresentationReport {
+    pub fn is_valid(&self) -> bool {
+        self.holder_proof_valid
+            && self.audience_valid
+            && !self.credential_checks.is_empty()
+            && self
+                .credential_checks
+                .iter()
+                .all(|check| matches!(check.result, Ok(true)))
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CredentialCheck> {
+        self.credential_checks
+            .iter()
+            .filter(|check| !matches!(check.result, Ok(true)))
+    }
+}
@@ -0,0 +1,295 @@
+//! Unsigned previews of a credential for review before signing. Every
+//! rendering is watermarked so a preview cannot pass for the real thing.
+
+use crate::Microcredential;
+use serde_json::json;
+use std::error::Error;
+use std::fmt;
+
+const WATERMARK: &str = "PREVIEW - NOT SIGNED";
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PreviewError {
+    /// The template uses a placeholder that is not one of `PLACEHOLDERS`.
+    UnknownPlaceholder(String),
+    Serialization(String),
+}
+
+impl fmt::Display for PreviewError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PreviewError::UnknownPlaceholder(name) => {
+                write!(f, "Unknown template placeholder: {{{{{}}}}}", name)
+            }
+            PreviewError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+        }
+    }
+}
+
+impl Error for PreviewError {}
+
+/// Placeholders a template can use, written as `{{subject.name}}`.
+pub const PLACEHOLDERS: &[&str] = &[
+    "subject.name",
+    "subject.email",
+    "skill.id",
+    "skill.name",
+    "skill.description",
+    "skill.level",
+    "issuer.name",
+    "issuer.url",
+    "issued_at",
+    "expires_at",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PreviewFormat {
+    Json,
+    Pdf,
+    /// SVG badge image.
+    Svg,
+}
+
+impl PreviewFormat {
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            PreviewFormat::Json => "application/json",
+            PreviewFormat::Pdf => "application/pdf",
+            PreviewFormat::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// Layout of the rendered credential. Text fields may contain placeholders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewTemplate {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub footer: String,
+    /// CSS colour of the badge band, e.g. `#1f4e79`.
+    pub accent_color: String,
+}
+
+impl Default for PreviewTemplate {
+    fn default() -> Self {
+        Self {
+            title: "{{skill.name}}".to_string(),
+            lines: vec![
+                "Awarded to {{subject.name}}".to_string(),
+                "Level: {{skill.level}}".to_string(),
+                "{{skill.description}}".to_string(),
+            ],
+            footer: "Issued by {{issuer.name}} on {{issued_at}}".to_string(),
+            accent_color: "#1f4e79".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preview {
+    pub format: PreviewFormat,
+    pub content: Vec<u8>,
+}
+
+impl Preview {
+    pub fn media_type(&self) -> &'static str {
+        self.format.media_type()
+    }
+}
+
+/// Renders `credential` with `template`. The credential is expected to be
+/// unsigned; it is rendered as given either way.
+pub fn render(
+    credential: &Microcredential,
+    template: &PreviewTemplate,
+    format: PreviewFormat,
+) -> Result<Preview, PreviewError> {
+    let title = fill(&template.title, credential)?;
+    let lines = template
+        .lines
+        .iter()
+        .map(|line| fill(line, credential))
+        .collect::<Result<Vec<_>, _>>()?;
+    let footer = fill(&template.footer, credential)?;
+
+    let content = match format {
+        PreviewFormat::Json => {
+            let document = json!({
+                "preview": true,
+                "watermark": WATERMARK,
+                "display": {"title": title, "lines": lines, "footer": footer},
+                "credential": credential,
+            });
+            serde_json::to_vec_pretty(&document)
+                .map_err(|e| PreviewError::Serialization(e.to_string()))?
+        }
+        PreviewFormat::Pdf => pdf(&title, &lines, &footer),
+        PreviewFormat::Svg => svg(&title, &lines, &footer, &template.accent_color).into_bytes(),
+    };
+    Ok(Preview { format, content })
+}
+
+fn fill(text: &str, credential: &Microcredential) -> Result<String, PreviewError> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + end].trim();
+        out.push_str(&placeholder(name, credential)?);
+        rest = &rest[start + end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn placeholder(name: &str, credential: &Microcredential) -> Result<String, PreviewError> {
+    let date = |at: &chrono::DateTime<chrono::Utc>| at.format("%Y-%m-%d").to_string();
+    Ok(match name {
+        "subject.name" => credential.subject.name.clone(),
+        "subject.email" => credential.subject.email.clone(),
+        "skill.id" => credential.skill.id.clone(),
+        "skill.name" => credential.skill.name.clone(),
+        "skill.description" => credential.skill.description.clone(),
+        "skill.level" => credential.skill.level.label().to_string(),
+        "issuer.name" => credential.issuer.name.clone(),
+        "issuer.url" => credential.issuer.url.clone(),
+        "issued_at" => date(&credential.issued_at),
+        "expires_at" => credential.expires_at.as_ref().map(date).unwrap_or_default(),
+        _ => return Err(PreviewError::UnknownPlaceholder(name.to_string())),
+    })
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn svg(title: &str, lines: &[String], footer: &str, accent_color: &str) -> String {
+    let mut body = format!(
+        "<rect width=\"600\" height=\"400\" fill=\"#ffffff\" stroke=\"{color}\" \
+         stroke-width=\"4\"/>\
+         <rect width=\"600\" height=\"80\" fill=\"{color}\"/>\
+         <text x=\"300\" y=\"52\" font-size=\"28\" fill=\"#ffffff\" \
+         text-anchor=\"middle\">{title}</text>",
+        color = xml_escape(accent_color),
+        title = xml_escape(title),
+    );
+    for (index, line) in lines.iter().enumerate() {
+        body.push_str(&format!(
+            "<text x=\"300\" y=\"{}\" font-size=\"18\" text-anchor=\"middle\">{}</text>",
+            140 + index * 36,
+            xml_escape(line)
+        ));
+    }
+    body.push_str(&format!(
+        "<text x=\"300\" y=\"360\" font-size=\"14\" fill=\"#555555\" \
+         text-anchor=\"middle\">{}</text>\
+         <text x=\"300\" y=\"250\" font-size=\"40\" fill=\"#cc0000\" fill-opacity=\"0.25\" \
+         text-anchor=\"middle\" transform=\"rotate(-20 300 250)\">{}</text>",
+        xml_escape(footer),
+        WATERMARK
+    ));
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"600\" height=\"400\" \
+         viewBox=\"0 0 600 400\" font-family=\"Helvetica, Arial, sans-serif\">{}</svg>",
+        body
+    )
+}
+
+/// PDF string literal in WinAnsi (Latin-1 subset); other characters become
+/// `?` since the standard fonts cannot show them.
+fn pdf_string(text: &str) -> String {
+    let mut out = String::from("(");
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            '\u{a0}'..='\u{ff}' => out.push_str(&format!("\\{:03o}", c as u32)),
+            _ => out.push('?'),
+        }
+    }
+    out.push(')');
+    out
+}
+
+/// Single A4 page in the standard Helvetica font.
+fn pdf(title: &str, lines: &[String], footer: &str) -> Vec<u8> {
+    let mut text = format!("BT /F1 24 Tf 72 760 Td {} Tj ET\n", pdf_string(title));
+    for (index, line) in lines.iter().enumerate() {
+        text.push_str(&format!(
+            "BT /F1 12 Tf 72 {} Td {} Tj ET\n",
+            720 - index * 20,
+            pdf_string(line)
+        ));
+    }
+    text.push_str(&format!(
+        "BT /F1 10 Tf 72 72 Td {} Tj ET\n\
+         BT 1 0 0 rg /F1 36 Tf 0.94 0.34 -0.34 0.94 140 380 Tm {} Tj ET\n",
+        pdf_string(footer),
+        pdf_string(WATERMARK)
+    ));
+
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] \
+         /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+         /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        format!("<< /Length {} >>\nstream\n{}endstream", text.len(), text),
+    ];
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.push_str(&format!("{} 0 obj\n{}\nendobj\n", index + 1, object));
+    }
+    let xref = out.len();
+    out.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        out.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    out.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    ));
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_strings_and_offsets() {
+        assert_eq!(pdf_string("a (b) \\ é ✓"), "(a \\(b\\) \\\\ \\351 ?)");
+
+        let pdf = String::from_utf8(pdf("Title", &["Line".to_string()], "Footer")).unwrap();
+        let xref: usize = pdf
+            .lines()
+            .skip_while(|line| *line != "startxref")
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pdf[xref..].starts_with("xref\n0 6\n"));
+        let first_object: usize = pdf[xref..].lines().nth(3).unwrap()[..10].parse().unwrap();
+        assert!(pdf[first_object..].starts_with("1 0 obj"));
+    }
+}
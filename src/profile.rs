@@ -0,0 +1,67 @@
+use crate::crypto::{canonical_json, hash_credential, verify_signature};
+use crate::Issuer;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssuerEndpoint {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Accreditation {
+    pub name: String,
+    pub accrediting_body: String,
+    pub url: Option<String>,
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+/// Self-signed description of an issuer: identity, keys, endpoints and
+/// accreditations. The signature proves control of the listed key; whether
+/// to trust the issuer is still the verifier operator's decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuerProfile {
+    pub issuer: Issuer,
+    pub endpoints: Vec<IssuerEndpoint>,
+    pub accreditations: Vec<Accreditation>,
+    /// PEM X.509 chain certifying the current key, leaf first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub certificate_chain: Vec<String>,
+    /// Addresses the issuer sends Blockcerts anchoring transactions from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blockchain_addresses: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub signature: Option<Vec<u8>>,
+}
+
+impl IssuerProfile {
+    pub fn signing_payload(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let mut unsigned = self.clone();
+        unsigned.signature = None;
+        canonical_json(&unsigned)
+    }
+
+    pub fn verify_self_signature(&self) -> bool {
+        self.verify_signature(&self.issuer.public_key)
+    }
+
+    /// Whether the profile is signed by `public_key`, e.g. the key a
+    /// verifier already trusts for this issuer.
+    pub fn verify_signature(&self, public_key: &[u8]) -> bool {
+        let Some(signature) = &self.signature else {
+            return false;
+        };
+        let Ok(payload) = self.signing_payload() else {
+            return false;
+        };
+        verify_signature(public_key, &hash_credential(&payload), signature).unwrap_or(false)
+    }
+
+    /// SHA-256 fingerprint of the current public key, for out-of-band
+    /// comparison before trusting the profile.
+    pub fn key_fingerprint(&self) -> String {
+        hex::encode(Sha256::digest(&self.issuer.public_key))
+    }
+}
@@ -0,0 +1,124 @@
+use crate::crypto::hash_with;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+/// Signature algorithm and key type used for a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum SignatureSuite {
+    #[default]
+    #[serde(rename = "Ed25519Signature2020")]
+    Ed25519,
+}
+
+/// What the signing key is asserting with the proof. Credentials must use
+/// `AssertionMethod`; `Authentication` proofs only prove control of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum ProofPurpose {
+    #[default]
+    AssertionMethod,
+    Authentication,
+}
+
+/// Digest applied to the canonical payload before it is signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum HashAlgorithm {
+    #[default]
+    #[serde(rename = "sha-256")]
+    Sha256,
+    #[serde(rename = "sha-512")]
+    Sha512,
+    /// Considerably faster than SHA-2 on large payloads and bulk jobs.
+    #[serde(rename = "blake3")]
+    Blake3,
+}
+
+impl HashAlgorithm {
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha256 => hash_with::<Sha256>(data),
+            HashAlgorithm::Sha512 => hash_with::<Sha512>(data),
+            HashAlgorithm::Blake3 => hash_with::<blake3::Hasher>(data),
+        }
+    }
+}
+
+/// Byte encoding of the credential that is hashed and signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum PayloadEncoding {
+    /// Canonical JSON, see `crypto::canonical_json`.
+    #[default]
+    Json,
+    /// Deterministic CBOR, see `cbor::to_canonical_vec`. Smaller than JSON
+    /// and has no whitespace or escaping choices to disagree on.
+    Cbor,
+}
+
+impl PayloadEncoding {
+    fn is_json(&self) -> bool {
+        *self == PayloadEncoding::Json
+    }
+}
+
+/// Parameters of a credential's signature, which the verifier dispatches
+/// on. Credentials without a proof were signed with Ed25519 over the
+/// SHA-256 digest of canonical JSON, by whichever issuer key was active at
+/// issuance.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Proof {
+    #[serde(rename = "type", default)]
+    pub suite: SignatureSuite,
+    #[serde(default)]
+    pub hash: HashAlgorithm,
+    #[serde(default, skip_serializing_if = "PayloadEncoding::is_json")]
+    pub payload: PayloadEncoding,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Utc>>,
+    /// `<issuer url>#<key id>`, see `key_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_method: Option<String>,
+    #[serde(default)]
+    pub proof_purpose: ProofPurpose,
+}
+
+impl Proof {
+    /// Key id fragment of `verification_method`.
+    pub fn key_id(&self) -> Option<&str> {
+        self.verification_method
+            .as_deref()
+            .and_then(|method| method.rsplit_once('#'))
+            .map(|(_, fragment)| fragment)
+    }
+}
+
+/// Short, stable identifier for a public key: the first 8 bytes of its
+/// SHA-256 fingerprint in hex.
+pub fn key_id(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::hash_credential;
+
+    #[test]
+    fn test_hash_algorithm_digests() {
+        assert_eq!(
+            HashAlgorithm::Sha256.digest(b"abc"),
+            hash_credential(b"abc")
+        );
+        assert_eq!(HashAlgorithm::Sha512.digest(b"abc").len(), 64);
+        assert_eq!(
+            HashAlgorithm::Blake3.digest(b"abc"),
+            blake3::hash(b"abc").as_bytes().to_vec()
+        );
+    }
+}
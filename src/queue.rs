@@ -0,0 +1,264 @@
+use crate::issuer::{CredentialIssuer, CredentialRequest};
+use crate::store::CredentialStore;
+use crate::Microcredential;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Hands an issued credential to the holder (email, wallet push, ...).
+pub trait Delivery {
+    fn deliver(&self, credential: &Microcredential) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// Exponential backoff for delivery failures. Issuance and storage errors
+/// are not retried; they fail the job immediately.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub multiplier: u32,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            multiplier: 2,
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the attempt following `attempts` failed ones.
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let factor = self.multiplier.saturating_pow(attempts.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum JobStatus {
+    Queued,
+    Retrying { attempts: u32, last_error: String },
+    Delivered { credential_id: Uuid },
+    Failed { error: String },
+    DeadLettered { credential_id: Uuid },
+}
+
+/// A credential that was issued and stored but could not be delivered
+/// within the retry policy.
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub job_id: Uuid,
+    pub credential: Microcredential,
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+struct PendingDelivery {
+    job_id: Uuid,
+    credential: Microcredential,
+    attempts: u32,
+    next_attempt: Instant,
+}
+
+type Shared<T> = Arc<Mutex<T>>;
+
+/// Issues, stores and delivers requests on a worker thread so callers only
+/// pay for the enqueue.
+pub struct IssuanceQueue {
+    sender: Option<Sender<(Uuid, CredentialRequest)>>,
+    worker: Option<JoinHandle<()>>,
+    statuses: Shared<HashMap<Uuid, JobStatus>>,
+    dead_letters: Shared<Vec<DeadLetter>>,
+}
+
+impl IssuanceQueue {
+    pub fn start<S, D>(
+        issuer: Arc<CredentialIssuer>,
+        store: Shared<S>,
+        delivery: D,
+        policy: RetryPolicy,
+    ) -> Self
+    where
+        S: CredentialStore + Send + 'static,
+        D: Delivery + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let statuses: Shared<HashMap<Uuid, JobStatus>> = Arc::default();
+        let dead_letters: Shared<Vec<DeadLetter>> = Arc::default();
+
+        let worker = Worker {
+            issuer,
+            store,
+            delivery,
+            policy,
+            statuses: Arc::clone(&statuses),
+            dead_letters: Arc::clone(&dead_letters),
+            pending: Vec::new(),
+        };
+        let handle = thread::spawn(move || worker.run(receiver));
+
+        Self {
+            sender: Some(sender),
+            worker: Some(handle),
+            statuses,
+            dead_letters,
+        }
+    }
+
+    pub fn enqueue(&self, request: CredentialRequest) -> Uuid {
+        let job_id = Uuid::new_v4();
+        self.statuses
+            .lock()
+            .unwrap()
+            .insert(job_id, JobStatus::Queued);
+        if let Some(sender) = &self.sender {
+            // The worker only exits after the sender is dropped.
+            let _ = sender.send((job_id, request));
+        }
+        job_id
+    }
+
+    pub fn status(&self, job_id: &Uuid) -> Option<JobStatus> {
+        self.statuses.lock().unwrap().get(job_id).cloned()
+    }
+
+    pub fn take_dead_letters(&self) -> Vec<DeadLetter> {
+        std::mem::take(&mut *self.dead_letters.lock().unwrap())
+    }
+
+    /// Stops accepting work and waits until queued jobs and pending retries
+    /// have finished.
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for IssuanceQueue {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+struct Worker<S, D> {
+    issuer: Arc<CredentialIssuer>,
+    store: Shared<S>,
+    delivery: D,
+    policy: RetryPolicy,
+    statuses: Shared<HashMap<Uuid, JobStatus>>,
+    dead_letters: Shared<Vec<DeadLetter>>,
+    pending: Vec<PendingDelivery>,
+}
+
+impl<S: CredentialStore, D: Delivery> Worker<S, D> {
+    fn run(mut self, receiver: mpsc::Receiver<(Uuid, CredentialRequest)>) {
+        let mut accepting = true;
+        while accepting || !self.pending.is_empty() {
+            let next_due = self.pending.iter().map(|p| p.next_attempt).min();
+            let received = match (accepting, next_due) {
+                (true, None) => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                (true, Some(due)) => {
+                    receiver.recv_timeout(due.saturating_duration_since(Instant::now()))
+                }
+                (false, Some(due)) => {
+                    thread::sleep(due.saturating_duration_since(Instant::now()));
+                    Err(RecvTimeoutError::Timeout)
+                }
+                (false, None) => break,
+            };
+            match received {
+                Ok((job_id, request)) => self.process(job_id, request),
+                Err(RecvTimeoutError::Disconnected) => accepting = false,
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+            self.retry_due();
+        }
+    }
+
+    fn process(&mut self, job_id: Uuid, request: CredentialRequest) {
+        let issued = self
+            .issuer
+            .issue_request(request)
+            .map_err(|e| e.to_string())
+            .and_then(|credential| {
+                self.store
+                    .lock()
+                    .unwrap()
+                    .insert(&credential)
+                    .map(|_| credential)
+                    .map_err(|e| e.to_string())
+            });
+        match issued {
+            Ok(credential) => self.attempt(PendingDelivery {
+                job_id,
+                credential,
+                attempts: 0,
+                next_attempt: Instant::now(),
+            }),
+            Err(error) => self.set_status(job_id, JobStatus::Failed { error }),
+        }
+    }
+
+    fn retry_due(&mut self) {
+        let now = Instant::now();
+        let (due, waiting) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|p| p.next_attempt <= now);
+        self.pending = waiting;
+        for pending in due {
+            self.attempt(pending);
+        }
+    }
+
+    fn attempt(&mut self, mut pending: PendingDelivery) {
+        pending.attempts += 1;
+        let credential_id = pending.credential.id;
+        match self.delivery.deliver(&pending.credential) {
+            Ok(()) => self.set_status(pending.job_id, JobStatus::Delivered { credential_id }),
+            Err(error) if pending.attempts >= self.policy.max_attempts => {
+                // Dead letter first, so it is there once the status says so.
+                let job_id = pending.job_id;
+                self.dead_letters.lock().unwrap().push(DeadLetter {
+                    job_id,
+                    credential: pending.credential,
+                    attempts: pending.attempts,
+                    last_error: error.to_string(),
+                });
+                self.set_status(job_id, JobStatus::DeadLettered { credential_id });
+            }
+            Err(error) => {
+                self.set_status(
+                    pending.job_id,
+                    JobStatus::Retrying {
+                        attempts: pending.attempts,
+                        last_error: error.to_string(),
+                    },
+                );
+                pending.next_attempt = Instant::now() + self.policy.backoff(pending.attempts);
+                self.pending.push(pending);
+            }
+        }
+    }
+
+    fn set_status(&self, job_id: Uuid, status: JobStatus) {
+        self.statuses.lock().unwrap().insert(job_id, status);
+    }
+}
@@ -0,0 +1,99 @@
+//! Salted credential digests for publication, e.g. in transparency logs or
+//! anchoring transactions. A plain hash of a credential can be matched by
+//! anyone who guesses its contents (a name, an email and a skill are easy
+//! to enumerate); mixing in a random per-credential salt prevents that.
+//!
+//! The salt is stored in the credential's metadata under `digest_salt`, so
+//! it is covered by the issuer signature and travels with the credential to
+//! the holder. A verifier given the credential recomputes the digest and
+//! looks it up in the published log; third parties who only see the log
+//! learn nothing.
+
+use crate::crypto::hash_credential;
+use crate::Microcredential;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::error::Error;
+use std::fmt;
+
+/// Metadata key holding the hex-encoded salt.
+pub const SALT_METADATA_KEY: &str = "digest_salt";
+
+/// Salt length in bytes.
+pub const SALT_LENGTH: usize = 32;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SaltError {
+    /// The credential has no `digest_salt` metadata.
+    MissingSalt,
+    InvalidSalt,
+    Serialization(String),
+}
+
+impl fmt::Display for SaltError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaltError::MissingSalt => write!(f, "Credential has no digest salt"),
+            SaltError::InvalidSalt => {
+                write!(f, "Digest salt must be {} hex-encoded bytes", SALT_LENGTH)
+            }
+            SaltError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+        }
+    }
+}
+
+impl Error for SaltError {}
+
+/// A fresh random salt, hex-encoded.
+pub fn generate_salt() -> String {
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    hex::encode(salt)
+}
+
+/// Hex SHA-256 of the salt followed by the credential's signing payload.
+/// The signature is not covered, so the digest can be published before or
+/// after signing.
+pub fn salted_digest(credential: &Microcredential) -> Result<String, SaltError> {
+    let salt = credential
+        .metadata
+        .get(SALT_METADATA_KEY)
+        .ok_or(SaltError::MissingSalt)?;
+    let mut data = decode_salt(salt)?;
+    data.extend(
+        credential
+            .signing_payload()
+            .map_err(|e| SaltError::Serialization(e.to_string()))?,
+    );
+    Ok(hex::encode(hash_credential(&data)))
+}
+
+/// Whether `digest` is the published digest of `credential`.
+pub fn matches_digest(credential: &Microcredential, digest: &str) -> bool {
+    salted_digest(credential).is_ok_and(|expected| expected.eq_ignore_ascii_case(digest))
+}
+
+fn decode_salt(salt: &str) -> Result<Vec<u8>, SaltError> {
+    if salt.len() != SALT_LENGTH * 2 {
+        return Err(SaltError::InvalidSalt);
+    }
+    hex::decode(salt).map_err(|_| SaltError::InvalidSalt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_salts_are_random_and_decodable() {
+        let (first, second) = (generate_salt(), generate_salt());
+        assert_ne!(first, second);
+        assert_eq!(decode_salt(&first).unwrap().len(), SALT_LENGTH);
+        assert!(matches!(decode_salt("abc"), Err(SaltError::InvalidSalt)));
+        assert!(matches!(
+            decode_salt(&"zz".repeat(SALT_LENGTH)),
+            Err(SaltError::InvalidSalt)
+        ));
+    }
+}
@@ -0,0 +1,63 @@
+use crate::crypto::CryptoKeyPair;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SignerError {
+    Transport(String),
+    Remote { status: u16, message: String },
+    InvalidResponse(String),
+}
+
+impl fmt::Display for SignerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignerError::Transport(msg) => write!(f, "Signer unreachable: {}", msg),
+            SignerError::Remote { status, message } => {
+                write!(f, "Signer returned {}: {}", status, message)
+            }
+            SignerError::InvalidResponse(msg) => write!(f, "Invalid signer response: {}", msg),
+        }
+    }
+}
+
+impl Error for SignerError {}
+
+/// Produces Ed25519 signatures for the issuer. Implementations may keep the
+/// private key out of process entirely (see the `kms` module).
+pub trait Signer: Send + Sync {
+    fn public_key(&self) -> Vec<u8>;
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignerError>;
+
+    /// Exportable secret key, for signers that hold one locally.
+    fn secret_key(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Checks that the key can be used, for health checks. The default only
+    /// checks that a public key is known; remote signers override it to
+    /// fetch the key's metadata from the service, which does not spend a
+    /// signing operation or leave an audit entry for one.
+    fn health_check(&self) -> Result<(), SignerError> {
+        if self.public_key().is_empty() {
+            return Err(SignerError::InvalidResponse("no public key".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl Signer for CryptoKeyPair {
+    fn public_key(&self) -> Vec<u8> {
+        CryptoKeyPair::public_key(self)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignerError> {
+        Ok(CryptoKeyPair::sign(self, message))
+    }
+
+    fn secret_key(&self) -> Option<Vec<u8>> {
+        Some(CryptoKeyPair::secret_key(self))
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+/// Current status of an issued credential.
+///
+/// Suspension is reversible (e.g. while an investigation is pending),
+/// revocation is permanent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CredentialStatus {
+    Active,
+    Suspended {
+        reason: String,
+        suspended_at: DateTime<Utc>,
+    },
+    Revoked {
+        reason: String,
+        revoked_at: DateTime<Utc>,
+    },
+}
+
+impl CredentialStatus {
+    pub fn is_active(&self) -> bool {
+        matches!(self, CredentialStatus::Active)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum StatusError {
+    AlreadyRevoked,
+    AlreadySuspended,
+    NotSuspended,
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatusError::AlreadyRevoked => write!(f, "Credential has already been revoked"),
+            StatusError::AlreadySuspended => write!(f, "Credential is already suspended"),
+            StatusError::NotSuspended => write!(f, "Credential is not suspended"),
+        }
+    }
+}
+
+impl Error for StatusError {}
+
+/// Status entries published by a single issuer. Credentials without an entry
+/// are considered active.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusList {
+    pub issuer_id: Uuid,
+    pub updated_at: DateTime<Utc>,
+    entries: HashMap<Uuid, CredentialStatus>,
+}
+
+impl StatusList {
+    pub fn new(issuer_id: Uuid, now: DateTime<Utc>) -> Self {
+        Self {
+            issuer_id,
+            updated_at: now,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn status_of(&self, credential_id: &Uuid) -> CredentialStatus {
+        self.entries
+            .get(credential_id)
+            .cloned()
+            .unwrap_or(CredentialStatus::Active)
+    }
+
+    pub fn suspend(
+        &mut self,
+        credential_id: Uuid,
+        reason: String,
+        now: DateTime<Utc>,
+    ) -> Result<(), StatusError> {
+        match self.status_of(&credential_id) {
+            CredentialStatus::Revoked { .. } => Err(StatusError::AlreadyRevoked),
+            CredentialStatus::Suspended { .. } => Err(StatusError::AlreadySuspended),
+            CredentialStatus::Active => {
+                self.entries.insert(
+                    credential_id,
+                    CredentialStatus::Suspended {
+                        reason,
+                        suspended_at: now,
+                    },
+                );
+                self.updated_at = now;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn reinstate(
+        &mut self,
+        credential_id: &Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<(), StatusError> {
+        match self.status_of(credential_id) {
+            CredentialStatus::Suspended { .. } => {
+                self.entries.remove(credential_id);
+                self.updated_at = now;
+                Ok(())
+            }
+            CredentialStatus::Revoked { .. } => Err(StatusError::AlreadyRevoked),
+            CredentialStatus::Active => Err(StatusError::NotSuspended),
+        }
+    }
+
+    pub fn revoke(
+        &mut self,
+        credential_id: Uuid,
+        reason: String,
+        now: DateTime<Utc>,
+    ) -> Result<(), StatusError> {
+        if let CredentialStatus::Revoked { .. } = self.status_of(&credential_id) {
+            return Err(StatusError::AlreadyRevoked);
+        }
+        self.entries.insert(
+            credential_id,
+            CredentialStatus::Revoked {
+                reason,
+                revoked_at: now,
+            },
+        );
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&Uuid, &CredentialStatus)> {
+        self.entries.iter()
+    }
+}
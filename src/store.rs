@@ -0,0 +1,182 @@
+use crate::audit::AuditError;
+use crate::Microcredential;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "sled")]
+mod sled;
+
+#[cfg(feature = "redis")]
+pub use self::redis::{RedisStatusCache, RedisStore};
+#[cfg(feature = "sled")]
+pub use self::sled::SledStore;
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum StoreError {
+    Duplicate(Uuid),
+    Backend(String),
+    RollbackFailed(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoreError::Duplicate(id) => write!(f, "Credential {} is already stored", id),
+            StoreError::Backend(msg) => write!(f, "Credential store error: {}", msg),
+            StoreError::RollbackFailed(msg) => write!(f, "Rollback failed: {}", msg),
+        }
+    }
+}
+
+impl Error for StoreError {}
+
+/// Persistence for issued credentials.
+pub trait CredentialStore {
+    fn insert(&mut self, credential: &Microcredential) -> Result<(), StoreError>;
+
+    fn get(&self, id: &Uuid) -> Result<Option<Microcredential>, StoreError>;
+
+    fn remove(&mut self, id: &Uuid) -> Result<Option<Microcredential>, StoreError>;
+
+    fn ids(&self) -> Result<Vec<Uuid>, StoreError>;
+
+    /// Every credential issued to `subject_id`, for duplicate checks at
+    /// issuance. The default scans every id; backends should keep an index.
+    fn subject_credentials(&self, subject_id: &Uuid) -> Result<Vec<Microcredential>, StoreError> {
+        let mut credentials = Vec::new();
+        for id in self.ids()? {
+            match self.get(&id)? {
+                Some(credential) if credential.subject.id == *subject_id => {
+                    credentials.push(credential)
+                }
+                _ => {}
+            }
+        }
+        Ok(credentials)
+    }
+
+    /// Checks that the backend is reachable, for health checks. The default
+    /// looks up the nil id.
+    fn ping(&self) -> Result<(), StoreError> {
+        self.get(&Uuid::nil()).map(|_| ())
+    }
+
+    /// Stores every credential or none of them. The default inserts one by
+    /// one and removes what was already written on failure; backends with
+    /// native transactions should override it.
+    fn insert_all(&mut self, credentials: &[Microcredential]) -> Result<(), StoreError> {
+        for (index, credential) in credentials.iter().enumerate() {
+            if let Err(err) = self.insert(credential) {
+                for written in &credentials[..index] {
+                    self.remove(&written.id).map_err(|rollback| {
+                        StoreError::RollbackFailed(format!("{} (after: {})", rollback, err))
+                    })?;
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keeps credentials ordered by id, so time-ordered ids (ULIDs) list in
+/// issuance order, and indexed by subject.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryStore {
+    credentials: BTreeMap<Uuid, Microcredential>,
+    subjects: HashMap<Uuid, BTreeSet<Uuid>>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.credentials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.credentials.is_empty()
+    }
+}
+
+impl CredentialStore for InMemoryStore {
+    fn insert(&mut self, credential: &Microcredential) -> Result<(), StoreError> {
+        if self.credentials.contains_key(&credential.id) {
+            return Err(StoreError::Duplicate(credential.id));
+        }
+        self.credentials.insert(credential.id, credential.clone());
+        self.subjects
+            .entry(credential.subject.id)
+            .or_default()
+            .insert(credential.id);
+        Ok(())
+    }
+
+    fn get(&self, id: &Uuid) -> Result<Option<Microcredential>, StoreError> {
+        Ok(self.credentials.get(id).cloned())
+    }
+
+    fn remove(&mut self, id: &Uuid) -> Result<Option<Microcredential>, StoreError> {
+        let removed = self.credentials.remove(id);
+        if let Some(credential) = &removed {
+            if let Some(ids) = self.subjects.get_mut(&credential.subject.id) {
+                ids.remove(id);
+                if ids.is_empty() {
+                    self.subjects.remove(&credential.subject.id);
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    fn ids(&self) -> Result<Vec<Uuid>, StoreError> {
+        Ok(self.credentials.keys().copied().collect())
+    }
+
+    fn subject_credentials(&self, subject_id: &Uuid) -> Result<Vec<Microcredential>, StoreError> {
+        Ok(self
+            .subjects
+            .get(subject_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.credentials.get(id).cloned())
+            .collect())
+    }
+}
+
+/// Why a batch was not issued. Nothing from the batch has been stored.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BatchError {
+    Issuance { index: usize, message: String },
+    Store(StoreError),
+    /// The batch was stored, but recording it in the audit log failed.
+    Audit(AuditError),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::Issuance { index, message } => {
+                write!(f, "Request {} could not be issued: {}", index, message)
+            }
+            BatchError::Store(err) => write!(f, "Batch could not be stored: {}", err),
+            BatchError::Audit(err) => write!(f, "Batch was stored but not audited: {}", err),
+        }
+    }
+}
+
+impl Error for BatchError {}
+
+impl From<StoreError> for BatchError {
+    fn from(err: StoreError) -> Self {
+        BatchError::Store(err)
+    }
+}
@@ -0,0 +1,250 @@
+use super::{CredentialStore, StoreError};
+use crate::status::StatusList;
+use crate::Microcredential;
+use redis::{Commands, Connection, ErrorKind, RedisError};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Credentials as JSON strings under `{prefix}credential:{id}`, shared by
+/// every instance connected to the same Redis, with the ids of each
+/// subject's credentials in the set `{prefix}subject:{subject id}`.
+pub struct RedisStore {
+    connection: Mutex<Connection>,
+    prefix: String,
+}
+
+impl RedisStore {
+    /// Connects to `url` (`redis://host:port/db`). `prefix` namespaces the
+    /// keys, e.g. `microcred:`.
+    pub fn connect(url: &str, prefix: &str) -> Result<Self, StoreError> {
+        Ok(Self {
+            connection: Mutex::new(connect(url)?),
+            prefix: prefix.to_string(),
+        })
+    }
+
+    /// Adds every stored credential to its subject's set, for stores
+    /// written before the subject index existed. Scans the key space.
+    pub fn rebuild_subject_index(&self) -> Result<(), StoreError> {
+        for id in self.ids()? {
+            if let Some(credential) = self.get(&id)? {
+                self.connection()
+                    .sadd::<_, _, ()>(self.subject_key(&credential.subject.id), id.to_string())
+                    .map_err(backend)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn key(&self, id: &Uuid) -> String {
+        format!("{}credential:{}", self.prefix, id)
+    }
+
+    fn subject_key(&self, subject_id: &Uuid) -> String {
+        format!("{}subject:{}", self.prefix, subject_id)
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CredentialStore for RedisStore {
+    /// Writes the credential and its subject index entry in one
+    /// transaction, retried if the key changes while it is checked.
+    fn insert(&mut self, credential: &Microcredential) -> Result<(), StoreError> {
+        let value = to_json(credential)?;
+        let key = self.key(&credential.id);
+        let subject_key = self.subject_key(&credential.subject.id);
+        let inserted = redis::transaction(&mut *self.connection(), &[&key], |connection, pipe| {
+            if connection.exists(&key)? {
+                return Ok(Some(false));
+            }
+            pipe.set(&key, &value)
+                .ignore()
+                .sadd(&subject_key, credential.id.to_string())
+                .ignore()
+                .query::<Option<()>>(connection)
+                .map(|committed| committed.map(|()| true))
+        })
+        .map_err(backend)?;
+        if !inserted {
+            return Err(StoreError::Duplicate(credential.id));
+        }
+        Ok(())
+    }
+
+    fn get(&self, id: &Uuid) -> Result<Option<Microcredential>, StoreError> {
+        let value: Option<String> = self.connection().get(self.key(id)).map_err(backend)?;
+        value.as_deref().map(from_json).transpose()
+    }
+
+    fn remove(&mut self, id: &Uuid) -> Result<Option<Microcredential>, StoreError> {
+        let value: Option<String> = self.connection().get_del(self.key(id)).map_err(backend)?;
+        let removed = value.as_deref().map(from_json).transpose()?;
+        if let Some(credential) = &removed {
+            self.connection()
+                .srem::<_, _, ()>(self.subject_key(&credential.subject.id), id.to_string())
+                .map_err(backend)?;
+        }
+        Ok(removed)
+    }
+
+    /// Scans the key space, so this is meant for maintenance jobs rather
+    /// than request paths.
+    fn ids(&self) -> Result<Vec<Uuid>, StoreError> {
+        let prefix = self.key(&Uuid::nil());
+        let prefix = &prefix[..prefix.len() - Uuid::nil().to_string().len()];
+        let mut connection = self.connection();
+        let keys: Vec<String> = connection
+            .scan_match(format!("{}*", prefix))
+            .map_err(backend)?
+            .collect();
+        let mut ids: Vec<Uuid> = keys
+            .iter()
+            .filter_map(|key| Uuid::parse_str(key.strip_prefix(prefix)?).ok())
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn subject_credentials(&self, subject_id: &Uuid) -> Result<Vec<Microcredential>, StoreError> {
+        let members: Vec<String> = self
+            .connection()
+            .smembers(self.subject_key(subject_id))
+            .map_err(backend)?;
+        let mut credentials = Vec::new();
+        for id in members.iter().filter_map(|id| Uuid::parse_str(id).ok()) {
+            credentials.extend(self.get(&id)?);
+        }
+        Ok(credentials)
+    }
+
+    /// Writes the batch and its subject index entries in one transaction,
+    /// which stores every credential or, if any id exists, none. A batch
+    /// repeating an id is refused before anything is sent.
+    fn insert_all(&mut self, credentials: &[Microcredential]) -> Result<(), StoreError> {
+        if credentials.is_empty() {
+            return Ok(());
+        }
+        let mut seen = HashSet::new();
+        if let Some(repeated) = credentials.iter().find(|c| !seen.insert(c.id)) {
+            return Err(StoreError::Duplicate(repeated.id));
+        }
+        let items = credentials
+            .iter()
+            .map(|credential| Ok((self.key(&credential.id), to_json(credential)?)))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        let keys: Vec<&String> = items.iter().map(|(key, _)| key).collect();
+        let existing = redis::transaction(&mut *self.connection(), &keys, |connection, pipe| {
+            for (credential, key) in credentials.iter().zip(&keys) {
+                if connection.exists(*key)? {
+                    return Ok(Some(Some(credential.id)));
+                }
+            }
+            pipe.mset(&items).ignore();
+            for credential in credentials {
+                pipe.sadd(
+                    self.subject_key(&credential.subject.id),
+                    credential.id.to_string(),
+                )
+                .ignore();
+            }
+            pipe.query::<Option<()>>(connection)
+                .map(|committed| committed.map(|()| None))
+        })
+        .map_err(backend)?;
+        match existing {
+            Some(id) => Err(StoreError::Duplicate(id)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Status lists cached in Redis under `{prefix}status:{issuer id}`, so
+/// verifier instances share the latest revocations. Verifiers load them
+/// with `CredentialVerifier::update_status_list`.
+pub struct RedisStatusCache {
+    connection: Mutex<Connection>,
+    prefix: String,
+    /// Entries expire after this many seconds, forcing a refresh from the
+    /// issuer. `None` keeps them until replaced.
+    ttl_seconds: Option<u64>,
+}
+
+impl RedisStatusCache {
+    pub fn connect(url: &str, prefix: &str, ttl_seconds: Option<u64>) -> Result<Self, StoreError> {
+        Ok(Self {
+            connection: Mutex::new(connect(url)?),
+            prefix: prefix.to_string(),
+            ttl_seconds,
+        })
+    }
+
+    fn key(&self, issuer_id: &Uuid) -> String {
+        format!("{}status:{}", self.prefix, issuer_id)
+    }
+
+    /// Caches `list` unless a list updated later is already cached. The
+    /// comparison and write are one transaction, retried if another
+    /// instance writes the list in between.
+    pub fn put(&self, list: &StatusList) -> Result<(), StoreError> {
+        let value = serde_json::to_string(list).map_err(|e| StoreError::Backend(e.to_string()))?;
+        let mut connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let key = self.key(&list.issuer_id);
+        redis::transaction(&mut *connection, &[&key], |connection, pipe| {
+            let cached: Option<String> = connection.get(&key)?;
+            if let Some(cached) = cached {
+                let cached: StatusList = serde_json::from_str(&cached).map_err(|e| {
+                    RedisError::from((ErrorKind::TypeError, "invalid status list", e.to_string()))
+                })?;
+                if cached.updated_at > list.updated_at {
+                    return Ok(Some(()));
+                }
+            }
+            match self.ttl_seconds {
+                Some(ttl) => pipe.set_ex(&key, &value, ttl),
+                None => pipe.set(&key, &value),
+            }
+            .ignore()
+            .query(connection)
+        })
+        .map_err(backend)
+    }
+
+    pub fn get(&self, issuer_id: &Uuid) -> Result<Option<StatusList>, StoreError> {
+        let mut connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let value: Option<String> = connection.get(self.key(issuer_id)).map_err(backend)?;
+        value
+            .map(|value| {
+                serde_json::from_str(&value).map_err(|e| StoreError::Backend(e.to_string()))
+            })
+            .transpose()
+    }
+
+    pub fn invalidate(&self, issuer_id: &Uuid) -> Result<(), StoreError> {
+        let mut connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        connection
+            .del::<_, ()>(self.key(issuer_id))
+            .map_err(backend)
+    }
+}
+
+fn connect(url: &str) -> Result<Connection, StoreError> {
+    redis::Client::open(url)
+        .and_then(|client| client.get_connection())
+        .map_err(backend)
+}
+
+fn backend(e: redis::RedisError) -> StoreError {
+    StoreError::Backend(e.to_string())
+}
+
+fn to_json(credential: &Microcredential) -> Result<String, StoreError> {
+    serde_json::to_string(credential).map_err(|e| StoreError::Backend(e.to_string()))
+}
+
+fn from_json(value: &str) -> Result<Microcredential, StoreError> {
+    serde_json::from_str(value).map_err(|e| StoreError::Backend(e.to_string()))
+}
@@ -0,0 +1,241 @@
+use super::{CredentialStore, StoreError};
+use crate::Microcredential;
+use sled::transaction::{ConflictableTransactionError, TransactionError};
+use sled::Transactional;
+use std::path::Path;
+use uuid::Uuid;
+
+/// Credentials in an embedded sled database, keyed by the id's bytes so
+/// they list in id order like `InMemoryStore`, with a `subjects` tree
+/// keyed by subject id followed by credential id. Every write is flushed to
+/// disk before it returns.
+pub struct SledStore {
+    tree: sled::Tree,
+    subjects: sled::Tree,
+}
+
+impl SledStore {
+    /// Opens (or creates) the database in the directory `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let db = sled::open(path).map_err(backend)?;
+        Self::from_db(&db)
+    }
+
+    /// Uses the `credentials` and `subjects` trees of an already open
+    /// database, for applications that keep their own data in the same
+    /// file. The subject index is built on first open of databases written
+    /// before it existed.
+    pub fn from_db(db: &sled::Db) -> Result<Self, StoreError> {
+        let store = Self {
+            tree: db.open_tree("credentials").map_err(backend)?,
+            subjects: db.open_tree("subjects").map_err(backend)?,
+        };
+        if store.subjects.is_empty() && !store.tree.is_empty() {
+            for entry in store.tree.iter() {
+                let (_, value) = entry.map_err(backend)?;
+                let credential = from_json(&value)?;
+                store
+                    .subjects
+                    .insert(subject_key(&credential), &[])
+                    .map_err(backend)?;
+            }
+            store.flush()?;
+        }
+        Ok(store)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    fn flush(&self) -> Result<(), StoreError> {
+        self.subjects.flush().map_err(backend)?;
+        self.tree.flush().map(|_| ()).map_err(backend)
+    }
+}
+
+impl CredentialStore for SledStore {
+    fn insert(&mut self, credential: &Microcredential) -> Result<(), StoreError> {
+        let value = to_json(credential)?;
+        self.tree
+            .compare_and_swap(credential.id.as_bytes(), None::<&[u8]>, Some(value))
+            .map_err(backend)?
+            .map_err(|_| StoreError::Duplicate(credential.id))?;
+        self.subjects
+            .insert(subject_key(credential), &[])
+            .map_err(backend)?;
+        self.flush()
+    }
+
+    fn get(&self, id: &Uuid) -> Result<Option<Microcredential>, StoreError> {
+        let value = self.tree.get(id.as_bytes()).map_err(backend)?;
+        value.as_deref().map(from_json).transpose()
+    }
+
+    fn remove(&mut self, id: &Uuid) -> Result<Option<Microcredential>, StoreError> {
+        let removed = self
+            .tree
+            .remove(id.as_bytes())
+            .map_err(backend)?
+            .as_deref()
+            .map(from_json)
+            .transpose()?;
+        if let Some(credential) = &removed {
+            self.subjects
+                .remove(subject_key(credential))
+                .map_err(backend)?;
+        }
+        self.flush()?;
+        Ok(removed)
+    }
+
+    fn ids(&self) -> Result<Vec<Uuid>, StoreError> {
+        self.tree
+            .iter()
+            .keys()
+            .map(|key| {
+                let key = key.map_err(backend)?;
+                Uuid::from_slice(&key).map_err(|e| StoreError::Backend(e.to_string()))
+            })
+            .collect()
+    }
+
+    fn subject_credentials(&self, subject_id: &Uuid) -> Result<Vec<Microcredential>, StoreError> {
+        let mut credentials = Vec::new();
+        for key in self.subjects.scan_prefix(subject_id.as_bytes()).keys() {
+            let key = key.map_err(backend)?;
+            if let Some(value) = self.tree.get(&key[16..]).map_err(backend)? {
+                credentials.push(from_json(&value)?);
+            }
+        }
+        Ok(credentials)
+    }
+
+    /// Writes the batch and its index entries in one sled transaction.
+    fn insert_all(&mut self, credentials: &[Microcredential]) -> Result<(), StoreError> {
+        let values = credentials
+            .iter()
+            .map(to_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        (&self.tree, &self.subjects)
+            .transaction(|(tx, subjects)| {
+                for (credential, value) in credentials.iter().zip(&values) {
+                    if tx.get(credential.id.as_bytes())?.is_some() {
+                        return Err(ConflictableTransactionError::Abort(StoreError::Duplicate(
+                            credential.id,
+                        )));
+                    }
+                    tx.insert(credential.id.as_bytes(), value.as_slice())?;
+                    subjects.insert(subject_key(credential), &[])?;
+                }
+                Ok(())
+            })
+            .map_err(|e| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(e) => backend(e),
+            })?;
+        self.flush()
+    }
+}
+
+/// Subject id followed by credential id, so a prefix scan lists a
+/// subject's credentials.
+fn subject_key(credential: &Microcredential) -> Vec<u8> {
+    [credential.subject.id.as_bytes().as_slice(), credential.id.as_bytes().as_slice()].concat()
+}
+
+fn backend(e: sled::Error) -> StoreError {
+    StoreError::Backend(e.to_string())
+}
+
+fn to_json(credential: &Microcredential) -> Result<Vec<u8>, StoreError> {
+    serde_json::to_vec(credential).map_err(|e| StoreError::Backend(e.to_string()))
+}
+
+fn from_json(value: &[u8]) -> Result<Microcredential, StoreError> {
+    serde_json::from_slice(value).map_err(|e| StoreError::Backend(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuer::CredentialIssuer;
+    use crate::{Skill, SkillLevel, Subject};
+    use std::time::{Duration, Instant};
+
+    /// sled releases its file lock only once background writes of a dropped
+    /// handle have finished, so a reopen right after a drop waits for it.
+    fn reopen(path: &Path) -> SledStore {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            match SledStore::open(path) {
+                Ok(store) => return store,
+                Err(e) if Instant::now() > deadline => panic!("reopening {:?}: {}", path, e),
+                Err(_) => std::thread::sleep(Duration::from_millis(20)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_sled_store_batches_and_reopens() {
+        let path = std::env::temp_dir().join(format!("microcred-sled-{}", Uuid::new_v4()));
+        let issuer = CredentialIssuer::new(
+            "Test College".to_string(),
+            "https://college.example".to_string(),
+        );
+        let credentials: Vec<Microcredential> = (0..3)
+            .map(|index| {
+                issuer
+                    .issue_credential(
+                        Subject {
+                            id: Uuid::new_v4(),
+                            name: format!("Student {}", index),
+                            email: format!("student{}@college.example", index),
+                            unknown_fields: Default::default(),
+                        },
+                        Skill {
+                            id: "welding".to_string(),
+                            name: "Welding".to_string(),
+                            description: "MIG welding".to_string(),
+                            level: SkillLevel::Intermediate,
+                            unknown_fields: Default::default(),
+                        },
+                        Vec::new(),
+                        None,
+                    )
+                    .unwrap()
+            })
+            .collect();
+
+        {
+            let mut store = SledStore::open(&path).unwrap();
+            store.insert(&credentials[1]).unwrap();
+            assert_eq!(
+                store.insert_all(&credentials),
+                Err(StoreError::Duplicate(credentials[1].id))
+            );
+            assert_eq!(store.len(), 1);
+            store.remove(&credentials[1].id).unwrap();
+            store.insert_all(&credentials).unwrap();
+        }
+
+        let store = reopen(&path);
+        let mut ids: Vec<Uuid> = credentials.iter().map(|credential| credential.id).collect();
+        ids.sort();
+        assert_eq!(store.ids().unwrap(), ids);
+        assert_eq!(
+            store.get(&credentials[2].id).unwrap().unwrap().subject.name,
+            "Student 2"
+        );
+        let subject = &credentials[2].subject.id;
+        let held = store.subject_credentials(subject).unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].id, credentials[2].id);
+        drop(store);
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
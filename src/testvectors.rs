@@ -0,0 +1,276 @@
+use crate::clock::ManualClock;
+use crate::crypto::CryptoKeyPair;
+use crate::ids::IdStrategy;
+use crate::issuer::CredentialIssuer;
+use crate::proof::{HashAlgorithm, PayloadEncoding};
+use crate::verifier::{CredentialVerifier, VerificationError};
+use crate::{
+    Evidence, EvidenceType, Issuer, IssuerKey, Microcredential, Skill, SkillLevel, Subject,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Secret key of RFC 8032 section 7.1, test 1.
+pub const VECTOR_SECRET_KEY: &str =
+    "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+pub const VECTOR_ISSUED_AT: &str = "2024-01-01T00:00:00Z";
+
+/// A signed credential together with the intermediate bytes a verifier
+/// must reproduce. Byte fields are lowercase hex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestVector {
+    pub name: String,
+    pub public_key: String,
+    pub credential: Microcredential,
+    pub signing_payload: String,
+    pub digest: String,
+    pub signature: String,
+    /// Whether the signature is expected to verify.
+    pub valid: bool,
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum VectorError {
+    InvalidHex(String),
+    Serialization(String),
+    PayloadMismatch,
+    DigestMismatch,
+    SignatureMismatch,
+    /// The signature check disagreed with the vector's `valid` flag.
+    UnexpectedResult {
+        expected: bool,
+        error: Option<VerificationError>,
+    },
+}
+
+impl fmt::Display for VectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VectorError::InvalidHex(field) => write!(f, "Field {} is not valid hex", field),
+            VectorError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+            VectorError::PayloadMismatch => write!(f, "Signing payload differs"),
+            VectorError::DigestMismatch => write!(f, "Digest differs"),
+            VectorError::SignatureMismatch => write!(f, "Signature differs"),
+            VectorError::UnexpectedResult { expected, error } => write!(
+                f,
+                "Expected the credential to be {}, got {}",
+                if *expected { "valid" } else { "invalid" },
+                match error {
+                    Some(error) => error.to_string(),
+                    None => "a valid signature".to_string(),
+                }
+            ),
+        }
+    }
+}
+
+impl Error for VectorError {}
+
+/// Deterministic vectors covering every hash algorithm and payload
+/// encoding, plus a tampered credential that must fail verification.
+pub fn generate() -> Vec<TestVector> {
+    let mut vectors = Vec::new();
+    for hash in [
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha512,
+        HashAlgorithm::Blake3,
+    ] {
+        for payload in [PayloadEncoding::Json, PayloadEncoding::Cbor] {
+            let name = format!("{:?}-{:?}", hash, payload).to_lowercase();
+            vectors.push(vector(&name, sign_sample(hash, payload), true));
+        }
+    }
+
+    let mut tampered = sign_sample(HashAlgorithm::Sha256, PayloadEncoding::Json);
+    tampered.skill.level = SkillLevel::Expert;
+    vectors.push(vector("tampered-skill-level", tampered, false));
+    vectors
+}
+
+/// Checks `vector` against this implementation: the payload, digest and
+/// signature must match byte for byte, and verification must succeed when
+/// `valid` is set and otherwise fail on the signature alone.
+pub fn validate(vector: &TestVector) -> Result<(), VectorError> {
+    let public_key = from_hex(&vector.public_key, "public_key")?;
+    let payload = vector
+        .credential
+        .signing_payload()
+        .map_err(|e| VectorError::Serialization(e.to_string()))?;
+    if payload != from_hex(&vector.signing_payload, "signing_payload")? {
+        return Err(VectorError::PayloadMismatch);
+    }
+    let proof = vector.credential.proof.clone().unwrap_or_default();
+    let digest = proof.hash.digest(&payload);
+    if digest != from_hex(&vector.digest, "digest")? {
+        return Err(VectorError::DigestMismatch);
+    }
+
+    let signature = vector.credential.signature.as_deref().unwrap_or_default();
+    if signature != from_hex(&vector.signature, "signature")? {
+        return Err(VectorError::SignatureMismatch);
+    }
+
+    let issuer = Issuer {
+        public_key: public_key.into(),
+        key_history: Vec::new(),
+        ..vector.credential.issuer.clone()
+    };
+    let clock = Arc::new(ManualClock::new(vector.credential.issued_at));
+    let mut verifier = CredentialVerifier::new();
+    verifier.set_clock(clock);
+    verifier.add_trusted_issuer(issuer);
+    match (vector.valid, verifier.verify_credential(&vector.credential).err()) {
+        (true, None) | (false, Some(VerificationError::InvalidSignature)) => Ok(()),
+        (expected, error) => Err(VectorError::UnexpectedResult { expected, error }),
+    }
+}
+
+fn sign_sample(hash: HashAlgorithm, payload: PayloadEncoding) -> Microcredential {
+    let issued_at = VECTOR_ISSUED_AT.parse::<DateTime<Utc>>().unwrap();
+    let key = CryptoKeyPair::from_secret_key(&from_hex(VECTOR_SECRET_KEY, "").unwrap()).unwrap();
+    let issuer_info = Issuer {
+        id: Uuid::from_u128(0x0190_0000_0000_7000_8000_0000_0000_0001),
+        name: "Test Vector University".to_string(),
+        url: "https://vectors.example.edu".to_string(),
+        public_key: key.public_key().into(),
+        key_history: vec![IssuerKey {
+            public_key: key.public_key().into(),
+            activated_at: issued_at,
+            retired_at: None,
+            endorsement: None,
+        }],
+        unknown_fields: Default::default(),
+    };
+
+    let mut issuer = CredentialIssuer::from_existing(issuer_info, &key.secret_key()).unwrap();
+    issuer.set_clock(Arc::new(ManualClock::new(issued_at)));
+    issuer.set_id_strategy(IdStrategy::content_derived("https://vectors.example.edu"));
+    issuer.set_hash_algorithm(hash);
+    issuer.set_payload_encoding(payload);
+    issuer
+        .issue_credential(
+            Subject {
+                id: Uuid::from_u128(0x0190_0000_0000_7000_8000_0000_0000_0002),
+                name: "Ada Lovelace".to_string(),
+                email: "ada@example.com".to_string(),
+                unknown_fields: Default::default(),
+            },
+            Skill {
+                id: "rust-programming".to_string(),
+                name: "Rust Programming".to_string(),
+                description: "Ownership, traits and async Rust".to_string(),
+                level: SkillLevel::Advanced,
+                unknown_fields: Default::default(),
+            },
+            vec![Evidence {
+                id: Uuid::from_u128(0x0190_0000_0000_7000_8000_0000_0000_0003),
+                name: "Capstone project".to_string(),
+                description: "A verifiable credential library".to_string(),
+                url: "https://example.com/capstone".to_string(),
+                evidence_type: EvidenceType::Project,
+                unknown_fields: Default::default(),
+            }],
+            Some("2029-01-01T00:00:00Z".parse().unwrap()),
+        )
+        .unwrap()
+}
+
+fn vector(name: &str, credential: Microcredential, valid: bool) -> TestVector {
+    let payload = credential.signing_payload().unwrap();
+    let hash = credential.proof.clone().unwrap_or_default().hash;
+    TestVector {
+        name: name.to_string(),
+        public_key: hex::encode(&credential.issuer.public_key),
+        signing_payload: hex::encode(&payload),
+        digest: hex::encode(hash.digest(&payload)),
+        signature: hex::encode(credential.signature.as_deref().unwrap_or_default()),
+        credential,
+        valid,
+    }
+}
+
+fn from_hex(text: &str, field: &str) -> Result<Vec<u8>, VectorError> {
+    hex::decode(text).map_err(|_| VectorError::InvalidHex(field.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors_are_deterministic_and_validate() {
+        let vectors = generate();
+        assert_eq!(vectors.len(), 7);
+        assert_eq!(
+            serde_json::to_string(&vectors).unwrap(),
+            serde_json::to_string(&generate()).unwrap()
+        );
+        assert_eq!(
+            vectors[0].public_key,
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+
+        // Independently checked golden bytes for one vector per encoding.
+        let golden: Vec<serde_json::Value> =
+            serde_json::from_str(include_str!("testvectors/golden.json")).unwrap();
+        for expected in &golden {
+            let vector = vectors
+                .iter()
+                .find(|vector| vector.name == expected["name"])
+                .unwrap();
+            assert_eq!(vector.signing_payload, expected["signing_payload"]);
+            assert_eq!(vector.digest, expected["digest"]);
+            assert_eq!(vector.signature, expected["signature"]);
+        }
+        assert_eq!(golden[0]["name"], "sha256-json");
+        assert_eq!(golden[1]["name"], "sha256-cbor");
+
+        // Round trip through JSON, as an external implementation would.
+        let json = serde_json::to_string_pretty(&vectors).unwrap();
+        let parsed: Vec<TestVector> = serde_json::from_str(&json).unwrap();
+        for vector in &parsed {
+            validate(vector).unwrap();
+        }
+
+        let mut wrong_payload = parsed[0].clone();
+        wrong_payload.signing_payload.replace_range(0..2, "00");
+        assert!(matches!(
+            validate(&wrong_payload),
+            Err(VectorError::PayloadMismatch)
+        ));
+
+        let mut wrong_flag = parsed[6].clone();
+        wrong_flag.valid = true;
+        assert!(matches!(
+            validate(&wrong_flag),
+            Err(VectorError::UnexpectedResult {
+                expected: true,
+                error: Some(VerificationError::InvalidSignature),
+            })
+        ));
+
+        let mut wrong_signature = parsed[0].clone();
+        wrong_signature.signature.replace_range(0..2, "00");
+        assert!(matches!(
+            validate(&wrong_signature),
+            Err(VectorError::SignatureMismatch)
+        ));
+
+        // An invalid vector must fail on its signature, not on anything else.
+        let mut unsigned = parsed[6].clone();
+        unsigned.credential.signature = None;
+        unsigned.signature.clear();
+        assert!(matches!(
+            validate(&unsigned),
+            Err(VectorError::UnexpectedResult {
+                expected: false,
+                error: Some(VerificationError::MissingSignature),
+            })
+        ));
+    }
+}
@@ -0,0 +1,170 @@
+//! Canonical timestamps. Signed bytes include every timestamp in the
+//! credential, so a system that stores or re-serializes them at a different
+//! sub-second precision breaks the signature. Normalizing to a fixed
+//! precision before signing avoids that.
+
+use chrono::{DateTime, Duration, DurationRound, SecondsFormat, Utc};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TimestampError {
+    /// Not an RFC 3339 timestamp.
+    Invalid(String),
+    /// The timestamp has an offset other than UTC.
+    NotUtc(String),
+    /// The timestamp, or the field named, is finer than the policy allows.
+    ExcessPrecision(String),
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimestampError::Invalid(value) => write!(f, "Invalid RFC 3339 timestamp: {}", value),
+            TimestampError::NotUtc(value) => write!(f, "Timestamp is not in UTC: {}", value),
+            TimestampError::ExcessPrecision(value) => {
+                write!(f, "Timestamp exceeds the allowed precision: {}", value)
+            }
+        }
+    }
+}
+
+impl Error for TimestampError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum TimestampPrecision {
+    #[default]
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    /// Keep timestamps as they are.
+    Nanoseconds,
+}
+
+impl TimestampPrecision {
+    fn unit(&self) -> Duration {
+        match self {
+            TimestampPrecision::Seconds => Duration::seconds(1),
+            TimestampPrecision::Milliseconds => Duration::milliseconds(1),
+            TimestampPrecision::Microseconds => Duration::microseconds(1),
+            TimestampPrecision::Nanoseconds => Duration::nanoseconds(1),
+        }
+    }
+
+    fn seconds_format(&self) -> SecondsFormat {
+        match self {
+            TimestampPrecision::Seconds => SecondsFormat::Secs,
+            TimestampPrecision::Milliseconds => SecondsFormat::Millis,
+            TimestampPrecision::Microseconds => SecondsFormat::Micros,
+            TimestampPrecision::Nanoseconds => SecondsFormat::AutoSi,
+        }
+    }
+}
+
+/// How timestamps are normalized before signing and checked on input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampPolicy {
+    pub precision: TimestampPrecision,
+    /// `parse` rejects timestamps with an offset other than `Z` or `+00:00`
+    /// instead of converting them.
+    pub require_utc: bool,
+    /// `parse` rejects timestamps finer than `precision` instead of
+    /// truncating them. Off by default, so credentials signed before
+    /// timestamps were normalized still parse; issuers normalize what they
+    /// sign either way.
+    pub reject_excess_precision: bool,
+}
+
+impl Default for TimestampPolicy {
+    fn default() -> Self {
+        Self {
+            precision: TimestampPrecision::Seconds,
+            require_utc: true,
+            reject_excess_precision: false,
+        }
+    }
+}
+
+impl TimestampPolicy {
+    /// `at` truncated to the policy's precision.
+    pub fn normalize(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        at.duration_trunc(self.precision.unit()).unwrap_or(at)
+    }
+
+    /// `at` rounded up to the policy's precision.
+    pub(crate) fn normalize_up(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let truncated = self.normalize(at);
+        if truncated == at {
+            at
+        } else {
+            truncated + self.precision.unit()
+        }
+    }
+
+    pub fn is_normalized(&self, at: &DateTime<Utc>) -> bool {
+        self.normalize(*at) == *at
+    }
+
+    /// RFC 3339 in UTC (`Z`), with exactly the policy's fractional digits.
+    pub fn format(&self, at: &DateTime<Utc>) -> String {
+        self.normalize(*at)
+            .to_rfc3339_opts(self.precision.seconds_format(), true)
+    }
+
+    pub fn parse(&self, text: &str) -> Result<DateTime<Utc>, TimestampError> {
+        let parsed = DateTime::parse_from_rfc3339(text)
+            .map_err(|_| TimestampError::Invalid(text.to_string()))?;
+        if self.require_utc && parsed.offset().local_minus_utc() != 0 {
+            return Err(TimestampError::NotUtc(text.to_string()));
+        }
+        let at = parsed.with_timezone(&Utc);
+        if self.reject_excess_precision && !self.is_normalized(&at) {
+            return Err(TimestampError::ExcessPrecision(text.to_string()));
+        }
+        Ok(self.normalize(at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_normalizes_and_parses() {
+        let policy = TimestampPolicy::default();
+        let at = policy.parse("2026-03-01T09:30:15Z").unwrap();
+        let precise = at + Duration::nanoseconds(123_456_789);
+        assert_eq!(policy.normalize(precise), at);
+        assert_eq!(policy.normalize_up(precise), at + Duration::seconds(1));
+        assert_eq!(policy.normalize_up(at), at);
+        assert_eq!(policy.format(&precise), "2026-03-01T09:30:15Z");
+
+        assert!(matches!(
+            policy.parse("2026-03-01T10:30:15+01:00"),
+            Err(TimestampError::NotUtc(_))
+        ));
+        assert_eq!(policy.parse("2026-03-01T09:30:15.5Z").unwrap(), at);
+        let exact = TimestampPolicy {
+            reject_excess_precision: true,
+            ..policy
+        };
+        assert!(matches!(
+            exact.parse("2026-03-01T09:30:15.5Z"),
+            Err(TimestampError::ExcessPrecision(_))
+        ));
+        assert!(matches!(
+            policy.parse("1 March 2026"),
+            Err(TimestampError::Invalid(_))
+        ));
+
+        let lenient = TimestampPolicy {
+            precision: TimestampPrecision::Milliseconds,
+            require_utc: false,
+            reject_excess_precision: false,
+        };
+        let parsed = lenient.parse("2026-03-01T10:30:15.123456+01:00").unwrap();
+        assert_eq!(lenient.format(&parsed), "2026-03-01T09:30:15.123Z");
+    }
+}
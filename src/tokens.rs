@@ -0,0 +1,252 @@
+//! Public verification links that do not expose credential ids. Each link
+//! carries a random token; the mapping to the credential lives in a
+//! `TokenStore`, keyed by a hash of the token so a leaked store does not
+//! leak working links. Tokens can expire and be limited to a number of
+//! views.
+
+use crate::crypto::hash_credential;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Random bytes per token; 256 bits cannot be enumerated.
+const TOKEN_BYTES: usize = 32;
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum TokenError {
+    /// Unknown or revoked token. Deliberately says nothing more.
+    NotFound,
+    Expired(DateTime<Utc>),
+    ViewLimitReached,
+    Store(String),
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenError::NotFound => write!(f, "Unknown verification token"),
+            TokenError::Expired(expires_at) => {
+                write!(
+                    f,
+                    "Verification token expired at {}",
+                    expires_at.to_rfc3339()
+                )
+            }
+            TokenError::ViewLimitReached => write!(f, "Verification token has no views left"),
+            TokenError::Store(msg) => write!(f, "Token store error: {}", msg),
+        }
+    }
+}
+
+impl Error for TokenError {}
+
+/// What a token grants access to, as kept by the `TokenStore`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenRecord {
+    pub credential_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_views: Option<u32>,
+    pub views: u32,
+}
+
+/// Persistence for token records. Keys are token hashes, never the tokens.
+pub trait TokenStore: Send + Sync {
+    fn insert(&self, key: &str, record: TokenRecord) -> Result<(), TokenError>;
+
+    fn get(&self, key: &str) -> Result<Option<TokenRecord>, TokenError>;
+
+    /// Increments the view count and returns the new count, or `None` for
+    /// unknown keys. Must be atomic so concurrent views cannot exceed a
+    /// limit.
+    fn record_view(&self, key: &str) -> Result<Option<u32>, TokenError>;
+
+    fn remove(&self, key: &str) -> Result<bool, TokenError>;
+
+    /// Removes every token for `credential_id`, e.g. when it is revoked.
+    fn remove_credential(&self, credential_id: &Uuid) -> Result<usize, TokenError>;
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryTokenStore {
+    records: Mutex<HashMap<String, TokenRecord>>,
+}
+
+impl InMemoryTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn records(&self) -> std::sync::MutexGuard<'_, HashMap<String, TokenRecord>> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl TokenStore for InMemoryTokenStore {
+    fn insert(&self, key: &str, record: TokenRecord) -> Result<(), TokenError> {
+        self.records().insert(key.to_string(), record);
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<TokenRecord>, TokenError> {
+        Ok(self.records().get(key).cloned())
+    }
+
+    fn record_view(&self, key: &str) -> Result<Option<u32>, TokenError> {
+        Ok(self.records().get_mut(key).map(|record| {
+            record.views += 1;
+            record.views
+        }))
+    }
+
+    fn remove(&self, key: &str) -> Result<bool, TokenError> {
+        Ok(self.records().remove(key).is_some())
+    }
+
+    fn remove_credential(&self, credential_id: &Uuid) -> Result<usize, TokenError> {
+        let mut records = self.records();
+        let before = records.len();
+        records.retain(|_, record| record.credential_id != *credential_id);
+        Ok(before - records.len())
+    }
+}
+
+/// Limits on a new token.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenOptions {
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_views: Option<u32>,
+}
+
+/// A newly created link. The token is only available here; the store keeps
+/// its hash.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationLink {
+    pub token: String,
+    pub url: String,
+    pub record: TokenRecord,
+}
+
+/// Creates and resolves links of the form `{base_url}/verify/{token}`.
+#[derive(Clone)]
+pub struct VerificationLinks {
+    base_url: String,
+    store: Arc<dyn TokenStore>,
+}
+
+impl VerificationLinks {
+    pub fn new(base_url: &str, store: Arc<dyn TokenStore>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            store,
+        }
+    }
+
+    pub fn create(
+        &self,
+        credential_id: Uuid,
+        options: &TokenOptions,
+        now: DateTime<Utc>,
+    ) -> Result<VerificationLink, TokenError> {
+        let mut bytes = [0u8; TOKEN_BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+        let record = TokenRecord {
+            credential_id,
+            created_at: now,
+            expires_at: options.expires_at,
+            max_views: options.max_views,
+            views: 0,
+        };
+        self.store.insert(&token_key(&token), record.clone())?;
+        Ok(VerificationLink {
+            url: format!("{}/verify/{}", self.base_url, token),
+            token,
+            record,
+        })
+    }
+
+    /// The credential `token` stands for, counting one view.
+    pub fn resolve(&self, token: &str, now: DateTime<Utc>) -> Result<Uuid, TokenError> {
+        let key = token_key(token);
+        let record = self.store.get(&key)?.ok_or(TokenError::NotFound)?;
+        if let Some(expires_at) = record.expires_at.filter(|at| now > *at) {
+            return Err(TokenError::Expired(expires_at));
+        }
+        let views = self.store.record_view(&key)?.ok_or(TokenError::NotFound)?;
+        if record.max_views.is_some_and(|max_views| views > max_views) {
+            return Err(TokenError::ViewLimitReached);
+        }
+        Ok(record.credential_id)
+    }
+
+    /// Invalidates one link. Returns false for unknown tokens.
+    pub fn revoke(&self, token: &str) -> Result<bool, TokenError> {
+        self.store.remove(&token_key(token))
+    }
+
+    /// Invalidates every link to `credential_id`.
+    pub fn revoke_credential(&self, credential_id: &Uuid) -> Result<usize, TokenError> {
+        self.store.remove_credential(credential_id)
+    }
+}
+
+fn token_key(token: &str) -> String {
+    hex::encode(hash_credential(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_links_expire_and_run_out_of_views() {
+        let store = Arc::new(InMemoryTokenStore::new());
+        let links = VerificationLinks::new("https://verify.test.edu/", store.clone());
+        let now = Utc::now();
+        let credential_id = Uuid::new_v4();
+
+        let link = links
+            .create(
+                credential_id,
+                &TokenOptions {
+                    expires_at: Some(now + Duration::days(7)),
+                    max_views: Some(2),
+                },
+                now,
+            )
+            .unwrap();
+        assert!(link.url.starts_with("https://verify.test.edu/verify/"));
+        assert!(!link.url.contains(&credential_id.to_string()));
+        assert_eq!(link.token.len(), 43);
+        assert!(store.get(&link.token).unwrap().is_none());
+
+        assert_eq!(links.resolve(&link.token, now), Ok(credential_id));
+        assert_eq!(links.resolve(&link.token, now), Ok(credential_id));
+        assert_eq!(
+            links.resolve(&link.token, now),
+            Err(TokenError::ViewLimitReached)
+        );
+
+        let open = links
+            .create(credential_id, &TokenOptions::default(), now)
+            .unwrap();
+        assert_ne!(open.token, link.token);
+        assert!(matches!(
+            links.resolve(&link.token, now + Duration::days(8)),
+            Err(TokenError::Expired(_))
+        ));
+        assert_eq!(links.revoke_credential(&credential_id), Ok(2));
+        assert_eq!(links.resolve(&open.token, now), Err(TokenError::NotFound));
+    }
+}
@@ -0,0 +1,297 @@
+use crate::attachment::AttachmentPolicy;
+use crate::catalog::SkillCatalog;
+use crate::evidence::EvidenceTypeRegistry;
+use crate::levels::LevelScale;
+use crate::{Evidence, Skill, Subject};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct ValidationRules {
+    pub max_name_length: usize,
+    pub max_description_length: usize,
+    pub min_evidence: usize,
+    pub max_evidence: usize,
+    pub evidence_types: EvidenceTypeRegistry,
+    pub attachments: AttachmentPolicy,
+    /// When set, skills must match the catalog's current definitions.
+    pub skill_catalog: Option<SkillCatalog>,
+    /// Scales skill levels may use; a level's rank and label must be on
+    /// its scale.
+    pub level_scales: Vec<LevelScale>,
+}
+
+impl Default for ValidationRules {
+    fn default() -> Self {
+        Self {
+            max_name_length: 200,
+            max_description_length: 2000,
+            min_evidence: 0,
+            max_evidence: 50,
+            evidence_types: EvidenceTypeRegistry::default(),
+            attachments: AttachmentPolicy::default(),
+            skill_catalog: None,
+            level_scales: LevelScale::builtin(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub field: String,
+    pub message: String,
+}
+
+/// Every problem found in the input, so callers can fix them all at once
+/// instead of one per attempt.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn add_issue(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ValidationIssue {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationReport> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Validation failed")?;
+        for (index, issue) in self.issues.iter().enumerate() {
+            let separator = if index == 0 { ": " } else { "; " };
+            write!(f, "{}{} {}", separator, issue.field, issue.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ValidationReport {}
+
+impl ValidationRules {
+    pub fn validate(
+        &self,
+        subject: &Subject,
+        skill: &Skill,
+        evidence: &[Evidence],
+    ) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        self.validate_subject(subject, &mut report);
+        self.validate_skill(skill, &mut report);
+        self.validate_evidence(evidence, &mut report);
+        report
+    }
+
+    pub fn validate_subject(&self, subject: &Subject, report: &mut ValidationReport) {
+        self.check_text("subject.name", &subject.name, self.max_name_length, report);
+        if !is_valid_email(&subject.email) {
+            report.add_issue("subject.email", "is not a valid email address");
+        }
+    }
+
+    pub fn validate_skill(&self, skill: &Skill, report: &mut ValidationReport) {
+        self.check_text("skill.id", &skill.id, self.max_name_length, report);
+        self.check_text("skill.name", &skill.name, self.max_name_length, report);
+        if skill.description.chars().count() > self.max_description_length {
+            report.add_issue(
+                "skill.description",
+                format!("exceeds {} characters", self.max_description_length),
+            );
+        }
+        let level = &skill.level;
+        match self
+            .level_scales
+            .iter()
+            .find(|scale| scale.id == level.scale_id())
+        {
+            None => report.add_issue(
+                "skill.level",
+                format!("uses unknown scale {}", level.scale_id()),
+            ),
+            Some(scale) if !scale.contains(level) => report.add_issue(
+                "skill.level",
+                format!(
+                    "{} (rank {}) is not a level of {}",
+                    level.label(),
+                    level.rank(),
+                    scale.name
+                ),
+            ),
+            Some(_) => {}
+        }
+        if let Some(catalog) = &self.skill_catalog {
+            catalog.validate(skill, report);
+        }
+    }
+
+    pub fn validate_evidence(&self, evidence: &[Evidence], report: &mut ValidationReport) {
+        if evidence.len() < self.min_evidence {
+            report.add_issue(
+                "evidence",
+                format!("requires at least {} entries", self.min_evidence),
+            );
+        }
+        if evidence.len() > self.max_evidence {
+            report.add_issue(
+                "evidence",
+                format!("allows at most {} entries", self.max_evidence),
+            );
+        }
+        for (index, item) in evidence.iter().enumerate() {
+            let field = format!("evidence[{}]", index);
+            self.check_text(
+                &format!("{}.name", field),
+                &item.name,
+                self.max_name_length,
+                report,
+            );
+            if item.description.chars().count() > self.max_description_length {
+                report.add_issue(
+                    format!("{}.description", field),
+                    format!("exceeds {} characters", self.max_description_length),
+                );
+            }
+            if !is_valid_url(&item.url) {
+                report.add_issue(format!("{}.url", field), "is not a valid http(s) URL");
+            }
+            self.evidence_types.validate(&field, item, report);
+        }
+    }
+
+    fn check_text(
+        &self,
+        field: &str,
+        value: &str,
+        max_length: usize,
+        report: &mut ValidationReport,
+    ) {
+        if value.trim().is_empty() {
+            report.add_issue(field, "must not be empty");
+        } else if value.chars().count() > max_length {
+            report.add_issue(field, format!("exceeds {} characters", max_length));
+        }
+    }
+}
+
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !email.chars().any(char::is_whitespace)
+        && domain.contains('.')
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+pub fn is_valid_url(url: &str) -> bool {
+    let Some(rest) = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    !host.is_empty() && !url.chars().any(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::CredentialBuilder;
+    use crate::SkillLevel;
+
+    #[test]
+    fn test_email_and_url_checks() {
+        assert!(is_valid_email("alice@example.com"));
+        assert!(!is_valid_email("alice@example"));
+        assert!(!is_valid_email("alice example.com"));
+        assert!(!is_valid_email("@example.com"));
+
+        assert!(is_valid_url("https://example.com/evidence"));
+        assert!(is_valid_url("http://localhost:8080"));
+        assert!(!is_valid_url("ftp://example.com"));
+        assert!(!is_valid_url("https:///missing-host"));
+    }
+
+    #[test]
+    fn test_builder_reports_every_issue() {
+        let report = CredentialBuilder::new()
+            .skill(Skill {
+                id: "rust".to_string(),
+                name: " ".to_string(),
+                description: "Rust".to_string(),
+                level: SkillLevel::Beginner,
+                unknown_fields: Default::default(),
+            })
+            .evidence(Evidence {
+                id: uuid::Uuid::new_v4(),
+                name: "Project".to_string(),
+                description: "A project".to_string(),
+                url: "not a url".to_string(),
+                evidence_type: crate::EvidenceType::Project,
+                unknown_fields: Default::default(),
+            })
+            .build()
+            .unwrap_err();
+
+        let fields: Vec<&str> = report.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, vec!["subject", "skill.name", "evidence[0].url"]);
+    }
+
+    #[test]
+    fn test_scaled_levels_must_be_on_their_scale() {
+        let rules = ValidationRules::default();
+        let skill = |level| Skill {
+            id: "rust".to_string(),
+            name: "Rust".to_string(),
+            description: "Rust".to_string(),
+            level,
+            unknown_fields: Default::default(),
+        };
+        let mut report = ValidationReport::default();
+        rules.validate_skill(
+            &skill(LevelScale::eqf().level("EQF 4").unwrap()),
+            &mut report,
+        );
+        assert!(report.is_valid());
+
+        for level in [
+            SkillLevel::Scaled {
+                scale: "eqf".to_string(),
+                rank: 12,
+                label: "EQF 4".to_string(),
+            },
+            SkillLevel::Scaled {
+                scale: "eqf".to_string(),
+                rank: 4,
+                label: "EQF 5".to_string(),
+            },
+            SkillLevel::Scaled {
+                scale: "house".to_string(),
+                rank: 1,
+                label: "Novice".to_string(),
+            },
+        ] {
+            let mut report = ValidationReport::default();
+            rules.validate_skill(&skill(level), &mut report);
+            assert_eq!(report.issues.len(), 1);
+            assert_eq!(report.issues[0].field, "skill.level");
+        }
+    }
+}
@@ -1,16 +1,55 @@
+use crate::attestation::{self, AttestationError, VerificationAttestation};
+use crate::blockcerts::{self, AnchorLookup, BlockcertsError, MerkleReceipt};
+use crate::challenge::possession_message;
+use crate::clock::{Clock, SystemClock};
 use crate::crypto::{hash_credential, verify_signature};
-use crate::{Issuer, Microcredential};
-use serde_json;
+use crate::events::EventHandler;
+use crate::health::{HealthCheck, HealthReport, HealthStatus, HealthThresholds};
+use crate::hierarchy::{build_path, check_constraints, url_host, IssuerCertificate, PathError};
+use crate::ld::context::{ContextLoader, OfflineContextLoader};
+use crate::ld::{self, LdError};
+use crate::levels::LevelScale;
+use crate::offline::{OfflinePack, OfflinePackError};
+use crate::presentation::{CredentialCheck, Presentation, PresentationReport};
+use crate::profile::IssuerProfile;
+use crate::proof::{ProofPurpose, SignatureSuite};
+use crate::signer::Signer;
+use crate::status::{CredentialStatus, StatusList};
+use crate::x509::{self, Certificate};
+use crate::{Issuer, Microcredential, SkillLevel};
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
+use std::ops::BitOr;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum VerificationError {
     SerializationError(String),
     InvalidSignature,
     ExpiredCredential,
     MissingSignature,
     TrustedIssuerNotFound,
+    NoActiveIssuerKey,
+    InsufficientSkillLevel,
+    /// The skill level's rank or label is not on its scale.
+    InvalidSkillLevel(String),
+    MissingHolderBinding,
+    HolderBindingMismatch,
+    SuspendedCredential(String),
+    RevokedCredential(String),
+    CertificateConstraintViolation(String),
+    UnsupportedProof(String),
+    InvalidCertificateChain(String),
+    /// The credential is not valid before this time.
+    NotYetValid(DateTime<Utc>),
+    /// The offline pack providing the trust data expired at this time.
+    OfflinePackExpired(DateTime<Utc>),
 }
 
 impl fmt::Display for VerificationError {
@@ -25,70 +64,1019 @@ impl fmt::Display for VerificationError {
             VerificationError::TrustedIssuerNotFound => {
                 write!(f, "Issuer is not in the trusted list")
             }
+            VerificationError::NoActiveIssuerKey => {
+                write!(f, "No issuer key was active at the time of issuance")
+            }
+            VerificationError::InsufficientSkillLevel => {
+                write!(f, "Skill level does not meet the required minimum")
+            }
+            VerificationError::InvalidSkillLevel(level) => {
+                write!(f, "Skill level {} is not on its scale", level)
+            }
+            VerificationError::MissingHolderBinding => {
+                write!(f, "Credential is not bound to a holder key")
+            }
+            VerificationError::HolderBindingMismatch => {
+                write!(f, "Presenter does not hold the credential's bound key")
+            }
+            VerificationError::SuspendedCredential(reason) => {
+                write!(f, "Credential is suspended: {}", reason)
+            }
+            VerificationError::RevokedCredential(reason) => {
+                write!(f, "Credential has been revoked: {}", reason)
+            }
+            VerificationError::UnsupportedProof(msg) => write!(f, "Unsupported proof: {}", msg),
+            VerificationError::CertificateConstraintViolation(msg) => {
+                write!(f, "Issuer certificate constraint violated: {}", msg)
+            }
+            VerificationError::InvalidCertificateChain(msg) => {
+                write!(f, "Invalid X.509 certificate chain: {}", msg)
+            }
+            VerificationError::NotYetValid(valid_from) => {
+                write!(
+                    f,
+                    "Credential is not valid until {}",
+                    valid_from.to_rfc3339()
+                )
+            }
+            VerificationError::OfflinePackExpired(valid_until) => write!(
+                f,
+                "Offline verification data expired at {}",
+                valid_until.to_rfc3339()
+            ),
         }
     }
 }
 
 impl Error for VerificationError {}
 
+/// Conditions a credential was accepted despite.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum VerificationWarning {
+    /// Expired, but within `VerifierPolicy::expiry_grace`.
+    ExpiredWithinGrace { expired_at: DateTime<Utc> },
+    /// Trust and revocation data come from an offline pack that is past its
+    /// validity, within `VerifierPolicy::offline_pack_grace`; revocations
+    /// since then are not reflected.
+    StaleOfflinePack { valid_until: DateTime<Utc> },
+}
+
+impl fmt::Display for VerificationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerificationWarning::ExpiredWithinGrace { expired_at } => write!(
+                f,
+                "Credential expired at {} and is accepted within the grace period",
+                expired_at.to_rfc3339()
+            ),
+            VerificationWarning::StaleOfflinePack { valid_until } => write!(
+                f,
+                "Offline verification data expired at {}",
+                valid_until.to_rfc3339()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VerifierPolicy {
+    /// Credentials must be at or above this level on the same scale.
+    pub min_skill_level: Option<SkillLevel>,
+    /// Scales known besides the built-in ones. Levels on a known scale must
+    /// match it; levels on unknown scales are not checked.
+    pub level_scales: Vec<LevelScale>,
+    /// Identifier of this relying party. When set, presentations created for
+    /// a different audience are rejected.
+    pub audience: Option<String>,
+    pub domain: Option<String>,
+    /// Reject presented credentials that are not bound to a holder key.
+    pub require_holder_binding: bool,
+    /// Reject Linked Data credentials with properties no context defines.
+    /// Such properties are dropped before signing, so they are unsigned.
+    pub require_defined_terms: bool,
+    /// Keep accepting credentials this long after they expire, with a
+    /// warning, to bridge the gap until a renewal arrives.
+    pub expiry_grace: Option<Duration>,
+    /// Reject credentials before their `valid_from`, or their issuance time
+    /// when they have none.
+    pub enforce_valid_from: bool,
+    /// Keep trusting a loaded offline pack this long after its validity,
+    /// with a warning. Without it, verification fails once the pack expires.
+    pub offline_pack_grace: Option<Duration>,
+}
+
+/// Outcome of `verify_credential_full`.
+#[derive(Debug, Clone)]
+pub struct VerificationReport {
+    pub credential_id: Uuid,
+    pub failures: Vec<VerificationError>,
+    pub warnings: Vec<VerificationWarning>,
+}
+
+impl VerificationReport {
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "Credential {} is valid", self.credential_id);
+        }
+        write!(f, "Credential {} failed verification", self.credential_id)?;
+        for (index, failure) in self.failures.iter().enumerate() {
+            let separator = if index == 0 { ": " } else { "; " };
+            write!(f, "{}{}", separator, failure)?;
+        }
+        Ok(())
+    }
+}
+
+/// Selection of checks for `verify_credential_checks`, combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Checks(u8);
+
+impl Checks {
+    pub const NONE: Checks = Checks(0);
+    /// The proof verifies under the issuer key active at issuance. Without
+    /// `TRUST` the key comes from the credential itself when the issuer is
+    /// not trusted, which only shows the credential is unaltered.
+    pub const SIGNATURE: Checks = Checks(1);
+    /// Within `valid_from` (when enforced) and expiry, grace included.
+    pub const VALIDITY: Checks = Checks(1 << 1);
+    /// The issuer is trusted directly or certified by a trusted root.
+    pub const TRUST: Checks = Checks(1 << 2);
+    /// Not suspended or revoked on the issuer's status list.
+    pub const STATUS: Checks = Checks(1 << 3);
+    /// Meets the verifier policy, e.g. the minimum skill level.
+    pub const POLICY: Checks = Checks(1 << 4);
+    pub const ALL: Checks = Checks(0b1_1111);
+
+    pub fn contains(self, other: Checks) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Checks {
+    type Output = Checks;
+
+    fn bitor(self, other: Checks) -> Checks {
+        Checks(self.0 | other.0)
+    }
+}
+
+#[derive(Clone)]
 pub struct CredentialVerifier {
     trusted_issuers: Vec<Issuer>,
+    trusted_roots: Vec<Issuer>,
+    certificates: Vec<IssuerCertificate>,
+    status_lists: HashMap<uuid::Uuid, StatusList>,
+    policy: VerifierPolicy,
+    event_handlers: Vec<Arc<dyn EventHandler>>,
+    clock: Arc<dyn Clock>,
+    context_loader: Arc<dyn ContextLoader>,
+    x509_roots: Vec<Certificate>,
+    blockchain_addresses: HashMap<uuid::Uuid, Vec<String>>,
+    attestation_signer: Option<(String, Arc<dyn Signer>)>,
+    offline_pack_valid_until: Option<DateTime<Utc>>,
 }
 
 impl CredentialVerifier {
     pub fn new() -> Self {
         Self {
             trusted_issuers: Vec::new(),
+            trusted_roots: Vec::new(),
+            certificates: Vec::new(),
+            status_lists: HashMap::new(),
+            policy: VerifierPolicy::default(),
+            event_handlers: Vec::new(),
+            clock: Arc::new(SystemClock),
+            context_loader: Arc::new(OfflineContextLoader::new()),
+            x509_roots: Vec::new(),
+            blockchain_addresses: HashMap::new(),
+            attestation_signer: None,
+            offline_pack_valid_until: None,
         }
     }
 
+    pub fn with_policy(policy: VerifierPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::new()
+        }
+    }
+
+    pub fn set_policy(&mut self, policy: VerifierPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn get_policy(&self) -> &VerifierPolicy {
+        &self.policy
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Sets the loader that resolves the contexts of Linked Data
+    /// credentials. Contexts it cannot resolve are rejected.
+    pub fn set_context_loader(&mut self, loader: Arc<dyn ContextLoader>) {
+        self.context_loader = loader;
+    }
+
+    pub fn add_event_handler(&mut self, handler: Arc<dyn EventHandler>) {
+        self.event_handlers.push(handler);
+    }
+
     pub fn add_trusted_issuer(&mut self, issuer: Issuer) {
+        self.trusted_issuers.retain(|trusted| trusted.id != issuer.id);
         self.trusted_issuers.push(issuer);
     }
 
+    /// Trusts the issuer described by a self-signed profile after checking
+    /// its signature. Compare `IssuerProfile::key_fingerprint()` out of band
+    /// before importing a profile from an untrusted channel. A profile for
+    /// an issuer that is already trusted must also be signed by the key
+    /// trusted for it, or by a key its history shows that key endorsed, so
+    /// nobody else can replace that key.
+    pub fn import_issuer_profile(
+        &mut self,
+        profile: &IssuerProfile,
+    ) -> Result<(), VerificationError> {
+        if profile.signature.is_none() {
+            return Err(VerificationError::MissingSignature);
+        }
+        if !profile.verify_self_signature() {
+            return Err(VerificationError::InvalidSignature);
+        }
+        let trusted = self
+            .trusted_issuers
+            .iter()
+            .find(|trusted| trusted.id == profile.issuer.id);
+        if trusted.is_some_and(|trusted| {
+            !profile.verify_signature(&trusted.public_key)
+                && !profile.issuer.succeeds(&trusted.public_key)
+        }) {
+            return Err(VerificationError::InvalidSignature);
+        }
+        self.add_trusted_issuer(profile.issuer.clone());
+        self.set_blockchain_addresses(&profile.issuer.id, profile.blockchain_addresses.clone());
+        Ok(())
+    }
+
+    /// Addresses the issuer anchors Blockcerts batches from, replacing any
+    /// set earlier or imported with its profile.
+    pub fn set_blockchain_addresses(&mut self, issuer_id: &uuid::Uuid, addresses: Vec<String>) {
+        self.blockchain_addresses.insert(*issuer_id, addresses);
+    }
+
+    /// Pins an X.509 CA certificate for `import_x509_profile`.
+    pub fn add_x509_root(&mut self, root: Certificate) {
+        if !self.x509_roots.contains(&root) {
+            self.x509_roots.push(root);
+        }
+    }
+
+    /// Trusts the issuer of a profile whose signing key is certified by an
+    /// X.509 chain ending at a pinned root. Returns the leaf certificate,
+    /// whose subject names the organization behind the issuer.
+    ///
+    /// The leaf must be usable for signatures, its organization must be the
+    /// issuer's name and its DNS names (subject alternative names, or the
+    /// common name without them) must include the host of the issuer URL.
+    /// An issuer that is already trusted is only replaced by a profile for
+    /// the same key.
+    pub fn import_x509_profile(
+        &mut self,
+        profile: &IssuerProfile,
+    ) -> Result<Certificate, VerificationError> {
+        if profile.signature.is_none() {
+            return Err(VerificationError::MissingSignature);
+        }
+        if !profile.verify_self_signature() {
+            return Err(VerificationError::InvalidSignature);
+        }
+        let invalid =
+            |e: x509::X509Error| VerificationError::InvalidCertificateChain(e.to_string());
+        let chain = profile
+            .certificate_chain
+            .iter()
+            .map(|pem| Certificate::from_pem(pem))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(invalid)?;
+        let leaf =
+            x509::verify_chain(&chain, &self.x509_roots, self.clock.now()).map_err(invalid)?;
+        if profile.issuer.public_key != leaf.public_key() {
+            return Err(VerificationError::InvalidCertificateChain(
+                "leaf certificate does not certify the issuer key".to_string(),
+            ));
+        }
+        if !leaf.allows_digital_signature() {
+            return Err(VerificationError::InvalidCertificateChain(
+                "leaf certificate key may not sign".to_string(),
+            ));
+        }
+        let host = url_host(&profile.issuer.url);
+        let names_host = if leaf.subject_dns_names().is_empty() {
+            leaf.subject_common_name()
+                .is_some_and(|name| name.eq_ignore_ascii_case(host))
+        } else {
+            leaf.subject_dns_names()
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(host))
+        };
+        if leaf.subject_organization().as_deref() != Some(profile.issuer.name.as_str())
+            || !names_host
+        {
+            return Err(VerificationError::InvalidCertificateChain(
+                "leaf certificate does not name the issuer".to_string(),
+            ));
+        }
+        let replaces_other_key = self.trusted_issuers.iter().any(|trusted| {
+            trusted.id == profile.issuer.id && trusted.public_key != profile.issuer.public_key
+        });
+        if replaces_other_key {
+            return Err(VerificationError::InvalidCertificateChain(
+                "issuer is already trusted with another key".to_string(),
+            ));
+        }
+        let leaf = leaf.clone();
+        self.add_trusted_issuer(profile.issuer.clone());
+        self.set_blockchain_addresses(&profile.issuer.id, profile.blockchain_addresses.clone());
+        Ok(leaf)
+    }
+
     pub fn remove_trusted_issuer(&mut self, issuer_id: &uuid::Uuid) {
         self.trusted_issuers.retain(|issuer| issuer.id != *issuer_id);
+        self.blockchain_addresses.remove(issuer_id);
     }
 
     pub fn get_trusted_issuers(&self) -> &[Issuer] {
         &self.trusted_issuers
     }
 
+    /// Pins a root authority. Issuers certified by it, directly or through
+    /// intermediates, are trusted without being added individually.
+    pub fn add_trusted_root(&mut self, root: Issuer) {
+        self.trusted_roots.retain(|trusted| trusted.id != root.id);
+        self.trusted_roots.push(root);
+    }
+
+    /// Makes a certificate available for path construction. Certificates are
+    /// only trusted once a path to a pinned root is found.
+    pub fn add_issuer_certificate(&mut self, certificate: IssuerCertificate) {
+        self.certificates.retain(|known| known.id != certificate.id);
+        self.certificates.push(certificate);
+    }
+
+    pub fn update_status_list(&mut self, status_list: StatusList) {
+        self.status_lists.insert(status_list.issuer_id, status_list);
+    }
+
+    pub fn credential_status(&self, credential: &Microcredential) -> CredentialStatus {
+        self.status_lists
+            .get(&credential.issuer.id)
+            .map(|list| list.status_of(&credential.id))
+            .unwrap_or(CredentialStatus::Active)
+    }
+
     pub fn verify_credential(
         &self,
         credential: &Microcredential,
     ) -> Result<bool, VerificationError> {
-        if credential.is_expired() {
-            return Err(VerificationError::ExpiredCredential);
+        let result = self.check_credential(credential);
+        for handler in &self.event_handlers {
+            handler.on_verified(credential, &result);
+        }
+        result
+    }
+
+    /// Runs every check, collecting all failures instead of stopping at the
+    /// first, so a credential can be fixed in one go.
+    pub fn verify_credential_full(&self, credential: &Microcredential) -> VerificationReport {
+        let mut report = VerificationReport {
+            credential_id: credential.id,
+            failures: Vec::new(),
+            warnings: Vec::new(),
+        };
+        match self.check_validity_period(
+            Some(credential.valid_from.unwrap_or(credential.issued_at)),
+            credential.expires_at,
+        ) {
+            Ok(warnings) => report.warnings = warnings,
+            Err(err) => report.failures.push(err),
+        }
+        report.warnings.extend(self.offline_warning());
+        for checks in [Checks::TRUST, Checks::SIGNATURE, Checks::STATUS, Checks::POLICY] {
+            if let Err(err) = self.check_selected(credential, checks) {
+                report.failures.push(err);
+            }
+        }
+
+        let result = match report.failures.first() {
+            Some(err) => Err(err.clone()),
+            None => Ok(true),
+        };
+        for handler in &self.event_handlers {
+            handler.on_verified(credential, &result);
         }
+        report
+    }
+
+    /// Key used to sign attestations, with the identifier (e.g. URL) this
+    /// verifier is known by to relying parties.
+    pub fn set_attestation_signer(&mut self, verifier_id: &str, signer: Box<dyn Signer>) {
+        self.attestation_signer = Some((verifier_id.to_string(), signer.into()));
+    }
 
-        let signature = credential
-            .signature
+    /// Runs `verify_credential_full` and signs the outcome, failures
+    /// included, for relying parties downstream.
+    pub fn attest_credential(
+        &self,
+        credential: &Microcredential,
+    ) -> Result<VerificationAttestation, AttestationError> {
+        let (verifier_id, signer) = self
+            .attestation_signer
             .as_ref()
-            .ok_or(VerificationError::MissingSignature)?;
+            .ok_or(AttestationError::NoSigner)?;
+        let report = self.verify_credential_full(credential);
+        let mut attestation = VerificationAttestation {
+            id: Uuid::new_v4(),
+            verifier: verifier_id.clone(),
+            verifier_key: signer.public_key(),
+            credential_id: credential.id,
+            credential_hash: attestation::credential_hash(credential)?,
+            checked_at: self.clock.now(),
+            valid: report.is_valid(),
+            failures: report.failures.iter().map(ToString::to_string).collect(),
+            warnings: report.warnings.iter().map(ToString::to_string).collect(),
+            signature: None,
+        };
+        let payload = attestation
+            .signing_payload()
+            .map_err(|e| AttestationError::Serialization(e.to_string()))?;
+        attestation.signature = Some(signer.sign(&hash_credential(&payload))?);
+        Ok(attestation)
+    }
+
+    /// Runs only the selected checks, e.g. `Checks::VALIDITY | Checks::STATUS`
+    /// to refresh a credential whose signature was already verified. Event
+    /// handlers are not notified of partial verifications.
+    pub fn verify_credential_checks(
+        &self,
+        credential: &Microcredential,
+        checks: Checks,
+    ) -> Result<bool, VerificationError> {
+        self.check_selected(credential, checks)
+    }
+
+    /// Like `verify_credential`, but also reports what the credential was
+    /// accepted despite, e.g. being in its expiry grace period.
+    pub fn verify_credential_with_warnings(
+        &self,
+        credential: &Microcredential,
+    ) -> Result<Vec<VerificationWarning>, VerificationError> {
+        self.verify_credential(credential)?;
+        let mut warnings = self.check_validity_period(
+            Some(credential.valid_from.unwrap_or(credential.issued_at)),
+            credential.expires_at,
+        )?;
+        warnings.extend(self.offline_warning());
+        Ok(warnings)
+    }
+
+    /// Snapshot of this verifier's trust list and status lists, valid for
+    /// `valid_for`. Add contexts with `insert_context`, then sign it.
+    pub fn export_offline_pack(&self, valid_for: Duration) -> OfflinePack {
+        let now = self.clock.now();
+        OfflinePack {
+            created_at: now,
+            valid_until: now + valid_for,
+            trusted_issuers: self.trusted_issuers.clone(),
+            trusted_roots: self.trusted_roots.clone(),
+            certificates: self.certificates.clone(),
+            status_lists: self.status_lists.values().cloned().collect(),
+            contexts: Default::default(),
+            signature: None,
+        }
+    }
+
+    /// Replaces the trust list, certificates, status lists and contexts with
+    /// those of a pack signed by `publisher_key`. Once the pack is past its
+    /// validity, verification fails with `OfflinePackExpired`, or within
+    /// `VerifierPolicy::offline_pack_grace` carries a `StaleOfflinePack`
+    /// warning.
+    pub fn load_offline_pack(
+        &mut self,
+        pack: &OfflinePack,
+        publisher_key: &[u8],
+    ) -> Result<(), OfflinePackError> {
+        pack.verify(publisher_key, self.clock.now())?;
+        let mut loader = OfflineContextLoader::new();
+        for (url, document) in &pack.contexts {
+            loader
+                .insert(url, document.clone())
+                .map_err(|e| OfflinePackError::InvalidContext(e.to_string()))?;
+        }
+        self.trusted_issuers = pack.trusted_issuers.clone();
+        self.trusted_roots = pack.trusted_roots.clone();
+        self.certificates = pack.certificates.clone();
+        self.status_lists = pack
+            .status_lists
+            .iter()
+            .map(|list| (list.issuer_id, list.clone()))
+            .collect();
+        self.context_loader = Arc::new(loader);
+        self.offline_pack_valid_until = Some(pack.valid_until);
+        Ok(())
+    }
+
+    /// Readiness of this verifier: whether it trusts anyone, whether its
+    /// trust data and revocation lists are current, and whether its
+    /// attestation key (if any) is usable.
+    pub fn health(&self, thresholds: &HealthThresholds) -> HealthReport {
+        let now = self.clock.now();
+        let expired_certificates = self
+            .certificates
+            .iter()
+            .filter(|certificate| certificate.not_after < now)
+            .count();
+        let trust_list = if self.trusted_issuers.is_empty() && self.trusted_roots.is_empty() {
+            HealthCheck::new("trust_list", HealthStatus::Unhealthy, "no trusted issuers")
+        } else if let Some(valid_until) = self.offline_pack_valid_until.filter(|at| *at < now) {
+            // Past the grace period every verification fails.
+            let status = match self.check_offline_pack() {
+                Ok(()) => HealthStatus::Degraded,
+                Err(_) => HealthStatus::Unhealthy,
+            };
+            HealthCheck::new(
+                "trust_list",
+                status,
+                format!("offline pack expired at {}", valid_until.to_rfc3339()),
+            )
+        } else if expired_certificates > 0 {
+            HealthCheck::new(
+                "trust_list",
+                HealthStatus::Degraded,
+                format!("{} issuer certificates expired", expired_certificates),
+            )
+        } else {
+            HealthCheck::new(
+                "trust_list",
+                HealthStatus::Healthy,
+                format!(
+                    "{} trusted issuers, {} trusted roots",
+                    self.trusted_issuers.len(),
+                    self.trusted_roots.len()
+                ),
+            )
+        };
+
+        let oldest = self.status_lists.values().map(|list| list.updated_at).min();
+        let status_lists = match oldest {
+            None => HealthCheck::new(
+                "status_lists",
+                HealthStatus::Degraded,
+                "no revocation lists loaded",
+            ),
+            Some(updated_at) if now - updated_at > thresholds.max_status_list_age => {
+                HealthCheck::new(
+                    "status_lists",
+                    HealthStatus::Degraded,
+                    format!("oldest revocation list from {}", updated_at.to_rfc3339()),
+                )
+            }
+            Some(updated_at) => HealthCheck::new(
+                "status_lists",
+                HealthStatus::Healthy,
+                format!("oldest revocation list from {}", updated_at.to_rfc3339()),
+            ),
+        };
+
+        let mut checks = vec![trust_list, status_lists];
+        if let Some((_, signer)) = &self.attestation_signer {
+            checks.push(match signer.health_check() {
+                Ok(()) => HealthCheck::new(
+                    "attestation_signer",
+                    HealthStatus::Healthy,
+                    "signer available",
+                ),
+                Err(e) => {
+                    HealthCheck::new("attestation_signer", HealthStatus::Unhealthy, e.to_string())
+                }
+            });
+        }
+        HealthReport {
+            checked_at: now,
+            checks,
+        }
+    }
+
+    /// Fails once the loaded offline pack is past its validity and grace.
+    fn check_offline_pack(&self) -> Result<(), VerificationError> {
+        match self.offline_pack_valid_until {
+            Some(valid_until) if self.clock.now() > valid_until => {
+                match self.policy.offline_pack_grace {
+                    Some(grace) if self.clock.now() <= valid_until + grace => Ok(()),
+                    _ => Err(VerificationError::OfflinePackExpired(valid_until)),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
 
-        let trusted_issuer = self
+    fn offline_warning(&self) -> Option<VerificationWarning> {
+        self.offline_pack_valid_until
+            .filter(|valid_until| self.clock.now() > *valid_until)
+            .map(|valid_until| VerificationWarning::StaleOfflinePack { valid_until })
+    }
+
+    /// Verifies a W3C credential exported with `export_linked_data`. The
+    /// issuer is matched by URL against the trusted issuers and the key by
+    /// the proof's verification method.
+    pub fn verify_linked_data(&self, document: &Value) -> Result<bool, VerificationError> {
+        let issuer = self.linked_data_issuer(document)?;
+        let date = |value: Option<&Value>| {
+            value
+                .and_then(Value::as_str)
+                .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+                .map(|at| at.with_timezone(&Utc))
+        };
+
+        let proof = ld::proof_options(document).map_err(|_| VerificationError::MissingSignature)?;
+        if proof.get("proofPurpose").and_then(Value::as_str) != Some("assertionMethod") {
+            return Err(VerificationError::UnsupportedProof(
+                "proof purpose is not assertionMethod".to_string(),
+            ));
+        }
+        let public_key = proof
+            .get("verificationMethod")
+            .and_then(Value::as_str)
+            .and_then(|method| method.split_once('#'))
+            .filter(|(controller, _)| {
+                *controller == issuer.url || *controller == crate::did::did_web(&issuer.url)
+            })
+            .map(|(_, key_id)| key_id)
+            .zip(date(proof.get("created")))
+            .and_then(|(key_id, created)| issuer.key_with_id(key_id, created))
+            .ok_or(VerificationError::NoActiveIssuerKey)?;
+
+        let loader = self.context_loader.as_ref();
+        if self.policy.require_defined_terms {
+            match ld::validate_terms(document, loader) {
+                Err(e @ LdError::UndefinedTerms(_)) => {
+                    return Err(VerificationError::SerializationError(e.to_string()))
+                }
+                Err(e) => return Err(VerificationError::UnsupportedProof(e.to_string())),
+                Ok(()) => {}
+            }
+        }
+        if !ld::verify_proof(document, public_key, loader)
+            .map_err(|e| VerificationError::UnsupportedProof(e.to_string()))?
+        {
+            return Err(VerificationError::InvalidSignature);
+        }
+        self.linked_data_status(issuer, document)
+    }
+
+    /// Verifies a Blockcerts v2 certificate: the issuer must be trusted (by
+    /// URL), the receipt must match the certificate and lead to a Merkle
+    /// root that every anchor carries, and every anchoring transaction must
+    /// come from one of the issuer's blockchain addresses.
+    pub fn verify_blockcerts(
+        &self,
+        document: &Value,
+        lookup: &dyn AnchorLookup,
+    ) -> Result<MerkleReceipt, VerificationError> {
+        let issuer = self.linked_data_issuer(document)?;
+        let addresses = self
+            .blockchain_addresses
+            .get(&issuer.id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let receipt =
+            blockcerts::verify(document, self.context_loader.as_ref(), lookup, addresses)
+                .map_err(|e| match e {
+                    BlockcertsError::MissingReceipt => VerificationError::MissingSignature,
+                    BlockcertsError::TargetHashMismatch
+                    | BlockcertsError::MerkleRootMismatch
+                    | BlockcertsError::AnchorMismatch(_)
+                    | BlockcertsError::UnknownSender(_) => VerificationError::InvalidSignature,
+                    e => VerificationError::UnsupportedProof(e.to_string()),
+                })?;
+        self.linked_data_status(issuer, document)?;
+        Ok(receipt)
+    }
+
+    /// The trusted issuer of a W3C credential, matched by URL, provided the
+    /// credential is within its validity period.
+    fn linked_data_issuer(&self, document: &Value) -> Result<&Issuer, VerificationError> {
+        self.check_offline_pack()?;
+        let issuer_url = document
+            .get("issuer")
+            .and_then(|issuer| issuer.get("id").unwrap_or(issuer).as_str())
+            .ok_or(VerificationError::TrustedIssuerNotFound)?;
+        let issuer = self
             .trusted_issuers
             .iter()
-            .find(|issuer| issuer.id == credential.issuer.id)
+            .find(|issuer| issuer.url == issuer_url)
             .ok_or(VerificationError::TrustedIssuerNotFound)?;
 
-        let mut credential_for_hash = credential.clone();
-        credential_for_hash.signature = None;
+        let date = |value: Option<&Value>| {
+            value
+                .and_then(Value::as_str)
+                .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+                .map(|at| at.with_timezone(&Utc))
+        };
+        let valid_from = date(document.get("validFrom")).or(date(document.get("issuanceDate")));
+        let expires_at = date(document.get("validUntil")).or(date(document.get("expirationDate")));
+        self.check_validity_period(valid_from, expires_at)?;
+        Ok(issuer)
+    }
+
+    fn check_validity_period(
+        &self,
+        valid_from: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Vec<VerificationWarning>, VerificationError> {
+        let now = self.clock.now();
+        if let Some(valid_from) = valid_from.filter(|_| self.policy.enforce_valid_from) {
+            if now < valid_from {
+                return Err(VerificationError::NotYetValid(valid_from));
+            }
+        }
+        match expires_at {
+            Some(expired_at) if now > expired_at => match self.policy.expiry_grace {
+                Some(grace) if now <= expired_at + grace => {
+                    Ok(vec![VerificationWarning::ExpiredWithinGrace { expired_at }])
+                }
+                _ => Err(VerificationError::ExpiredCredential),
+            },
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn linked_data_status(
+        &self,
+        issuer: &Issuer,
+        document: &Value,
+    ) -> Result<bool, VerificationError> {
+        let credential_id = document
+            .get("id")
+            .and_then(Value::as_str)
+            .and_then(|id| id.strip_prefix("urn:uuid:"))
+            .and_then(|id| Uuid::parse_str(id).ok());
+        let status = credential_id
+            .zip(self.status_lists.get(&issuer.id))
+            .map(|(id, list)| list.status_of(&id))
+            .unwrap_or(CredentialStatus::Active);
+        match status {
+            CredentialStatus::Active => Ok(true),
+            CredentialStatus::Suspended { reason, .. } => {
+                Err(VerificationError::SuspendedCredential(reason))
+            }
+            CredentialStatus::Revoked { reason, .. } => {
+                Err(VerificationError::RevokedCredential(reason))
+            }
+        }
+    }
+
+    fn check_credential(&self, credential: &Microcredential) -> Result<bool, VerificationError> {
+        self.check_selected(credential, Checks::ALL)
+    }
+
+    fn check_selected(
+        &self,
+        credential: &Microcredential,
+        checks: Checks,
+    ) -> Result<bool, VerificationError> {
+        // An offline pack supplies both the trust list and status lists.
+        if checks.contains(Checks::TRUST) || checks.contains(Checks::STATUS) {
+            self.check_offline_pack()?;
+        }
+        if checks.contains(Checks::VALIDITY) {
+            self.check_validity_period(
+                Some(credential.valid_from.unwrap_or(credential.issued_at)),
+                credential.expires_at,
+            )?;
+        }
+        if checks.contains(Checks::SIGNATURE) || checks.contains(Checks::TRUST) {
+            let signature = match checks.contains(Checks::SIGNATURE) {
+                true => Some(
+                    credential
+                        .signature
+                        .as_ref()
+                        .ok_or(VerificationError::MissingSignature)?,
+                ),
+                false => None,
+            };
+            let issuer = if checks.contains(Checks::TRUST) {
+                self.trusted_issuer(credential)?
+            } else {
+                self.trusted_issuer(credential).unwrap_or(&credential.issuer)
+            };
+            if let Some(signature) = signature {
+                self.check_signature(credential, issuer, signature)?;
+            }
+        }
+        if checks.contains(Checks::STATUS) {
+            match self.credential_status(credential) {
+                CredentialStatus::Active => {}
+                CredentialStatus::Suspended { reason, .. } => {
+                    return Err(VerificationError::SuspendedCredential(reason));
+                }
+                CredentialStatus::Revoked { reason, .. } => {
+                    return Err(VerificationError::RevokedCredential(reason));
+                }
+            }
+        }
+        if checks.contains(Checks::POLICY) {
+            self.check_level_scale(&credential.skill.level)?;
+            if let Some(min_level) = &self.policy.min_skill_level {
+                if !credential.skill.level.meets(min_level) {
+                    return Err(VerificationError::InsufficientSkillLevel);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn check_level_scale(&self, level: &SkillLevel) -> Result<(), VerificationError> {
+        let builtin = LevelScale::builtin();
+        let scale = self
+            .policy
+            .level_scales
+            .iter()
+            .chain(&builtin)
+            .find(|scale| scale.id == level.scale_id());
+        match scale {
+            Some(scale) if !scale.contains(level) => Err(VerificationError::InvalidSkillLevel(
+                format!("{} (rank {})", level.label(), level.rank()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// The issuer of `credential` if it is trusted directly or certified by
+    /// a trusted root.
+    fn trusted_issuer(&self, credential: &Microcredential) -> Result<&Issuer, VerificationError> {
+        match self
+            .trusted_issuers
+            .iter()
+            .find(|issuer| issuer.id == credential.issuer.id)
+        {
+            Some(trusted_issuer) => Ok(trusted_issuer),
+            None => self.certified_issuer(credential),
+        }
+    }
+
+    fn check_signature(
+        &self,
+        credential: &Microcredential,
+        issuer: &Issuer,
+        signature: &[u8],
+    ) -> Result<(), VerificationError> {
+        let proof = credential.proof.clone().unwrap_or_default();
+        if proof.proof_purpose != ProofPurpose::AssertionMethod {
+            return Err(VerificationError::UnsupportedProof(format!(
+                "proof purpose {:?}",
+                proof.proof_purpose
+            )));
+        }
+        let public_key = match proof.key_id() {
+            Some(key_id) => issuer.key_with_id(key_id, credential.issued_at),
+            None => issuer.key_at(credential.issued_at),
+        }
+        .ok_or(VerificationError::NoActiveIssuerKey)?;
 
-        let credential_json = serde_json::to_vec(&credential_for_hash)
+        let payload = credential
+            .signing_payload()
             .map_err(|e| VerificationError::SerializationError(e.to_string()))?;
+        let digest = proof.hash.digest(&payload);
+        let mut is_valid = match proof.suite {
+            SignatureSuite::Ed25519 => verify_signature(public_key, &digest, signature)
+                .map_err(|_| VerificationError::InvalidSignature)?,
+        };
 
-        let credential_hash = hash_credential(&credential_json);
+        if !is_valid {
+            let byte_key_payload = credential
+                .byte_key_signing_payload()
+                .map_err(|e| VerificationError::SerializationError(e.to_string()))?;
+            is_valid =
+                verify_signature(public_key, &proof.hash.digest(&byte_key_payload), signature)
+                    .map_err(|_| VerificationError::InvalidSignature)?;
+        }
 
-        let is_valid = verify_signature(&trusted_issuer.public_key, &credential_hash, signature)
-            .map_err(|_| VerificationError::InvalidSignature)?;
+        if !is_valid && credential.proof.is_none() && credential.unknown_fields.is_empty() {
+            let legacy_payload = credential
+                .legacy_signing_payload()
+                .map_err(|e| VerificationError::SerializationError(e.to_string()))?;
+            is_valid = verify_signature(public_key, &hash_credential(&legacy_payload), signature)
+                .map_err(|_| VerificationError::InvalidSignature)?;
+        }
 
         if !is_valid {
             return Err(VerificationError::InvalidSignature);
         }
+        Ok(())
+    }
 
-        Ok(true)
+    fn certified_issuer(
+        &self,
+        credential: &Microcredential,
+    ) -> Result<&Issuer, VerificationError> {
+        let path = build_path(
+            credential.issuer.id,
+            credential.issued_at,
+            &self.trusted_roots,
+            &self.certificates,
+        )
+        .map_err(|_| VerificationError::TrustedIssuerNotFound)?;
+        check_constraints(&path, credential).map_err(|e| match e {
+            PathError::ConstraintViolation(msg) => {
+                VerificationError::CertificateConstraintViolation(msg)
+            }
+            _ => VerificationError::TrustedIssuerNotFound,
+        })?;
+        Ok(&path[0].subject)
+    }
+
+    /// Checks the holder proof and every credential in the presentation,
+    /// collecting all results instead of stopping at the first failure.
+    pub fn verify_presentation(&self, presentation: &Presentation) -> PresentationReport {
+        let credential_checks = presentation
+            .credentials
+            .iter()
+            .map(|credential| CredentialCheck {
+                credential_id: credential.id,
+                result: self
+                    .check_holder_binding(credential, &presentation.holder_key)
+                    .and_then(|()| self.verify_credential(credential)),
+            })
+            .collect();
+
+        PresentationReport {
+            presentation_id: presentation.id,
+            holder_proof_valid: presentation.verify_holder_proof(),
+            audience_valid: self.is_intended_audience(presentation),
+            credential_checks,
+        }
+    }
+
+    fn check_holder_binding(
+        &self,
+        credential: &Microcredential,
+        holder_key: &[u8],
+    ) -> Result<(), VerificationError> {
+        match &credential.holder_binding {
+            Some(binding) if binding.public_key != holder_key => {
+                Err(VerificationError::HolderBindingMismatch)
+            }
+            None if self.policy.require_holder_binding => {
+                Err(VerificationError::MissingHolderBinding)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a proof of possession: `signature` must be the bound holder
+    /// key's signature over `challenge::possession_message` for this
+    /// verifier's `verifier_id` and the `challenge` it chose.
+    pub fn verify_possession(
+        &self,
+        credential: &Microcredential,
+        verifier_id: &str,
+        challenge: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, VerificationError> {
+        let binding = credential
+            .holder_binding
+            .as_ref()
+            .ok_or(VerificationError::MissingHolderBinding)?;
+        let message = possession_message(verifier_id, challenge);
+        let is_valid = verify_signature(&binding.public_key, &message, signature)
+            .map_err(|_| VerificationError::HolderBindingMismatch)?;
+        if !is_valid {
+            return Err(VerificationError::HolderBindingMismatch);
+        }
+        self.verify_credential(credential)
+    }
+
+    fn is_intended_audience(&self, presentation: &Presentation) -> bool {
+        let audience_matches = self
+            .policy
+            .audience
+            .as_ref()
+            .is_none_or(|audience| *audience == presentation.audience);
+        let domain_matches = self
+            .policy
+            .domain
+            .as_ref()
+            .is_none_or(|domain| presentation.domain.as_ref() == Some(domain));
+        audience_matches && domain_matches
     }
 
     pub fn verify_credential_chain(
@@ -106,4 +1094,73 @@ impl Default for CredentialVerifier {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// A `CredentialVerifier` shared between threads, e.g. as web service state.
+/// Readers load a snapshot atomically and verify against it without taking
+/// any lock; updates copy the current verifier, change the copy and publish
+/// it, so in-flight verifications keep the trust list they started with.
+pub struct SharedVerifier {
+    current: ArcSwap<CredentialVerifier>,
+    /// Serializes updates so concurrent writers cannot lose each other's
+    /// changes.
+    update_lock: Mutex<()>,
+}
+
+impl SharedVerifier {
+    pub fn new(verifier: CredentialVerifier) -> Self {
+        Self {
+            current: ArcSwap::from_pointee(verifier),
+            update_lock: Mutex::new(()),
+        }
+    }
+
+    /// The verifier as of now. Later updates do not affect it.
+    pub fn snapshot(&self) -> Arc<CredentialVerifier> {
+        self.current.load_full()
+    }
+
+    /// Applies `change` to a copy of the verifier and publishes the copy.
+    pub fn update<R>(&self, change: impl FnOnce(&mut CredentialVerifier) -> R) -> R {
+        let _guard = self.update_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut next = (*self.snapshot()).clone();
+        let result = change(&mut next);
+        self.current.store(Arc::new(next));
+        result
+    }
+
+    pub fn add_trusted_issuer(&self, issuer: Issuer) {
+        self.update(|verifier| verifier.add_trusted_issuer(issuer));
+    }
+
+    pub fn remove_trusted_issuer(&self, issuer_id: &Uuid) {
+        self.update(|verifier| verifier.remove_trusted_issuer(issuer_id));
+    }
+
+    pub fn update_status_list(&self, status_list: StatusList) {
+        self.update(|verifier| verifier.update_status_list(status_list));
+    }
+
+    pub fn verify_credential(
+        &self,
+        credential: &Microcredential,
+    ) -> Result<bool, VerificationError> {
+        self.snapshot().verify_credential(credential)
+    }
+
+    pub fn verify_credential_full(&self, credential: &Microcredential) -> VerificationReport {
+        self.snapshot().verify_credential_full(credential)
+    }
+}
+
+impl From<CredentialVerifier> for SharedVerifier {
+    fn from(verifier: CredentialVerifier) -> Self {
+        Self::new(verifier)
+    }
+}
+
+impl Default for SharedVerifier {
+    fn default() -> Self {
+        Self::new(CredentialVerifier::new())
+    }
+}
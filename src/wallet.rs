@@ -0,0 +1,288 @@
+//! Apple Wallet passes (`.pkpass`) and Google Wallet "Save to Wallet" JWTs
+//! for credentials, with issuer branding and a QR code that links to the
+//! credential's verification page.
+//!
+//! Both platforms only accept signatures from credentials they issue (an
+//! Apple Pass Type ID certificate, a Google service account RSA key), so
+//! signing is delegated to `PassSigner` and `JwtSigner`.
+
+use crate::signer::SignerError;
+use crate::Microcredential;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use sha1::{Digest, Sha1};
+use std::error::Error;
+use std::fmt;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WalletError {
+    /// Apple Wallet rejects passes without `icon.png`.
+    MissingIcon,
+    Signer(SignerError),
+    Archive(String),
+    Serialization(String),
+}
+
+impl fmt::Display for WalletError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WalletError::MissingIcon => write!(f, "Pass has no icon"),
+            WalletError::Signer(e) => write!(f, "Signing failed: {}", e),
+            WalletError::Archive(msg) => write!(f, "Failed to write pass archive: {}", msg),
+            WalletError::Serialization(msg) => write!(f, "Serialization error: {}", msg),
+        }
+    }
+}
+
+impl Error for WalletError {}
+
+impl From<SignerError> for WalletError {
+    fn from(e: SignerError) -> Self {
+        WalletError::Signer(e)
+    }
+}
+
+impl From<zip::result::ZipError> for WalletError {
+    fn from(e: zip::result::ZipError) -> Self {
+        WalletError::Archive(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for WalletError {
+    fn from(e: std::io::Error) -> Self {
+        WalletError::Archive(e.to_string())
+    }
+}
+
+/// Produces the detached PKCS#7 (DER) signature of a pass manifest with
+/// the Pass Type ID certificate, including the Apple WWDR intermediate.
+pub trait PassSigner: Send + Sync {
+    fn sign_manifest(&self, manifest: &[u8]) -> Result<Vec<u8>, SignerError>;
+}
+
+/// Produces RS256 signatures with a Google Cloud service account key.
+pub trait JwtSigner: Send + Sync {
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, SignerError>;
+
+    /// `kid` of the service account key, if it should be named.
+    fn key_id(&self) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WalletBranding {
+    pub organization_name: String,
+    pub background_color: [u8; 3],
+    pub foreground_color: [u8; 3],
+    pub label_color: [u8; 3],
+    pub icon_png: Vec<u8>,
+    pub logo_png: Option<Vec<u8>>,
+    /// Publicly hosted logo, required by Google Wallet.
+    pub logo_url: Option<String>,
+    /// Verification page encoded in the QR code; `{id}` is replaced with
+    /// the credential id.
+    pub verification_url: String,
+}
+
+impl WalletBranding {
+    pub fn verification_url_for(&self, credential: &Microcredential) -> String {
+        self.verification_url
+            .replace("{id}", &credential.id.to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PkPassConfig {
+    pub pass_type_identifier: String,
+    pub team_identifier: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoogleWalletConfig {
+    /// Numeric issuer id from the Google Pay & Wallet console.
+    pub issuer_id: String,
+    pub class_suffix: String,
+    pub service_account_email: String,
+}
+
+/// `pass.json` of a generic Apple Wallet pass for `credential`.
+pub fn pass_json(
+    credential: &Microcredential,
+    branding: &WalletBranding,
+    config: &PkPassConfig,
+) -> Value {
+    let field = |key: &str, label: &str, value: Value| {
+        json!({ "key": key, "label": label, "value": value })
+    };
+    let date_field = |key: &str, label: &str, at: &DateTime<Utc>| {
+        let mut field = field(key, label, json!(rfc3339(at)));
+        field["dateStyle"] = json!("PKDateStyleMedium");
+        field
+    };
+
+    let mut auxiliary = vec![date_field("issued", "ISSUED", &credential.issued_at)];
+    if let Some(expires_at) = &credential.expires_at {
+        auxiliary.push(date_field("expires", "EXPIRES", expires_at));
+    }
+    let mut pass = json!({
+        "formatVersion": 1,
+        "passTypeIdentifier": config.pass_type_identifier,
+        "teamIdentifier": config.team_identifier,
+        "serialNumber": credential.id.to_string(),
+        "organizationName": branding.organization_name,
+        "description": format!("{} credential", credential.skill.name),
+        "logoText": credential.issuer.name,
+        "backgroundColor": rgb(branding.background_color),
+        "foregroundColor": rgb(branding.foreground_color),
+        "labelColor": rgb(branding.label_color),
+        "barcodes": [{
+            "format": "PKBarcodeFormatQR",
+            "message": branding.verification_url_for(credential),
+            "messageEncoding": "iso-8859-1",
+            "altText": "Scan to verify",
+        }],
+        "generic": {
+            "primaryFields": [field("skill", "SKILL", json!(credential.skill.name))],
+            "secondaryFields": [
+                field("holder", "HOLDER", json!(credential.subject.name)),
+                field("level", "LEVEL", json!(credential.skill.level.label())),
+            ],
+            "auxiliaryFields": auxiliary,
+            "backFields": [
+                field("issuer", "ISSUER", json!(format!(
+                    "{} ({})",
+                    credential.issuer.name, credential.issuer.url
+                ))),
+                field("description", "DESCRIPTION", json!(credential.skill.description)),
+                field("credential", "CREDENTIAL ID", json!(credential.id.to_string())),
+            ],
+        },
+    });
+    if let Some(expires_at) = &credential.expires_at {
+        pass["expirationDate"] = json!(rfc3339(expires_at));
+    }
+    pass
+}
+
+/// Signed `.pkpass` archive: `pass.json`, images, `manifest.json` with the
+/// SHA-1 of every file, and the manifest `signature`.
+pub fn pkpass(
+    credential: &Microcredential,
+    branding: &WalletBranding,
+    config: &PkPassConfig,
+    signer: &dyn PassSigner,
+) -> Result<Vec<u8>, WalletError> {
+    if branding.icon_png.is_empty() {
+        return Err(WalletError::MissingIcon);
+    }
+    let pass = serde_json::to_vec(&pass_json(credential, branding, config))
+        .map_err(|e| WalletError::Serialization(e.to_string()))?;
+    let mut files: Vec<(&str, &[u8])> =
+        vec![("pass.json", &pass), ("icon.png", &branding.icon_png)];
+    if let Some(logo) = &branding.logo_png {
+        files.push(("logo.png", logo));
+    }
+
+    let manifest: Map<String, Value> = files
+        .iter()
+        .map(|(name, bytes)| (name.to_string(), json!(hex::encode(Sha1::digest(bytes)))))
+        .collect();
+    let manifest =
+        serde_json::to_vec(&manifest).map_err(|e| WalletError::Serialization(e.to_string()))?;
+    let signature = signer.sign_manifest(&manifest)?;
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, bytes) in files.into_iter().chain([
+        ("manifest.json", &manifest[..]),
+        ("signature", &signature[..]),
+    ]) {
+        archive.start_file(name, options)?;
+        archive.write_all(bytes)?;
+    }
+    Ok(archive.finish()?.into_inner())
+}
+
+/// Google Wallet "Save to Wallet" JWT carrying a generic pass object for
+/// `credential`. The pass class `{issuer_id}.{class_suffix}` must exist.
+pub fn google_wallet_jwt(
+    credential: &Microcredential,
+    branding: &WalletBranding,
+    config: &GoogleWalletConfig,
+    signer: &dyn JwtSigner,
+    now: DateTime<Utc>,
+) -> Result<String, WalletError> {
+    let text = |value: &str| json!({ "defaultValue": { "language": "en", "value": value } });
+    let expired = credential.expires_at.is_some_and(|at| at < now);
+    let mut object = json!({
+        "id": format!("{}.{}", config.issuer_id, credential.id),
+        "classId": format!("{}.{}", config.issuer_id, config.class_suffix),
+        "state": if expired { "EXPIRED" } else { "ACTIVE" },
+        "cardTitle": text(&branding.organization_name),
+        "header": text(&credential.skill.name),
+        "subheader": text(&credential.subject.name),
+        "hexBackgroundColor": format!("#{}", hex::encode(branding.background_color)),
+        "barcode": {
+            "type": "QR_CODE",
+            "value": branding.verification_url_for(credential),
+            "alternateText": "Scan to verify",
+        },
+        "textModulesData": [
+            { "id": "level", "header": "Level", "body": credential.skill.level.label() },
+            { "id": "issuer", "header": "Issuer", "body": credential.issuer.name },
+            { "id": "credential", "header": "Credential ID", "body": credential.id.to_string() },
+        ],
+    });
+    let mut validity = json!({ "start": { "date": rfc3339(&credential.issued_at) } });
+    if let Some(expires_at) = &credential.expires_at {
+        validity["end"] = json!({ "date": rfc3339(expires_at) });
+    }
+    object["validTimeInterval"] = validity;
+    if let Some(logo_url) = &branding.logo_url {
+        object["logo"] = json!({ "sourceUri": { "uri": logo_url } });
+    }
+
+    let mut header = json!({ "alg": "RS256", "typ": "JWT" });
+    if let Some(key_id) = signer.key_id() {
+        header["kid"] = json!(key_id);
+    }
+    let claims = json!({
+        "iss": config.service_account_email,
+        "aud": "google",
+        "typ": "savetowallet",
+        "iat": now.timestamp(),
+        "origins": [],
+        "payload": { "genericObjects": [object] },
+    });
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let signature = signer.sign(signing_input.as_bytes())?;
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+/// Link that opens the "Save to Google Wallet" flow for `jwt`.
+pub fn google_save_url(jwt: &str) -> String {
+    format!("https://pay.google.com/gp/v/save/{}", jwt)
+}
+
+fn rgb(color: [u8; 3]) -> String {
+    format!("rgb({}, {}, {})", color[0], color[1], color[2])
+}
+
+fn rfc3339(at: &DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
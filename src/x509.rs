@@ -0,0 +1,569 @@
+//! X.509 certificate chains binding an issuer's signing key to its
+//! organizational identity, for relying parties whose trust is anchored in
+//! a PKI rather than in trusted-issuer lists.
+//!
+//! Only Ed25519 certificates are understood: every certificate in a chain
+//! must carry an Ed25519 key and be signed with Ed25519.
+
+use crate::crypto::verify_signature;
+use crate::pkcs8::{self, read_tlv, Pkcs8Error};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::error::Error;
+use std::fmt;
+
+const SEQUENCE: u8 = 0x30;
+const SET: u8 = 0x31;
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OCTET_STRING: u8 = 0x04;
+const OID: u8 = 0x06;
+const BOOLEAN: u8 = 0x01;
+const UTC_TIME: u8 = 0x17;
+const GENERALIZED_TIME: u8 = 0x18;
+const VERSION_TAG: u8 = 0xa0;
+const EXTENSIONS_TAG: u8 = 0xa3;
+
+/// Content of the Ed25519 `AlgorithmIdentifier` (OID 1.3.101.112).
+const ED25519_ALGORITHM: [u8; 5] = [0x06, 0x03, 0x2b, 0x65, 0x70];
+const OID_COMMON_NAME: [u8; 3] = [0x55, 0x04, 0x03];
+const OID_ORGANIZATION: [u8; 3] = [0x55, 0x04, 0x0a];
+const OID_KEY_USAGE: [u8; 3] = [0x55, 0x1d, 0x0f];
+const OID_BASIC_CONSTRAINTS: [u8; 3] = [0x55, 0x1d, 0x13];
+const OID_SUBJECT_ALT_NAME: [u8; 3] = [0x55, 0x1d, 0x11];
+/// `dNSName` choice of a `GeneralName`.
+const DNS_NAME_TAG: u8 = 0x82;
+/// `digitalSignature` and `keyCertSign` in the first byte of the key usage
+/// bits.
+const DIGITAL_SIGNATURE: u8 = 0x80;
+const KEY_CERT_SIGN: u8 = 0x04;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum X509Error {
+    InvalidEncoding(String),
+    UnsupportedAlgorithm,
+    UnsupportedCriticalExtension(String),
+    EmptyChain,
+    OutsideValidity(String),
+    NotCa(String),
+    /// A CA has more intermediates below it than its `pathLenConstraint`.
+    PathLengthExceeded(String),
+    InvalidSignature(String),
+    UntrustedRoot,
+}
+
+impl fmt::Display for X509Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            X509Error::InvalidEncoding(msg) => write!(f, "Invalid certificate: {}", msg),
+            X509Error::UnsupportedAlgorithm => {
+                write!(f, "Only Ed25519 certificates are supported")
+            }
+            X509Error::UnsupportedCriticalExtension(oid) => {
+                write!(f, "Unsupported critical extension {}", oid)
+            }
+            X509Error::EmptyChain => write!(f, "Certificate chain is empty"),
+            X509Error::OutsideValidity(subject) => {
+                write!(f, "Certificate {} is not valid at this time", subject)
+            }
+            X509Error::NotCa(subject) => {
+                write!(f, "Certificate {} may not issue certificates", subject)
+            }
+            X509Error::PathLengthExceeded(subject) => {
+                write!(f, "Certificate {} may not issue this deep a chain", subject)
+            }
+            X509Error::InvalidSignature(subject) => {
+                write!(f, "Certificate {} has an invalid signature", subject)
+            }
+            X509Error::UntrustedRoot => write!(f, "Chain does not end at a trusted root"),
+        }
+    }
+}
+
+impl Error for X509Error {}
+
+impl From<Pkcs8Error> for X509Error {
+    fn from(e: Pkcs8Error) -> Self {
+        match e {
+            Pkcs8Error::UnsupportedAlgorithm => X509Error::UnsupportedAlgorithm,
+            other => X509Error::InvalidEncoding(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Certificate {
+    der: Vec<u8>,
+    tbs: Vec<u8>,
+    issuer: Vec<u8>,
+    subject: Vec<u8>,
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    is_ca: bool,
+    path_len: Option<usize>,
+    key_usage: Option<u8>,
+    dns_names: Vec<String>,
+    pub serial: Vec<u8>,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+impl Certificate {
+    pub fn from_der(der: &[u8]) -> Result<Self, X509Error> {
+        let (certificate, rest) = read_tlv(der, SEQUENCE)?;
+        if !rest.is_empty() {
+            return Err(X509Error::InvalidEncoding("trailing data".to_string()));
+        }
+        let (tbs, rest) = read_raw(certificate, SEQUENCE)?;
+        let (algorithm, rest) = read_tlv(rest, SEQUENCE)?;
+        if algorithm != ED25519_ALGORITHM {
+            return Err(X509Error::UnsupportedAlgorithm);
+        }
+        let (signature, _) = read_tlv(rest, BIT_STRING)?;
+        let signature = signature
+            .strip_prefix(&[0])
+            .ok_or_else(|| X509Error::InvalidEncoding("invalid signature".to_string()))?;
+
+        let (mut body, _) = read_tlv(tbs, SEQUENCE)?;
+        if body.first() == Some(&VERSION_TAG) {
+            body = read_tlv(body, VERSION_TAG)?.1;
+        }
+        let (serial, body) = read_tlv(body, INTEGER)?;
+        let (tbs_algorithm, body) = read_tlv(body, SEQUENCE)?;
+        if tbs_algorithm != algorithm {
+            return Err(X509Error::InvalidEncoding(
+                "signature algorithms differ".to_string(),
+            ));
+        }
+        let (issuer, body) = read_raw(body, SEQUENCE)?;
+        let (validity, body) = read_tlv(body, SEQUENCE)?;
+        let (not_before, validity) = read_time(validity)?;
+        let (not_after, _) = read_time(validity)?;
+        let (subject, body) = read_raw(body, SEQUENCE)?;
+        let (spki, mut body) = read_raw(body, SEQUENCE)?;
+
+        let mut certificate = Self {
+            der: der.to_vec(),
+            tbs: tbs.to_vec(),
+            issuer: issuer.to_vec(),
+            subject: subject.to_vec(),
+            public_key: pkcs8::public_key_from_der(spki)?,
+            signature: signature.to_vec(),
+            is_ca: false,
+            path_len: None,
+            key_usage: None,
+            dns_names: Vec::new(),
+            serial: serial.to_vec(),
+            not_before,
+            not_after,
+        };
+        // Optional unique identifiers, then extensions.
+        while let Some(tag) = body.first().copied() {
+            let (value, rest) = read_tlv(body, tag)?;
+            if tag == EXTENSIONS_TAG {
+                certificate.read_extensions(read_tlv(value, SEQUENCE)?.0)?;
+            }
+            body = rest;
+        }
+        Ok(certificate)
+    }
+
+    pub fn from_pem(pem: &str) -> Result<Self, X509Error> {
+        Self::from_der(&pkcs8::pem_decode("CERTIFICATE", pem)?)
+    }
+
+    /// Parses every `CERTIFICATE` block of `pem`, in order.
+    pub fn chain_from_pem(pem: &str) -> Result<Vec<Self>, X509Error> {
+        const END: &str = "-----END CERTIFICATE-----";
+        pem.split_inclusive(END)
+            .filter(|block| block.contains(END))
+            .map(Self::from_pem)
+            .collect()
+    }
+
+    pub fn to_pem(&self) -> String {
+        pkcs8::pem_encode("CERTIFICATE", &self.der)
+    }
+
+    pub fn der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// The certified Ed25519 public key.
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    pub fn subject_common_name(&self) -> Option<String> {
+        name_attribute(&self.subject, &OID_COMMON_NAME)
+    }
+
+    pub fn subject_organization(&self) -> Option<String> {
+        name_attribute(&self.subject, &OID_ORGANIZATION)
+    }
+
+    /// `dNSName` entries of the subject alternative name extension.
+    pub fn subject_dns_names(&self) -> &[String] {
+        &self.dns_names
+    }
+
+    /// Whether the key may sign data other than certificates: key usage
+    /// is absent or includes `digitalSignature`.
+    pub fn allows_digital_signature(&self) -> bool {
+        self.key_usage
+            .is_none_or(|bits| bits & DIGITAL_SIGNATURE != 0)
+    }
+
+    /// Whether the certificate may sign other certificates: basic
+    /// constraints `cA` and, if key usage is present, `keyCertSign`.
+    pub fn is_ca(&self) -> bool {
+        self.is_ca && self.key_usage.is_none_or(|bits| bits & KEY_CERT_SIGN != 0)
+    }
+
+    pub fn is_valid_at(&self, at: DateTime<Utc>) -> bool {
+        self.not_before <= at && at <= self.not_after
+    }
+
+    fn is_issued_by(&self, issuer: &Certificate) -> bool {
+        self.issuer == issuer.subject
+            && verify_signature(&issuer.public_key, &self.tbs, &self.signature).unwrap_or(false)
+    }
+
+    /// Fails if this CA has more than its allowed number of intermediate
+    /// certificates below it.
+    fn check_path_len(&self, intermediates: usize) -> Result<(), X509Error> {
+        match self.path_len {
+            Some(limit) if intermediates > limit => {
+                Err(X509Error::PathLengthExceeded(self.label()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn label(&self) -> String {
+        self.subject_common_name()
+            .unwrap_or_else(|| format!("with serial {:02x?}", self.serial))
+    }
+
+    fn read_extensions(&mut self, mut extensions: &[u8]) -> Result<(), X509Error> {
+        while !extensions.is_empty() {
+            let (extension, rest) = read_tlv(extensions, SEQUENCE)?;
+            extensions = rest;
+            let (oid, mut extension) = read_tlv(extension, OID)?;
+            let mut critical = false;
+            if extension.first() == Some(&BOOLEAN) {
+                let (flag, rest) = read_tlv(extension, BOOLEAN)?;
+                critical = flag != [0];
+                extension = rest;
+            }
+            let (value, _) = read_tlv(extension, OCTET_STRING)?;
+            match oid {
+                oid if oid == OID_BASIC_CONSTRAINTS => {
+                    let (mut constraints, _) = read_tlv(value, SEQUENCE)?;
+                    if constraints.first() == Some(&BOOLEAN) {
+                        let (flag, rest) = read_tlv(constraints, BOOLEAN)?;
+                        self.is_ca = flag != [0];
+                        constraints = rest;
+                    }
+                    if constraints.first() == Some(&INTEGER) {
+                        let (limit, _) = read_tlv(constraints, INTEGER)?;
+                        if limit.len() > 4 {
+                            return Err(X509Error::InvalidEncoding(
+                                "pathLenConstraint too large".to_string(),
+                            ));
+                        }
+                        self.path_len = Some(
+                            limit
+                                .iter()
+                                .fold(0, |value, byte| value << 8 | *byte as usize),
+                        );
+                    }
+                }
+                oid if oid == OID_SUBJECT_ALT_NAME => {
+                    let (mut names, _) = read_tlv(value, SEQUENCE)?;
+                    while let Some(tag) = names.first().copied() {
+                        let (name, rest) = read_tlv(names, tag)?;
+                        if tag == DNS_NAME_TAG {
+                            self.dns_names
+                                .push(String::from_utf8_lossy(name).into_owned());
+                        }
+                        names = rest;
+                    }
+                }
+                oid if oid == OID_KEY_USAGE => {
+                    let (bits, _) = read_tlv(value, BIT_STRING)?;
+                    self.key_usage = Some(bits.get(1).copied().unwrap_or(0));
+                }
+                oid if critical => {
+                    return Err(X509Error::UnsupportedCriticalExtension(hex::encode(oid)))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validates `chain` (leaf first) at `at`, ending at one of `roots`, which
+/// may or may not be included in the chain itself. Returns the leaf.
+/// Path length constraints are enforced, roots' included.
+pub fn verify_chain<'a>(
+    chain: &'a [Certificate],
+    roots: &[Certificate],
+    at: DateTime<Utc>,
+) -> Result<&'a Certificate, X509Error> {
+    let leaf = chain.first().ok_or(X509Error::EmptyChain)?;
+    for (depth, certificate) in chain.iter().enumerate() {
+        if roots.contains(certificate) && depth > 0 {
+            certificate.check_path_len(depth - 1)?;
+            return Ok(leaf);
+        }
+        if !certificate.is_valid_at(at) {
+            return Err(X509Error::OutsideValidity(certificate.label()));
+        }
+        if depth > 0 && !certificate.is_ca() {
+            return Err(X509Error::NotCa(certificate.label()));
+        }
+        if depth > 0 {
+            certificate.check_path_len(depth - 1)?;
+        }
+        let issued_by_root = roots.iter().find(|root| certificate.is_issued_by(root));
+        if let Some(root) = issued_by_root {
+            if !root.is_ca() {
+                return Err(X509Error::NotCa(root.label()));
+            }
+            if !root.is_valid_at(at) {
+                return Err(X509Error::OutsideValidity(root.label()));
+            }
+            root.check_path_len(depth)?;
+            return Ok(leaf);
+        }
+        match chain.get(depth + 1) {
+            Some(next) if certificate.is_issued_by(next) => {}
+            Some(_) => return Err(X509Error::InvalidSignature(certificate.label())),
+            None => return Err(X509Error::UntrustedRoot),
+        }
+    }
+    Err(X509Error::UntrustedRoot)
+}
+
+/// Like `read_tlv`, but returns the whole TLV instead of its content.
+fn read_raw(der: &[u8], tag: u8) -> Result<(&[u8], &[u8]), X509Error> {
+    let (_, rest) = read_tlv(der, tag)?;
+    Ok((&der[..der.len() - rest.len()], rest))
+}
+
+fn read_time(der: &[u8]) -> Result<(DateTime<Utc>, &[u8]), X509Error> {
+    let (text, rest, tag) = match der.first() {
+        Some(&UTC_TIME) => {
+            let (value, rest) = read_tlv(der, UTC_TIME)?;
+            (value, rest, UTC_TIME)
+        }
+        _ => {
+            let (value, rest) = read_tlv(der, GENERALIZED_TIME)?;
+            (value, rest, GENERALIZED_TIME)
+        }
+    };
+    let text = String::from_utf8_lossy(text);
+    let text = match tag {
+        // RFC 5280: two-digit years 50-99 are 19xx, 00-49 are 20xx.
+        UTC_TIME if text.as_bytes().first().is_some_and(|digit| *digit >= b'5') => {
+            format!("19{}", text)
+        }
+        UTC_TIME => format!("20{}", text),
+        _ => text.into_owned(),
+    };
+    let at = NaiveDateTime::parse_from_str(&text, "%Y%m%d%H%M%SZ")
+        .map_err(|e| X509Error::InvalidEncoding(format!("invalid time {}: {}", text, e)))?;
+    Ok((at.and_utc(), rest))
+}
+
+fn name_attribute(name: &[u8], oid: &[u8]) -> Option<String> {
+    let (mut sets, _) = read_tlv(name, SEQUENCE).ok()?;
+    while !sets.is_empty() {
+        let (set, rest) = read_tlv(sets, SET).ok()?;
+        sets = rest;
+        let (attribute, _) = read_tlv(set, SEQUENCE).ok()?;
+        let (attribute_oid, value) = read_tlv(attribute, OID).ok()?;
+        if attribute_oid == oid {
+            let (text, _) = read_tlv(value, *value.first()?).ok()?;
+            return Some(String::from_utf8_lossy(text).into_owned());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    pub(crate) const ROOT_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBnTCCAU+gAwIBAgIUdBuoLX+It5yHZG6fuOP5uz+SUVwwBQYDK2VwMDsxHzAd
+BgNVBAoMFkV4YW1wbGUgVHJ1c3QgU2VydmljZXMxGDAWBgNVBAMMD0V4YW1wbGUg
+Um9vdCBDQTAgFw0yNjEwMTYxMjExMjFaGA8yMTI2MDkyMjEyMTEyMVowOzEfMB0G
+A1UECgwWRXhhbXBsZSBUcnVzdCBTZXJ2aWNlczEYMBYGA1UEAwwPRXhhbXBsZSBS
+b290IENBMCowBQYDK2VwAyEAnUyDhgGPtK4v8sq0zcC/K8SuWBEEauyijd5dfCkS
+pIyjYzBhMB0GA1UdDgQWBBT0ACXYXfyt9mqNt+S/2csKAuYQiTAfBgNVHSMEGDAW
+gBT0ACXYXfyt9mqNt+S/2csKAuYQiTAPBgNVHRMBAf8EBTADAQH/MA4GA1UdDwEB
+/wQEAwICBDAFBgMrZXADQQDMwzwkqgWvUvjqqp73gOwVwESh7+bszK3kHruZgv0Z
++heR/cCLeOdbzjUcBfxGtxfZCWXKosXUFCE79GIl7cMK
+-----END CERTIFICATE-----
+";
+
+    /// Leaf for "credentials.test.edu" followed by its issuing CA.
+    pub(crate) const CHAIN_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBiDCCATqgAwIBAgIBAzAFBgMrZXAwPjEfMB0GA1UECgwWRXhhbXBsZSBUcnVz
+dCBTZXJ2aWNlczEbMBkGA1UEAwwSRXhhbXBsZSBJc3N1aW5nIENBMCAXDTI2MTAx
+NjEyMTEyMVoYDzIxMjIwODE0MTIxMTIxWjA5MRgwFgYDVQQKDA9UZXN0IFVuaXZl
+cnNpdHkxHTAbBgNVBAMMFGNyZWRlbnRpYWxzLnRlc3QuZWR1MCowBQYDK2VwAyEA
+OutkNi3ck8v26cdgDPOLNZDC0LSbg2Bt9s+DpDm2oeajYDBeMAwGA1UdEwEB/wQC
+MAAwDgYDVR0PAQH/BAQDAgeAMB0GA1UdDgQWBBRWF0PqW3h7h9m2JCVn2E86MrCc
+gzAfBgNVHSMEGDAWgBR2Oji8LwAfonDw61jDjEOwp63qFDAFBgMrZXADQQAZJNts
+Y0fdn0rT+IqH+X2T4hTo1XprF/zOrqKSw9BFOeWireFBayhu7iTL1MYJtPgc94wc
+TZ2xkBmSIrlO6ycF
+-----END CERTIFICATE-----
+-----BEGIN CERTIFICATE-----
+MIIBjTCCAT+gAwIBAgIBAjAFBgMrZXAwOzEfMB0GA1UECgwWRXhhbXBsZSBUcnVz
+dCBTZXJ2aWNlczEYMBYGA1UEAwwPRXhhbXBsZSBSb290IENBMCAXDTI2MTAxNjEy
+MTEyMVoYDzIxMjUwNTEwMTIxMTIxWjA+MR8wHQYDVQQKDBZFeGFtcGxlIFRydXN0
+IFNlcnZpY2VzMRswGQYDVQQDDBJFeGFtcGxlIElzc3VpbmcgQ0EwKjAFBgMrZXAD
+IQBoccGZlKV/JKfnhqIeFazmSIH95HscIEHeDD4YxQucCKNjMGEwDwYDVR0TAQH/
+BAUwAwEB/zAOBgNVHQ8BAf8EBAMCAgQwHQYDVR0OBBYEFHY6OLwvAB+icPDrWMOM
+Q7CnreoUMB8GA1UdIwQYMBaAFPQAJdhd/K32ao235L/ZywoC5hCJMAUGAytlcANB
+AK8ZRxa34hj0B8QphA7mWaxrWrRnOAS5tNX4P6GTW5DgcJmLljaB7hSG0UERvicr
+NoQu0k4rzdphgmBApegdIgs=
+-----END CERTIFICATE-----
+";
+
+    /// Secret key certified by the leaf of `CHAIN_PEM`.
+    pub(crate) const LEAF_SECRET_KEY: &str =
+        "55a61eb09bb2f534f0f6b7c2cd7ed6c8196bf5268c635184771dbfd97b80a35d";
+
+    /// Root with `pathlen:0`, so it may not issue `CONSTRAINED_CHAIN_PEM`.
+    const CONSTRAINED_ROOT_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBkTCCAUOgAwIBAgIUR/YSQqnvZsU3o5MS3aS1QvSUBA4wBQYDK2VwMEQxHzAd
+BgNVBAoMFkV4YW1wbGUgVHJ1c3QgU2VydmljZXMxITAfBgNVBAMMGEV4YW1wbGUg
+Q29uc3RyYWluZWQgUm9vdDAgFw0yNjEwMTYxMzM1MTdaGA8yMTI2MDkyMjEzMzUx
+N1owRDEfMB0GA1UECgwWRXhhbXBsZSBUcnVzdCBTZXJ2aWNlczEhMB8GA1UEAwwY
+RXhhbXBsZSBDb25zdHJhaW5lZCBSb290MCowBQYDK2VwAyEA4WKV429GmlfuEfvo
+G5AidULN6KHvnGSlTbBFP8gtY+mjRTBDMBIGA1UdEwEB/wQIMAYBAf8CAQAwDgYD
+VR0PAQH/BAQDAgIEMB0GA1UdDgQWBBRMqcvB781BLR755Xaf0a2+71pO1jAFBgMr
+ZXADQQBgMihsqFc3h37FsIoVixD3gFBifo8uTbPhs1ol/YTmEY1RlrgNOxCKDG0B
+JYm5HQXHOpebT61aWbZ6ipKsLvoH
+-----END CERTIFICATE-----
+";
+
+    /// Leaf (key agreement only, two DNS names) and intermediate CA below
+    /// `CONSTRAINED_ROOT_PEM`.
+    const CONSTRAINED_CHAIN_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBuTCCAWugAwIBAgIBAzAFBgMrZXAwQjEfMB0GA1UECgwWRXhhbXBsZSBUcnVz
+dCBTZXJ2aWNlczEfMB0GA1UEAwwWRXhhbXBsZSBDb25zdHJhaW5lZCBDQTAgFw0y
+NjEwMTYxMzM1MTdaGA8yMTIyMDgxNDEzMzUxN1owOTEYMBYGA1UECgwPVGVzdCBV
+bml2ZXJzaXR5MR0wGwYDVQQDDBRjcmVkZW50aWFscy50ZXN0LmVkdTAqMAUGAytl
+cAMhAGegkJ3QVG50a7Mqv9DcO7U/Uh/2JmzFBiDh24o3DjSzo4GMMIGJMAwGA1Ud
+EwEB/wQCMAAwDgYDVR0PAQH/BAQDAgMIMCkGA1UdEQQiMCCCFGNyZWRlbnRpYWxz
+LnRlc3QuZWR1ggh0ZXN0LmVkdTAdBgNVHQ4EFgQUrPKMEh2XG3ijl9sZ95gpnZoY
+wNUwHwYDVR0jBBgwFoAUt9GF50tKag+jDImjULCz5xxfEykwBQYDK2VwA0EAgTyA
+QZ7v6naXyVLpMhEk0IHojAVLU5agBxoxySqRGA0UnE66kOTSf/OFajmX12IiF0B0
+zslEdPuLOEpQsNuTBA==
+-----END CERTIFICATE-----
+-----BEGIN CERTIFICATE-----
+MIIBmjCCAUygAwIBAgIBAjAFBgMrZXAwRDEfMB0GA1UECgwWRXhhbXBsZSBUcnVz
+dCBTZXJ2aWNlczEhMB8GA1UEAwwYRXhhbXBsZSBDb25zdHJhaW5lZCBSb290MCAX
+DTI2MTAxNjEzMzUxN1oYDzIxMjUwNTEwMTMzNTE3WjBCMR8wHQYDVQQKDBZFeGFt
+cGxlIFRydXN0IFNlcnZpY2VzMR8wHQYDVQQDDBZFeGFtcGxlIENvbnN0cmFpbmVk
+IENBMCowBQYDK2VwAyEAQmIzqvqoVDvN1bQreRytTzKRsvl7dSrxIOD4KNo7IiKj
+YzBhMA8GA1UdEwEB/wQFMAMBAf8wDgYDVR0PAQH/BAQDAgIEMB0GA1UdDgQWBBS3
+0YXnS0pqD6MMiaNQsLPnHF8TKTAfBgNVHSMEGDAWgBRMqcvB781BLR755Xaf0a2+
+71pO1jAFBgMrZXADQQC/3VZQlVxz5g7K63hzq/DipFas2qkbMvpH18/2uCzCSMQy
+YCw/XUjxUfuIxvqiUNFvRdm8hCJ+GTUJGLV3TMAL
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_parses_openssl_certificates() {
+        let chain = Certificate::chain_from_pem(CHAIN_PEM).unwrap();
+        assert_eq!(chain.len(), 2);
+        let leaf = &chain[0];
+        assert_eq!(
+            leaf.subject_common_name().as_deref(),
+            Some("credentials.test.edu")
+        );
+        assert_eq!(
+            leaf.subject_organization().as_deref(),
+            Some("Test University")
+        );
+        assert_eq!(leaf.serial, vec![3]);
+        assert!(!leaf.is_ca());
+        assert!(leaf.allows_digital_signature());
+        assert!(leaf.subject_dns_names().is_empty());
+        assert!(chain[1].is_ca());
+        // UTCTime and GeneralizedTime validity bounds.
+        assert_eq!(
+            leaf.not_before,
+            Utc.with_ymd_and_hms(2026, 10, 16, 12, 11, 21).unwrap()
+        );
+        assert_eq!(
+            leaf.not_after,
+            Utc.with_ymd_and_hms(2122, 8, 14, 12, 11, 21).unwrap()
+        );
+        assert_eq!(Certificate::from_pem(&leaf.to_pem()).unwrap(), *leaf);
+    }
+
+    #[test]
+    fn test_chain_validation() {
+        let root = Certificate::from_pem(ROOT_PEM).unwrap();
+        let chain = Certificate::chain_from_pem(CHAIN_PEM).unwrap();
+        let at = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+
+        let leaf = verify_chain(&chain, std::slice::from_ref(&root), at).unwrap();
+        assert_eq!(leaf, &chain[0]);
+        let mut with_root = chain.clone();
+        with_root.push(root.clone());
+        assert!(verify_chain(&with_root, std::slice::from_ref(&root), at).is_ok());
+
+        assert_eq!(verify_chain(&chain, &[], at), Err(X509Error::UntrustedRoot));
+        assert_eq!(
+            verify_chain(&chain[..1], std::slice::from_ref(&root), at),
+            Err(X509Error::UntrustedRoot)
+        );
+        let later = Utc.with_ymd_and_hms(2123, 1, 1, 0, 0, 0).unwrap();
+        assert!(matches!(
+            verify_chain(&chain, std::slice::from_ref(&root), later),
+            Err(X509Error::OutsideValidity(_))
+        ));
+        // The leaf may not act as a CA for itself.
+        let reversed = vec![chain[0].clone(), chain[0].clone()];
+        assert!(matches!(
+            verify_chain(&reversed, std::slice::from_ref(&root), at),
+            Err(X509Error::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_constraints_and_alternative_names() {
+        let root = Certificate::from_pem(CONSTRAINED_ROOT_PEM).unwrap();
+        let chain = Certificate::chain_from_pem(CONSTRAINED_CHAIN_PEM).unwrap();
+        let at = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let leaf = &chain[0];
+        assert_eq!(
+            leaf.subject_dns_names(),
+            ["credentials.test.edu".to_string(), "test.edu".to_string()]
+        );
+        assert!(!leaf.allows_digital_signature());
+
+        assert!(matches!(
+            verify_chain(&chain, std::slice::from_ref(&root), at),
+            Err(X509Error::PathLengthExceeded(_))
+        ));
+        let mut with_root = chain.clone();
+        with_root.push(root.clone());
+        assert!(matches!(
+            verify_chain(&with_root, std::slice::from_ref(&root), at),
+            Err(X509Error::PathLengthExceeded(_))
+        ));
+        // Certificates issued directly by the root are within its limit.
+        assert!(verify_chain(&chain[1..], std::slice::from_ref(&root), at).is_ok());
+    }
+}